@@ -0,0 +1,236 @@
+//! This file contains type [`Baseline`], the diagnostics a project already had
+//! when it adopted the checker or a new rule, which `--baseline` suppresses so
+//! that only new ones fail the check.
+//!
+//! Diagnostics are identified by their rule, project, subject, message and
+//! languages rather than their locations, so that editing the files around
+//! them does not make them new.
+
+use crate::checker::Checker;
+use crate::editor::subject_of;
+use crate::rules::Diagnostic;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Version of the baseline format, bumped on incompatible changes.
+pub(crate) const BASELINE_VERSION: u32 = 1;
+
+/// What identifies a diagnostic in a [`Baseline`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+struct Fingerprint {
+    /// Root of the project, if any.
+    project: Option<PathBuf>,
+    /// Name of the rule.
+    rule: String,
+    /// What the diagnostic is about, see [`subject_of()`].
+    subject: String,
+    /// Message reported to users, if any.
+    message: Option<String>,
+    /// Languages affected.
+    langs: Vec<String>,
+}
+
+impl Fingerprint {
+    /// Creates the fingerprint of `diagnostic` found by `rule` in the project
+    /// whose root is `project`.
+    fn new(project: Option<&Path>, rule: &str, diagnostic: &Diagnostic) -> Self {
+        Self {
+            project: project.map(Path::to_path_buf),
+            rule: rule.into(),
+            subject: subject_of(&diagnostic.key).into_owned(),
+            message: diagnostic.error_msg.clone(),
+            langs: diagnostic.langs.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// A [`Fingerprint`] and the number of diagnostics that have it.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct BaselineEntry {
+    #[serde(flatten)]
+    fingerprint: Fingerprint,
+    /// Number of diagnostics, e.g., the uses of a key that does not exist.
+    count: usize,
+}
+
+/// The file format of a [`Baseline`].
+#[derive(Debug, Deserialize, Serialize)]
+struct BaselineFile {
+    /// Version of the format, [`BASELINE_VERSION`].
+    version: u32,
+    /// Suppressed diagnostics, in the order they were found.
+    diagnostics: Vec<BaselineEntry>,
+}
+
+/// The diagnostics that are suppressed.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Baseline {
+    /// `Fingerprint => Count`
+    diagnostics: IndexMap<Fingerprint, usize>,
+}
+
+impl Baseline {
+    /// Creates an empty baseline, which suppresses nothing.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the diagnostics found by `checker` in the project whose root is
+    /// `project`.
+    pub(crate) fn record(&mut self, project: Option<&Path>, checker: &Checker) {
+        for (rule, diagnostic) in checker.errors() {
+            *self
+                .diagnostics
+                .entry(Fingerprint::new(project, rule, diagnostic))
+                .or_default() += 1;
+        }
+    }
+
+    /// Returns the number of diagnostics in the baseline.
+    pub(crate) fn len(&self) -> usize {
+        self.diagnostics.values().sum()
+    }
+
+    /// Removes the diagnostics in the baseline from those found by `checker`
+    /// in the project whose root is `project`, returns how many are removed.
+    ///
+    /// A fingerprint suppresses as many diagnostics as it had when recorded,
+    /// further ones are new.
+    pub(crate) fn suppress(&self, project: Option<&Path>, checker: &mut Checker) -> usize {
+        let mut remaining = self.diagnostics.clone();
+        let mut n_suppressed = 0;
+        checker.retain_errors(|rule, diagnostic| {
+            match remaining.get_mut(&Fingerprint::new(project, rule, diagnostic)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    n_suppressed += 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+
+        n_suppressed
+    }
+
+    /// Parses a baseline written by [`Baseline::render()`].
+    pub(crate) fn parse(contents: &str) -> Result<Self, String> {
+        let file = serde_json::from_str::<BaselineFile>(contents).map_err(|e| e.to_string())?;
+        if file.version != BASELINE_VERSION {
+            return Err(format!(
+                "unsupported version {}, expected {}",
+                file.version, BASELINE_VERSION
+            ));
+        }
+
+        let mut baseline = Self::new();
+        for entry in file.diagnostics {
+            *baseline.diagnostics.entry(entry.fingerprint).or_default() += entry.count;
+        }
+
+        Ok(baseline)
+    }
+
+    /// Renders the baseline in JSON.
+    pub(crate) fn render(&self) -> String {
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            diagnostics: self
+                .diagnostics
+                .iter()
+                .map(|(fingerprint, count)| BaselineEntry {
+                    fingerprint: fingerprint.clone(),
+                    count: *count,
+                })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&file).expect("baseline should be serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Errors;
+
+    fn checker(diagnostics: Vec<(&'static str, Diagnostic)>) -> Checker {
+        let mut errors = Errors::new();
+        for (rule, diagnostic) in diagnostics {
+            errors.entry(rule).or_default().push(diagnostic);
+        }
+        Checker::with_errors(errors)
+    }
+
+    fn missing_key(line: usize) -> Diagnostic {
+        Diagnostic::error("Pulling".into(), None).at(Path::new("src/main.rs").into(), line, 4)
+    }
+
+    #[test]
+    fn test_suppress() {
+        let mut baseline = Baseline::new();
+        baseline.record(
+            None,
+            &checker(vec![
+                ("UseOfKeysDoNotExist", missing_key(1)),
+                (
+                    "MissingTranslations",
+                    Diagnostic::error("Restarting".into(), None).with_langs(vec!["de".into()]),
+                ),
+            ]),
+        );
+        assert_eq!(baseline.len(), 2);
+
+        // Moved, and used once more
+        let mut found = checker(vec![
+            ("UseOfKeysDoNotExist", missing_key(5)),
+            ("UseOfKeysDoNotExist", missing_key(9)),
+            (
+                "MissingTranslations",
+                Diagnostic::error("Restarting".into(), None).with_langs(vec!["de".into()]),
+            ),
+            (
+                "MissingTranslations",
+                Diagnostic::error("Restarting".into(), None).with_langs(vec!["fr".into()]),
+            ),
+        ]);
+        assert_eq!(baseline.suppress(None, &mut found), 2);
+        let remaining = found
+            .errors()
+            .map(|(rule, diagnostic)| (rule, diagnostic.key.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            remaining,
+            [
+                ("UseOfKeysDoNotExist", missing_key(9).key.to_string()),
+                ("MissingTranslations", "Restarting".into()),
+            ]
+        );
+
+        // Another project
+        let mut found = checker(vec![("UseOfKeysDoNotExist", missing_key(1))]);
+        assert_eq!(baseline.suppress(Some(Path::new("other")), &mut found), 0);
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut baseline = Baseline::new();
+        baseline.record(
+            Some(Path::new("topgrade")),
+            &checker(vec![
+                ("UseOfKeysDoNotExist", missing_key(1)),
+                ("UseOfKeysDoNotExist", missing_key(2)),
+            ]),
+        );
+        let rendered = baseline.render();
+        assert!(rendered.contains(r#""subject": "key 'Pulling'""#));
+        assert!(rendered.contains(r#""count": 2"#));
+        assert_eq!(Baseline::parse(&rendered).unwrap(), baseline);
+
+        assert!(Baseline::parse(r#"{"version": 0, "diagnostics": []}"#)
+            .unwrap_err()
+            .contains("unsupported version 0"));
+        assert!(Baseline::parse("{").is_err());
+    }
+}
@@ -1,16 +1,82 @@
 //! This file contains the checker type.
 
-use crate::locale_file_parser::LocalizedTexts;
-use crate::locale_key_collector::LocaleKey;
-use crate::rules::Rule;
-use std::collections::HashMap;
+use crate::config::LanguageMaintainers;
+use crate::error::Error;
+use crate::rules::{CheckContext, Diagnostic, Errors, Rule, Severity, RULES};
+use crate::timings::Timings;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// How [`Checker::report_to_user()`] organizes the diagnostics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ReportLayout {
+    /// Grouped by severity, then by rule.
+    #[default]
+    ByRule,
+    /// Grouped by the affected language, then by rule, useful for translators.
+    ByLanguage,
+}
+
+/// Which rules a [`Checker`] registers, `rules.enabled` and `rules.disabled` in
+/// the config, and the severities of their diagnostics, `rules.severity`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct RuleSelection {
+    /// Names of the rules to register, including opt-in ones, every rule that
+    /// runs by default if empty.
+    pub(crate) enabled: Vec<String>,
+    /// Names of the rules not to register.
+    pub(crate) disabled: Vec<String>,
+    /// Severity of every diagnostic of a rule: `RuleName => Severity`, rules
+    /// not listed keep the severities they report.
+    pub(crate) severities: BTreeMap<String, Severity>,
+}
+
+impl RuleSelection {
+    /// Returns true if the rule named `name` should run, `by_default` tells if
+    /// it runs when no rule is explicitly enabled, i.e., false for opt-in
+    /// rules.
+    pub(crate) fn is_enabled(&self, name: &str, by_default: bool) -> bool {
+        let listed = |names: &[String]| names.iter().any(|listed| listed == name);
+        let enabled = if self.enabled.is_empty() {
+            by_default
+        } else {
+            listed(&self.enabled)
+        };
+
+        enabled && !listed(&self.disabled)
+    }
+
+    /// Fails if any selected name is not the name of a rule.
+    fn validate(&self) -> Result<(), Error> {
+        for (option, names) in [
+            ("enabled", self.enabled.iter().collect::<Vec<_>>()),
+            ("disabled", self.disabled.iter().collect()),
+            ("severity", self.severities.keys().collect()),
+        ] {
+            for name in names {
+                if !RULES.iter().any(|doc| doc.name == name) {
+                    return Err(Error::Usage(format!(
+                        "unknown rule '{}' in `rules.{}`",
+                        name, option
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
 
 /// This type and its methods are the code where we check the locale file.
 pub(crate) struct Checker {
-    /// The registered (will be applied) rule
-    rules: Vec<Box<dyn Rule>>,
-    /// `HashMap<RuleName, Vec<(Key, OptionalErrorMessage)>>`
-    errors: HashMap<String, Vec<(String, Option<String>)>>,
+    /// The registered (will be applied) rule, along with its name.
+    rules: Vec<(&'static str, Box<dyn Rule>)>,
+    /// Errors found by the rules.
+    errors: Errors,
+    /// Rules to register, others are skipped by [`Checker::register_rule()`].
+    selection: RuleSelection,
 }
 
 impl Checker {
@@ -18,25 +84,121 @@ impl Checker {
     pub(crate) fn new() -> Self {
         Self {
             rules: Vec::new(),
-            errors: HashMap::new(),
+            errors: Errors::new(),
+            selection: RuleSelection::default(),
+        }
+    }
+
+    /// Creates a new checker with 0 rule registered, which only registers the
+    /// rules `selection` enables, fails if it names a rule that does not
+    /// exist.
+    pub(crate) fn with_selection(selection: RuleSelection) -> Result<Self, Error> {
+        selection.validate()?;
+        Ok(Self {
+            selection,
+            ..Self::new()
+        })
+    }
+
+    /// Creates a checker with 0 rule registered that has already found
+    /// `errors`, e.g., the diagnostics of a report, so that they can be
+    /// reported to the user.
+    pub(crate) fn with_errors(errors: Errors) -> Self {
+        Self {
+            rules: Vec::new(),
+            errors,
+            selection: RuleSelection::default(),
+        }
+    }
+
+    /// Register a rule, unless it is not selected.
+    pub(crate) fn register_rule<R: Rule + 'static>(&mut self, rule: R) {
+        if self.selection.is_enabled(R::name(), true) {
+            self.rules.push((R::name(), Box::new(rule)))
         }
     }
 
-    /// Register a rule.
-    pub(crate) fn register_rule(&mut self, rule: impl Rule + 'static) {
-        self.rules.push(Box::new(rule))
+    /// Returns true if the rule named `name`, which runs by default if
+    /// `by_default` is true, is selected, used to skip building opt-in rules.
+    pub(crate) fn is_enabled(&self, name: &str, by_default: bool) -> bool {
+        self.selection.is_enabled(name, by_default)
     }
 
     /// Run the check process.
-    pub(crate) fn check(&mut self, localized_texts: &LocalizedTexts, locale_keys: &[LocaleKey]) {
-        for rule in self.rules.iter() {
-            rule.check(localized_texts, locale_keys, &mut self.errors)
+    ///
+    /// Rules run concurrently, their diagnostics are merged in the order the
+    /// rules are registered so that the result is deterministic, with the
+    /// severities overridden by the selection. Time spent on
+    /// every rule will be recorded in `timings`, and every finished rule
+    /// advances `progress`.
+    pub(crate) fn check(
+        &mut self,
+        ctx: &CheckContext<'_>,
+        timings: &mut Timings,
+        progress: &ProgressBar,
+    ) {
+        let results = self
+            .rules
+            .par_iter()
+            .map(|(name, rule)| {
+                let start = Instant::now();
+                let diagnostics = rule.check(ctx);
+                let duration = start.elapsed();
+                progress.set_message(*name);
+                progress.inc(1);
+
+                (*name, diagnostics, duration)
+            })
+            .collect::<Vec<_>>();
+
+        for (name, mut diagnostics, duration) in results {
+            timings.record_phase(format!("rule {}", name), duration);
+            if let Some(&severity) = self.selection.severities.get(name) {
+                for diagnostic in diagnostics.iter_mut() {
+                    diagnostic.severity = severity;
+                }
+            }
+            if !diagnostics.is_empty() {
+                self.errors.entry(name).or_default().extend(diagnostics);
+            }
         }
     }
 
-    /// Returns the number of errors stored in `self.errors`.
+    /// Returns the names of the registered rules, in registration order.
+    pub(crate) fn rule_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|(name, _)| *name)
+    }
+
+    /// Returns the number of registered rules.
+    pub(crate) fn n_rules(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns everything found by the rules: `(RuleName, Diagnostic)`.
+    pub(crate) fn errors(&self) -> impl Iterator<Item = (&'static str, &Diagnostic)> {
+        self.errors
+            .iter()
+            .flat_map(|(rule, errors)| errors.iter().map(move |diagnostic| (*rule, diagnostic)))
+    }
+
+    /// Keeps only the diagnostics for which `keep(RuleName, Diagnostic)`
+    /// returns true, in order.
+    pub(crate) fn retain_errors(
+        &mut self,
+        mut keep: impl FnMut(&'static str, &Diagnostic) -> bool,
+    ) {
+        for (rule, diagnostics) in self.errors.iter_mut() {
+            diagnostics.retain(|diagnostic| keep(rule, diagnostic));
+        }
+        self.errors.retain(|_, diagnostics| !diagnostics.is_empty());
+    }
+
+    /// Returns the number of errors stored in `self.errors`, diagnostics with
+    /// other severities are not counted.
     fn n_errors(&self) -> usize {
-        self.errors.values().map(|errors| errors.len()).sum()
+        self.errors()
+            .filter(|(_, diagnostic)| diagnostic.severity == Severity::Error)
+            .count()
     }
 
     /// Return true if there is no error.
@@ -44,18 +206,50 @@ impl Checker {
         self.n_errors() != 0
     }
 
-    /// Print the errors that are found in a human-readable way.
-    pub(crate) fn report_to_user(&self) {
+    /// Print the errors that are found in a human-readable way, organized
+    /// according to `layout`, with the `maintainers` of every language if it
+    /// is organized by language.
+    pub(crate) fn report_to_user(&self, layout: ReportLayout, maintainers: &LanguageMaintainers) {
+        match layout {
+            ReportLayout::ByRule => self.report_by_rule(),
+            ReportLayout::ByLanguage => self.report_by_language(maintainers),
+        }
+    }
+
+    /// Print the errors grouped by rule.
+    ///
+    /// Warnings and informational diagnostics are printed after errors.
+    fn report_by_rule(&self) {
         if !self.has_error() {
             println!("No error found!");
-        } else {
-            println!("Errors Found:");
+        }
+
+        for (severity, title) in [
+            (Severity::Error, "Errors Found:"),
+            (Severity::Warning, "Warnings Found:"),
+            (Severity::Info, "Infos Found:"),
+        ] {
+            let has_severity = self
+                .errors()
+                .any(|(_, diagnostic)| diagnostic.severity == severity);
+            if !has_severity {
+                continue;
+            }
 
+            println!("{}", title);
             for (rule, errors) in self.errors.iter() {
+                let mut errors = errors
+                    .iter()
+                    .filter(|diagnostic| diagnostic.severity == severity)
+                    .peekable();
+                if errors.peek().is_none() {
+                    continue;
+                }
+
                 println!("  {}", rule);
-                for (key, opt_error_msg) in errors {
-                    print!("    {}", key);
-                    match opt_error_msg {
+                for diagnostic in errors {
+                    print!("    {}", diagnostic.headline());
+                    match &diagnostic.error_msg {
                         Some(error_msg) => println!(": {}", error_msg),
                         None => println!(),
                     }
@@ -63,30 +257,216 @@ impl Checker {
             }
         }
     }
+
+    /// Print the diagnostics grouped by the affected language.
+    ///
+    /// A diagnostic that affects multiple languages is printed in every
+    /// language's section, diagnostics that are not about any specific language
+    /// are printed last.
+    fn report_by_language(&self, maintainers: &LanguageMaintainers) {
+        if !self.has_error() {
+            println!("No error found!");
+        }
+
+        // `Language => RuleName => Diagnostics`, `None` means not language-specific
+        let mut by_lang: BTreeMap<Option<&str>, BTreeMap<&str, Vec<&Diagnostic>>> = BTreeMap::new();
+        for (rule, diagnostic) in self.errors() {
+            let langs = diagnostic
+                .langs
+                .iter()
+                .map(|lang| Some(&**lang))
+                .collect::<Vec<_>>();
+            let langs = if langs.is_empty() { vec![None] } else { langs };
+
+            for lang in langs {
+                by_lang
+                    .entry(lang)
+                    .or_default()
+                    .entry(rule)
+                    .or_default()
+                    .push(diagnostic);
+            }
+        }
+
+        // Move the not language-specific section to the end
+        let general = by_lang.remove(&None);
+        let sections = by_lang
+            .into_iter()
+            .map(|(lang, rules)| {
+                let lang = lang.expect("removed");
+                let title = match maintainers.of(lang) {
+                    [] => format!("Language {}:", lang),
+                    handles => format!("Language {} (maintainers: {}):", lang, handles.join(" ")),
+                };
+                (title, rules)
+            })
+            .chain(general.map(|rules| ("Not language-specific:".to_string(), rules)));
+
+        for (title, rules) in sections {
+            println!("{}", title);
+            for (rule, diagnostics) in rules {
+                println!("  {}", rule);
+                for diagnostic in diagnostics {
+                    print!("    {}", diagnostic.headline());
+                    if let Some(error_msg) = &diagnostic.error_msg {
+                        print!(": {}", error_msg);
+                    }
+                    match diagnostic.severity {
+                        Severity::Error => println!(),
+                        Severity::Warning => println!(" (warning)"),
+                        Severity::Info => println!(" (info)"),
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{KeyEngMatchesConfig, LanguageTiers, PlaceholderSyntax};
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use crate::rules::key_and_eng_matches::KeyEngMatches;
+    use crate::rules::missing_translations::MissingTranslations;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_check_merges_in_registration_order() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([("Restarting".into(), Translations::default())]),
+        };
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+
+        let mut checker = Checker::new();
+        checker.register_rule(MissingTranslations::new(LanguageTiers::default()));
+        checker.register_rule(KeyEngMatches::new(
+            PlaceholderSyntax::default(),
+            KeyEngMatchesConfig::default(),
+            Default::default(),
+        ));
+        checker.check(&ctx, &mut Timings::new(), &ProgressBar::hidden());
+
+        assert_eq!(
+            checker.errors().map(|(rule, _)| rule).collect::<Vec<_>>(),
+            ["MissingTranslations", "KeyEngMatches"]
+        );
+    }
+
+    #[test]
+    fn test_rule_selection() {
+        let mut selection = RuleSelection::default();
+        assert!(selection.is_enabled("KeyEngMatches", true));
+        assert!(!selection.is_enabled("HardCodedStrings", false));
+
+        selection.disabled = vec!["KeyEngMatches".into()];
+        assert!(!selection.is_enabled("KeyEngMatches", true));
+        assert!(selection.is_enabled("MissingTranslations", true));
+
+        selection.enabled = vec!["MissingTranslations".into(), "HardCodedStrings".into()];
+        assert!(selection.is_enabled("MissingTranslations", true));
+        assert!(selection.is_enabled("HardCodedStrings", false));
+        assert!(!selection.is_enabled("TrailingNewline", true));
+    }
+
+    #[test]
+    fn test_with_selection() {
+        let mut checker = Checker::with_selection(RuleSelection {
+            disabled: vec!["KeyEngMatches".into()],
+            ..Default::default()
+        })
+        .unwrap();
+        checker.register_rule(MissingTranslations::new(LanguageTiers::default()));
+        checker.register_rule(KeyEngMatches::new(
+            PlaceholderSyntax::default(),
+            KeyEngMatchesConfig::default(),
+            Default::default(),
+        ));
+        assert_eq!(
+            checker.rule_names().collect::<Vec<_>>(),
+            ["MissingTranslations"]
+        );
+    }
+
+    #[test]
+    fn test_with_selection_unknown_rule() {
+        let res = Checker::with_selection(RuleSelection {
+            disabled: vec!["KeyEngMatch".into()],
+            ..Default::default()
+        });
+        assert!(matches!(
+            res,
+            Err(Error::Usage(message)) if message == "unknown rule 'KeyEngMatch' in `rules.disabled`"
+        ));
+    }
+
+    #[test]
+    fn test_check_overrides_severities() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([("Restarting".into(), Translations::default())]),
+        };
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+
+        let mut checker = Checker::with_selection(RuleSelection {
+            severities: BTreeMap::from([("KeyEngMatches".into(), Severity::Warning)]),
+            ..Default::default()
+        })
+        .unwrap();
+        checker.register_rule(KeyEngMatches::new(
+            PlaceholderSyntax::default(),
+            KeyEngMatchesConfig::default(),
+            Default::default(),
+        ));
+        checker.check(&ctx, &mut Timings::new(), &ProgressBar::hidden());
+
+        assert!(checker.errors().next().is_some());
+        assert!(checker
+            .errors()
+            .all(|(_, diagnostic)| diagnostic.severity == Severity::Warning));
+        assert!(!checker.has_error());
+    }
 
     #[test]
     fn test_n_errors_and_has_error() {
         let mut checker = Checker::new();
         assert_eq!(checker.n_errors(), 0);
         assert!(!checker.has_error());
-        checker.errors.insert("rule_name".to_string(), Vec::new());
+        checker.errors.insert("rule_name", Vec::new());
         assert_eq!(checker.n_errors(), 0);
         assert!(!checker.has_error());
 
-        checker
-            .errors
-            .insert("rule_name2".into(), vec![("locale_key".into(), None)]);
+        checker.errors.insert(
+            "rule_name2",
+            vec![Diagnostic::error("locale_key".into(), None)],
+        );
         assert_eq!(checker.n_errors(), 1);
         assert!(checker.has_error());
 
         let errors_of_rule_name = checker.errors.get_mut("rule_name").unwrap();
-        errors_of_rule_name.push(("locale_key".into(), None));
+        errors_of_rule_name.push(Diagnostic::error("locale_key".into(), None));
         assert_eq!(checker.n_errors(), 2);
         assert!(checker.has_error());
+
+        let errors_of_rule_name = checker.errors.get_mut("rule_name").unwrap();
+        errors_of_rule_name.push(Diagnostic {
+            location: None,
+            key: "locale_key".into(),
+            error_msg: None,
+            severity: Severity::Warning,
+            langs: Vec::new(),
+        });
+        assert_eq!(checker.n_errors(), 2);
     }
 }
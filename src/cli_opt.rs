@@ -1,133 +1,1749 @@
 //! This module defines this tool's CLI options.
 
-use clap::Parser;
-use std::{
-    borrow::Cow,
-    path::{Path, PathBuf},
-};
+use crate::checker::ReportLayout;
+use crate::config::{Config, ConfigFormat, ProjectConfig, CARGO_MANIFEST, DEFAULT_CONFIG_FILE};
+use crate::editor::OutputFormat;
+use crate::error::Error;
+use crate::export::ExportFormat;
+use crate::logging::LogFormat;
+use crate::notify::{self, WebhookFormat};
+use crate::project::{self, Project};
+use crate::rules::{Severity, RULES};
+use crate::source::{GitRev, Source, StdinBuffer};
+use crate::usage_map::UsageMapFormat;
+use clap::builder::{BoolishValueParser, PossibleValuesParser};
+use clap::error::ErrorKind;
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand};
+use std::path::{Path, PathBuf};
 
+/// Output of `--version`, with the build metadata embedded by `build.rs`, so
+/// that bug reports and CI logs identify the build that produced a report.
+const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "\ncommit: ",
+    env!("TOPGRADE_I18N_GIT_COMMIT"),
+    "\nbuild date: ",
+    env!("TOPGRADE_I18N_BUILD_DATE"),
+    "\nfeatures: ",
+    env!("TOPGRADE_I18N_FEATURES"),
+    "\nlocale formats: yaml",
+);
+
+/// Every option can also be set through an environment variable named
+/// `TOPGRADE_I18N_<OPTION>`, e.g., `TOPGRADE_I18N_LOCALE_FILE`, or
+/// `TOPGRADE_I18N_<SUBCOMMAND>_<OPTION>` for the options only a subcommand
+/// has, e.g., `TOPGRADE_I18N_TREND_LANG`, options specified on the command
+/// line take precedence. Options that take multiple values accept a
+/// comma-separated list. Of the config keys, only `languages.tiers.tier1`,
+/// `languages.tiers.tier2` and `languages.aliases` can be set through
+/// environment variables, `TOPGRADE_I18N_LANGUAGES_TIER1`,
+/// `TOPGRADE_I18N_LANGUAGES_TIER2` and `TOPGRADE_I18N_LANGUAGES_ALIASES`, the
+/// others through the config file or the options above.
 #[derive(Parser, Debug)]
+#[command(
+    version,
+    long_version = LONG_VERSION,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    group = ArgGroup::new("fixes").args(["fix", "fix_unused"]).multiple(true)
+)]
 pub(crate) struct Cli {
-    /// The path to the locale file
-    #[arg(long)]
-    locale_file: PathBuf,
-    /// Rust files to check.
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    project_args: ProjectArgs,
+    /// The path to the config file.
     ///
-    /// If any path points to a directory, then all the Rust files in that directory
-    /// will be checked.
-    #[arg(long, required = true)]
-    rust_src_to_check: Vec<PathBuf>,
+    /// If not specified, `i18n-checker.toml` under the current directory will
+    /// be used if it exists, otherwise the `[package.metadata.i18n-check]` or
+    /// `[workspace.metadata.i18n-check]` table of `Cargo.toml`, if any.
+    /// `Cargo.toml` can also be given here explicitly.
+    #[arg(long, global = true, env = "TOPGRADE_I18N_CONFIG")]
+    config: Option<PathBuf>,
+    /// Follow symlinks when walking the Rust source directories, same as
+    /// `scan.follow_symlinks` in the config file.
+    #[arg(
+        long,
+        global = true,
+        env = "TOPGRADE_I18N_FOLLOW_SYMLINKS",
+        value_parser = BoolishValueParser::new()
+    )]
+    follow_symlinks: bool,
+    /// Walk `target`, `node_modules`, `.git` and the vendor directories too,
+    /// same as `scan.default_ignores = false` in the config file.
+    #[arg(
+        long,
+        global = true,
+        env = "TOPGRADE_I18N_NO_DEFAULT_IGNORES",
+        value_parser = BoolishValueParser::new()
+    )]
+    no_default_ignores: bool,
+    /// Also collect the keys of the `t!()` invocations generated by macros
+    /// from the output of `cargo expand`, same as `scan.cargo_expand` in the
+    /// config file.
+    #[arg(
+        long,
+        global = true,
+        env = "TOPGRADE_I18N_CARGO_EXPAND",
+        value_parser = BoolishValueParser::new()
+    )]
+    cargo_expand: bool,
+    /// Also collect the keys of the `t!()` invocations in this file of code
+    /// already expanded by `cargo expand`, can be repeated, same as
+    /// `scan.expanded_files` in the config file.
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        env = "TOPGRADE_I18N_EXPANDED_FILE",
+        value_delimiter = ','
+    )]
+    expanded_file: Vec<PathBuf>,
+    /// Record every check in this SQLite database, and read the runs shown by
+    /// `trend` from it, same as `history.file` in the config file.
+    #[arg(long, global = true, env = "TOPGRADE_I18N_HISTORY_FILE")]
+    history_file: Option<PathBuf>,
+    /// Run only this rule, including an opt-in one, can be repeated, replaces
+    /// `rules.enabled` in the config file.
+    #[arg(
+        long,
+        global = true,
+        value_name = "RULE",
+        value_parser = PossibleValuesParser::new(RULES.map(|doc| doc.name)),
+        env = "TOPGRADE_I18N_ENABLE_RULE",
+        value_delimiter = ','
+    )]
+    enable_rule: Vec<String>,
+    /// Do not run this rule, can be repeated, added to `rules.disabled` in the
+    /// config file.
+    #[arg(
+        long,
+        global = true,
+        value_name = "RULE",
+        value_parser = PossibleValuesParser::new(RULES.map(|doc| doc.name)),
+        env = "TOPGRADE_I18N_DISABLE_RULE",
+        value_delimiter = ','
+    )]
+    disable_rule: Vec<String>,
+    /// Report every problem a rule finds with this severity, e.g.,
+    /// `KeyEngMatches=warning` so that it does not fail the check, can be
+    /// repeated, overrides `rules.severity` in the config file.
+    #[arg(
+        long,
+        global = true,
+        value_name = "RULE=SEVERITY",
+        value_parser = parse_rule_severity,
+        env = "TOPGRADE_I18N_RULE_SEVERITY",
+        value_delimiter = ','
+    )]
+    rule_severity: Vec<(String, Severity)>,
+    /// How the internal events, e.g., phases, parsed files and failures, are
+    /// logged to stderr, `json` emits them as JSON lines for CI systems.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t,
+        env = "TOPGRADE_I18N_LOG_FORMAT"
+    )]
+    log_format: LogFormat,
+    /// If this tool crashes, write a redacted bug report bundle, i.e., the
+    /// report, the backtrace and the Rust file being parsed with its strings
+    /// and comments redacted, to a new directory under this one.
+    #[arg(long, global = true, env = "TOPGRADE_I18N_BUG_REPORT_DIR")]
+    bug_report_dir: Option<PathBuf>,
+    /// Check the files in this git revision rather than the ones on the file
+    /// system, no worktree is needed.
+    ///
+    /// Paths are relative to the current directory, which should be in the git
+    /// repository.
+    #[arg(long, env = "TOPGRADE_I18N_GIT_REV")]
+    git_rev: Option<String>,
+    /// Read the contents of the Rust file at this path from stdin, e.g., the
+    /// unsaved buffer of an editor, and check only it: its keys and the rules
+    /// about the Rust files, against the locale file.
+    ///
+    /// Diagnostics are printed in `--format`, `quickfix` if it is `text`.
+    #[arg(
+        long,
+        value_name = "VIRTUAL_PATH",
+        conflicts_with_all = ["git_rev", "locale_only"],
+        env = "TOPGRADE_I18N_STDIN_SOURCE"
+    )]
+    stdin_source: Option<PathBuf>,
+    /// Print the time spent in every phase and the slowest files to parse.
+    #[arg(long, env = "TOPGRADE_I18N_TIMINGS", value_parser = BoolishValueParser::new())]
+    timings: bool,
+    /// How the report is organized.
+    #[arg(long, value_enum, default_value_t, env = "TOPGRADE_I18N_LAYOUT")]
+    layout: ReportLayout,
+    /// Write metrics (e.g., number of missing translations per language) to
+    /// this file in the Prometheus/OpenMetrics text format.
+    #[arg(long, env = "TOPGRADE_I18N_METRICS_FILE")]
+    metrics_file: Option<PathBuf>,
+    /// Write the diagnostics of every project, with their locations, to this
+    /// file as a JSON report, which `merge-reports` combines.
+    #[arg(long, env = "TOPGRADE_I18N_REPORT_FILE")]
+    report_file: Option<PathBuf>,
+    /// Suppress the diagnostics recorded in this file, so that only new ones
+    /// are reported and fail the check. If it does not exist, the current
+    /// diagnostics are recorded to it instead.
+    #[arg(long, value_name = "FILE", env = "TOPGRADE_I18N_BASELINE")]
+    baseline: Option<PathBuf>,
+    /// Write where every locale key is used to this file in JSON, in format
+    /// `{key: [{file, line, column, args}]}`.
+    #[arg(long, env = "TOPGRADE_I18N_EMIT_USAGE_MAP")]
+    emit_usage_map: Option<PathBuf>,
+    /// Write `i18n-keys.lock`, which lists every key of the locale file with
+    /// the placeholders of its English translation, to the project root, or
+    /// the current directory if there is none.
+    #[arg(long, conflicts_with = "check_lock", env = "TOPGRADE_I18N_WRITE_LOCK", value_parser = BoolishValueParser::new())]
+    write_lock: bool,
+    /// Fail if `i18n-keys.lock` is missing or outdated, i.e., a key was added
+    /// or removed, or its placeholders changed, without `--write-lock`.
+    #[arg(long, env = "TOPGRADE_I18N_CHECK_LOCK", value_parser = BoolishValueParser::new())]
+    check_lock: bool,
+    /// Fix what can be fixed in the locale file, keeping its order, comments
+    /// and formatting: append a stub entry, with the English translation
+    /// derived from the key, for every key used by `t!()` that does not exist,
+    /// and rewrite the English translations `KeyEngMatches` reports in `exact`
+    /// mode to the ones derived from the keys.
+    #[arg(long, conflicts_with = "git_rev", env = "TOPGRADE_I18N_FIX", value_parser = BoolishValueParser::new())]
+    fix: bool,
+    /// Remove the keys `UnusedLocaleKeys` reports, i.e., that no `t!()`
+    /// invocation uses, with their translations from the locale file. Nothing
+    /// is removed if any Rust file is skipped, as the keys it uses are unknown,
+    /// or if no `t!()` invocation is found, even with `--allow-empty`. Refused
+    /// if `UnusedLocaleKeys` is disabled, or not among the enabled rules.
+    #[arg(
+        long,
+        conflicts_with_all = ["git_rev", "locale_only", "stdin_source"],
+        env = "TOPGRADE_I18N_FIX_UNUSED",
+        value_parser = BoolishValueParser::new()
+    )]
+    fix_unused: bool,
+    /// With `--fix` or `--fix-unused`, print the changes to the locale file as
+    /// a unified diff instead of writing them.
+    #[arg(long, requires = "fixes", env = "TOPGRADE_I18N_DRY_RUN", value_parser = BoolishValueParser::new())]
+    dry_run: bool,
+    /// Print one diagnostic per line in a stable, tab-separated format that is
+    /// easy to parse in shell scripts, instead of the human-readable report.
+    ///
+    /// Fields: `<severity> <rule> <project> <languages> <file> <line> <column>
+    /// <key> <message>`.
+    #[arg(long, env = "TOPGRADE_I18N_PORCELAIN", value_parser = BoolishValueParser::new())]
+    porcelain: bool,
+    /// How diagnostics are printed, `quickfix` and `gcc` point every
+    /// diagnostic at a `file:line:col` for editors, `github` annotates pull
+    /// requests in GitHub Actions, `json`, `sarif` and `junit` print a single
+    /// document for scripts, code scanning and CI test reports.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        conflicts_with = "porcelain",
+        env = "TOPGRADE_I18N_FORMAT"
+    )]
+    format: OutputFormat,
+    /// Write the document of `--format json`, `sarif` or `junit` to this
+    /// file, replaced if it exists, instead of stdout.
+    #[arg(long, value_name = "FILE", env = "TOPGRADE_I18N_OUTPUT")]
+    output: Option<PathBuf>,
+    /// Do not fail when no Rust file is found or no `t!()` invocation is
+    /// collected, which usually means a mistyped path.
+    #[arg(long, env = "TOPGRADE_I18N_ALLOW_EMPTY", value_parser = BoolishValueParser::new())]
+    allow_empty: bool,
+    /// Print the effective configuration, i.e., the defaults merged with the
+    /// config file and the environment variables, then exit.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "toml",
+        env = "TOPGRADE_I18N_PRINT_CONFIG"
+    )]
+    print_config: Option<ConfigFormat>,
+    /// Post a summary of the run, i.e., the error counts, the coverage changes
+    /// since the last recorded run and a link to the report, to this webhook.
+    #[arg(long, value_name = "URL", env = "TOPGRADE_I18N_NOTIFY_WEBHOOK")]
+    notify_webhook: Option<String>,
+    /// Payload format of `--notify-webhook`.
+    #[arg(long, value_enum, default_value_t, env = "TOPGRADE_I18N_NOTIFY_FORMAT")]
+    notify_format: WebhookFormat,
+    /// Link to the report included in the notification, the GitHub Actions
+    /// run by default, if any.
+    #[arg(long, value_name = "URL", env = "TOPGRADE_I18N_REPORT_URL")]
+    report_url: Option<String>,
+    /// With `--porcelain`, terminate every field with NUL instead of separating
+    /// them with tabs and terminating records with newline, and do not escape
+    /// the fields.
+    #[arg(
+        short = 'z',
+        requires = "porcelain",
+        env = "TOPGRADE_I18N_NUL_TERMINATED", value_parser = BoolishValueParser::new()
+    )]
+    nul_terminated: bool,
+}
+
+/// Parses a `--rule-severity` value, `RULE=SEVERITY`.
+fn parse_rule_severity(value: &str) -> Result<(String, Severity), String> {
+    let Some((rule, severity)) = value.split_once('=') else {
+        return Err(format!("expect 'RULE=SEVERITY', found '{}'", value));
+    };
+    if !RULES.iter().any(|doc| doc.name == rule) {
+        return Err(format!("unknown rule '{}'", rule));
+    }
+    let severity = match severity {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        "info" => Severity::Info,
+        _ => {
+            return Err(format!(
+                "unknown severity '{}', expect 'error', 'warning' or 'info'",
+                severity
+            ))
+        }
+    };
+
+    Ok((rule.to_string(), severity))
 }
 
 impl Cli {
-    /// Accesses the `--locale-file` option.
-    pub(crate) fn locale_file(&self) -> &Path {
-        &self.locale_file
+    /// Accesses the subcommand.
+    pub(crate) fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
     }
 
-    /// Flattens the input paths and returns it.
+    /// Returns the projects to check.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+
+    /// Returns the config file to load: the one given by `--config`, otherwise
+    /// [`DEFAULT_CONFIG_FILE`] or `Cargo.toml` under the current directory,
+    /// whichever exists first, `None` if there is neither.
+    pub(crate) fn config_file(&self) -> Option<&Path> {
+        if let Some(path) = &self.config {
+            return Some(path);
+        }
+
+        [DEFAULT_CONFIG_FILE, CARGO_MANIFEST]
+            .into_iter()
+            .map(Path::new)
+            .find(|path| path.exists())
+    }
+
+    /// Loads the config file, see [`Cli::config_file()`], the default config
+    /// is used if there is none, or if it is a Cargo manifest without the
+    /// config.
     ///
-    /// For directories, it will walk through the directory and get all the Rust
-    /// files.
+    /// `TOPGRADE_I18N_LANGUAGES_*` environment variables override the values in
+    /// the config file, see [`Config::with_env_overrides()`].
     ///
-    /// Symlink will be silently ignored.
-    pub(crate) fn rust_src_to_check(&self) -> Vec<Cow<Path>> {
-        let mut rust_files_to_check = Vec::with_capacity(self.rust_src_to_check.len());
+    /// Fails if the config file or an environment variable is invalid.
+    pub(crate) fn config(&self) -> Result<Config, Error> {
+        let mut config = match self.config_file() {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+        if self.follow_symlinks {
+            config.scan.follow_symlinks = true;
+        }
+        if self.no_default_ignores {
+            config.scan.default_ignores = false;
+        }
+        if self.cargo_expand {
+            config.scan.cargo_expand = true;
+        }
+        config
+            .scan
+            .expanded_files
+            .extend(self.expanded_file.iter().cloned());
+        if let Some(history_file) = &self.history_file {
+            config.history.file = Some(history_file.clone());
+        }
+        if !self.enable_rule.is_empty() {
+            config.rules.enabled = self.enable_rule.clone();
+            config
+                .rules
+                .disabled
+                .retain(|name| !self.enable_rule.contains(name));
+        }
+        config
+            .rules
+            .disabled
+            .extend(self.disable_rule.iter().cloned());
+        config
+            .rules
+            .severity
+            .extend(self.rule_severity.iter().cloned());
 
-        for entry_path in self.rust_src_to_check.iter() {
-            let entry_metadata = std::fs::symlink_metadata(entry_path).unwrap_or_else(|e| {
-                panic!(
-                    "Error: cannot get the metadata of the specified file {} due to error {:?}",
-                    entry_path.display(),
-                    e
-                )
-            });
+        config.with_env_overrides(std::env::vars())
+    }
 
-            if entry_metadata.is_file() {
-                if is_rust_file(entry_path) {
-                    rust_files_to_check.push(Cow::Borrowed(entry_path.as_path()));
-                }
-            } else if entry_metadata.is_dir() {
-                let walk_dir_iter = walkdir::WalkDir::new(entry_path);
-                for res_entry in walk_dir_iter {
-                    let entry = res_entry.unwrap_or_else(|e| {
-                        panic!(
-                            "Error: cannot get the entry of the specified file due to error {:?}",
-                            e
-                        )
-                    });
-
-                    let entry_path = entry.path();
-                    let entry_metadata = entry.metadata().unwrap_or_else(|e| {
-                        panic!(
-                            "Error: cannot get the metadata of the specified file {} due to error {:?}",
-                            entry_path.display(),
-                            e
-                        )
-                    });
-
-                    if entry_metadata.is_file() && is_rust_file(entry_path) {
-                        rust_files_to_check.push(Cow::Owned(entry_path.to_path_buf()));
-                    }
-                }
+    /// Returns where the files to check should be read from, stdin is read
+    /// to the end with `--stdin-source`.
+    pub(crate) fn source(&self) -> Result<Source, Error> {
+        if let Some(path) = &self.stdin_source {
+            return Ok(Source::Stdin(StdinBuffer::read(path.clone())?));
+        }
+        Ok(match &self.git_rev {
+            Some(rev) => Source::GitRev(GitRev::new(rev.clone(), PathBuf::from("."))),
+            None => Source::WorkTree,
+        })
+    }
+
+    /// Accesses the `--stdin-source` option.
+    pub(crate) fn stdin_source(&self) -> Option<&Path> {
+        self.stdin_source.as_deref()
+    }
+
+    /// Accesses the `--timings` option.
+    pub(crate) fn timings(&self) -> bool {
+        self.timings
+    }
+
+    /// Accesses the `--log-format` option.
+    pub(crate) fn log_format(&self) -> LogFormat {
+        self.log_format
+    }
+
+    /// Accesses the `--bug-report-dir` option.
+    pub(crate) fn bug_report_dir(&self) -> Option<&Path> {
+        self.bug_report_dir.as_deref()
+    }
+
+    /// Returns the name of the subcommand, `check` if there is none.
+    pub(crate) fn command_name(&self) -> &'static str {
+        match self.command() {
+            None => "check",
+            Some(Command::Compare(_)) => "compare",
+            Some(Command::ListKeys(_)) => "list-keys",
+            Some(Command::ExplainKey(_)) => "explain-key",
+            Some(Command::Repl(_)) => "repl",
+            Some(Command::Export(_)) => "export",
+            Some(Command::Trend(_)) => "trend",
+            Some(Command::MergeReports(_)) => "merge-reports",
+            Some(Command::Report(_)) => "report",
+            Some(Command::Todo(_)) => "todo",
+            Some(Command::Codegen(_)) => "codegen",
+            Some(Command::Docs(_)) => "docs",
+            Some(Command::Rules) => "rules",
+            Some(Command::Doctor(_)) => "doctor",
+            Some(Command::Init(_)) => "init",
+        }
+    }
+
+    /// Accesses the `--layout` option.
+    pub(crate) fn layout(&self) -> ReportLayout {
+        self.layout
+    }
+
+    /// Accesses the `--metrics-file` option.
+    pub(crate) fn metrics_file(&self) -> Option<&Path> {
+        self.metrics_file.as_deref()
+    }
+
+    /// Accesses the `--baseline` option.
+    pub(crate) fn baseline(&self) -> Option<&Path> {
+        self.baseline.as_deref()
+    }
+
+    /// Accesses the `--report-file` option.
+    pub(crate) fn report_file(&self) -> Option<&Path> {
+        self.report_file.as_deref()
+    }
+
+    /// Accesses the `--emit-usage-map` option.
+    pub(crate) fn emit_usage_map(&self) -> Option<&Path> {
+        self.emit_usage_map.as_deref()
+    }
+
+    /// Accesses the `--write-lock` option.
+    pub(crate) fn write_lock(&self) -> bool {
+        self.write_lock
+    }
+
+    /// Accesses the `--check-lock` option.
+    pub(crate) fn check_lock(&self) -> bool {
+        self.check_lock
+    }
+
+    /// Accesses the `--fix` option.
+    pub(crate) fn fix(&self) -> bool {
+        self.fix
+    }
+
+    /// Accesses the `--fix-unused` option.
+    pub(crate) fn fix_unused(&self) -> bool {
+        self.fix_unused
+    }
+
+    /// Accesses the `--dry-run` option.
+    pub(crate) fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Accesses the `--porcelain` option.
+    pub(crate) fn porcelain(&self) -> bool {
+        self.porcelain
+    }
+
+    /// Accesses the `--format` option, `quickfix` instead of `text` with
+    /// `--stdin-source`, which is meant for editors.
+    pub(crate) fn format(&self) -> OutputFormat {
+        match self.format {
+            OutputFormat::Text if self.stdin_source.is_some() && !self.porcelain => {
+                OutputFormat::Quickfix
             }
+            format => format,
+        }
+    }
+
+    /// Accesses the `--output` option.
+    pub(crate) fn output(&self) -> Option<&Path> {
+        self.output.as_deref()
+    }
+
+    /// Returns true if the output is meant to be parsed by programs, in which
+    /// case nothing but the diagnostics is printed to stdout.
+    pub(crate) fn machine_readable(&self) -> bool {
+        self.porcelain || self.format() != OutputFormat::Text
+    }
+
+    /// Accesses the `--allow-empty` option, implied by `--stdin-source`, as a
+    /// buffer without any `t!()` invocation is normal.
+    pub(crate) fn allow_empty(&self) -> bool {
+        self.allow_empty || self.stdin_source.is_some()
+    }
+
+    /// Accesses the `--print-config` option.
+    pub(crate) fn print_config(&self) -> Option<ConfigFormat> {
+        self.print_config
+    }
+
+    /// Accesses the `--notify-webhook` option.
+    pub(crate) fn notify_webhook(&self) -> Option<&str> {
+        self.notify_webhook.as_deref()
+    }
+
+    /// Accesses the `--notify-format` option.
+    pub(crate) fn notify_format(&self) -> WebhookFormat {
+        self.notify_format
+    }
+
+    /// Returns the link to the report, `--report-url`, or the GitHub Actions
+    /// run, see [`notify::ci_run_url()`].
+    pub(crate) fn report_url(&self) -> Option<String> {
+        self.report_url
+            .clone()
+            .or_else(|| notify::ci_run_url(std::env::vars()))
+    }
+
+    /// Accesses the `-z` option.
+    pub(crate) fn nul_terminated(&self) -> bool {
+        self.nul_terminated
+    }
+}
+
+/// Subcommands.
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Compare the check results of two git revisions.
+    ///
+    /// Reports the newly introduced errors, the fixed errors and the changes of
+    /// translation coverage of every language.
+    Compare(CompareArgs),
+    /// List every key used by `t!()` along with its locations, the locale file
+    /// is not needed.
+    ListKeys(ListKeysArgs),
+    /// Print everything known about a key: where it is defined, its
+    /// translations, the derived English text, its call sites, and the result
+    /// of every rule.
+    ExplainKey(ExplainKeyArgs),
+    /// Check the project once, then answer queries read from stdin, e.g.,
+    /// `key "Restart*"`, `unused`, `file src/steps/git.rs` or `coverage de`.
+    ///
+    /// Type `help` in the REPL for the available queries.
+    Repl(ReplArgs),
+    /// Write the keys, translations, call sites and diagnostics of every
+    /// project to a file, e.g., a SQLite database to query with SQL.
+    Export(ExportArgs),
+    /// Render reports for translators, e.g., with `--issues`, one Markdown
+    /// issue body per language with its missing and incorrect translations.
+    Report(ReportArgs),
+    /// Write `<lang>.md` files listing the untranslated keys of every
+    /// language with their English texts and where they are used, meant to be
+    /// checked in and regenerated on each run.
+    Todo(TodoArgs),
+    /// Generate Rust code from the locale file and the `t!()` invocations,
+    /// e.g., with `--tests`, a test module that `cargo test` runs in Topgrade.
+    Codegen(CodegenArgs),
+    /// Print how the translation coverage and the errors of a language evolve
+    /// across the runs recorded in the history file.
+    Trend(TrendArgs),
+    /// Combine the JSON reports written by `--report-file`, e.g., by sharded
+    /// CI jobs, deduplicating identical diagnostics, and print the merged
+    /// result.
+    ///
+    /// Fails if the merged result has any error.
+    MergeReports(MergeReportsArgs),
+    /// Render the documentation of every rule, i.e., its code, description,
+    /// rationale, options and examples, to Markdown.
+    ///
+    /// Printed as a single page unless `--out-dir` is given.
+    Docs(DocsArgs),
+    /// List every rule with its code, whether it runs by default and with the
+    /// current config and flags, and a short description.
+    ///
+    /// The names are the values `--enable-rule` and `--disable-rule` accept.
+    Rules,
+    /// Validate the setup: the config file, the locale file, the Rust files
+    /// and git, with a suggestion for every problem found.
+    Doctor(DoctorArgs),
+    /// Write a starter `i18n-checker.toml` to the current directory,
+    /// with every option commented out with its default value.
+    Init(InitArgs),
+}
+
+/// Options of the `compare` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct CompareArgs {
+    /// The old revision.
+    #[arg(long, env = "TOPGRADE_I18N_COMPARE_FROM")]
+    from: String,
+    /// The new revision.
+    #[arg(long, env = "TOPGRADE_I18N_COMPARE_TO")]
+    to: String,
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl CompareArgs {
+    /// Returns the old revision.
+    pub(crate) fn from(&self) -> Source {
+        Source::GitRev(GitRev::new(self.from.clone(), PathBuf::from(".")))
+    }
+
+    /// Returns the new revision.
+    pub(crate) fn to(&self) -> Source {
+        Source::GitRev(GitRev::new(self.to.clone(), PathBuf::from(".")))
+    }
+
+    /// Returns the projects to compare.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+}
+
+/// Options of the `explain-key` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ExplainKeyArgs {
+    /// The key to explain.
+    key: String,
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl ExplainKeyArgs {
+    /// Accesses the key to explain.
+    pub(crate) fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the projects where the key is explained.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+}
+
+/// Options of the `repl` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ReplArgs {
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl ReplArgs {
+    /// Returns the projects to query, one after another.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+}
+
+/// Options of the `export` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ExportArgs {
+    /// Format of the exported file.
+    #[arg(long, value_enum, env = "TOPGRADE_I18N_EXPORT_FORMAT")]
+    format: ExportFormat,
+    /// The file to write, replaced if it exists.
+    #[arg(long, short, env = "TOPGRADE_I18N_EXPORT_OUTPUT")]
+    output: PathBuf,
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl ExportArgs {
+    /// Accesses the `--format` option.
+    pub(crate) fn format(&self) -> ExportFormat {
+        self.format
+    }
+
+    /// Accesses the `--output` option.
+    pub(crate) fn output(&self) -> &Path {
+        &self.output
+    }
+
+    /// Returns the projects to export.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+}
+
+/// Options of the `report` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ReportArgs {
+    /// Render one Markdown issue body per language, with its missing and
+    /// incorrect keys, their English texts and where they are used.
+    #[arg(
+        long,
+        required = true,
+        env = "TOPGRADE_I18N_REPORT_ISSUES",
+        value_parser = BoolishValueParser::new()
+    )]
+    issues: bool,
+    /// Write every issue body to `<lang>.md` in this directory instead of
+    /// printing them, in a subdirectory per project if there are multiple
+    /// projects.
+    #[arg(long, env = "TOPGRADE_I18N_REPORT_OUT_DIR")]
+    out_dir: Option<PathBuf>,
+    /// URL that file paths are appended to for links, e.g.,
+    /// `https://github.com/topgrade-rs/topgrade/blob/main/`.
+    #[arg(long, value_name = "URL", env = "TOPGRADE_I18N_REPORT_LINK_BASE")]
+    link_base: Option<String>,
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl ReportArgs {
+    /// Accesses the `--out-dir` option.
+    pub(crate) fn out_dir(&self) -> Option<&Path> {
+        self.out_dir.as_deref()
+    }
+
+    /// Accesses the `--link-base` option.
+    pub(crate) fn link_base(&self) -> Option<&str> {
+        self.link_base.as_deref()
+    }
+
+    /// Returns the projects to report.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+}
+
+/// Options of the `todo` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct TodoArgs {
+    /// Directory the TODO files are written to, in a subdirectory per project
+    /// if there are multiple projects.
+    #[arg(long, default_value = "i18n-todo", env = "TOPGRADE_I18N_TODO_OUT_DIR")]
+    out_dir: PathBuf,
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl TodoArgs {
+    /// Accesses the `--out-dir` option.
+    pub(crate) fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    /// Returns the projects to write the TODO files of.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+}
+
+/// Options of the `codegen` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct CodegenArgs {
+    /// Generate a module of `#[test]`s asserting that every key passed to
+    /// `t!()` exists in the locale file, and that every required language,
+    /// i.e., tier-1 one, is present.
+    #[arg(
+        long,
+        required = true,
+        env = "TOPGRADE_I18N_CODEGEN_TESTS",
+        value_parser = BoolishValueParser::new()
+    )]
+    tests: bool,
+    /// The file to write, replaced if it exists, printed if not specified.
+    #[arg(long, short, env = "TOPGRADE_I18N_CODEGEN_OUTPUT")]
+    output: Option<PathBuf>,
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl CodegenArgs {
+    /// Accesses the `--output` option.
+    pub(crate) fn output(&self) -> Option<&Path> {
+        self.output.as_deref()
+    }
+
+    /// Returns the projects to generate code for.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+
+    /// Accesses the `--locale-only` option.
+    pub(crate) fn locale_only(&self) -> bool {
+        self.project_args.locale_only
+    }
+}
+
+/// Options of the `trend` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct TrendArgs {
+    /// The language to show.
+    #[arg(long, env = "TOPGRADE_I18N_TREND_LANG")]
+    lang: String,
+    /// How many of the most recent runs to show.
+    #[arg(long, default_value_t = 30, env = "TOPGRADE_I18N_TREND_LAST")]
+    last: usize,
+}
+
+impl TrendArgs {
+    /// Accesses the `--lang` option.
+    pub(crate) fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Accesses the `--last` option.
+    pub(crate) fn last(&self) -> usize {
+        self.last
+    }
+}
+
+/// Options of the `merge-reports` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct MergeReportsArgs {
+    /// The reports to merge.
+    #[arg(
+        required = true,
+        env = "TOPGRADE_I18N_MERGE_REPORTS_REPORTS",
+        value_delimiter = ','
+    )]
+    reports: Vec<PathBuf>,
+    /// Write the merged report to this file, replaced if it exists.
+    #[arg(long, short, env = "TOPGRADE_I18N_MERGE_REPORTS_OUTPUT")]
+    output: Option<PathBuf>,
+    /// How the report is organized.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        env = "TOPGRADE_I18N_MERGE_REPORTS_LAYOUT"
+    )]
+    layout: ReportLayout,
+    /// How diagnostics are printed, `quickfix` and `gcc` point every
+    /// diagnostic at a `file:line:col` for editors, `github` annotates pull
+    /// requests in GitHub Actions, `json`, `sarif` and `junit` print a single
+    /// document for scripts, code scanning and CI test reports.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        env = "TOPGRADE_I18N_MERGE_REPORTS_FORMAT"
+    )]
+    format: OutputFormat,
+}
+
+impl MergeReportsArgs {
+    /// Accesses the reports to merge.
+    pub(crate) fn reports(&self) -> &[PathBuf] {
+        &self.reports
+    }
+
+    /// Accesses the `--output` option.
+    pub(crate) fn output(&self) -> Option<&Path> {
+        self.output.as_deref()
+    }
+
+    /// Accesses the `--layout` option.
+    pub(crate) fn layout(&self) -> ReportLayout {
+        self.layout
+    }
+
+    /// Accesses the `--format` option.
+    pub(crate) fn format(&self) -> OutputFormat {
+        self.format
+    }
+}
+
+/// Options of the `docs` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct DocsArgs {
+    /// Write one file per rule, named after its code, and an `index.md` to
+    /// this directory instead.
+    #[arg(long, env = "TOPGRADE_I18N_DOCS_OUT_DIR")]
+    out_dir: Option<PathBuf>,
+}
+
+impl DocsArgs {
+    /// Accesses the `--out-dir` option.
+    pub(crate) fn out_dir(&self) -> Option<&Path> {
+        self.out_dir.as_deref()
+    }
+}
+
+/// Options of the `doctor` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct DoctorArgs {
+    #[command(flatten)]
+    project_args: ProjectArgs,
+}
+
+impl DoctorArgs {
+    /// Returns the projects to validate.
+    pub(crate) fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        self.project_args.projects(config)
+    }
+}
+
+/// Options of the `init` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct InitArgs {
+    /// Overwrite the config file if it exists.
+    #[arg(long, env = "TOPGRADE_I18N_INIT_FORCE", value_parser = BoolishValueParser::new())]
+    force: bool,
+    /// Also print snippets that run the check as a pre-commit hook.
+    #[arg(
+        long,
+        env = "TOPGRADE_I18N_INIT_PRE_COMMIT",
+        value_parser = BoolishValueParser::new()
+    )]
+    pre_commit: bool,
+}
+
+impl InitArgs {
+    /// Accesses the `--force` option.
+    pub(crate) fn force(&self) -> bool {
+        self.force
+    }
+
+    /// Accesses the `--pre-commit` option.
+    pub(crate) fn pre_commit(&self) -> bool {
+        self.pre_commit
+    }
+}
+
+/// Options of the `list-keys` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ListKeysArgs {
+    /// Rust files to scan.
+    ///
+    /// If any path points to a directory, then all the Rust files in that directory
+    /// will be scanned.
+    #[arg(
+        long,
+        required = true,
+        env = "TOPGRADE_I18N_RUST_SRC_TO_CHECK",
+        value_delimiter = ','
+    )]
+    rust_src_to_check: Vec<PathBuf>,
+    /// Scan the files in this git revision rather than the ones on the file
+    /// system.
+    #[arg(long, env = "TOPGRADE_I18N_GIT_REV")]
+    git_rev: Option<String>,
+    /// Output format.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        env = "TOPGRADE_I18N_LIST_KEYS_FORMAT"
+    )]
+    format: UsageMapFormat,
+}
+
+impl ListKeysArgs {
+    /// Accesses the `--rust-src-to-check` option.
+    pub(crate) fn rust_src_to_check(&self) -> &[PathBuf] {
+        &self.rust_src_to_check
+    }
+
+    /// Returns where the files to scan should be read from.
+    pub(crate) fn source(&self) -> Source {
+        match &self.git_rev {
+            Some(rev) => Source::GitRev(GitRev::new(rev.clone(), PathBuf::from("."))),
+            None => Source::WorkTree,
         }
+    }
 
-        rust_files_to_check
+    /// Accesses the `--format` option.
+    pub(crate) fn format(&self) -> UsageMapFormat {
+        self.format
     }
 }
 
-/// Returns if the given path points to a Rust file by checking its file extension.
-fn is_rust_file<P: AsRef<Path> + ?Sized>(file_path: &P) -> bool {
-    const RUST_FILE_EXTENSION: &str = "rs";
+/// Options that specify the projects to check.
+#[derive(Args, Debug)]
+pub(crate) struct ProjectArgs {
+    /// The path to the locale file, same as `--locale-file`.
+    #[arg(value_name = "LOCALE_FILE", conflicts_with_all = ["locale_file", "project"])]
+    locale_file_arg: Option<PathBuf>,
+    /// Rust files to check, added to `--rust-src-to-check`.
+    #[arg(value_name = "RUST_SRC", conflicts_with = "project")]
+    rust_src_args: Vec<PathBuf>,
+    /// The path to the locale file.
+    ///
+    /// If not specified, `locales/app.yml`, or the single YAML file under
+    /// `locales`, will be used.
+    #[arg(long, env = "TOPGRADE_I18N_LOCALE_FILE")]
+    locale_file: Option<PathBuf>,
+    /// Rust files to check.
+    ///
+    /// If any path points to a directory, then all the Rust files in that directory
+    /// will be checked. If not specified, `src` will be checked.
+    #[arg(long, env = "TOPGRADE_I18N_RUST_SRC_TO_CHECK", value_delimiter = ',')]
+    rust_src_to_check: Vec<PathBuf>,
+    /// Root directories of the projects to check, can be specified multiple
+    /// times.
+    ///
+    /// A project's locale file and Rust files are expected to be
+    /// `<project>/locales/app.yml` and `<project>/src`.
+    #[arg(
+        long,
+        conflicts_with_all = ["locale_file", "rust_src_to_check"],
+        env = "TOPGRADE_I18N_PROJECT",
+        value_delimiter = ','
+    )]
+    project: Vec<PathBuf>,
+    /// Only check the locale file, Rust files won't be scanned, and rules that
+    /// need them won't be applied.
+    #[arg(
+        long,
+        env = "TOPGRADE_I18N_LOCALE_ONLY",
+        value_parser = BoolishValueParser::new()
+    )]
+    locale_only: bool,
+}
 
-    if let Some(extension) = file_path.as_ref().extension() {
-        if extension == RUST_FILE_EXTENSION {
-            return true;
+impl ProjectArgs {
+    /// Returns the projects to check.
+    ///
+    /// If `--project` is not specified, the project specified by `--locale-file`
+    /// and `--rust-src-to-check`, or the positional arguments, will be
+    /// returned. The paths not specified are taken from the `[project]` section
+    /// of the config, `config`, otherwise detected in the current directory,
+    /// see [`project::detect_locale_file()`] and [`project::detect_rust_src()`],
+    /// and printed to stderr.
+    fn projects(&self, config: &ProjectConfig) -> Vec<Project> {
+        if self.project.is_empty() {
+            let locale_file = match self
+                .locale_file
+                .as_ref()
+                .or(self.locale_file_arg.as_ref())
+                .or(config.locale_file.as_ref())
+            {
+                Some(locale_file) => locale_file.clone(),
+                None => {
+                    let locale_file =
+                        project::detect_locale_file(Path::new("")).unwrap_or_else(|| {
+                            missing_argument(
+                                "cannot find the locale file, specify it with `--locale-file`",
+                            )
+                        });
+                    eprintln!("Auto-detected locale file: {}", locale_file.display());
+                    locale_file
+                }
+            };
+            let mut rust_src_to_check = self
+                .rust_src_to_check
+                .iter()
+                .chain(self.rust_src_args.iter())
+                .cloned()
+                .collect::<Vec<_>>();
+            if rust_src_to_check.is_empty() {
+                rust_src_to_check.extend(config.rust_src.iter().cloned());
+            }
+            if rust_src_to_check.is_empty() && !self.locale_only {
+                let rust_src = project::detect_rust_src(Path::new("")).unwrap_or_else(|| {
+                    missing_argument(
+                        "cannot find the Rust files, specify them with `--rust-src-to-check`",
+                    )
+                });
+                eprintln!("Auto-detected Rust files: {}", rust_src.display());
+                rust_src_to_check.push(rust_src);
+            }
+            vec![Project::new(locale_file, rust_src_to_check)]
+        } else {
+            self.project
+                .iter()
+                .map(|root| Project::from_root(root.clone()))
+                .collect()
         }
     }
+}
 
-    false
+/// Exits with a clap error saying that a required argument is missing and
+/// `msg`.
+fn missing_argument(msg: &str) -> ! {
+    <Cli as CommandFactory>::command()
+        .error(ErrorKind::MissingRequiredArgument, msg)
+        .exit()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::tempdir;
-
-    #[test]
-    fn test_cli_rust_src_to_check() {
-        let root_tempdir = tempdir().unwrap();
-        let root_tempdir_path = root_tempdir.path();
-
-        let file_foo = root_tempdir_path.join("foo");
-        std::fs::File::create(&file_foo).unwrap();
-        let file_bar_rs = root_tempdir_path.join("bar.rs");
-        std::fs::File::create(&file_bar_rs).unwrap();
-        let dir_baz = root_tempdir_path.join("baz");
-        std::fs::create_dir(&dir_baz).unwrap();
-        let file_qux_rs_under_dir_baz = dir_baz.join("qux.rs");
-        std::fs::File::create(&file_qux_rs_under_dir_baz).unwrap();
-
-        let cli = Cli {
-            // This field won't be used so let's give it a NULL value
-            locale_file: PathBuf::new(),
-            rust_src_to_check: vec![file_foo.clone(), file_bar_rs.clone(), dir_baz.clone()],
+
+    #[test]
+    fn test_cli_projects() {
+        let cli = Cli::parse_from([
+            "checker",
+            "--locale-file",
+            "app.yml",
+            "--rust-src-to-check",
+            "src",
+        ]);
+        assert_eq!(
+            cli.projects(&ProjectConfig::default()),
+            [Project::new("app.yml".into(), vec!["src".into()])]
+        );
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--project", "bar"]);
+        assert_eq!(
+            cli.projects(&ProjectConfig::default()),
+            [
+                Project::from_root("foo".into()),
+                Project::from_root("bar".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cli_positional_paths() {
+        let cli = Cli::parse_from(["checker", "locales/app.yml", "src", "build.rs"]);
+        assert_eq!(
+            cli.projects(&ProjectConfig::default()),
+            [Project::new(
+                "locales/app.yml".into(),
+                vec!["src".into(), "build.rs".into()]
+            )]
+        );
+
+        let cli = Cli::parse_from(["checker", "app.yml", "--locale-only"]);
+        assert_eq!(
+            cli.projects(&ProjectConfig::default()),
+            [Project::new("app.yml".into(), Vec::new())]
+        );
+
+        // Tests run in the crate root, which has `src`
+        let cli = Cli::parse_from(["checker", "app.yml"]);
+        assert_eq!(
+            cli.projects(&ProjectConfig::default()),
+            [Project::new("app.yml".into(), vec!["src".into()])]
+        );
+        let res = Cli::try_parse_from(["checker", "app.yml", "src", "--project", "foo"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_cli_projects_from_config() {
+        let config = ProjectConfig {
+            locale_file: Some("i18n/app.yml".into()),
+            rust_src: vec!["src".into(), "xtask".into()],
+        };
+
+        let cli = Cli::parse_from(["checker"]);
+        assert_eq!(
+            cli.projects(&config),
+            [Project::new(
+                "i18n/app.yml".into(),
+                vec!["src".into(), "xtask".into()]
+            )]
+        );
+
+        // The command line takes precedence
+        let cli = Cli::parse_from(["checker", "app.yml", "build.rs"]);
+        assert_eq!(
+            cli.projects(&config),
+            [Project::new("app.yml".into(), vec!["build.rs".into()])]
+        );
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert_eq!(cli.projects(&config), [Project::from_root("foo".into())]);
+    }
+
+    #[test]
+    fn test_cli_project_conflicts_with_locale_file() {
+        let res = Cli::try_parse_from(["checker", "--project", "foo", "--locale-file", "app.yml"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_cli_locale_only() {
+        let cli = Cli::parse_from(["checker", "--locale-file", "app.yml", "--locale-only"]);
+        assert!(cli.locale_only());
+        assert_eq!(
+            cli.projects(&ProjectConfig::default()),
+            [Project::new("app.yml".into(), Vec::new())]
+        );
+    }
+
+    #[test]
+    fn test_cli_z_requires_porcelain() {
+        let res = Cli::try_parse_from(["checker", "--project", "foo", "-z"]);
+        assert!(res.is_err());
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--porcelain", "-z"]);
+        assert!(cli.porcelain());
+        assert!(cli.nul_terminated());
+    }
+
+    #[test]
+    fn test_cli_format() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert_eq!(cli.format(), OutputFormat::Text);
+        assert!(!cli.machine_readable());
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--format", "quickfix"]);
+        assert_eq!(cli.format(), OutputFormat::Quickfix);
+        assert!(cli.machine_readable());
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--format", "json"]);
+        assert_eq!(cli.format(), OutputFormat::Json);
+        assert!(cli.machine_readable());
+
+        let cli = Cli::parse_from([
+            "checker",
+            "--project",
+            "foo",
+            "--format",
+            "junit",
+            "--output",
+            "report.xml",
+        ]);
+        assert_eq!(cli.format(), OutputFormat::Junit);
+        assert_eq!(cli.output(), Some(Path::new("report.xml")));
+
+        let result = Cli::try_parse_from([
+            "checker",
+            "--project",
+            "foo",
+            "--porcelain",
+            "--format",
+            "quickfix",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_allow_empty() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert!(!cli.allow_empty());
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--allow-empty"]);
+        assert!(cli.allow_empty());
+    }
+
+    #[test]
+    fn test_cli_stdin_source() {
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--stdin-source", "src/a.rs"]);
+        assert_eq!(cli.stdin_source(), Some(Path::new("src/a.rs")));
+        assert_eq!(cli.format(), OutputFormat::Quickfix);
+        assert!(cli.machine_readable());
+        assert!(cli.allow_empty());
+
+        let cli = Cli::parse_from(["checker", "--stdin-source", "src/a.rs", "--format", "gcc"]);
+        assert_eq!(cli.format(), OutputFormat::Gcc);
+
+        let cli = Cli::parse_from(["checker", "--stdin-source", "src/a.rs", "--porcelain"]);
+        assert_eq!(cli.format(), OutputFormat::Text);
+        assert!(cli.porcelain());
+
+        for conflicting in ["--locale-only", "--git-rev=HEAD"] {
+            let result =
+                Cli::try_parse_from(["checker", "--stdin-source", "src/a.rs", conflicting]);
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_cli_lock() {
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--check-lock"]);
+        assert!(cli.check_lock());
+        assert!(!cli.write_lock());
+
+        let result = Cli::try_parse_from(["checker", "--check-lock", "--write-lock"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_merge_reports() {
+        let cli = Cli::parse_from([
+            "checker",
+            "merge-reports",
+            "a.json",
+            "b.json",
+            "-o",
+            "merged.json",
+            "--format",
+            "gcc",
+        ]);
+        let Some(Command::MergeReports(args)) = cli.command() else {
+            panic!("merge-reports subcommand should be parsed");
+        };
+        assert_eq!(
+            args.reports(),
+            [PathBuf::from("a.json"), PathBuf::from("b.json")]
+        );
+        assert_eq!(args.output(), Some(Path::new("merged.json")));
+        assert_eq!(args.format(), OutputFormat::Gcc);
+        assert_eq!(args.layout(), ReportLayout::ByRule);
+
+        assert!(Cli::try_parse_from(["checker", "merge-reports"]).is_err());
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--report-file", "a.json"]);
+        assert_eq!(cli.report_file(), Some(Path::new("a.json")));
+    }
+
+    #[test]
+    fn test_cli_baseline() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert_eq!(cli.baseline(), None);
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--baseline", "baseline.json"]);
+        assert_eq!(cli.baseline(), Some(Path::new("baseline.json")));
+    }
+
+    #[test]
+    fn test_cli_fix() {
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--fix"]);
+        assert!(cli.fix());
+
+        let result = Cli::try_parse_from(["checker", "--fix", "--git-rev", "HEAD"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_fix_unused() {
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--fix-unused"]);
+        assert!(cli.fix_unused());
+        assert!(!cli.dry_run());
+
+        let cli = Cli::parse_from(["checker", "--fix", "--fix-unused", "--dry-run"]);
+        assert!(cli.fix());
+        assert!(cli.fix_unused());
+        assert!(cli.dry_run());
+
+        for args in [
+            &["checker", "--fix-unused", "--locale-only"][..],
+            &["checker", "--fix-unused", "--stdin-source", "src/main.rs"],
+            &["checker", "--project", "foo", "--dry-run"],
+        ] {
+            assert!(Cli::try_parse_from(args).is_err());
+        }
+    }
+
+    #[test]
+    fn test_cli_log_format() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert_eq!(cli.log_format(), LogFormat::Text);
+
+        let cli = Cli::parse_from([
+            "checker",
+            "list-keys",
+            "--rust-src-to-check",
+            "src",
+            "--log-format",
+            "json",
+        ]);
+        assert_eq!(cli.log_format(), LogFormat::Json);
+    }
+
+    #[test]
+    fn test_cli_command_name() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert_eq!(cli.command_name(), "check");
+
+        let cli = Cli::parse_from(["checker", "docs"]);
+        assert_eq!(cli.command_name(), "docs");
+        assert!(cli.bug_report_dir().is_none());
+
+        let cli = Cli::parse_from(["checker", "docs", "--bug-report-dir", "/tmp"]);
+        assert_eq!(cli.bug_report_dir(), Some(Path::new("/tmp")));
+
+        let cli = Cli::parse_from(["checker", "rules", "--disable-rule", "KeyEngMatches"]);
+        assert_eq!(cli.command_name(), "rules");
+    }
+
+    #[test]
+    fn test_cli_follow_symlinks() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert!(!cli.config().unwrap().scan.follow_symlinks);
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--follow-symlinks"]);
+        assert!(cli.config().unwrap().scan.follow_symlinks);
+    }
+
+    #[test]
+    fn test_cli_no_default_ignores() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert!(cli.config().unwrap().scan.default_ignores);
+
+        let cli = Cli::parse_from(["checker", "--project", "foo", "--no-default-ignores"]);
+        assert!(!cli.config().unwrap().scan.default_ignores);
+    }
+
+    #[test]
+    fn test_cli_cargo_expand() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert!(!cli.config().unwrap().scan.cargo_expand);
+        assert!(cli.config().unwrap().scan.expanded_files.is_empty());
+
+        let cli = Cli::parse_from([
+            "checker",
+            "--cargo-expand",
+            "--expanded-file",
+            "a.rs",
+            "--expanded-file",
+            "b.rs",
+        ]);
+        assert!(cli.config().unwrap().scan.cargo_expand);
+        assert_eq!(
+            cli.config().unwrap().scan.expanded_files,
+            [PathBuf::from("a.rs"), PathBuf::from("b.rs")]
+        );
+    }
+
+    #[test]
+    fn test_cli_enable_and_disable_rule() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert!(cli.config().unwrap().rules.enabled.is_empty());
+        assert!(cli.config().unwrap().rules.disabled.is_empty());
+
+        let cli = Cli::parse_from([
+            "checker",
+            "--enable-rule",
+            "MissingTranslations",
+            "--enable-rule",
+            "HardCodedStrings",
+            "--disable-rule",
+            "KeyEngMatches",
+        ]);
+        let config = cli.config().unwrap();
+        assert_eq!(
+            config.rules.enabled,
+            ["MissingTranslations", "HardCodedStrings"]
+        );
+        assert_eq!(config.rules.disabled, ["KeyEngMatches"]);
+
+        let res = Cli::try_parse_from(["checker", "--disable-rule", "KeyEngMatch"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_cli_rule_severity() {
+        let cli = Cli::parse_from([
+            "checker",
+            "--rule-severity",
+            "KeyEngMatches=warning",
+            "--rule-severity",
+            "UnusedLocaleKeys=error",
+        ]);
+        assert_eq!(
+            cli.config()
+                .unwrap()
+                .rules
+                .severity
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [
+                ("KeyEngMatches".to_string(), Severity::Warning),
+                ("UnusedLocaleKeys".to_string(), Severity::Error)
+            ]
+        );
+
+        for value in [
+            "KeyEngMatches",
+            "KeyEngMatch=warning",
+            "KeyEngMatches=fatal",
+        ] {
+            let res = Cli::try_parse_from(["checker", "--rule-severity", value]);
+            assert!(res.is_err());
+        }
+    }
+
+    #[test]
+    fn test_cli_print_config() {
+        let cli = Cli::parse_from(["checker", "--print-config"]);
+        assert_eq!(cli.print_config(), Some(ConfigFormat::Toml));
+
+        let cli = Cli::parse_from(["checker", "--print-config", "json"]);
+        assert_eq!(cli.print_config(), Some(ConfigFormat::Json));
+    }
+
+    #[test]
+    fn test_cli_list_keys() {
+        let cli = Cli::parse_from([
+            "checker",
+            "list-keys",
+            "--rust-src-to-check",
+            "src",
+            "--format",
+            "json",
+        ]);
+        let Some(Command::ListKeys(args)) = cli.command() else {
+            panic!("list-keys subcommand should be parsed");
         };
+        assert_eq!(args.rust_src_to_check(), [PathBuf::from("src")]);
+        assert_eq!(args.format(), UsageMapFormat::Json);
+    }
 
-        let flattened = cli.rust_src_to_check();
+    #[test]
+    fn test_cli_explain_key() {
+        let cli = Cli::parse_from([
+            "checker",
+            "explain-key",
+            "Restarting {app}",
+            "app.yml",
+            "src",
+        ]);
+        let Some(Command::ExplainKey(args)) = cli.command() else {
+            panic!("explain-key subcommand should be parsed");
+        };
+        assert_eq!(args.key(), "Restarting {app}");
         assert_eq!(
-            flattened,
-            [file_bar_rs.clone(), file_qux_rs_under_dir_baz.clone()]
+            args.projects(&ProjectConfig::default()),
+            [Project::new("app.yml".into(), vec!["src".into()])]
         );
+    }
 
-        let file_quux_rs_under_dir_baz = dir_baz.join("quux");
-        std::fs::File::create(&file_quux_rs_under_dir_baz).unwrap();
+    #[test]
+    fn test_cli_export() {
+        let cli = Cli::parse_from([
+            "checker",
+            "export",
+            "--format",
+            "sqlite",
+            "-o",
+            "i18n.db",
+            "--project",
+            "foo",
+        ]);
+        let Some(Command::Export(args)) = cli.command() else {
+            panic!("export subcommand should be parsed");
+        };
+        assert_eq!(args.format(), ExportFormat::Sqlite);
+        assert_eq!(args.output(), Path::new("i18n.db"));
+        assert_eq!(
+            args.projects(&ProjectConfig::default()),
+            [Project::from_root("foo".into())]
+        );
+    }
+
+    #[test]
+    fn test_cli_notify_webhook() {
+        let cli = Cli::parse_from(["checker", "--project", "foo"]);
+        assert_eq!(cli.notify_webhook(), None);
 
-        let flattened = cli.rust_src_to_check();
+        let cli = Cli::parse_from([
+            "checker",
+            "--project",
+            "foo",
+            "--notify-webhook",
+            "https://hooks.slack.com/services/x",
+            "--notify-format",
+            "slack",
+            "--report-url",
+            "https://ci/1",
+        ]);
         assert_eq!(
-            flattened,
-            [file_bar_rs.clone(), file_qux_rs_under_dir_baz.clone()]
+            cli.notify_webhook(),
+            Some("https://hooks.slack.com/services/x")
         );
+        assert_eq!(cli.notify_format(), WebhookFormat::Slack);
+        assert_eq!(cli.report_url().as_deref(), Some("https://ci/1"));
+    }
+
+    #[test]
+    fn test_cli_report() {
+        assert!(Cli::try_parse_from(["checker", "report", "--project", "foo"]).is_err());
+
+        let cli = Cli::parse_from([
+            "checker",
+            "report",
+            "--issues",
+            "--out-dir",
+            "issues",
+            "--project",
+            "foo",
+        ]);
+        let Some(Command::Report(args)) = cli.command() else {
+            panic!("report subcommand should be parsed");
+        };
+        assert_eq!(args.out_dir(), Some(Path::new("issues")));
+        assert_eq!(args.link_base(), None);
+        assert_eq!(
+            args.projects(&ProjectConfig::default()),
+            [Project::from_root("foo".into())]
+        );
+    }
+
+    #[test]
+    fn test_cli_todo() {
+        let cli = Cli::parse_from(["checker", "todo"]);
+        let Some(Command::Todo(args)) = cli.command() else {
+            panic!("todo subcommand should be parsed");
+        };
+        assert_eq!(args.out_dir(), Path::new("i18n-todo"));
+        assert_eq!(cli.command_name(), "todo");
+
+        let cli = Cli::parse_from(["checker", "todo", "--out-dir", "todo", "--project", "foo"]);
+        let Some(Command::Todo(args)) = cli.command() else {
+            panic!("todo subcommand should be parsed");
+        };
+        assert_eq!(args.out_dir(), Path::new("todo"));
+        assert_eq!(
+            args.projects(&ProjectConfig::default()),
+            [Project::from_root("foo".into())]
+        );
+    }
+
+    #[test]
+    fn test_cli_codegen() {
+        assert!(Cli::try_parse_from(["checker", "codegen"]).is_err());
+
+        let cli = Cli::parse_from(["checker", "codegen", "--tests", "-o", "src/i18n_keys.rs"]);
+        let Some(Command::Codegen(args)) = cli.command() else {
+            panic!("codegen subcommand should be parsed");
+        };
+        assert_eq!(args.output(), Some(Path::new("src/i18n_keys.rs")));
+        assert_eq!(cli.command_name(), "codegen");
+    }
+
+    #[test]
+    fn test_cli_trend() {
+        let cli = Cli::parse_from(["checker", "trend", "--lang", "de", "--last", "5"]);
+        let Some(Command::Trend(args)) = cli.command() else {
+            panic!("trend subcommand should be parsed");
+        };
+        assert_eq!(args.lang(), "de");
+        assert_eq!(args.last(), 5);
+        assert_eq!(cli.config().unwrap().history.file, None);
+
+        let cli = Cli::parse_from(["checker", "trend", "--lang", "de", "--history-file", "h.db"]);
+        let Some(Command::Trend(args)) = cli.command() else {
+            panic!("trend subcommand should be parsed");
+        };
+        assert_eq!(args.last(), 30);
+        assert_eq!(
+            cli.config().unwrap().history.file,
+            Some(PathBuf::from("h.db"))
+        );
+    }
+
+    #[test]
+    fn test_cli_compare() {
+        let cli = Cli::parse_from([
+            "checker",
+            "compare",
+            "--from",
+            "v15.0.0",
+            "--to",
+            "HEAD",
+            "--project",
+            "foo",
+        ]);
+        let Some(Command::Compare(args)) = cli.command() else {
+            panic!("compare subcommand should be parsed");
+        };
+        assert_eq!(args.from, "v15.0.0");
+        assert_eq!(args.to, "HEAD");
+        assert_eq!(
+            args.projects(&ProjectConfig::default()),
+            [Project::from_root("foo".into())]
+        );
+    }
+
+    #[test]
+    fn test_cli_env() {
+        // Options that mean the same in every command they are in
+        const SHARED: [&str; 5] = [
+            "TOPGRADE_I18N_LOCALE_FILE",
+            "TOPGRADE_I18N_RUST_SRC_TO_CHECK",
+            "TOPGRADE_I18N_PROJECT",
+            "TOPGRADE_I18N_LOCALE_ONLY",
+            "TOPGRADE_I18N_GIT_REV",
+        ];
+
+        let cli = <Cli as CommandFactory>::command();
+        let mut envs = Vec::new();
+        for command in std::iter::once(&cli).chain(cli.get_subcommands()) {
+            for arg in command.get_arguments() {
+                if arg.get_long().is_none() {
+                    continue;
+                }
+                let env = arg.get_env().unwrap_or_else(|| {
+                    panic!(
+                        "--{} of {} has no environment variable",
+                        arg.get_long().unwrap(),
+                        command.get_name()
+                    )
+                });
+                envs.push((env.to_str().unwrap(), command.get_name()));
+            }
+        }
+        envs.sort();
+        for pair in envs.windows(2) {
+            let [(env, command), (other_env, other_command)] = pair else {
+                unreachable!()
+            };
+            assert!(
+                env != other_env || SHARED.contains(env),
+                "{} is used by both {} and {}",
+                env,
+                command,
+                other_command
+            );
+        }
     }
 }
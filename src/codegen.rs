@@ -0,0 +1,209 @@
+//! This file contains `codegen --tests`, which generates a Rust file of
+//! `#[test]`s asserting that every key passed to `t!()` exists in the locale
+//! file and that every required language is present.
+//!
+//! The file is meant to be checked in to Topgrade as a test module of the
+//! crate that calls `rust_i18n::i18n!()`, e.g., `#[cfg(test)] mod i18n_keys;`,
+//! so that `cargo test` enforces the guarantee without this binary. It looks
+//! keys up with the functions `i18n!()` generates, as `t!()` does.
+
+use crate::config::{LanguageTiers, VariantsConfig};
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// First line of the generated file.
+const HEADER: &str =
+    "// @generated by `topgrade-i18n-check codegen --tests`, do not edit, regenerate it instead.";
+
+/// The generated tests of a project.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TestFile<'a> {
+    /// The parsed locale file.
+    pub(crate) localized_texts: &'a LocalizedTexts,
+    /// The `t!()` invocations found.
+    pub(crate) locale_keys: &'a [LocaleKey],
+    /// The tier-1 languages are required, or every language of the locale
+    /// file if no tier is configured.
+    pub(crate) tiers: &'a LanguageTiers,
+    /// How key variants are defined.
+    pub(crate) variants: &'a VariantsConfig,
+}
+
+impl TestFile<'_> {
+    /// Returns the languages the tests require.
+    fn required_languages(&self) -> Vec<String> {
+        if self.tiers.tier1.is_empty() && self.tiers.tier2.is_empty() {
+            let mut languages = self
+                .localized_texts
+                .languages()
+                .iter()
+                .map(|lang| lang.to_string())
+                .collect::<Vec<_>>();
+            languages.sort();
+            languages
+        } else {
+            self.tiers.tier1.clone()
+        }
+    }
+
+    /// Returns the key rust-i18n looks `key` up with: plural forms are stored
+    /// as `<key>.<category>`, and a key that is only defined through its
+    /// variants exists if any of them does.
+    fn lookup_key(&self, key: &str) -> String {
+        let texts = &self.localized_texts.texts;
+        match texts.get(key) {
+            Some(translations) if translations.is_plural() => format!("{}.other", key),
+            Some(_) => key.to_string(),
+            None => texts
+                .keys()
+                .find(|defined| matches!(self.variants.split(defined), (base, Some(_)) if base == key))
+                .map(|defined| defined.to_string())
+                .unwrap_or_else(|| key.to_string()),
+        }
+    }
+
+    /// Renders the test file, keys are sorted so that the output is stable.
+    pub(crate) fn render(&self) -> String {
+        let keys = self
+            .locale_keys
+            .iter()
+            .map(|locale_key| self.lookup_key(&locale_key.key))
+            .collect::<BTreeSet<_>>();
+
+        let mut out = String::new();
+        writeln!(out, "{}", HEADER).unwrap();
+        writeln!(out, "//!").unwrap();
+        writeln!(
+            out,
+            "//! Checks that every key passed to `t!()` exists in the locale file, and that"
+        )
+        .unwrap();
+        writeln!(out, "//! every required language is present.").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "/// Keys passed to `t!()`.").unwrap();
+        writeln!(out, "const KEYS: &[&str] = &[").unwrap();
+        for key in keys.iter() {
+            writeln!(out, "    {:?},", key).unwrap();
+        }
+        writeln!(out, "];").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "/// Languages that should be present.").unwrap();
+        writeln!(out, "const LANGUAGES: &[&str] = &[").unwrap();
+        for lang in self.required_languages() {
+            writeln!(out, "    {:?},", lang).unwrap();
+        }
+        writeln!(out, "];").unwrap();
+        out.push_str(
+            r#"
+#[test]
+fn every_key_exists() {
+    let languages = rust_i18n::available_locales!();
+    let missing = KEYS
+        .iter()
+        .filter(|key| {
+            !languages
+                .iter()
+                .any(|lang| crate::_rust_i18n_try_translate(lang, key).is_some())
+        })
+        .collect::<Vec<_>>();
+    assert!(missing.is_empty(), "keys missing from the locale file: {:?}", missing);
+}
+
+#[test]
+fn every_language_is_present() {
+    let languages = rust_i18n::available_locales!();
+    let missing = LANGUAGES
+        .iter()
+        .filter(|lang| !languages.contains(*lang))
+        .collect::<Vec<_>>();
+    assert!(missing.is_empty(), "languages missing from the locale file: {:?}", missing);
+}
+"#,
+        );
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::Interner;
+    use std::path::Path;
+
+    #[test]
+    fn test_render() {
+        let yaml = serde_yaml_ng::from_str(
+            r#"
+_version: 2
+"{count} updates":
+  en:
+    one: "%{count} update"
+    other: "%{count} updates"
+  de: "%{count} Updates"
+Welcome@feminine:
+  en: Welcome
+Say "hi":
+  en: Say "hi"
+"#,
+        )
+        .unwrap();
+        let variants = VariantsConfig {
+            separator: Some("@".into()),
+            names: Vec::new(),
+        };
+        let localized_texts =
+            LocalizedTexts::new(yaml, &mut Interner::new(), &Default::default(), &variants)
+                .unwrap();
+        let locale_keys = ["Welcome", "{count} updates", "Say \"hi\"", "Gone", "Gone"]
+            .into_iter()
+            .map(|key| LocaleKey {
+                key: key.into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 0,
+                args: Vec::new(),
+                locale: None,
+                ignored: false,
+            })
+            .collect::<Vec<_>>();
+        let test_file = TestFile {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            tiers: &LanguageTiers::default(),
+            variants: &variants,
+        };
+
+        let rendered = test_file.render();
+        assert!(rendered.starts_with(HEADER));
+        assert!(rendered.contains(
+            r#"const KEYS: &[&str] = &[
+    "Gone",
+    "Say \"hi\"",
+    "Welcome@feminine",
+    "{count} updates.other",
+];
+
+/// Languages that should be present.
+const LANGUAGES: &[&str] = &[
+    "de",
+    "en",
+];
+"#
+        ));
+        assert!(rendered.contains("fn every_key_exists()"));
+
+        let tiers = LanguageTiers {
+            tier1: vec!["en".into()],
+            tier2: vec!["de".into()],
+        };
+        let rendered = TestFile {
+            tiers: &tiers,
+            ..test_file
+        }
+        .render();
+        assert!(rendered.contains("const LANGUAGES: &[&str] = &[\n    \"en\",\n];"));
+    }
+}
@@ -0,0 +1,184 @@
+//! This file contains type [`Comparison`], the differences between the check
+//! results of two revisions.
+
+use crate::checker::Checker;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::rules::{Diagnostic, Severity};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+/// Something found by a rule: `(RuleName, Diagnostic)`
+type ErrorEntry = (&'static str, Diagnostic);
+
+/// The differences between the check results of two revisions.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Comparison {
+    /// Errors that only exist in the new revision.
+    introduced: Vec<ErrorEntry>,
+    /// Errors that only exist in the old revision.
+    fixed: Vec<ErrorEntry>,
+    /// `Language => (OldCoverage, NewCoverage)`, a coverage is `None` if the
+    /// language does not exist in that revision.
+    coverage: BTreeMap<String, (Option<f64>, Option<f64>)>,
+}
+
+impl Comparison {
+    /// Compares the check results of the old revision (`from`) and the new
+    /// revision (`to`).
+    pub(crate) fn new(from: (&LocalizedTexts, &Checker), to: (&LocalizedTexts, &Checker)) -> Self {
+        let (from_texts, from_checker) = from;
+        let (to_texts, to_checker) = to;
+
+        let collect_errors = |checker: &Checker| {
+            checker
+                .errors()
+                .map(|(rule, diagnostic)| (rule, diagnostic.clone()))
+                .collect::<BTreeSet<ErrorEntry>>()
+        };
+        let from_errors = collect_errors(from_checker);
+        let to_errors = collect_errors(to_checker);
+
+        let introduced = to_errors.difference(&from_errors).cloned().collect();
+        let fixed = from_errors.difference(&to_errors).cloned().collect();
+
+        let mut coverage: BTreeMap<String, (Option<f64>, Option<f64>)> = BTreeMap::new();
+        for (lang, ratio) in from_texts.coverage() {
+            coverage.entry(lang).or_default().0 = Some(ratio);
+        }
+        for (lang, ratio) in to_texts.coverage() {
+            coverage.entry(lang).or_default().1 = Some(ratio);
+        }
+
+        Self {
+            introduced,
+            fixed,
+            coverage,
+        }
+    }
+
+    /// Returns true if the new revision introduces any error, diagnostics
+    /// with other severities are not counted.
+    pub(crate) fn has_introduced_error(&self) -> bool {
+        self.introduced
+            .iter()
+            .any(|(_, diagnostic)| diagnostic.severity == Severity::Error)
+    }
+
+    /// Print the comparison in a human-readable way.
+    pub(crate) fn report_to_user(&self) {
+        let print_errors = |errors: &[ErrorEntry]| {
+            for (rule, diagnostic) in errors {
+                print!("    {}: {}", rule, diagnostic.headline());
+                if diagnostic.severity != Severity::Error {
+                    print!(" ({:?})", diagnostic.severity);
+                }
+                match &diagnostic.error_msg {
+                    Some(error_msg) => println!(": {}", error_msg),
+                    None => println!(),
+                }
+            }
+        };
+
+        println!("Introduced errors: {}", self.introduced.len());
+        print_errors(&self.introduced);
+        println!("Fixed errors: {}", self.fixed.len());
+        print_errors(&self.fixed);
+
+        println!("Coverage:");
+        let format_ratio = |opt_ratio: Option<f64>| match opt_ratio {
+            Some(ratio) => format!("{:.1}%", ratio * 100.0),
+            None => "-".to_string(),
+        };
+        for (lang, (from, to)) in self.coverage.iter() {
+            print!(
+                "    {}: {} -> {}",
+                lang,
+                format_ratio(*from),
+                format_ratio(*to)
+            );
+            match (from, to) {
+                (Some(from), Some(to)) => println!(" ({:+.1})", (to - from) * 100.0),
+                _ => println!(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LanguageTiers;
+    use crate::locale_file_parser::Translations;
+    use crate::rules::missing_translations::MissingTranslations;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indexmap::IndexMap;
+    use indicatif::ProgressBar;
+
+    fn check(localized_texts: &LocalizedTexts) -> Checker {
+        let mut checker = Checker::new();
+        checker.register_rule(MissingTranslations::new(LanguageTiers::default()));
+        let ctx = CheckContext {
+            localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+        checker.check(&ctx, &mut Timings::new(), &ProgressBar::hidden());
+        checker
+    }
+
+    #[test]
+    fn test_comparison() {
+        let from_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                ("Fixed".into(), Translations::default()),
+                ("Unchanged".into(), Translations::default()),
+            ]),
+        };
+        let to_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Fixed".into(),
+                    Translations {
+                        en: Some("Fixed".into()),
+                        ..Default::default()
+                    },
+                ),
+                ("Unchanged".into(), Translations::default()),
+                ("Introduced".into(), Translations::default()),
+                (
+                    "Translated".into(),
+                    Translations {
+                        en: Some("Translated".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+
+        let comparison = Comparison::new(
+            (&from_texts, &check(&from_texts)),
+            (&to_texts, &check(&to_texts)),
+        );
+
+        let missing_en = Some("Missing translations for [English]".to_string());
+        assert_eq!(
+            comparison,
+            Comparison {
+                introduced: vec![(
+                    "MissingTranslations",
+                    Diagnostic::error("Introduced".into(), missing_en.clone())
+                        .with_langs(vec!["en".into()])
+                )],
+                fixed: vec![(
+                    "MissingTranslations",
+                    Diagnostic::error("Fixed".into(), missing_en).with_langs(vec!["en".into()])
+                )],
+                coverage: BTreeMap::from([("en".to_string(), (None, Some(0.5)))]),
+            }
+        );
+        assert!(comparison.has_introduced_error());
+    }
+}
@@ -0,0 +1,1261 @@
+//! This file contains type [`Config`], the configuration file of this tool.
+
+use crate::checker::RuleSelection;
+use crate::error::Error;
+use crate::macro_matcher::MacroFamily;
+use crate::rules::Severity;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+pub(crate) use topgrade_i18n_locale_checker::placeholder::PlaceholderSyntax;
+
+/// Name of the configuration file that will be loaded if it exists in the
+/// current directory and `--config` is not specified.
+pub(crate) const DEFAULT_CONFIG_FILE: &str = "i18n-checker.toml";
+
+/// Cargo manifest, the configuration can also be stored in its
+/// `[package.metadata.i18n-check]` or `[workspace.metadata.i18n-check]` table.
+pub(crate) const CARGO_MANIFEST: &str = "Cargo.toml";
+
+/// Name of the table under `package.metadata` or `workspace.metadata` that
+/// stores the configuration in [`CARGO_MANIFEST`].
+const CARGO_METADATA_TABLE: &str = "i18n-check";
+
+/// Prefix of the environment variables that configure this tool.
+const ENV_PREFIX: &str = "TOPGRADE_I18N_";
+
+/// The configuration file.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct Config {
+    /// The project to check if none is given on the command line.
+    pub(crate) project: ProjectConfig,
+    /// Options about languages.
+    pub(crate) languages: LanguagesConfig,
+    /// Options about placeholders.
+    pub(crate) placeholders: PlaceholdersConfig,
+    /// Options about key variants.
+    pub(crate) variants: VariantsConfig,
+    /// Options about scanning the Rust files.
+    pub(crate) scan: ScanConfig,
+    /// Options about the run history.
+    pub(crate) history: HistoryConfig,
+    /// Options of the rules.
+    pub(crate) rules: RulesConfig,
+}
+
+impl Config {
+    /// Loads the configuration file at `path`.
+    ///
+    /// If `path` is a [`CARGO_MANIFEST`], the configuration is read from its
+    /// metadata, see [`Config::load_from_cargo_manifest()`], the default config
+    /// is used if the manifest has no such metadata.
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        if path.file_name() == Some(CARGO_MANIFEST.as_ref()) {
+            return Ok(Self::load_from_cargo_manifest(path)?.unwrap_or_default());
+        }
+
+        let contents = read_config_file(path)?;
+        let mut config: Self = toml::from_str(&contents).map_err(|e| Error::Invalid {
+            what: "config file",
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        config.resolve_paths(path);
+
+        Ok(config)
+    }
+
+    /// Loads the configuration from the `[package.metadata.i18n-check]` table
+    /// of the Cargo manifest at `path`, or `[workspace.metadata.i18n-check]` if
+    /// the former does not exist.
+    ///
+    /// Returns `None` if neither table exists.
+    pub(crate) fn load_from_cargo_manifest(path: &Path) -> Result<Option<Self>, Error> {
+        let contents = read_config_file(path)?;
+        let invalid = |message: String| Error::Invalid {
+            what: "Cargo manifest",
+            path: path.to_path_buf(),
+            message,
+        };
+        let manifest: toml::Table =
+            toml::from_str(&contents).map_err(|e| invalid(e.to_string()))?;
+
+        let Some(table) = ["package", "workspace"].into_iter().find_map(|section| {
+            manifest
+                .get(section)?
+                .get("metadata")?
+                .get(CARGO_METADATA_TABLE)
+                .cloned()
+        }) else {
+            return Ok(None);
+        };
+        let mut config: Self = table.try_into().map_err(|e: toml::de::Error| {
+            invalid(format!("invalid `{}` table: {}", CARGO_METADATA_TABLE, e))
+        })?;
+        config.resolve_paths(path);
+
+        Ok(Some(config))
+    }
+
+    /// Validates the config file at `path`, which can be a [`CARGO_MANIFEST`].
+    ///
+    /// Returns the keys that are not recognized, which are otherwise silently
+    /// ignored, e.g., `rules.too_many_placeholder.max`, or a message describing
+    /// why the config is invalid.
+    pub(crate) fn validate(path: &Path) -> Result<Vec<String>, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {} due to error {}", path.display(), e))?;
+        let mut table: toml::Table = toml::from_str(&contents)
+            .map_err(|e| format!("invalid TOML in {}: {}", path.display(), e))?;
+
+        let mut prefix = String::new();
+        if path.file_name() == Some(CARGO_MANIFEST.as_ref()) {
+            let metadata = ["package", "workspace"].into_iter().find_map(|section| {
+                let metadata = table
+                    .remove(section)?
+                    .get_mut("metadata")?
+                    .as_table_mut()?
+                    .remove(CARGO_METADATA_TABLE)?;
+                Some((section, metadata))
+            });
+            let Some((section, metadata)) = metadata else {
+                return Ok(Vec::new());
+            };
+            let toml::Value::Table(metadata) = metadata else {
+                return Err(format!(
+                    "{}.metadata.{} in {} should be a table",
+                    section,
+                    CARGO_METADATA_TABLE,
+                    path.display()
+                ));
+            };
+            prefix = format!("{}.metadata.{}.", section, CARGO_METADATA_TABLE);
+            table = metadata;
+        }
+
+        let mut unknown_keys = Vec::new();
+        let _: Self = serde_ignored::deserialize(toml::Value::Table(table), |key| {
+            unknown_keys.push(format!("{}{}", prefix, key))
+        })
+        .map_err(|e| format!("invalid config in {}: {}", path.display(), e))?;
+
+        Ok(unknown_keys)
+    }
+
+    /// Resolves the relative paths in the config against the directory of the
+    /// config file at `path`.
+    fn resolve_paths(&mut self, path: &Path) {
+        let Some(dir) = path.parent() else {
+            return;
+        };
+        for file in [
+            &mut self.project.locale_file,
+            &mut self.rules.glossary.file,
+            &mut self.history.file,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *file = dir.join(&*file);
+        }
+        for file in self
+            .project
+            .rust_src
+            .iter_mut()
+            .chain(self.scan.expanded_files.iter_mut())
+        {
+            *file = dir.join(&*file);
+        }
+    }
+
+    /// Renders the config in `format`, so users can see the effective
+    /// configuration.
+    pub(crate) fn render(&self, format: ConfigFormat) -> String {
+        match format {
+            ConfigFormat::Toml => {
+                toml::to_string_pretty(self).expect("config should be serializable to TOML")
+            }
+            ConfigFormat::Json => {
+                serde_json::to_string_pretty(self).expect("config should be serializable to JSON")
+            }
+        }
+    }
+
+    /// Overrides the config with environment variables, `vars` should be
+    /// [`std::env::vars()`] except in tests.
+    ///
+    /// * `TOPGRADE_I18N_LANGUAGES_TIER1`: comma-separated languages, replaces
+    ///   `languages.tiers.tier1`
+    /// * `TOPGRADE_I18N_LANGUAGES_TIER2`: comma-separated languages, replaces
+    ///   `languages.tiers.tier2`
+    /// * `TOPGRADE_I18N_LANGUAGES_ALIASES`: comma-separated `alias=canonical`
+    ///   pairs, merged into `languages.aliases`
+    ///
+    /// Only these keys are supported, the other keys either have an option,
+    /// which has its own environment variable, e.g., `--history-file` for
+    /// `history.file`, or can only be set in the config file.
+    ///
+    /// Fails if a value is invalid.
+    pub(crate) fn with_env_overrides(
+        mut self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, Error> {
+        let split = |value: &str| -> Vec<String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        };
+
+        for (name, value) in vars {
+            let Some(name) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+
+            match name {
+                "LANGUAGES_TIER1" => self.languages.tiers.tier1 = split(&value),
+                "LANGUAGES_TIER2" => self.languages.tiers.tier2 = split(&value),
+                "LANGUAGES_ALIASES" => {
+                    for pair in split(&value) {
+                        let Some((alias, canonical)) = pair.split_once('=') else {
+                            return Err(Error::Usage(format!(
+                                "invalid language alias '{}' in {}LANGUAGES_ALIASES, expect 'alias=canonical'",
+                                pair, ENV_PREFIX
+                            )));
+                        };
+                        self.languages
+                            .aliases
+                            .0
+                            .insert(alias.trim().to_string(), canonical.trim().to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// Reads the config file at `path`.
+fn read_config_file(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|error| Error::Read {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Formats in which [`Config::render()`] renders the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ConfigFormat {
+    /// The format of the config file.
+    #[default]
+    Toml,
+    /// JSON
+    Json,
+}
+
+/// The `[project]` section.
+///
+/// Paths are relative to the config file, so that the tool can be run with no
+/// flags from anywhere in the repository.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct ProjectConfig {
+    /// The path to the locale file, detected if not specified, same as
+    /// `--locale-file`.
+    pub(crate) locale_file: Option<PathBuf>,
+    /// Rust files or directories to check, `src` is detected if empty, same as
+    /// `--rust-src-to-check`.
+    pub(crate) rust_src: Vec<PathBuf>,
+}
+
+/// The `[scan]` section.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct ScanConfig {
+    /// How deep directories are walked, relative to the specified ones, 32 by
+    /// default.
+    pub(crate) max_depth: usize,
+    /// The maximum number of files visited, 20000 by default.
+    pub(crate) max_files: usize,
+    /// Files larger than this, in bytes, are skipped with a note, as they are
+    /// probably generated, 1 MiB by default.
+    pub(crate) max_file_size: usize,
+    /// Follow symlinks, links that lead to a directory already visited are
+    /// skipped with a warning.
+    pub(crate) follow_symlinks: bool,
+    /// Skip the directories named in [`DEFAULT_IGNORED_DIRS`] and
+    /// `vendor_dirs`, true by default.
+    pub(crate) default_ignores: bool,
+    /// Names of the directories holding vendored code, skipped along with
+    /// [`DEFAULT_IGNORED_DIRS`], `["vendor"]` by default.
+    pub(crate) vendor_dirs: Vec<String>,
+    /// Also collect the keys of the `t!()` invocations generated by macros
+    /// from the output of `cargo expand`, run in the project root.
+    pub(crate) cargo_expand: bool,
+    /// Files holding code already expanded by `cargo expand`, relative to the
+    /// config file, whose keys are collected like with `cargo_expand`.
+    pub(crate) expanded_files: Vec<PathBuf>,
+    /// The i18n stacks whose translation macros are collected, rust-i18n's
+    /// `t!()` by default.
+    pub(crate) macros: Vec<MacroFamily>,
+}
+
+/// Directories that never contain code to check, skipped unless
+/// `scan.default_ignores` is false.
+pub(crate) const DEFAULT_IGNORED_DIRS: [&str; 3] = ["target", "node_modules", ".git"];
+
+impl ScanConfig {
+    /// Returns true if a directory named `name` should be skipped.
+    pub(crate) fn is_ignored_dir(&self, name: &OsStr) -> bool {
+        self.default_ignores
+            && DEFAULT_IGNORED_DIRS
+                .iter()
+                .map(OsStr::new)
+                .chain(self.vendor_dirs.iter().map(OsStr::new))
+                .any(|ignored| ignored == name)
+    }
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 32,
+            max_files: 20_000,
+            max_file_size: 1024 * 1024,
+            follow_symlinks: false,
+            default_ignores: true,
+            vendor_dirs: vec!["vendor".to_string()],
+            cargo_expand: false,
+            expanded_files: Vec::new(),
+            macros: vec![MacroFamily::RustI18n],
+        }
+    }
+}
+
+/// The `[placeholders]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct PlaceholdersConfig {
+    /// How placeholders are written in translations.
+    pub(crate) syntax: PlaceholderSyntax,
+}
+
+/// The `[variants]` section.
+///
+/// A key can have variants, e.g., for grammatical genders, defined as keys
+/// suffixed with the separator and the variant name, e.g., `Welcome@feminine`
+/// if the separator is `@`. Using the key itself is fine if only its variants
+/// are defined, as the variant is picked at runtime.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct VariantsConfig {
+    /// Separator between the key and the variant name, variants are not
+    /// recognized if it is not specified.
+    pub(crate) separator: Option<String>,
+    /// Allowed variant names, any name is allowed if it is empty.
+    pub(crate) names: Vec<String>,
+}
+
+impl VariantsConfig {
+    /// Splits `key` into the key it is a variant of and the variant name, the
+    /// name is `None` if `key` is not a variant.
+    pub(crate) fn split<'a>(&self, key: &'a str) -> (&'a str, Option<&'a str>) {
+        let split = self
+            .separator
+            .as_deref()
+            .filter(|separator| !separator.is_empty())
+            .and_then(|separator| key.rsplit_once(separator));
+        match split {
+            Some((base, name)) if !base.is_empty() && !name.is_empty() => (base, Some(name)),
+            _ => (key, None),
+        }
+    }
+}
+
+/// The `[history]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct HistoryConfig {
+    /// Path to the run-history database, relative to the config file, every
+    /// check is recorded in it if it is specified.
+    pub(crate) file: Option<PathBuf>,
+    /// Trend gates, checked against the last recorded run.
+    pub(crate) gates: TrendGatesConfig,
+}
+
+/// The `[history.gates]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct TrendGatesConfig {
+    /// Fail if the coverage of a gated language dropped by more than this many
+    /// percentage points since the last recorded run of another commit,
+    /// disabled if not specified.
+    pub(crate) max_coverage_drop: Option<f64>,
+    /// Fail if the errors of a gated language increased by more than this
+    /// since the last recorded run of another commit, disabled if not
+    /// specified.
+    pub(crate) max_error_increase: Option<usize>,
+    /// Languages that are gated, the tier-1 languages if empty.
+    pub(crate) languages: Vec<String>,
+}
+
+/// The `[rules]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct RulesConfig {
+    /// Names of the rules to run, including opt-in ones, every rule that runs
+    /// by default if empty.
+    pub(crate) enabled: Vec<String>,
+    /// Names of the rules not to run.
+    pub(crate) disabled: Vec<String>,
+    /// Keys that no rule checks, neither in the locale file nor in `t!()`
+    /// invocations, along with their variants.
+    pub(crate) ignore_keys: Vec<String>,
+    /// Severity of every problem a rule finds: `RuleName => Severity`, e.g.,
+    /// to stage a new rule as a warning before it fails the check.
+    pub(crate) severity: BTreeMap<String, Severity>,
+    /// Options of the `EmojiPolicy` rule.
+    pub(crate) emoji_policy: EmojiPolicyConfig,
+    /// Options of the `ErrorMessages` rule.
+    pub(crate) error_messages: ErrorMessagesConfig,
+    /// Options of the `GlossaryTerms` rule.
+    pub(crate) glossary: GlossaryConfig,
+    /// Options of the `HardCodedStrings` rule.
+    pub(crate) hard_coded_strings: HardCodedStringsConfig,
+    /// Options of the `KeyEngMatches` rule.
+    pub(crate) key_eng_matches: KeyEngMatchesConfig,
+    /// Options of the `PseudoText` rule.
+    pub(crate) pseudo_text: PseudoTextConfig,
+    /// Options of the `QuotingStyle` rule.
+    pub(crate) quoting_style: QuotingStyleConfig,
+    /// Options of the `StubTranslations` rule.
+    pub(crate) stub_translations: StubTranslationsConfig,
+    /// Options of the `SuspiciousWhitespace` rule.
+    pub(crate) suspicious_whitespace: SuspiciousWhitespaceConfig,
+    /// Options of the `TooManyPlaceholders` rule.
+    pub(crate) too_many_placeholders: TooManyPlaceholdersConfig,
+    /// Options of the `UnicodeNormalization` rule.
+    pub(crate) unicode_normalization: UnicodeNormalizationConfig,
+}
+
+impl RulesConfig {
+    /// Returns the rules selected by `enabled` and `disabled`, with the
+    /// severities of `severity`.
+    pub(crate) fn selection(&self) -> RuleSelection {
+        RuleSelection {
+            enabled: self.enabled.clone(),
+            disabled: self.disabled.clone(),
+            severities: self.severity.clone(),
+        }
+    }
+
+    /// Returns true if the rule named `name` runs unless it is disabled, false
+    /// for the opt-in rules, which need their options to be set.
+    pub(crate) fn runs_by_default(&self, name: &str) -> bool {
+        match name {
+            "HardCodedStrings" => self.hard_coded_strings.enabled,
+            "ErrorMessages" => self.error_messages.enabled,
+            "GlossaryTerms" => self.glossary.file.is_some(),
+            _ => true,
+        }
+    }
+}
+
+/// The `[rules.suspicious_whitespace]` section.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct SuspiciousWhitespaceConfig {
+    /// Languages that legitimately use a space before punctuation, `["fr"]`
+    /// by default.
+    pub(crate) space_before_punctuation: Vec<String>,
+}
+
+impl Default for SuspiciousWhitespaceConfig {
+    fn default() -> Self {
+        Self {
+            space_before_punctuation: vec!["fr".to_string()],
+        }
+    }
+}
+
+/// The `[rules.too_many_placeholders]` section.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct TooManyPlaceholdersConfig {
+    /// The maximum number of distinct placeholders a key can have, 4 by
+    /// default.
+    pub(crate) max: usize,
+}
+
+impl Default for TooManyPlaceholdersConfig {
+    fn default() -> Self {
+        Self { max: 4 }
+    }
+}
+
+/// The `[rules.pseudo_text]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct PseudoTextConfig {
+    /// Regular expressions, translations that match any of them are pseudo
+    /// text, in addition to the built-in heuristics.
+    pub(crate) patterns: Vec<String>,
+}
+
+/// The `[rules.quoting_style]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct QuotingStyleConfig {
+    /// `Language => Quote pairs allowed`, a quote pair is written as the
+    /// opening and closing quote characters, e.g., `de = ["»«", "„“"]`.
+    ///
+    /// Languages not listed should use the same quotes as English.
+    pub(crate) languages: HashMap<String, Vec<String>>,
+}
+
+/// The `[rules.stub_translations]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct StubTranslationsConfig {
+    /// Regular expressions, translations that match any of them are stubs, in
+    /// addition to the built-in ones like `TODO`.
+    pub(crate) patterns: Vec<String>,
+}
+
+/// The `[rules.emoji_policy]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct EmojiPolicyConfig {
+    /// The policy.
+    pub(crate) mode: EmojiPolicyMode,
+}
+
+/// What `EmojiPolicy` requires of the emoji in translations.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum EmojiPolicyMode {
+    /// Every translation should use the same emoji as English.
+    #[default]
+    MatchEnglish,
+    /// No translation should contain emoji.
+    Forbid,
+}
+
+/// The `[rules.error_messages]` section.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct ErrorMessagesConfig {
+    /// Not every team localizes error messages, the rule is disabled unless
+    /// this is true.
+    pub(crate) enabled: bool,
+    /// Severity of the problems found, `warning` by default.
+    pub(crate) severity: Severity,
+    /// Error messages that are fine to be hard-coded.
+    pub(crate) allowlist: Vec<String>,
+    /// Functions, or macros followed by `!`, that construct errors.
+    pub(crate) sinks: Vec<String>,
+    /// A string literal with fewer words than this is not considered a
+    /// message, 2 by default.
+    pub(crate) min_words: usize,
+}
+
+impl Default for ErrorMessagesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            severity: Severity::Warning,
+            allowlist: Vec::new(),
+            sinks: ["panic!", "bail!", "anyhow!", "Err"]
+                .map(String::from)
+                .to_vec(),
+            min_words: 2,
+        }
+    }
+}
+
+/// The `[rules.glossary]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct GlossaryConfig {
+    /// Path to the glossary file, relative to the config file, the rule is
+    /// disabled if it is not specified.
+    pub(crate) file: Option<PathBuf>,
+}
+
+/// The `[rules.hard_coded_strings]` section.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct HardCodedStringsConfig {
+    /// The rule is a heuristic, it is disabled unless this is true.
+    pub(crate) enabled: bool,
+    /// String literals that are fine to be hard-coded.
+    pub(crate) allowlist: Vec<String>,
+    /// Functions and methods, or macros followed by `!`, that print
+    /// user-facing text, e.g., `println!` and `print_separator`.
+    pub(crate) sinks: Vec<String>,
+    /// A string literal with fewer words than this is not considered a
+    /// sentence, 2 by default.
+    pub(crate) min_words: usize,
+    /// Regular expressions, string literals that match any of them are
+    /// ignored, URLs and commands with flags by default.
+    pub(crate) ignore_patterns: Vec<String>,
+}
+
+impl Default for HardCodedStringsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowlist: Vec::new(),
+            sinks: [
+                "print!",
+                "println!",
+                "eprint!",
+                "eprintln!",
+                "print_separator",
+                "print_info",
+                "print_warning",
+                "print_error",
+            ]
+            .map(String::from)
+            .to_vec(),
+            min_words: 2,
+            ignore_patterns: [r"[a-z]+://", r"^\S+ --?[A-Za-z]"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+/// The `[rules.key_eng_matches]` section.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct KeyEngMatchesConfig {
+    /// How the English translation is compared with the key.
+    pub(crate) mode: KeyEngMatchesMode,
+    /// In [`KeyEngMatchesMode::Exact`] mode, report differences only in runs
+    /// of whitespace and trailing punctuation as warnings rather than errors.
+    pub(crate) lenient_formatting: bool,
+}
+
+/// How `KeyEngMatches` compares the English translation with the key.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum KeyEngMatchesMode {
+    /// The English translation should be exactly the key, with placeholders
+    /// written in the configured syntax.
+    #[default]
+    Exact,
+    /// The English translation should contain exactly the placeholders of the
+    /// key, the wording can differ.
+    Placeholders,
+}
+
+/// The `[rules.unicode_normalization]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct UnicodeNormalizationConfig {
+    /// The normalization form keys and translations should be in.
+    pub(crate) form: NormalizationForm,
+}
+
+/// Unicode normalization forms.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub(crate) enum NormalizationForm {
+    /// Canonical composition.
+    #[default]
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility composition.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Returns the name of this form.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            NormalizationForm::Nfc => "NFC",
+            NormalizationForm::Nfd => "NFD",
+            NormalizationForm::Nfkc => "NFKC",
+            NormalizationForm::Nfkd => "NFKD",
+        }
+    }
+}
+
+/// The `[languages]` section.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct LanguagesConfig {
+    /// `Alias => CanonicalName`, e.g., `"zh-CN" = "zh_CN"`.
+    pub(crate) aliases: LanguageAliases,
+    /// How important every language is.
+    pub(crate) tiers: LanguageTiers,
+    /// Who maintains every language.
+    pub(crate) maintainers: LanguageMaintainers,
+}
+
+/// The `[languages.tiers]` section, languages should be specified with their
+/// canonical names.
+///
+/// If no tier is configured, every language is treated as a tier-1 language.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct LanguageTiers {
+    /// Languages that must be complete, problems are errors.
+    pub(crate) tier1: Vec<String>,
+    /// Languages whose problems are warnings.
+    pub(crate) tier2: Vec<String>,
+}
+
+impl LanguageTiers {
+    /// Returns the severity of the problems affecting `lang`.
+    ///
+    /// Languages that are in neither tier are informational.
+    pub(crate) fn severity_of(&self, lang: &str) -> Severity {
+        let contains = |tier: &[String]| tier.iter().any(|tier_lang| tier_lang == lang);
+
+        let no_tier = self.tier1.is_empty() && self.tier2.is_empty();
+
+        if no_tier || contains(&self.tier1) {
+            Severity::Error
+        } else if contains(&self.tier2) {
+            Severity::Warning
+        } else {
+            Severity::Info
+        }
+    }
+}
+
+/// Language aliases, used to normalize language names so that cosmetic
+/// differences (e.g., `zh-CN` and `zh_CN`) won't be treated as different
+/// languages.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(transparent)]
+pub(crate) struct LanguageAliases(HashMap<String, String>);
+
+impl LanguageAliases {
+    /// Returns the canonical name of `lang`, `lang` itself is returned if it
+    /// is not an alias.
+    pub(crate) fn canonicalize<'a>(&'a self, lang: &'a str) -> &'a str {
+        self.0.get(lang).map(String::as_str).unwrap_or(lang)
+    }
+}
+
+impl<const N: usize> From<[(&str, &str); N]> for LanguageAliases {
+    fn from(aliases: [(&str, &str); N]) -> Self {
+        Self(
+            aliases
+                .into_iter()
+                .map(|(alias, canonical)| (alias.to_string(), canonical.to_string()))
+                .collect(),
+        )
+    }
+}
+
+/// The `[languages.maintainers]` section, `Language => Handles`, e.g.,
+/// `de = ["@alice"]`, languages should be specified with their canonical
+/// names.
+///
+/// The handles are mentioned in the reports, issues and notifications about
+/// their languages, so that a regression reaches someone who can fix it.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(transparent)]
+pub(crate) struct LanguageMaintainers(HashMap<String, Vec<String>>);
+
+impl LanguageMaintainers {
+    /// Returns the maintainers of `lang`, empty if it has none.
+    pub(crate) fn of(&self, lang: &str) -> &[String] {
+        self.0.get(lang).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the maintainers of any of `langs`, without duplicates, in the
+    /// order of `langs`.
+    pub(crate) fn of_any<'a>(&self, langs: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let mut maintainers = Vec::new();
+        for maintainer in langs.into_iter().flat_map(|lang| self.of(lang)) {
+            if !maintainers.contains(maintainer) {
+                maintainers.push(maintainer.clone());
+            }
+        }
+
+        maintainers
+    }
+}
+
+impl<const N: usize> From<[(&str, &[&str]); N]> for LanguageMaintainers {
+    fn from(maintainers: [(&str, &[&str]); N]) -> Self {
+        Self(
+            maintainers
+                .into_iter()
+                .map(|(lang, handles)| {
+                    (
+                        lang.to_string(),
+                        handles.iter().map(|handle| handle.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_overrides() {
+        let config = Config {
+            languages: LanguagesConfig {
+                aliases: LanguageAliases::from([("zh-CN", "zh_CN")]),
+                tiers: LanguageTiers {
+                    tier1: vec!["en".into()],
+                    tier2: vec!["de".into()],
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = config
+            .with_env_overrides([
+                ("TOPGRADE_I18N_LANGUAGES_TIER1".into(), "en, zh_CN".into()),
+                (
+                    "TOPGRADE_I18N_LANGUAGES_ALIASES".into(),
+                    "pt_BR=pt-BR".into(),
+                ),
+                ("LANGUAGES_TIER2".into(), "fr".into()),
+            ])
+            .unwrap();
+
+        assert_eq!(config.languages.tiers.tier1, ["en", "zh_CN"]);
+        assert_eq!(config.languages.tiers.tier2, ["de"]);
+        assert_eq!(config.languages.aliases.canonicalize("zh-CN"), "zh_CN");
+        assert_eq!(config.languages.aliases.canonicalize("pt_BR"), "pt-BR");
+
+        let res = Config::default()
+            .with_env_overrides([("TOPGRADE_I18N_LANGUAGES_ALIASES".into(), "pt_BR".into())]);
+        assert!(
+            matches!(&res, Err(Error::Usage(message)) if message.contains("'pt_BR'")),
+            "{:?}",
+            res
+        );
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+[languages.aliases]
+"zh-CN" = "zh_CN"
+"pt_BR" = "pt-BR"
+"#,
+        )
+        .unwrap();
+
+        let aliases = &config.languages.aliases;
+        assert_eq!(aliases.canonicalize("zh-CN"), "zh_CN");
+        assert_eq!(aliases.canonicalize("zh_CN"), "zh_CN");
+        assert_eq!(aliases.canonicalize("pt_BR"), "pt-BR");
+        assert_eq!(aliases.canonicalize("de"), "de");
+    }
+
+    #[test]
+    fn test_language_maintainers() {
+        let config: Config = toml::from_str(
+            r#"
+[languages.maintainers]
+de = ["@alice", "@bob"]
+fr = ["@bob"]
+"#,
+        )
+        .unwrap();
+
+        let maintainers = &config.languages.maintainers;
+        assert_eq!(maintainers.of("de"), ["@alice", "@bob"]);
+        assert!(maintainers.of("ja").is_empty());
+        assert_eq!(maintainers.of_any(["fr", "ja", "de"]), ["@bob", "@alice"]);
+    }
+
+    #[test]
+    fn test_variants() {
+        assert_eq!(
+            VariantsConfig::default().split("Welcome@feminine"),
+            ("Welcome@feminine", None)
+        );
+
+        let variants = VariantsConfig {
+            separator: Some("@".into()),
+            names: Vec::new(),
+        };
+        assert_eq!(
+            variants.split("Welcome@feminine"),
+            ("Welcome", Some("feminine"))
+        );
+        assert_eq!(variants.split("a@b@neuter"), ("a@b", Some("neuter")));
+        assert_eq!(variants.split("Welcome"), ("Welcome", None));
+        assert_eq!(variants.split("Mail me@"), ("Mail me@", None));
+    }
+
+    #[test]
+    fn test_scan_macros() {
+        assert_eq!(Config::default().scan.macros, [MacroFamily::RustI18n]);
+        let config: Config = toml::from_str(
+            r#"
+[scan]
+macros = ["rust-i18n", "fluent", "gettext"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.scan.macros,
+            [
+                MacroFamily::RustI18n,
+                MacroFamily::Fluent,
+                MacroFamily::Gettext
+            ]
+        );
+        assert!(toml::from_str::<Config>("[scan]\nmacros = [\"qt\"]").is_err());
+    }
+
+    #[test]
+    fn test_language_tiers() {
+        let config: Config = toml::from_str(
+            r#"
+[languages.tiers]
+tier1 = ["en", "zh_CN"]
+tier2 = ["de"]
+"#,
+        )
+        .unwrap();
+
+        let tiers = &config.languages.tiers;
+        assert_eq!(tiers.severity_of("en"), Severity::Error);
+        assert_eq!(tiers.severity_of("zh_CN"), Severity::Error);
+        assert_eq!(tiers.severity_of("de"), Severity::Warning);
+        assert_eq!(tiers.severity_of("fr"), Severity::Info);
+
+        assert_eq!(LanguageTiers::default().severity_of("fr"), Severity::Error);
+    }
+
+    #[test]
+    fn test_normalization_form() {
+        let config: Config = toml::from_str(
+            r#"
+[rules.unicode_normalization]
+form = "NFKC"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.rules.unicode_normalization.form,
+            NormalizationForm::Nfkc
+        );
+    }
+
+    #[test]
+    fn test_placeholder_syntax() {
+        let config: Config = toml::from_str(
+            r#"
+[placeholders]
+syntax = "{0}"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.placeholders.syntax, PlaceholderSyntax::Positional);
+    }
+
+    #[test]
+    fn test_key_eng_matches_mode() {
+        let config: Config = toml::from_str(
+            r#"
+[rules.key_eng_matches]
+mode = "placeholders"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.rules.key_eng_matches.mode,
+            KeyEngMatchesMode::Placeholders
+        );
+    }
+
+    #[test]
+    fn test_too_many_placeholders_max() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.rules.too_many_placeholders.max, 4);
+
+        let config: Config = toml::from_str(
+            r#"
+[rules.too_many_placeholders]
+max = 2
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.rules.too_many_placeholders.max, 2);
+    }
+
+    #[test]
+    fn test_quoting_style() {
+        let config: Config = toml::from_str(
+            r#"
+[rules.quoting_style.languages]
+de = ["»«", "„“"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.rules.quoting_style.languages,
+            HashMap::from([("de".to_string(), vec!["»«".to_string(), "„“".to_string()])])
+        );
+    }
+
+    #[test]
+    fn test_emoji_policy_mode() {
+        let config: Config = toml::from_str(
+            r#"
+[rules.emoji_policy]
+mode = "forbid"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.rules.emoji_policy.mode, EmojiPolicyMode::Forbid);
+    }
+
+    #[test]
+    fn test_hard_coded_strings() {
+        let config: Config = toml::from_str(
+            r#"
+[rules.hard_coded_strings]
+enabled = true
+sinks = ["println!"]
+min_words = 3
+"#,
+        )
+        .unwrap();
+        let hard_coded_strings = &config.rules.hard_coded_strings;
+        assert!(hard_coded_strings.enabled);
+        assert_eq!(hard_coded_strings.sinks, ["println!"]);
+        assert_eq!(hard_coded_strings.min_words, 3);
+        assert_eq!(
+            hard_coded_strings.ignore_patterns,
+            HardCodedStringsConfig::default().ignore_patterns
+        );
+    }
+
+    #[test]
+    fn test_error_messages() {
+        let config: Config = toml::from_str(
+            r#"
+[rules.error_messages]
+enabled = true
+severity = "error"
+"#,
+        )
+        .unwrap();
+        assert!(config.rules.error_messages.enabled);
+        assert_eq!(config.rules.error_messages.severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_render() {
+        let config = Config::default();
+        let rendered: Config = toml::from_str(&config.render(ConfigFormat::Toml)).unwrap();
+        assert_eq!(rendered, config);
+
+        let config = Config {
+            rules: RulesConfig {
+                glossary: GlossaryConfig {
+                    file: Some("glossary.toml".into()),
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rendered: serde_json::Value =
+            serde_json::from_str(&config.render(ConfigFormat::Json)).unwrap();
+        assert_eq!(rendered["rules"]["glossary"]["file"], "glossary.toml");
+        assert_eq!(rendered["placeholders"]["syntax"], "%{name}");
+    }
+
+    #[test]
+    fn test_load_from_cargo_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join(CARGO_MANIFEST);
+
+        std::fs::write(&manifest, "[package]\nname = \"foo\"\n").unwrap();
+        assert_eq!(Config::load_from_cargo_manifest(&manifest).unwrap(), None);
+        assert_eq!(Config::load(&manifest).unwrap(), Config::default());
+
+        std::fs::write(
+            &manifest,
+            r#"
+[workspace.metadata.i18n-check.languages.tiers]
+tier1 = ["de"]
+
+[package.metadata.i18n-check.rules.glossary]
+file = "glossary.toml"
+"#,
+        )
+        .unwrap();
+        let config = Config::load(&manifest).unwrap();
+        assert_eq!(config.languages, LanguagesConfig::default());
+        assert_eq!(
+            config.rules.glossary.file,
+            Some(dir.path().join("glossary.toml"))
+        );
+
+        std::fs::write(
+            &manifest,
+            "[workspace.metadata.i18n-check.languages.tiers]\ntier1 = [\"de\"]\n",
+        )
+        .unwrap();
+        let config = Config::load_from_cargo_manifest(&manifest)
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.languages.tiers.tier1, ["de"]);
+
+        std::fs::write(
+            &manifest,
+            "[package
+",
+        )
+        .unwrap();
+        assert!(matches!(
+            Config::load(&manifest),
+            Err(Error::Invalid {
+                what: "Cargo manifest",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join(DEFAULT_CONFIG_FILE);
+
+        assert!(matches!(
+            Config::load(&config_file),
+            Err(Error::Read { path, .. }) if path == config_file
+        ));
+
+        std::fs::write(
+            &config_file,
+            "[placeholders]
+syntax = \"$name\"\n",
+        )
+        .unwrap();
+        let err = Config::load(&config_file).unwrap_err();
+        assert!(
+            matches!(&err, Error::Invalid { what: "config file", path, .. } if *path == config_file),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config_file = dir.path().join(DEFAULT_CONFIG_FILE);
+        std::fs::write(
+            &config_file,
+            "[rules.too_many_placeholder]\nmax = 3\n[languages.aliases]\nzh-CN = \"zh_CN\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::validate(&config_file),
+            Ok(vec!["rules.too_many_placeholder".to_string()])
+        );
+
+        std::fs::write(&config_file, "[placeholders]\nsyntax = \"$name\"\n").unwrap();
+        assert!(Config::validate(&config_file).is_err());
+
+        let manifest = dir.path().join(CARGO_MANIFEST);
+        std::fs::write(&manifest, "[package]\nname = \"foo\"\n").unwrap();
+        assert_eq!(Config::validate(&manifest), Ok(Vec::new()));
+        std::fs::write(
+            &manifest,
+            "[package.metadata.i18n-check.rules.pseudo_text]\npattern = []\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::validate(&manifest),
+            Ok(vec![
+                "package.metadata.i18n-check.rules.pseudo_text.pattern".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join(DEFAULT_CONFIG_FILE);
+        std::fs::write(
+            &config_file,
+            "[project]\nlocale_file = \"locales/app.yml\"\nrust_src = [\"src\", \"xtask\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&config_file).unwrap();
+        assert_eq!(
+            config.project.locale_file,
+            Some(dir.path().join("locales/app.yml"))
+        );
+        assert_eq!(
+            config.project.rust_src,
+            [dir.path().join("src"), dir.path().join("xtask")]
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let config: Config = toml::from_str(
+            r#"
+[rules]
+enabled = ["MissingTranslations"]
+ignore_keys = ["Experimental"]
+
+[rules.severity]
+MissingTranslations = "warning"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.rules.selection(),
+            RuleSelection {
+                enabled: vec!["MissingTranslations".into()],
+                disabled: Vec::new(),
+                severities: BTreeMap::from([("MissingTranslations".into(), Severity::Warning)]),
+            }
+        );
+        assert_eq!(config.rules.ignore_keys, ["Experimental"]);
+    }
+
+    #[test]
+    fn test_rules_runs_by_default() {
+        let mut rules = RulesConfig::default();
+        assert!(rules.runs_by_default("MissingTranslations"));
+        assert!(!rules.runs_by_default("HardCodedStrings"));
+        assert!(!rules.runs_by_default("GlossaryTerms"));
+
+        rules.hard_coded_strings.enabled = true;
+        rules.glossary.file = Some("glossary.toml".into());
+        assert!(rules.runs_by_default("HardCodedStrings"));
+        assert!(rules.runs_by_default("GlossaryTerms"));
+    }
+
+    #[test]
+    fn test_empty_config() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config, Config::default());
+    }
+}
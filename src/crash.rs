@@ -0,0 +1,328 @@
+//! This file contains the panic hook, which turns panics into a concise report
+//! instead of a raw backtrace.
+//!
+//! Expected failures, e.g., a missing file, are [`Error`]s returned to `main`,
+//! so a panic is a bug: a report with the version, the subcommand and the file
+//! being parsed, if any, is printed, and a redacted bundle to attach to the bug
+//! report is written if `--bug-report-dir` is given.
+
+#[cfg(doc)]
+use crate::error::Error;
+use crate::logging;
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where bugs should be reported.
+const ISSUES_URL: &str = "https://github.com/topgrade-rs/topgrade_i18n_locale_checker/issues";
+
+/// The Rust file being parsed, included in the report as it likely caused the
+/// panic.
+static CURRENT_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Records that `file` is being parsed, `None` once it is done.
+pub(crate) fn set_current_file(file: Option<&Path>) {
+    *CURRENT_FILE.lock().unwrap_or_else(|e| e.into_inner()) = file.map(Path::to_path_buf);
+}
+
+/// Returns the Rust file being parsed, if any.
+fn current_file() -> Option<PathBuf> {
+    CURRENT_FILE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Installs the panic hook, `subcommand` is the subcommand being run, the
+/// bundle is written under `bundle_dir` if given.
+///
+/// With `--log-format json`, the panic is logged as an event as well.
+pub(crate) fn install_hook(subcommand: &'static str, bundle_dir: Option<PathBuf>) {
+    std::panic::set_hook(Box::new(move |info| {
+        let message = logging::panic_message(info.payload());
+        let location = info.location().map(ToString::to_string);
+        let file = current_file();
+        tracing::error!(
+            error = %message,
+            location,
+            file = file.as_ref().map(|file| file.display().to_string()),
+            "panicked"
+        );
+        if logging::is_json() {
+            return;
+        }
+
+        let report = Report {
+            version: env!("CARGO_PKG_VERSION"),
+            subcommand,
+            file: file.as_deref(),
+            message: &message,
+            location: location.as_deref(),
+        };
+        eprint!("{}", report.render());
+        match &bundle_dir {
+            Some(dir) => match write_bundle(dir, &report, &Backtrace::force_capture()) {
+                Ok(bundle) => eprintln!(
+                    "A redacted bug report bundle has been written to {}, please attach it to the issue",
+                    bundle.display()
+                ),
+                Err(e) => eprintln!("Cannot write the bug report bundle due to error {:?}", e),
+            },
+            None => eprintln!("Pass --bug-report-dir <DIR> to write a bundle to attach to the issue"),
+        }
+    }));
+}
+
+/// What is known about an unexpected panic.
+#[derive(Debug)]
+struct Report<'a> {
+    /// Version of this tool.
+    version: &'a str,
+    /// The subcommand being run.
+    subcommand: &'a str,
+    /// The Rust file being parsed, if any.
+    file: Option<&'a Path>,
+    /// The panic message.
+    message: &'a str,
+    /// Where it panicked in the code of this tool.
+    location: Option<&'a str>,
+}
+
+impl Report<'_> {
+    /// Renders the report shown to the user.
+    fn render(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "topgrade-i18n-check {} crashed, this is a bug, please report it at {}",
+            self.version, ISSUES_URL
+        )
+        .unwrap();
+        writeln!(out, "  subcommand: {}", self.subcommand).unwrap();
+        if let Some(file) = self.file {
+            writeln!(out, "  file: {}", file.display()).unwrap();
+        }
+        writeln!(out, "  error: {}", self.message).unwrap();
+        if let Some(location) = self.location {
+            writeln!(out, "  location: {}", location).unwrap();
+        }
+
+        out
+    }
+}
+
+/// Writes a bug report bundle for `report` to a new directory under `dir`,
+/// returns that directory.
+///
+/// The bundle has `report.txt`, with the report, the platform, the redacted
+/// command line and `backtrace`, and, if a Rust file was being parsed, that
+/// file with its strings and comments redacted, see [`redact_rust()`].
+fn write_bundle(dir: &Path, report: &Report, backtrace: &Backtrace) -> std::io::Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let bundle = dir.join(format!("topgrade-i18n-check-crash-{}", timestamp));
+    std::fs::create_dir_all(&bundle)?;
+
+    let home = std::env::var("HOME").ok();
+    let redact_home = |s: &str| match home.as_deref() {
+        Some(home) if !home.is_empty() => s.replace(home, "~"),
+        _ => s.to_string(),
+    };
+    let mut contents = redact_home(&report.render());
+    writeln!(
+        contents,
+        "  platform: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+    .unwrap();
+    let args = std::env::args().collect::<Vec<_>>().join(" ");
+    writeln!(contents, "  command line: {}", redact_home(&args)).unwrap();
+    writeln!(contents, "\nBacktrace:\n{}", backtrace).unwrap();
+    std::fs::write(bundle.join("report.txt"), contents)?;
+
+    if let Some(file) = report.file {
+        if let (Ok(source), Some(name)) = (std::fs::read_to_string(file), file.file_name()) {
+            std::fs::write(bundle.join(name), redact_rust(&source))?;
+        }
+    }
+
+    Ok(bundle)
+}
+
+/// Redacts the contents of the string literals and comments in the Rust code
+/// `source`: letters become `x` and digits become `0`, everything else, e.g.,
+/// the code, escapes and placeholders, is kept so that the code still
+/// reproduces the crash.
+pub(crate) fn redact_rust(source: &str) -> String {
+    /// Redacts a single character.
+    fn redact(c: char) -> char {
+        if c.is_alphabetic() {
+            'x'
+        } else if c.is_numeric() {
+            '0'
+        } else {
+            c
+        }
+    }
+
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    out.push(redact(c));
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                out.push(chars.next().expect("peeked"));
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    out.push(redact(c));
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            // A raw string, `r"..."` or `r#"..."#`
+            'r' if matches!(chars.peek(), Some('"' | '#'))
+                && !out[..out.len() - 1].ends_with(|c: char| c.is_alphanumeric() || c == '_') =>
+            {
+                let mut n_hashes = 0;
+                while chars.peek() == Some(&'#') {
+                    out.push(chars.next().expect("peeked"));
+                    n_hashes += 1;
+                }
+                if chars.peek() != Some(&'"') {
+                    continue;
+                }
+                out.push(chars.next().expect("peeked"));
+                let terminator = format!("\"{}", "#".repeat(n_hashes));
+                let mut literal = String::new();
+                for c in chars.by_ref() {
+                    literal.push(c);
+                    if literal.ends_with(&terminator) {
+                        break;
+                    }
+                }
+                let contents_len = literal.len().saturating_sub(terminator.len());
+                out.extend(literal[..contents_len].chars().map(redact));
+                out.push_str(&literal[contents_len..]);
+            }
+            // A char literal, which may be `'"'`, rather than a lifetime
+            '\'' => {
+                let mut lookahead = chars.clone();
+                match (lookahead.next(), lookahead.next()) {
+                    (Some('\\'), _) => {
+                        out.extend(chars.next());
+                        out.extend(chars.next());
+                        for c in chars.by_ref() {
+                            out.push(c);
+                            if c == '\'' {
+                                break;
+                            }
+                        }
+                    }
+                    (Some(_), Some('\'')) => {
+                        out.extend(chars.next());
+                        out.extend(chars.next());
+                    }
+                    _ => {}
+                }
+            }
+            '"' => {
+                while let Some(c) = chars.next() {
+                    if c == '\\' {
+                        out.push(c);
+                        out.extend(chars.next());
+                        continue;
+                    }
+                    out.push(redact(c));
+                    if c == '"' {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_render() {
+        let report = Report {
+            version: "0.1.0",
+            subcommand: "check",
+            file: Some(Path::new("src/main.rs")),
+            message: "index out of bounds",
+            location: Some("src/rules/mod.rs:1:1"),
+        };
+        assert_eq!(
+            report.render(),
+            format!(
+                "topgrade-i18n-check 0.1.0 crashed, this is a bug, please report it at {}
+  subcommand: check
+  file: src/main.rs
+  error: index out of bounds
+  location: src/rules/mod.rs:1:1
+",
+                ISSUES_URL
+            )
+        );
+    }
+
+    #[test]
+    fn test_redact_rust() {
+        assert_eq!(
+            redact_rust("// Secret 42\nfn main() { t!(\"Hello {name}\\n\"); }\n"),
+            "// xxxxxx 00\nfn main() { t!(\"xxxxx {xxxx}\\n\"); }\n"
+        );
+        assert_eq!(
+            redact_rust("let s = r#\"a \"b\"#; /* c */ x"),
+            "let s = r#\"x \"x\"#; /* x */ x"
+        );
+        assert_eq!(redact_rust("for x in iter {}"), "for x in iter {}");
+        assert_eq!(
+            redact_rust("if c == '\"' || c == '\\'' { f::<'a>(\"b\") }"),
+            "if c == '\"' || c == '\\'' { f::<'a>(\"x\") }"
+        );
+    }
+
+    #[test]
+    fn test_write_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "t!(\"Restarting\");\n").unwrap();
+        let report = Report {
+            version: "0.1.0",
+            subcommand: "check",
+            file: Some(&file),
+            message: "index out of bounds",
+            location: None,
+        };
+
+        let bundle = write_bundle(dir.path(), &report, &Backtrace::disabled()).unwrap();
+        let contents = std::fs::read_to_string(bundle.join("report.txt")).unwrap();
+        assert!(contents.contains("  error: index out of bounds\n"));
+        assert_eq!(
+            std::fs::read_to_string(bundle.join("lib.rs")).unwrap(),
+            "t!(\"xxxxxxxxxx\");\n"
+        );
+    }
+}
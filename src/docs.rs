@@ -0,0 +1,168 @@
+//! This file contains the `docs` subcommand, which renders the documentation
+//! of the rules to Markdown.
+
+use crate::error::Error;
+use crate::rules::RuleDoc;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Name of the index file written by [`write_files()`].
+const INDEX_FILE: &str = "index.md";
+
+/// Renders the documentation of `doc`, with headings starting at `level`.
+pub(crate) fn render_rule(doc: &RuleDoc, level: usize) -> String {
+    let heading = "#".repeat(level);
+    let subheading = "#".repeat(level + 1);
+
+    let mut out = String::new();
+    writeln!(out, "{} {}: {}", heading, doc.code, doc.name).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "{}", doc.description).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "{} Rationale", subheading).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "{}", doc.rationale).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "{} Options", subheading).unwrap();
+    writeln!(out).unwrap();
+    if doc.options.is_empty() {
+        writeln!(out, "None.").unwrap();
+    }
+    for (option, description) in doc.options {
+        writeln!(out, "* `{}`: {}", option, description).unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "{} Examples", subheading).unwrap();
+    for (title, example) in [("Failing", doc.failing), ("Passing", doc.passing)] {
+        writeln!(out).unwrap();
+        writeln!(out, "{}:", title).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "```{}", doc.example_syntax).unwrap();
+        write!(out, "{}", example).unwrap();
+        writeln!(out, "```").unwrap();
+    }
+
+    out
+}
+
+/// Renders a table of the rules, linking to `link(doc)` if given.
+fn render_table(docs: &[&RuleDoc], link: Option<fn(&RuleDoc) -> String>) -> String {
+    let mut out = String::new();
+    writeln!(out, "| Code | Rule | Description |").unwrap();
+    writeln!(out, "| --- | --- | --- |").unwrap();
+    for doc in docs {
+        let code = match link {
+            Some(link) => format!("[{}]({})", doc.code, link(doc)),
+            None => doc.code.to_string(),
+        };
+        writeln!(out, "| {} | {} | {} |", code, doc.name, doc.description).unwrap();
+    }
+
+    out
+}
+
+/// Renders the documentation of every rule in `docs` as a single page.
+pub(crate) fn render_page(docs: &[&RuleDoc]) -> String {
+    let mut out = String::from("# Rules\n\n");
+    out.push_str(&render_table(docs, None));
+    for doc in docs {
+        out.push('\n');
+        out.push_str(&render_rule(doc, 2));
+    }
+
+    out
+}
+
+/// Writes the documentation of every rule in `docs` to `<code>.md` under
+/// `dir`, along with an index linking to them.
+pub(crate) fn write_files(dir: &Path, docs: &[&RuleDoc]) -> Result<(), Error> {
+    let write = |name: &str, contents: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).map_err(|error| Error::Write { path, error })
+    };
+
+    std::fs::create_dir_all(dir).map_err(|error| Error::Write {
+        path: dir.to_path_buf(),
+        error,
+    })?;
+    let mut index = String::from("# Rules\n\n");
+    index.push_str(&render_table(docs, Some(|doc| format!("{}.md", doc.code))));
+    write(INDEX_FILE, &index)?;
+    for doc in docs {
+        write(&format!("{}.md", doc.code), &render_rule(doc, 1))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RULES;
+
+    const DOC: RuleDoc = RuleDoc {
+        name: "Foo",
+        code: "I18N999",
+        description: "Checks foo.",
+        rationale: "Foo is bad.",
+        options: &[("rules.foo.max", "Max foo.")],
+        example_syntax: "yaml",
+        failing: "foo:\n  en: foo\n",
+        passing: "bar:\n  en: bar\n",
+    };
+
+    #[test]
+    fn test_render_rule() {
+        assert_eq!(
+            render_rule(&DOC, 1),
+            "# I18N999: Foo
+
+Checks foo.
+
+## Rationale
+
+Foo is bad.
+
+## Options
+
+* `rules.foo.max`: Max foo.
+
+## Examples
+
+Failing:
+
+```yaml
+foo:
+  en: foo
+```
+
+Passing:
+
+```yaml
+bar:
+  en: bar
+```
+"
+        );
+    }
+
+    #[test]
+    fn test_render_page() {
+        let page = render_page(&[&DOC]);
+        assert!(page.starts_with(
+            "# Rules\n\n| Code | Rule | Description |\n| --- | --- | --- |\n| I18N999 | Foo | Checks foo. |\n\n## I18N999: Foo\n"
+        ));
+    }
+
+    #[test]
+    fn test_write_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = dir.path().join("rules");
+        write_files(&out_dir, &RULES).unwrap();
+
+        let index = std::fs::read_to_string(out_dir.join(INDEX_FILE)).unwrap();
+        assert!(index.contains("| [I18N003](I18N003.md) | UseOfKeysDoNotExist |"));
+        let rule = std::fs::read_to_string(out_dir.join("I18N003.md")).unwrap();
+        assert!(rule.starts_with("# I18N003: UseOfKeysDoNotExist\n"));
+    }
+}
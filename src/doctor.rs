@@ -0,0 +1,402 @@
+//! This file contains the `doctor` subcommand, which validates the setup and
+//! suggests how to fix every problem found.
+
+use crate::config::{Config, ScanConfig};
+use crate::interner::Interner;
+use crate::locale_file_parser::LOCALE_FILE_VERSION;
+use crate::locale_key_collector::{skip_reason, LocaleKeyCollector};
+use crate::project::Project;
+use crate::source::Source;
+use crate::timings::Timings;
+use indicatif::ProgressBar;
+use serde_yaml_ng::Value as Yaml;
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Status {
+    /// Nothing wrong.
+    Ok,
+    /// Works, but probably not as intended.
+    Warning,
+    /// Does not work.
+    Failure,
+}
+
+/// Result of a check.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Finding {
+    /// Outcome.
+    pub(crate) status: Status,
+    /// What has been found.
+    pub(crate) message: String,
+    /// How to fix it, `None` if there is nothing to fix.
+    pub(crate) suggestion: Option<String>,
+}
+
+impl Finding {
+    /// Creates a [`Status::Ok`] finding.
+    fn ok(message: String) -> Self {
+        Self {
+            status: Status::Ok,
+            message,
+            suggestion: None,
+        }
+    }
+
+    /// Creates a finding that needs fixing.
+    fn problem(status: Status, message: String, suggestion: &str) -> Self {
+        Self {
+            status,
+            message,
+            suggestion: Some(suggestion.to_string()),
+        }
+    }
+}
+
+/// Checks the config file, `None` if there is no config file.
+pub(crate) fn check_config(config_file: Option<&Path>) -> Vec<Finding> {
+    let Some(path) = config_file else {
+        return vec![Finding::ok(
+            "No config file found, the defaults are used".to_string(),
+        )];
+    };
+
+    match Config::validate(path) {
+        Ok(unknown_keys) => {
+            let mut findings = vec![Finding::ok(format!(
+                "Config file {} is valid",
+                path.display()
+            ))];
+            findings.extend(unknown_keys.into_iter().map(|key| {
+                Finding::problem(
+                    Status::Warning,
+                    format!("Unknown config key `{}` is ignored", key),
+                    "Check the spelling, `--print-config` shows every known option",
+                )
+            }));
+            findings
+        }
+        Err(e) => vec![Finding::problem(
+            Status::Failure,
+            format!("Config file is invalid: {}", e),
+            "Fix the config file, `init` writes a starter config with every option documented",
+        )],
+    }
+}
+
+/// Checks if the locale file exists, parses, and has the expected version.
+pub(crate) fn check_locale_file(locale_file: &Path) -> Vec<Finding> {
+    let contents = match std::fs::read_to_string(locale_file) {
+        Ok(contents) => contents,
+        Err(e) => {
+            return vec![Finding::problem(
+                Status::Failure,
+                format!("Cannot read locale file {}: {}", locale_file.display(), e),
+                "Pass the locale file with `--locale-file`, or the project root with `--project`",
+            )]
+        }
+    };
+
+    let yaml: Yaml = match serde_yaml_ng::from_str(&contents) {
+        Ok(yaml) => yaml,
+        Err(e) => {
+            return vec![Finding::problem(
+                Status::Failure,
+                format!(
+                    "Locale file {} is not valid YAML: {}",
+                    locale_file.display(),
+                    e
+                ),
+                "Fix the YAML syntax at the reported location",
+            )]
+        }
+    };
+    let Yaml::Mapping(mapping) = yaml else {
+        return vec![Finding::problem(
+            Status::Failure,
+            format!("Locale file {} is not a mapping", locale_file.display()),
+            "The locale file should map every key to its translations",
+        )];
+    };
+
+    let mut findings = vec![Finding::ok(format!(
+        "Locale file {} parses, {} keys",
+        locale_file.display(),
+        mapping.len().saturating_sub(1)
+    ))];
+    let version = mapping.get("_version").and_then(Yaml::as_i64);
+    if version != Some(LOCALE_FILE_VERSION) {
+        let found = match version {
+            Some(version) => format!("version {}", version),
+            None => "no `_version`".to_string(),
+        };
+        findings.push(Finding::problem(
+            Status::Failure,
+            format!(
+                "Locale file has {}, expected version {}",
+                found, LOCALE_FILE_VERSION
+            ),
+            "Add `_version: 2` to the top of the locale file and use the version 2 layout",
+        ));
+    }
+
+    findings
+}
+
+/// Checks if the Rust files of `project`, walked within the limits in `scan`,
+/// exist and parse, and if at least one `t!()` invocation is found in them.
+pub(crate) fn check_rust_src(project: &Project, scan: &ScanConfig) -> Vec<Finding> {
+    let missing = project
+        .rust_src()
+        .iter()
+        .filter(|path| !path.exists())
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return vec![Finding::problem(
+            Status::Failure,
+            format!("Rust sources [{}] do not exist", missing.join(", ")),
+            "Pass the Rust sources with `--rust-src-to-check`, or the project root with `--project`",
+        )];
+    }
+
+    let mut findings = Vec::new();
+    let mut parsable: Vec<Cow<'_, Path>> = Vec::new();
+    let files = match project.rust_src_to_check(&Source::WorkTree, scan) {
+        Ok(files) => files,
+        Err(e) => {
+            return vec![Finding::problem(
+                Status::Failure,
+                e.to_string(),
+                "Pass the Rust sources with `--rust-src-to-check`, or raise the limit in the config",
+            )];
+        }
+    };
+    for file in files {
+        let contents = match std::fs::read(&file) {
+            Ok(contents) => contents,
+            Err(e) => {
+                findings.push(Finding::problem(
+                    Status::Failure,
+                    format!("Rust file {} cannot be read: {}", file.display(), e),
+                    "Check the permissions of the file",
+                ));
+                continue;
+            }
+        };
+        if let Some(reason) = skip_reason(&contents, scan.max_file_size) {
+            findings.push(Finding::problem(
+                Status::Warning,
+                format!("Rust file {} is skipped, {}", file.display(), reason),
+                "Exclude the file from `--rust-src-to-check` if it is generated",
+            ));
+        } else if std::str::from_utf8(&contents)
+            .ok()
+            .is_some_and(|contents| syn::parse_file(contents).is_ok())
+        {
+            parsable.push(file);
+        } else {
+            findings.push(Finding::problem(
+                Status::Failure,
+                format!("Rust file {} cannot be parsed", file.display()),
+                "Run `cargo check` to find the syntax error",
+            ));
+        }
+    }
+
+    let mut collector = LocaleKeyCollector::new(&scan.macros);
+    if let Err(e) = collector.collect(
+        &parsable,
+        &Source::WorkTree,
+        scan.max_file_size,
+        &mut Interner::new(),
+        &mut Timings::new(),
+        &ProgressBar::hidden(),
+    ) {
+        findings.push(Finding::problem(
+            Status::Failure,
+            e.to_string(),
+            "Pass a string literal as the key of the invocation",
+        ));
+        return findings;
+    }
+    let n_invocations = collector.locale_keys().len();
+    if n_invocations == 0 {
+        findings.push(Finding::problem(
+            Status::Failure,
+            format!("No `t!()` invocation found in {} Rust files", parsable.len()),
+            "Check that `--rust-src-to-check` points to the code that calls `t!()`, invocations nested in macros other than `format!()` and the like are not found",
+        ));
+    } else {
+        findings.push(Finding::ok(format!(
+            "{} `t!()` invocations found in {} Rust files",
+            n_invocations,
+            parsable.len()
+        )));
+    }
+
+    findings
+}
+
+/// Checks if git is available, which `--git-rev` and `compare` need.
+pub(crate) fn check_git() -> Finding {
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            Finding::ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+        _ => Finding::problem(
+            Status::Warning,
+            "git is not available, `--git-rev` and `compare` won't work".to_string(),
+            "Install git and make sure it is in PATH",
+        ),
+    }
+}
+
+/// Renders `findings` in a human-readable way.
+pub(crate) fn render(findings: &[Finding]) -> String {
+    let mut out = String::new();
+    for finding in findings {
+        let status = match finding.status {
+            Status::Ok => "ok",
+            Status::Warning => "warn",
+            Status::Failure => "FAIL",
+        };
+        writeln!(out, "[{:>4}] {}", status, finding.message).unwrap();
+        if let Some(suggestion) = &finding.suggestion {
+            writeln!(out, "       help: {}", suggestion).unwrap();
+        }
+    }
+
+    out
+}
+
+/// Runs every check against `projects` and the config file, prints the
+/// findings, returns true if any check fails.
+pub(crate) fn doctor(projects: &[Project], config_file: Option<&Path>) -> bool {
+    let mut findings = check_config(config_file);
+    let scan = config_file
+        .and_then(|path| Config::load(path).ok())
+        .map(|config| config.scan)
+        .unwrap_or_default();
+    for project in projects {
+        findings.extend(check_locale_file(project.locale_file()));
+        if !project.rust_src().is_empty() {
+            findings.extend(check_rust_src(project, &scan));
+        }
+    }
+    findings.push(check_git());
+
+    print!("{}", render(&findings));
+
+    findings
+        .iter()
+        .any(|finding| finding.status == Status::Failure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn statuses(findings: &[Finding]) -> Vec<Status> {
+        findings.iter().map(|finding| finding.status).collect()
+    }
+
+    #[test]
+    fn test_check_config() {
+        assert_eq!(statuses(&check_config(None)), [Status::Ok]);
+
+        let dir = tempdir().unwrap();
+        let config_file = dir.path().join("config.toml");
+        std::fs::write(&config_file, "[rule]\n").unwrap();
+        assert_eq!(
+            statuses(&check_config(Some(&config_file))),
+            [Status::Ok, Status::Warning]
+        );
+
+        std::fs::write(&config_file, "[rules\n").unwrap();
+        assert_eq!(
+            statuses(&check_config(Some(&config_file))),
+            [Status::Failure]
+        );
+    }
+
+    #[test]
+    fn test_check_locale_file() {
+        let dir = tempdir().unwrap();
+        let locale_file = dir.path().join("app.yml");
+        assert_eq!(
+            statuses(&check_locale_file(&locale_file)),
+            [Status::Failure]
+        );
+
+        std::fs::write(&locale_file, "_version: 2\nRestarting:\n  en: Restarting\n").unwrap();
+        assert_eq!(
+            check_locale_file(&locale_file),
+            [Finding::ok(format!(
+                "Locale file {} parses, 1 keys",
+                locale_file.display()
+            ))]
+        );
+
+        std::fs::write(&locale_file, "_version: 1\n").unwrap();
+        assert_eq!(
+            statuses(&check_locale_file(&locale_file)),
+            [Status::Ok, Status::Failure]
+        );
+
+        std::fs::write(&locale_file, "a: [\n").unwrap();
+        assert_eq!(
+            statuses(&check_locale_file(&locale_file)),
+            [Status::Failure]
+        );
+    }
+
+    #[test]
+    fn test_check_rust_src() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        let project = Project::new(dir.path().join("app.yml"), vec![src.clone()]);
+        assert_eq!(
+            statuses(&check_rust_src(&project, &ScanConfig::default())),
+            [Status::Failure]
+        );
+
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("main.rs"), "fn main() {}\n").unwrap();
+        assert_eq!(
+            statuses(&check_rust_src(&project, &ScanConfig::default())),
+            [Status::Failure]
+        );
+
+        std::fs::write(src.join("lib.rs"), "fn f() { t!(\"Restarting\"); }\n").unwrap();
+        std::fs::write(src.join("broken.rs"), "fn {\n").unwrap();
+        assert_eq!(
+            statuses(&check_rust_src(&project, &ScanConfig::default())),
+            [Status::Failure, Status::Ok]
+        );
+
+        std::fs::remove_file(src.join("broken.rs")).unwrap();
+        std::fs::write(src.join("blob.rs"), b"\0\x01").unwrap();
+        assert_eq!(
+            statuses(&check_rust_src(&project, &ScanConfig::default())),
+            [Status::Warning, Status::Ok]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let findings = [
+            Finding::ok("Fine".to_string()),
+            Finding::problem(Status::Warning, "Hmm".to_string(), "Fix it"),
+        ];
+        assert_eq!(
+            render(&findings),
+            "[  ok] Fine\n[warn] Hmm\n       help: Fix it\n"
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! This file contains the output formats meant to be parsed by editors, which
+//! point every diagnostic at a file, line and column.
+//!
+//! Diagnostics found in the Rust files point at the `t!()` invocation,
+//! diagnostics about a locale key point at where the key is defined in the
+//! locale file, or at its first line if the definition cannot be found.
+
+use crate::checker::Checker;
+use crate::explain::key_line;
+use crate::rules::{code_of, Diagnostic, Severity};
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// How diagnostics are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Human-readable report, organized according to `--layout`.
+    #[default]
+    Text,
+    /// `file:line:col: severity: message`, Vim's default `errorformat`
+    /// recognizes it, e.g., `:cexpr system('topgrade-i18n-check --format quickfix')`.
+    Quickfix,
+    /// `path:line:column: error: [I18N003] message`, the GCC style that Emacs
+    /// compilation-mode, VS Code problem matchers and most editors parse.
+    Gcc,
+    /// The diagnostics of every project with their rule, code, severity,
+    /// location, key and message, along with statistics, in one JSON document.
+    Json,
+    /// A SARIF 2.1.0 log, for GitHub code scanning.
+    Sarif,
+    /// A JUnit XML report, a test suite per rule and a test case per key it
+    /// reports, for CI systems that render test results.
+    Junit,
+    /// `::error file=path,line=1,col=1,title=Rule (I18N003)::message`, GitHub
+    /// Actions workflow commands, which annotate the pull request diff.
+    Github,
+}
+
+impl OutputFormat {
+    /// Returns true if the diagnostics of every project are printed as a
+    /// single document, once every project is checked.
+    pub(crate) fn is_document(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json | OutputFormat::Sarif | OutputFormat::Junit
+        )
+    }
+}
+
+/// Where a diagnostic points to.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Location<'a> {
+    /// The file.
+    pub(crate) file: Cow<'a, Path>,
+    /// Line number, starts from 1.
+    pub(crate) line: usize,
+    /// Column number, starts from 1.
+    pub(crate) column: usize,
+    /// What the diagnostic is about, e.g., `key 'Restarting'`, can be empty.
+    pub(crate) subject: Cow<'a, str>,
+}
+
+impl<'a> Location<'a> {
+    /// Locates `diagnostic`, either at its [`Diagnostic::location`] in the
+    /// Rust files, or at its key in `locale_file`, whose contents are
+    /// `locale_file_contents`.
+    ///
+    /// Columns of [`Diagnostic::location`] start from 0, they are converted
+    /// to start from 1.
+    pub(crate) fn of(
+        diagnostic: &'a Diagnostic,
+        locale_file: &'a Path,
+        locale_file_contents: &str,
+    ) -> Self {
+        let subject = subject_of(&diagnostic.key);
+        match &diagnostic.location {
+            Some(location) => Self {
+                file: Cow::Borrowed(&location.file),
+                line: location.line,
+                column: location.column + 1,
+                subject,
+            },
+            None => Self {
+                file: Cow::Borrowed(locale_file),
+                line: key_line(locale_file_contents, &diagnostic.key).unwrap_or(1),
+                column: 1,
+                subject,
+            },
+        }
+    }
+}
+
+/// Returns what the diagnostic whose key is `key` is about, see
+/// [`Location::subject`], empty if `key` is.
+pub(crate) fn subject_of(key: &str) -> Cow<'_, str> {
+    if key.is_empty() {
+        return Cow::Borrowed("");
+    }
+
+    Cow::Owned(format!("key '{}'", key))
+}
+
+/// Writes the diagnostics found by `checker` in `format`, which should be one
+/// meant for editors, i.e., neither [`OutputFormat::Text`] nor a document.
+pub(crate) fn write_diagnostics(
+    out: &mut impl Write,
+    format: OutputFormat,
+    checker: &Checker,
+    locale_file: &Path,
+    locale_file_contents: &str,
+) -> io::Result<()> {
+    let diagnostics = checker
+        .errors()
+        .map(|(rule, diagnostic)| {
+            (
+                Location::of(diagnostic, locale_file, locale_file_contents),
+                rule,
+                diagnostic,
+            )
+        })
+        .collect();
+
+    write_located(out, format, diagnostics)
+}
+
+/// Writes `diagnostics`, which are already located, `(Location, RuleName,
+/// Diagnostic)`, in `format`, which should be one meant for editors, see
+/// [`write_diagnostics()`].
+///
+/// Diagnostics are sorted by location so that editors list them in order.
+pub(crate) fn write_located(
+    out: &mut impl Write,
+    format: OutputFormat,
+    mut diagnostics: Vec<(Location<'_>, &str, &Diagnostic)>,
+) -> io::Result<()> {
+    diagnostics.sort_by(|(loc_a, rule_a, a), (loc_b, rule_b, b)| {
+        (&loc_a.file, loc_a.line, loc_a.column, rule_a, a).cmp(&(
+            &loc_b.file,
+            loc_b.line,
+            loc_b.column,
+            rule_b,
+            b,
+        ))
+    });
+
+    for (location, rule, diagnostic) in diagnostics {
+        let mut message = location.subject.to_string();
+        if let Some(error_msg) = &diagnostic.error_msg {
+            message.push_str(": ");
+            message.push_str(error_msg);
+        }
+
+        if format == OutputFormat::Github {
+            write_workflow_command(out, &location, rule, diagnostic.severity, &message)?;
+            continue;
+        }
+
+        let (severity, message) = match format {
+            OutputFormat::Text | OutputFormat::Json | OutputFormat::Sarif | OutputFormat::Junit => {
+                unreachable!("the {:?} format is not meant for editors", format)
+            }
+            OutputFormat::Github => unreachable!("workflow commands are written above"),
+            OutputFormat::Quickfix => (
+                diagnostic.severity.as_str(),
+                format!("{}: {}", rule, message),
+            ),
+            OutputFormat::Gcc => (
+                gcc_severity(diagnostic.severity),
+                format!("[{}] {}", code_of(rule), message),
+            ),
+        };
+        writeln!(
+            out,
+            "{}:{}:{}: {}: {}",
+            location.file.display(),
+            location.line,
+            location.column,
+            severity,
+            single_line(&message)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes a diagnostic found by `rule` at `location` as a GitHub Actions
+/// workflow command, `::error`, `::warning` or `::notice`.
+fn write_workflow_command(
+    out: &mut impl Write,
+    location: &Location<'_>,
+    rule: &str,
+    severity: Severity,
+    message: &str,
+) -> io::Result<()> {
+    let command = match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "notice",
+    };
+    writeln!(
+        out,
+        "::{} file={},line={},col={},title={}::{}",
+        command,
+        escape_workflow_property(&location.file.display().to_string()),
+        location.line,
+        location.column,
+        escape_workflow_property(&format!("{} ({})", rule, code_of(rule))),
+        escape_workflow_data(message)
+    )
+}
+
+/// Escapes the message of a workflow command, which takes a single line.
+fn escape_workflow_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property of a workflow command, whose `:` and `,` are delimiters.
+fn escape_workflow_property(property: &str) -> String {
+    escape_workflow_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Returns how GCC names `severity`.
+fn gcc_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Escapes newlines in `message` so that a diagnostic takes a single line.
+fn single_line(message: &str) -> Cow<'_, str> {
+    if message.contains('\n') {
+        Cow::Owned(message.replace('\n', "\\n"))
+    } else {
+        Cow::Borrowed(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{KeyEngMatchesConfig, PlaceholderSyntax};
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use crate::locale_key_collector::LocaleKey;
+    use crate::rules::key_and_eng_matches::KeyEngMatches;
+    use crate::rules::use_of_keys_do_not_exist::UseOfKeysDoNotExist;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indexmap::IndexMap;
+    use indicatif::ProgressBar;
+
+    #[test]
+    fn test_location_of() {
+        let diagnostic =
+            Diagnostic::error("Restarting".into(), None).at(Path::new("src/main.rs").into(), 3, 4);
+        assert_eq!(
+            Location::of(&diagnostic, Path::new("app.yml"), ""),
+            Location {
+                file: Cow::Borrowed(Path::new("src/main.rs")),
+                line: 3,
+                column: 5,
+                subject: Cow::Borrowed("key 'Restarting'"),
+            }
+        );
+
+        let diagnostic =
+            Diagnostic::error("".into(), None).at(Path::new("src/main.rs").into(), 3, 4);
+        assert_eq!(
+            Location::of(&diagnostic, Path::new("app.yml"), "").subject,
+            ""
+        );
+
+        let diagnostic = Diagnostic::error("Restarting".into(), None);
+        assert_eq!(
+            Location::of(
+                &diagnostic,
+                Path::new("app.yml"),
+                "_version: 2\nRestarting:\n"
+            ),
+            Location {
+                file: Cow::Borrowed(Path::new("app.yml")),
+                line: 2,
+                column: 1,
+                subject: Cow::Borrowed("key 'Restarting'"),
+            }
+        );
+        assert_eq!(Location::of(&diagnostic, Path::new("app.yml"), "").line, 1);
+    }
+
+    #[test]
+    fn test_write_diagnostics() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Restarting".into(),
+                Translations {
+                    en: Some("Restart".into()),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let locale_keys = vec![LocaleKey {
+            key: "Pulling".into(),
+            file: Path::new("src/steps/git.rs").into(),
+            line: 10,
+            column: 4,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
+        }];
+        let mut checker = Checker::new();
+        checker.register_rule(KeyEngMatches::new(
+            PlaceholderSyntax::default(),
+            KeyEngMatchesConfig::default(),
+            Default::default(),
+        ));
+        checker.register_rule(UseOfKeysDoNotExist::new(Default::default()));
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+        checker.check(&ctx, &mut Timings::new(), &ProgressBar::hidden());
+
+        let mut out = Vec::new();
+        write_diagnostics(
+            &mut out,
+            OutputFormat::Quickfix,
+            &checker,
+            Path::new("locales/app.yml"),
+            "_version: 2\nRestarting:\n  en: Restart\n",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "locales/app.yml:2:1: error: KeyEngMatches: key 'Restarting'\n\
+             src/steps/git.rs:10:5: error: UseOfKeysDoNotExist: key 'Pulling'\n"
+        );
+
+        let mut out = Vec::new();
+        write_diagnostics(
+            &mut out,
+            OutputFormat::Gcc,
+            &checker,
+            Path::new("locales/app.yml"),
+            "_version: 2\nRestarting:\n  en: Restart\n",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "locales/app.yml:2:1: error: [I18N002] key 'Restarting'\n\
+             src/steps/git.rs:10:5: error: [I18N003] key 'Pulling'\n"
+        );
+    }
+
+    #[test]
+    fn test_write_located_github() {
+        let diagnostic = Diagnostic {
+            location: None,
+            key: "Restarting".into(),
+            error_msg: Some("100% done,\nreally".into()),
+            severity: Severity::Warning,
+            langs: Vec::new(),
+        };
+        let location = Location {
+            file: Cow::Borrowed(Path::new("locales/a,b.yml")),
+            line: 2,
+            column: 1,
+            subject: Cow::Borrowed("key 'Restarting'"),
+        };
+        let mut out = Vec::new();
+        write_located(
+            &mut out,
+            OutputFormat::Github,
+            vec![(location, "KeyEngMatches", &diagnostic)],
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "::warning file=locales/a%2Cb.yml,line=2,col=1,title=KeyEngMatches (I18N002)::key 'Restarting': 100%25 done,%0Areally\n"
+        );
+    }
+
+    #[test]
+    fn test_gcc_severity() {
+        assert_eq!(gcc_severity(Severity::Error), "error");
+        assert_eq!(gcc_severity(Severity::Info), "note");
+    }
+
+    #[test]
+    fn test_single_line() {
+        assert_eq!(single_line("foo"), "foo");
+        assert_eq!(single_line("foo\nbar"), "foo\\nbar");
+    }
+}
@@ -0,0 +1,222 @@
+//! This file contains type [`Error`], why this tool cannot finish the check,
+//! as opposed to the problems the rules find, and the exit codes.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Exit code when the check finds any error.
+pub(crate) const EXIT_CODE_ON_ERROR: i32 = 1;
+
+/// Exit code when the check cannot be done, e.g., the options are invalid, or
+/// a file cannot be read or parsed, same as the one of clap's usage errors.
+pub(crate) const EXIT_CODE_ON_FAILURE: i32 = 2;
+
+/// Why this tool cannot finish, reported to the user as `Error: <Display>`.
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// The options or the config are invalid, e.g., they do not make sense
+    /// together.
+    Usage(String),
+    /// The file at `path` cannot be read.
+    Read {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// The file at `path` cannot be written.
+    Write {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// `git <command>` failed, `message` is what git printed to stderr, or why
+    /// it could not be run.
+    Git { command: String, message: String },
+    /// The locale file at `path` is not valid YAML.
+    Yaml {
+        path: PathBuf,
+        error: serde_yaml_ng::Error,
+    },
+    /// The locale file at `path` is valid YAML, but not a valid locale file,
+    /// e.g., `_version` is missing.
+    LocaleFile { path: PathBuf, message: String },
+    /// The Rust file at `path` cannot be parsed.
+    RustSyntax { path: PathBuf, error: syn::Error },
+    /// A translation macro invocation in the Rust file at `path` cannot be
+    /// understood, e.g., its key is not a string literal.
+    Invocation {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// `path`, under the directory `dir`, is nested deeper than `max_depth`
+    /// levels, the Rust source path is probably wrong.
+    TooDeep {
+        path: PathBuf,
+        dir: PathBuf,
+        max_depth: usize,
+    },
+    /// More than `max_files` files are found under `paths`, the Rust source
+    /// paths are probably wrong.
+    TooManyFiles {
+        paths: Vec<PathBuf>,
+        max_files: usize,
+    },
+    /// No Rust file is found under `paths`, or no `t!()` invocation in the
+    /// `n_rust_files` found, which usually means a mistyped path.
+    EmptyScan {
+        paths: Vec<PathBuf>,
+        n_rust_files: usize,
+    },
+    /// Another file this tool reads, described by `what`, e.g., `baseline`, at
+    /// `path` is invalid.
+    Invalid {
+        what: &'static str,
+        path: PathBuf,
+        message: String,
+    },
+    /// Something else failed, e.g., the history database.
+    Other(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(message) | Error::Other(message) => write!(f, "{}", message),
+            Error::Read { path, error } => write!(f, "cannot read {}: {}", path.display(), error),
+            Error::Write { path, error } => {
+                write!(f, "cannot write {}: {}", path.display(), error)
+            }
+            Error::Git { command, message } => write!(f, "`git {}` failed: {}", command, message),
+            Error::Yaml { path, error } => {
+                write!(f, "invalid YAML in {}: {}", path.display(), error)
+            }
+            Error::LocaleFile { path, message } => {
+                write!(f, "invalid locale file {}: {}", path.display(), message)
+            }
+            Error::RustSyntax { path, error } => {
+                let start = error.span().start();
+                write!(
+                    f,
+                    "cannot parse {}:{}:{}: {}",
+                    path.display(),
+                    start.line,
+                    start.column + 1,
+                    error
+                )
+            }
+            Error::Invocation {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column + 1, message),
+            Error::TooDeep {
+                path,
+                dir,
+                max_depth,
+            } => write!(
+                f,
+                "{} is nested more than {} levels deep under {}, is the path right? Raise `scan.max_depth` in the config if it is intended",
+                path.display(),
+                max_depth,
+                dir.display()
+            ),
+            Error::TooManyFiles { paths, max_files } => {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "more than {} files found under [{}], is the path right? Raise `scan.max_files` in the config if it is intended",
+                    max_files,
+                    paths.join(", ")
+                )
+            }
+            Error::EmptyScan {
+                paths,
+                n_rust_files,
+            } => {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>();
+                if *n_rust_files == 0 {
+                    write!(f, "no Rust file found in [{}]", paths.join(", "))?;
+                } else {
+                    write!(
+                        f,
+                        "no `t!()` invocation found in the {} Rust files in [{}]",
+                        n_rust_files,
+                        paths.join(", ")
+                    )?;
+                }
+                write!(
+                    f,
+                    ", nothing was checked, pass --allow-empty if this is intended"
+                )
+            }
+            Error::Invalid {
+                what,
+                path,
+                message,
+            } => write!(f, "invalid {} {}: {}", what, path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = Error::Read {
+            path: "locales/app.yml".into(),
+            error: std::io::Error::new(std::io::ErrorKind::NotFound, "not found"),
+        };
+        assert_eq!(error.to_string(), "cannot read locales/app.yml: not found");
+
+        let error = Error::LocaleFile {
+            path: "locales/app.yml".into(),
+            message: "locale file version should be 2".into(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "invalid locale file locales/app.yml: locale file version should be 2"
+        );
+
+        let error = Error::Invocation {
+            path: "src/main.rs".into(),
+            line: 3,
+            column: 4,
+            message: "the first argument to t!() should be a string literal".into(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "src/main.rs:3:5: the first argument to t!() should be a string literal"
+        );
+
+        let error = Error::EmptyScan {
+            paths: vec!["src".into(), "lib".into()],
+            n_rust_files: 2,
+        };
+        assert_eq!(
+            error.to_string(),
+            "no `t!()` invocation found in the 2 Rust files in [src, lib], nothing was checked, pass --allow-empty if this is intended"
+        );
+    }
+
+    #[test]
+    fn test_display_rust_syntax() {
+        let error = Error::RustSyntax {
+            path: "src/main.rs".into(),
+            error: syn::parse_file("fn main() {\n    let\n}\n").err().unwrap(),
+        };
+        assert!(error
+            .to_string()
+            .starts_with("cannot parse src/main.rs:3:1: "));
+    }
+}
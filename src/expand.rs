@@ -0,0 +1,196 @@
+//! This file contains the `cargo expand` integration, which collects the keys
+//! of the `t!()` invocations generated by macros, e.g., Topgrade's step
+//! definition macros, that are invisible in the source code.
+//!
+//! `cargo expand` expands `t!()` too, so the keys are found in the calls it
+//! expands to, `crate::_rust_i18n_translate(locale, "key")`, and the names of
+//! its arguments in the `let keys = &["name", ...];` binding that follows.
+
+use crate::error::Error;
+use crate::interner::Interner;
+use crate::locale_key_collector::{str_literal, LocaleKey};
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use syn::spanned::Spanned;
+use syn::visit_mut::VisitMut;
+
+/// Name of the file that keys found in the output of `cargo expand` are
+/// reported in.
+pub(crate) const CARGO_EXPAND_OUTPUT: &str = "<cargo expand>";
+
+/// Functions that `t!()` expands to, whose second argument is the key.
+const TRANSLATE_FNS: [&str; 2] = ["_rust_i18n_translate", "_rust_i18n_try_translate"];
+
+/// Runs `cargo expand` in `dir`, or the current directory if `None`, and
+/// returns the expanded code.
+///
+/// Fails if `cargo expand` is not installed or fails.
+pub(crate) fn cargo_expand(dir: Option<&Path>) -> Result<String, Error> {
+    let mut command = Command::new("cargo");
+    command.args(["expand", "--color", "never"]);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command.output().map_err(|e| {
+        Error::Usage(format!(
+            "cannot run `cargo expand`: {}, install it with `cargo install cargo-expand`",
+            e
+        ))
+    })?;
+    if !output.status.success() {
+        return Err(Error::Other(format!(
+            "`cargo expand` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    String::from_utf8(output.stdout)
+        .map_err(|_| Error::Other("the output of `cargo expand` is not valid UTF-8".to_string()))
+}
+
+/// Returns the keys of the expanded `t!()` invocations in `contents`, the
+/// expanded code of `file`.
+///
+/// Fails if `contents` is not valid Rust.
+pub(crate) fn expanded_keys(
+    file: &Path,
+    contents: &str,
+    interner: &mut Interner,
+) -> Result<Vec<LocaleKey>, Error> {
+    let mut parsed_file = syn::parse_file(contents).map_err(|error| Error::RustSyntax {
+        path: file.to_path_buf(),
+        error,
+    })?;
+    let mut collector = ExpandedKeyCollector {
+        file: Arc::from(file),
+        locale_keys: Vec::new(),
+        awaiting_args: false,
+        interner,
+    };
+    collector.visit_file_mut(&mut parsed_file);
+
+    Ok(collector.locale_keys)
+}
+
+/// Collector of the keys of expanded `t!()` invocations.
+struct ExpandedKeyCollector<'interner> {
+    /// The expanded file.
+    file: Arc<Path>,
+    /// Keys collected from `file`.
+    locale_keys: Vec<LocaleKey>,
+    /// True right after a key is collected, until the next `let`, which is
+    /// its `keys` binding if it has arguments.
+    awaiting_args: bool,
+    /// Interner used to intern the collected keys.
+    interner: &'interner mut Interner,
+}
+
+impl VisitMut for ExpandedKeyCollector<'_> {
+    fn visit_expr_call_mut(&mut self, i: &mut syn::ExprCall) {
+        let is_translate_call =
+            match &*i.func {
+                syn::Expr::Path(func) => func.path.segments.last().is_some_and(|last_segment| {
+                    TRANSLATE_FNS.iter().any(|f| last_segment.ident == f)
+                }),
+                _ => false,
+            };
+        if let (true, Some(key)) = (
+            is_translate_call,
+            i.args.iter().nth(1).and_then(str_literal),
+        ) {
+            let start = i.span().start();
+            self.locale_keys.push(LocaleKey {
+                key: self.interner.intern(&key.value()),
+                file: Arc::clone(&self.file),
+                line: start.line,
+                column: start.column,
+                args: Vec::new(),
+                locale: None,
+                ignored: false,
+            });
+            self.awaiting_args = true;
+        }
+
+        syn::visit_mut::visit_expr_call_mut(self, i);
+    }
+
+    fn visit_local_mut(&mut self, i: &mut syn::Local) {
+        // let keys = &["name", ...];
+        let is_keys = matches!(&i.pat, syn::Pat::Ident(pat) if pat.ident == "keys");
+        let names = i.init.as_ref().and_then(|init| match &*init.expr {
+            syn::Expr::Reference(expr) => match &*expr.expr {
+                syn::Expr::Array(array) => array
+                    .elems
+                    .iter()
+                    .map(|elem| str_literal(elem).map(|literal| literal.value()))
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            },
+            _ => None,
+        });
+        let awaiting_args = std::mem::take(&mut self.awaiting_args);
+        if let (true, true, Some(names), Some(locale_key)) =
+            (awaiting_args, is_keys, names, self.locale_keys.last_mut())
+        {
+            locale_key.args = names
+                .iter()
+                .map(|name| self.interner.intern(name))
+                .collect();
+        }
+
+        syn::visit_mut::visit_local_mut(self, i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expanded_keys() {
+        let contents = r#"
+fn step() {
+    let a = crate::_rust_i18n_translate(&rust_i18n::locale(), "Pulling");
+    let b = {
+        let msg = crate::_rust_i18n_translate(&rust_i18n::locale(), "Restarting {app}");
+        let keys = &["app"];
+        let values = &[format!("{}", app)];
+        rust_i18n::replace_patterns(&msg, keys, values)
+    };
+    let keys = &["unrelated"];
+    let c = _rust_i18n_translate(locale, key);
+    let d = other("Not a key", "Not a key either");
+}
+"#;
+        let locale_keys = expanded_keys(
+            Path::new(CARGO_EXPAND_OUTPUT),
+            contents,
+            &mut Interner::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            locale_keys,
+            [
+                LocaleKey {
+                    key: "Pulling".into(),
+                    file: Path::new(CARGO_EXPAND_OUTPUT).into(),
+                    line: 3,
+                    column: 12,
+                    args: Vec::new(),
+                    locale: None,
+                    ignored: false,
+                },
+                LocaleKey {
+                    key: "Restarting {app}".into(),
+                    file: Path::new(CARGO_EXPAND_OUTPUT).into(),
+                    line: 5,
+                    column: 18,
+                    args: vec!["app".into()],
+                    locale: None,
+                    ignored: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! This file contains the `explain-key` subcommand, which shows everything
+//! known about a single key.
+
+use crate::checker::Checker;
+use crate::config::PlaceholderSyntax;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use crate::rules::key_and_eng_matches::derived_en;
+use crate::rules::{Diagnostic, Severity};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Everything needed to explain a key in a project.
+pub(crate) struct KeyExplanation<'a> {
+    /// The key to explain.
+    pub(crate) key: &'a str,
+    /// Path to the locale file.
+    pub(crate) locale_file: &'a Path,
+    /// Contents of the locale file.
+    pub(crate) locale_file_contents: &'a str,
+    /// The parsed locale file.
+    pub(crate) localized_texts: &'a LocalizedTexts,
+    /// The `t!()` invocations found in the Rust files.
+    pub(crate) locale_keys: &'a [LocaleKey],
+    /// The checker that has checked the project.
+    pub(crate) checker: &'a Checker,
+    /// How placeholders are written in translations.
+    pub(crate) syntax: PlaceholderSyntax,
+}
+
+impl KeyExplanation<'_> {
+    /// Returns true if any error is about the key.
+    pub(crate) fn has_error(&self) -> bool {
+        self.checker.errors().any(|(_, diagnostic)| {
+            diagnostic.severity == Severity::Error && self.is_about_key(diagnostic)
+        })
+    }
+
+    /// Returns true if `diagnostic` is about the key, either at its definition
+    /// or at a call site of it.
+    fn is_about_key(&self, diagnostic: &Diagnostic) -> bool {
+        *diagnostic.key == *self.key
+    }
+
+    /// Renders the explanation in a human-readable way.
+    pub(crate) fn render(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Key {:?}", self.key).unwrap();
+
+        match key_line(self.locale_file_contents, self.key) {
+            Some(line) => writeln!(
+                out,
+                "  Locale file: {}:{}",
+                self.locale_file.display(),
+                line
+            ),
+            None => writeln!(
+                out,
+                "  Locale file: not found in {}",
+                self.locale_file.display()
+            ),
+        }
+        .unwrap();
+        writeln!(
+            out,
+            "  Derived English: {}",
+            derived_en(self.key, self.syntax)
+        )
+        .unwrap();
+
+        if let Some(translations) = self.localized_texts.texts.get(self.key) {
+            writeln!(out, "  Translations:").unwrap();
+            if let Some(en) = &translations.en {
+                writeln!(out, "    en: {}", en).unwrap();
+            }
+            for (lang, translation) in translations.others.iter() {
+                writeln!(out, "    {}: {}", lang, translation).unwrap();
+            }
+
+            let missing = self
+                .localized_texts
+                .languages()
+                .into_iter()
+                .filter(|lang| {
+                    if &**lang == "en" {
+                        translations.en.is_none()
+                    } else {
+                        !translations.others.contains_key(lang)
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                writeln!(out, "  Missing: {}", missing.join(", ")).unwrap();
+            }
+        }
+
+        let call_sites = self
+            .locale_keys
+            .iter()
+            .filter(|locale_key| *locale_key.key == *self.key)
+            .collect::<Vec<_>>();
+        if call_sites.is_empty() {
+            writeln!(out, "  Call sites: none").unwrap();
+        } else {
+            writeln!(out, "  Call sites:").unwrap();
+            for call_site in call_sites {
+                write!(
+                    out,
+                    "    {}:{}:{}",
+                    call_site.file.display(),
+                    call_site.line,
+                    call_site.column
+                )
+                .unwrap();
+                if !call_site.args.is_empty() {
+                    let args = call_site.args.iter().map(|arg| &**arg).collect::<Vec<_>>();
+                    write!(out, " ({})", args.join(", ")).unwrap();
+                }
+                writeln!(out).unwrap();
+            }
+        }
+
+        writeln!(out, "  Rules:").unwrap();
+        for rule in self.checker.rule_names() {
+            let diagnostics = self
+                .checker
+                .errors()
+                .filter(|(name, diagnostic)| *name == rule && self.is_about_key(diagnostic))
+                .map(|(_, diagnostic)| diagnostic)
+                .collect::<Vec<_>>();
+            if diagnostics.is_empty() {
+                writeln!(out, "    pass {}", rule).unwrap();
+            }
+            for diagnostic in diagnostics {
+                write!(out, "    {} {}", diagnostic.severity.as_str(), rule).unwrap();
+                if !diagnostic.langs.is_empty() {
+                    let langs = diagnostic
+                        .langs
+                        .iter()
+                        .map(|lang| &**lang)
+                        .collect::<Vec<_>>();
+                    write!(out, " [{}]", langs.join(", ")).unwrap();
+                }
+                if let Some(error_msg) = &diagnostic.error_msg {
+                    write!(out, ": {}", error_msg).unwrap();
+                }
+                writeln!(out).unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+/// Returns the line number, starting from 1, where `key` is defined in the
+/// locale file `contents`, `None` if it is not found.
+///
+/// Keys are top-level mapping keys, they may be quoted.
+pub(crate) fn key_line(contents: &str, key: &str) -> Option<usize> {
+    let candidates = [
+        format!("{}:", key),
+        format!("\"{}\":", key.replace('\\', "\\\\").replace('"', "\\\"")),
+        format!("'{}':", key.replace('\'', "''")),
+    ];
+
+    contents
+        .lines()
+        .position(|line| {
+            candidates
+                .iter()
+                .any(|candidate| line.starts_with(candidate.as_str()))
+        })
+        .map(|idx| idx + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::Translations;
+    use crate::rules::key_and_eng_matches::KeyEngMatches;
+    use crate::rules::missing_translations::MissingTranslations;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indexmap::IndexMap;
+    use indicatif::ProgressBar;
+
+    #[test]
+    fn test_key_line() {
+        let contents = "_version: 2\n\"Restarting {app}\":\n  en: x\nUpdating:\n  en: y\n";
+        assert_eq!(key_line(contents, "Restarting {app}"), Some(2));
+        assert_eq!(key_line(contents, "Updating"), Some(4));
+        assert_eq!(key_line(contents, "en"), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting {app}".into(),
+                    Translations {
+                        en: Some("Restarting %{app}".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating".into(),
+                    Translations {
+                        en: Some("Updating".into()),
+                        others: IndexMap::from([("de".into(), "Aktualisierung".into())]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let locale_keys = vec![LocaleKey {
+            key: "Restarting {app}".into(),
+            file: Path::new("src/main.rs").into(),
+            line: 10,
+            column: 4,
+            args: vec!["app".into()],
+            locale: None,
+            ignored: false,
+        }];
+        let mut checker = Checker::new();
+        checker.register_rule(MissingTranslations::new(Default::default()));
+        checker.register_rule(KeyEngMatches::new(
+            PlaceholderSyntax::default(),
+            Default::default(),
+            Default::default(),
+        ));
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+        checker.check(&ctx, &mut Timings::new(), &ProgressBar::hidden());
+
+        let explanation = KeyExplanation {
+            key: "Restarting {app}",
+            locale_file: Path::new("locales/app.yml"),
+            locale_file_contents: "_version: 2\nRestarting {app}:\n  en: Restarting %{app}\n",
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            checker: &checker,
+            syntax: PlaceholderSyntax::default(),
+        };
+        assert!(explanation.has_error());
+        assert_eq!(
+            explanation.render(),
+            "Key \"Restarting {app}\"
+  Locale file: locales/app.yml:2
+  Derived English: Restarting %{app}
+  Translations:
+    en: Restarting %{app}
+  Missing: de
+  Call sites:
+    src/main.rs:10:4 (app)
+  Rules:
+    error MissingTranslations [de]: Missing translations for [de]
+    pass KeyEngMatches
+"
+        );
+    }
+}
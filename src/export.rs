@@ -0,0 +1,310 @@
+//! This file contains the `export` subcommand, which writes the full model of
+//! the checked projects, i.e., keys, translations, call sites and diagnostics,
+//! to a file that other tools can query.
+//!
+//! With `--format sqlite`, it is a small SQLite database, so that ad-hoc
+//! questions can be answered with SQL, e.g., the keys used in more than 5 files
+//! without a `de` translation:
+//!
+//! ```sql
+//! SELECT key FROM call_sites
+//! GROUP BY project_id, key
+//! HAVING COUNT(DISTINCT file) > 5
+//!    AND key NOT IN (SELECT key FROM translations WHERE lang = 'de');
+//! ```
+
+use crate::checker::Checker;
+use crate::error::Error;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use crate::rules::code_of;
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+/// Format of the exported file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// A SQLite database, see [`SCHEMA`] for its tables.
+    Sqlite,
+}
+
+/// Tables of the SQLite database, every row belongs to a project.
+const SCHEMA: &str = "
+-- The checked projects, `root` is NULL if the project is not specified by its
+-- root directory
+CREATE TABLE projects (
+    id INTEGER PRIMARY KEY,
+    root TEXT,
+    locale_file TEXT NOT NULL
+);
+-- Keys defined in the locale file, and keys used by `t!()` but not defined
+CREATE TABLE keys (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    key TEXT NOT NULL,
+    defined INTEGER NOT NULL,
+    PRIMARY KEY (project_id, key)
+);
+-- Translations of the defined keys, English included
+CREATE TABLE translations (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    key TEXT NOT NULL,
+    lang TEXT NOT NULL,
+    text TEXT NOT NULL,
+    PRIMARY KEY (project_id, key, lang)
+);
+-- Invocations of `t!()`, `args` is the comma-separated named arguments
+CREATE TABLE call_sites (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    key TEXT NOT NULL,
+    file TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    column INTEGER NOT NULL,
+    args TEXT NOT NULL
+);
+-- Everything found by the rules, `file`, `line` and `column` are where it is
+-- found in the Rust files, NULL if it is about the locale file
+CREATE TABLE diagnostics (
+    id INTEGER PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    rule TEXT NOT NULL,
+    code TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    key TEXT NOT NULL,
+    message TEXT,
+    file TEXT,
+    line INTEGER,
+    column INTEGER
+);
+-- Languages affected by the diagnostics
+CREATE TABLE diagnostic_langs (
+    diagnostic_id INTEGER NOT NULL REFERENCES diagnostics (id),
+    lang TEXT NOT NULL
+);
+";
+
+/// A SQLite database being exported.
+pub(crate) struct SqliteExport {
+    /// Connection to the database.
+    conn: Connection,
+}
+
+impl SqliteExport {
+    /// Creates the database at `path` with the tables in [`SCHEMA`], the file
+    /// is replaced if it exists.
+    pub(crate) fn create(path: &Path) -> Result<Self, Error> {
+        let fail = |error| Error::Write {
+            path: path.to_path_buf(),
+            error,
+        };
+        if path.exists() {
+            std::fs::remove_file(path).map_err(fail)?;
+        }
+        let conn = Connection::open(path)
+            .and_then(|conn| conn.execute_batch(SCHEMA).map(|()| conn))
+            .map_err(|e| fail(std::io::Error::other(e)))?;
+
+        Ok(Self { conn })
+    }
+
+    /// Adds everything known about a project: its keys and translations in
+    /// `localized_texts`, its call sites in `locale_keys`, and the diagnostics
+    /// in `checker`.
+    pub(crate) fn add_project(
+        &mut self,
+        root: Option<&Path>,
+        locale_file: &Path,
+        localized_texts: &LocalizedTexts,
+        locale_keys: &[LocaleKey],
+        checker: &Checker,
+    ) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO projects (root, locale_file) VALUES (?1, ?2)",
+            params![
+                root.map(|root| root.display().to_string()),
+                locale_file.display().to_string()
+            ],
+        )?;
+        let project_id = tx.last_insert_rowid();
+
+        {
+            let mut insert_key = tx.prepare(
+                "INSERT OR IGNORE INTO keys (project_id, key, defined) VALUES (?1, ?2, ?3)",
+            )?;
+            let mut insert_translation = tx.prepare(
+                "INSERT INTO translations (project_id, key, lang, text) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (key, translations) in localized_texts.texts.iter() {
+                insert_key.execute(params![project_id, &**key, true])?;
+                let en = translations.en.iter().map(|en| ("en", en));
+                let others = translations
+                    .others
+                    .iter()
+                    .map(|(lang, translation)| (&**lang, translation));
+                for (lang, translation) in en.chain(others) {
+                    insert_translation.execute(params![
+                        project_id,
+                        &**key,
+                        lang,
+                        &**translation
+                    ])?;
+                }
+            }
+
+            let mut insert_call_site = tx.prepare(
+                "INSERT INTO call_sites (project_id, key, file, line, column, args) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for locale_key in locale_keys {
+                insert_key.execute(params![project_id, &*locale_key.key, false])?;
+                insert_call_site.execute(params![
+                    project_id,
+                    &*locale_key.key,
+                    locale_key.file.display().to_string(),
+                    locale_key.line,
+                    locale_key.column,
+                    locale_key.args.join(","),
+                ])?;
+            }
+
+            let mut insert_diagnostic = tx.prepare(
+                "INSERT INTO diagnostics (project_id, rule, code, severity, key, message, file, line, column) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            let mut insert_lang =
+                tx.prepare("INSERT INTO diagnostic_langs (diagnostic_id, lang) VALUES (?1, ?2)")?;
+            for (rule, diagnostic) in checker.errors() {
+                let location = diagnostic.location.as_ref();
+                insert_diagnostic.execute(params![
+                    project_id,
+                    rule,
+                    code_of(rule),
+                    diagnostic.severity.as_str(),
+                    &*diagnostic.key,
+                    diagnostic.error_msg,
+                    location.map(|location| location.file.display().to_string()),
+                    location.map(|location| location.line),
+                    location.map(|location| location.column),
+                ])?;
+                let diagnostic_id = tx.last_insert_rowid();
+                for lang in diagnostic.langs.iter() {
+                    insert_lang.execute(params![diagnostic_id, &**lang])?;
+                }
+            }
+        }
+
+        tx.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LanguageTiers;
+    use crate::locale_file_parser::Translations;
+    use crate::rules::missing_translations::MissingTranslations;
+    use crate::rules::use_of_keys_do_not_exist::UseOfKeysDoNotExist;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indexmap::IndexMap;
+    use indicatif::ProgressBar;
+
+    #[test]
+    fn test_sqlite_export() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting {app}".into(),
+                    Translations {
+                        en: Some("Restarting %{app}".into()),
+                        others: IndexMap::from([("de".into(), "Neustart von %{app}".into())]),
+                        ..Default::default()
+                    },
+                ),
+                ("Unused".into(), Translations::default()),
+            ]),
+        };
+        let locale_keys = vec![
+            LocaleKey {
+                key: "Restarting {app}".into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 4,
+                args: vec!["app".into()],
+                locale: None,
+                ignored: false,
+            },
+            LocaleKey {
+                key: "Undefined".into(),
+                file: Path::new("bar.rs").into(),
+                line: 2,
+                column: 0,
+                args: Vec::new(),
+                locale: None,
+                ignored: false,
+            },
+        ];
+        let mut checker = Checker::new();
+        checker.register_rule(MissingTranslations::new(LanguageTiers::default()));
+        checker.register_rule(UseOfKeysDoNotExist::new(Default::default()));
+        checker.check(
+            &CheckContext {
+                localized_texts: &localized_texts,
+                locale_keys: &locale_keys,
+                set_locale_calls: &[],
+                concatenations: &[],
+                sink_literals: &[],
+            },
+            &mut Timings::new(),
+            &ProgressBar::hidden(),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("i18n.db");
+        std::fs::write(&db, "stale").unwrap();
+        let mut export = SqliteExport::create(&db).unwrap();
+        export
+            .add_project(
+                None,
+                Path::new("locales/app.yml"),
+                &localized_texts,
+                &locale_keys,
+                &checker,
+            )
+            .unwrap();
+        drop(export);
+
+        let conn = Connection::open(&db).unwrap();
+        let query = |sql: &str| {
+            let mut stmt = conn.prepare(sql).unwrap();
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .unwrap()
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .unwrap();
+            rows
+        };
+        assert_eq!(
+            query("SELECT key || ':' || defined FROM keys ORDER BY key"),
+            ["Restarting {app}:1", "Undefined:0", "Unused:1"]
+        );
+        assert_eq!(
+            query("SELECT lang || ':' || text FROM translations ORDER BY lang"),
+            ["de:Neustart von %{app}", "en:Restarting %{app}"]
+        );
+        assert_eq!(
+            query("SELECT file || ':' || line || ':' || column || ':' || args FROM call_sites"),
+            ["foo.rs:1:4:app", "bar.rs:2:0:"]
+        );
+        assert_eq!(
+            query("SELECT DISTINCT code || ':' || severity FROM diagnostics ORDER BY code"),
+            ["I18N001:error", "I18N003:error"]
+        );
+        assert_eq!(
+            query("SELECT key || ':' || coalesce(file || ':' || line || ':' || column, '-') FROM diagnostics ORDER BY code, key"),
+            ["Unused:-", "Undefined:bar.rs:2:0"]
+        );
+        assert_eq!(
+            query("SELECT d.key FROM diagnostics d JOIN diagnostic_langs l ON l.diagnostic_id = d.id WHERE l.lang = 'de'"),
+            ["Unused"]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! This file contains the fixes `--fix` and `--fix-unused` apply to the locale
+//! file.
+//!
+//! The locale file is edited as text rather than parsed and serialized again,
+//! so that the order of the keys, the comments and the formatting are
+//! preserved.
+
+use crate::explain::key_line;
+use similar::TextDiff;
+use std::path::Path;
+
+/// Indentation of the translations when the locale file has none to copy.
+const DEFAULT_INDENT: &str = "  ";
+
+/// Quotes `text` as a YAML double-quoted scalar.
+fn quote(text: &str) -> String {
+    // JSON strings are valid YAML double-quoted scalars
+    serde_json::to_string(text).expect("a string should be serializable")
+}
+
+/// Returns the indentation of the translations in the locale file `contents`,
+/// i.e., of its first indented line.
+fn indent_of(contents: &str) -> &str {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty())
+        .unwrap_or(DEFAULT_INDENT)
+}
+
+/// Appends an entry to the locale file `contents` for every `(Key, English)`
+/// of `stubs`, with only its English translation.
+pub(crate) fn append_stubs(contents: &str, stubs: &[(&str, String)]) -> String {
+    if stubs.is_empty() {
+        return contents.to_string();
+    }
+
+    let indent = indent_of(contents).to_string();
+    let mut fixed = contents.to_string();
+    if !fixed.is_empty() && !fixed.ends_with('\n') {
+        fixed.push('\n');
+    }
+    for (key, en) in stubs {
+        fixed.push_str(&format!("{}:\n{}en: {}\n", quote(key), indent, quote(en)));
+    }
+
+    fixed
+}
+
+/// Returns the index of the line after the translations of the key on line
+/// `key_line`, starting from 1, of the locale file `lines`, i.e., of the next
+/// unindented line.
+fn block_end(lines: &[&str], key_line: usize) -> usize {
+    lines[key_line..]
+        .iter()
+        .position(|line| !line.trim().is_empty() && !line.starts_with([' ', '\t']))
+        .map_or(lines.len(), |idx| key_line + idx)
+}
+
+/// Sets the English translation of `key` in the locale file `contents` to
+/// `en`, replacing the current one, which may span multiple lines, or adding
+/// it right after the key if there is none.
+///
+/// Returns `None` if the translations of `key` are not a block mapping, e.g.,
+/// `key: {en: x}`, or if `key` is not found.
+pub(crate) fn set_english(contents: &str, key: &str, en: &str) -> Option<String> {
+    let key_line = key_line(contents, key)?;
+    let lines = contents.split_inclusive('\n').collect::<Vec<_>>();
+    // The key is followed by nothing but maybe a comment
+    let after_key = [
+        format!("{}:", key),
+        format!("{}:", quote(key)),
+        format!("'{}':", key.replace('\'', "''")),
+    ]
+    .iter()
+    .find_map(|candidate| lines[key_line - 1].strip_prefix(candidate.as_str()))?
+    .trim();
+    if !after_key.is_empty() && !after_key.starts_with('#') {
+        return None;
+    }
+
+    let block_end = block_end(&lines, key_line);
+    let en_line = (key_line..block_end).find(|idx| {
+        let line = lines[*idx].trim_start();
+        line.starts_with("en:") || line.starts_with("\"en\":")
+    });
+
+    let mut fixed = lines[..key_line].concat();
+    match en_line {
+        Some(en_line) => {
+            let line = lines[en_line];
+            let indent = &line[..line.len() - line.trim_start().len()];
+            // A block scalar continues on the lines indented deeper
+            let value_end = lines[en_line + 1..block_end]
+                .iter()
+                .position(|line| {
+                    !line.trim().is_empty() && line.len() - line.trim_start().len() <= indent.len()
+                })
+                .map_or(block_end, |idx| en_line + 1 + idx);
+            fixed.push_str(&lines[key_line..en_line].concat());
+            fixed.push_str(&format!("{}en: {}\n", indent, quote(en)));
+            fixed.push_str(&lines[value_end..].concat());
+        }
+        None => {
+            let indent = match lines[key_line..block_end]
+                .iter()
+                .find(|line| !line.trim().is_empty())
+            {
+                Some(line) => &line[..line.len() - line.trim_start().len()],
+                None => indent_of(contents),
+            };
+            if !fixed.ends_with('\n') {
+                fixed.push('\n');
+            }
+            fixed.push_str(&format!("{}en: {}\n", indent, quote(en)));
+            fixed.push_str(&lines[key_line..].concat());
+        }
+    }
+
+    Some(fixed)
+}
+
+/// Removes `key` and its translations from the locale file `contents`, the
+/// comments and blank lines before the next key are kept.
+///
+/// Returns `None` if `key` is not found.
+pub(crate) fn remove_key(contents: &str, key: &str) -> Option<String> {
+    let key_line = key_line(contents, key)?;
+    let lines = contents.split_inclusive('\n').collect::<Vec<_>>();
+    let mut end = block_end(&lines, key_line);
+    while end > key_line && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+
+    Some([&lines[..key_line - 1], &lines[end..]].concat().concat())
+}
+
+/// Renders the changes from `old` to `new` of the file at `path` as a unified
+/// diff, empty if there is none.
+pub(crate) fn diff(path: &Path, old: &str, new: &str) -> String {
+    let path = path.display().to_string();
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_stubs() {
+        let contents = "_version: 2\n\n# Steps\nRestarting:\n    en: Restarting";
+        assert_eq!(
+            append_stubs(
+                contents,
+                &[
+                    ("Pulling {repo}", "Pulling %{repo}".into()),
+                    ("Say \"hi\"", "Say \"hi\"".into()),
+                ]
+            ),
+            "_version: 2\n\n# Steps\nRestarting:\n    en: Restarting\n\
+             \"Pulling {repo}\":\n    en: \"Pulling %{repo}\"\n\
+             \"Say \\\"hi\\\"\":\n    en: \"Say \\\"hi\\\"\"\n"
+        );
+        assert_eq!(
+            append_stubs("_version: 2\n", &[("Done", "Done".into())]),
+            "_version: 2\n\"Done\":\n  en: \"Done\"\n"
+        );
+        assert_eq!(append_stubs(contents, &[]), contents);
+    }
+
+    #[test]
+    fn test_set_english() {
+        let contents = "_version: 2\n\
+                        # Restarts\n\
+                        Restarting {app}: # comment\n  de: x\n  en: Restart\n  fr: y\n\
+                        Updating:\n  en: |\n    Updating\n    things\n\n  de: z\n\
+                        Pulling:\n  de: w\n\
+                        Inline: {en: x}\n\
+                        \"Error: {e}\":\n  en: Error\n";
+        assert_eq!(
+            set_english(contents, "Restarting {app}", "Restarting %{app}").unwrap(),
+            contents.replace("  en: Restart\n", "  en: \"Restarting %{app}\"\n")
+        );
+        assert_eq!(
+            set_english(contents, "Updating", "Updating").unwrap(),
+            contents.replace(
+                "  en: |\n    Updating\n    things\n\n",
+                "  en: \"Updating\"\n"
+            )
+        );
+        assert_eq!(
+            set_english(contents, "Pulling", "Pulling").unwrap(),
+            contents.replace("Pulling:\n", "Pulling:\n  en: \"Pulling\"\n")
+        );
+        assert_eq!(
+            set_english(contents, "Error: {e}", "Error: %{e}").unwrap(),
+            contents.replace("  en: Error\n", "  en: \"Error: %{e}\"\n")
+        );
+        assert_eq!(set_english(contents, "Inline", "Inline"), None);
+        assert_eq!(set_english(contents, "Gone", "Gone"), None);
+    }
+
+    #[test]
+    fn test_remove_key() {
+        let contents = "_version: 2\n\
+                        Restarting:\n  en: Restarting\n  de: x\n\n\
+                        # Steps\n\
+                        \"Pulling {repo}\":\n  en: |\n    Pulling\n\n    %{repo}\n\
+                        Inline: {en: Inline}\n";
+        assert_eq!(
+            remove_key(contents, "Restarting").unwrap(),
+            "_version: 2\n\n# Steps\n\"Pulling {repo}\":\n  en: |\n    Pulling\n\n    %{repo}\nInline: {en: Inline}\n"
+        );
+        assert_eq!(
+            remove_key(contents, "Pulling {repo}").unwrap(),
+            "_version: 2\nRestarting:\n  en: Restarting\n  de: x\n\n# Steps\nInline: {en: Inline}\n"
+        );
+        assert_eq!(
+            remove_key(contents, "Inline").unwrap(),
+            "_version: 2\nRestarting:\n  en: Restarting\n  de: x\n\n# Steps\n\"Pulling {repo}\":\n  en: |\n    Pulling\n\n    %{repo}\n"
+        );
+        assert_eq!(remove_key(contents, "Gone"), None);
+    }
+
+    #[test]
+    fn test_diff() {
+        let path = Path::new("locales/app.yml");
+        assert_eq!(
+            diff(path, "_version: 2\nDone:\n  en: Done\n", "_version: 2\n"),
+            "--- a/locales/app.yml\n+++ b/locales/app.yml\n@@ -1,3 +1 @@\n _version: 2\n-Done:\n-  en: Done\n"
+        );
+        assert_eq!(diff(path, "_version: 2\n", "_version: 2\n"), "");
+    }
+}
@@ -0,0 +1,93 @@
+//! This file contains type [`Glossary`], the glossary file that maps English
+//! terms to their required translations.
+//!
+//! ```toml
+//! [[term]]
+//! en = "step"
+//! de = "Schritt"
+//! fr = "étape"
+//!
+//! # Never translated
+//! [[term]]
+//! en = "Topgrade"
+//! keep = true
+//! ```
+
+use crate::error::Error;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The glossary file.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(default)]
+pub(crate) struct Glossary {
+    /// The terms.
+    #[serde(rename = "term")]
+    pub(crate) terms: Vec<GlossaryTerm>,
+}
+
+/// A term in the glossary.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub(crate) struct GlossaryTerm {
+    /// The English term.
+    pub(crate) en: String,
+    /// If true, the term should never be translated.
+    #[serde(default)]
+    pub(crate) keep: bool,
+    /// `Language => Required translation`
+    #[serde(flatten)]
+    pub(crate) translations: HashMap<String, String>,
+}
+
+impl Glossary {
+    /// Loads the glossary file at `path`.
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path).map_err(|error| Error::Read {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        toml::from_str(&contents).map_err(|e| Error::Invalid {
+            what: "glossary file",
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_glossary() {
+        let glossary: Glossary = toml::from_str(
+            r#"
+[[term]]
+en = "step"
+de = "Schritt"
+
+[[term]]
+en = "Topgrade"
+keep = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            glossary.terms,
+            [
+                GlossaryTerm {
+                    en: "step".into(),
+                    keep: false,
+                    translations: HashMap::from([("de".into(), "Schritt".into())]),
+                },
+                GlossaryTerm {
+                    en: "Topgrade".into(),
+                    keep: true,
+                    translations: HashMap::new(),
+                },
+            ]
+        );
+    }
+}
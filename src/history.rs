@@ -0,0 +1,631 @@
+//! This file contains type [`History`], a SQLite database of past runs, and
+//! the `trend` subcommand, which shows how the coverage and the errors of a
+//! language evolve across them.
+//!
+//! A run is recorded per commit, checking a commit again replaces its run.
+//! Trend gates compare a run with the last run of another commit, so that a
+//! locale regression fails the check.
+
+use crate::checker::Checker;
+use crate::config::{LanguageTiers, TrendGatesConfig};
+use crate::locale_file_parser::LocalizedTexts;
+use crate::rules::Severity;
+use indexmap::IndexSet;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Tables of the history database, created if they do not exist.
+const SCHEMA: &str = "
+-- One run per commit
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    commit_hash TEXT NOT NULL UNIQUE,
+    timestamp INTEGER NOT NULL,
+    n_errors INTEGER NOT NULL,
+    n_warnings INTEGER NOT NULL
+);
+-- Statistics of every language in every project of a run, `project` is NULL
+-- if the project is not specified by its root directory
+CREATE TABLE IF NOT EXISTS language_stats (
+    run_id INTEGER NOT NULL REFERENCES runs (id),
+    project TEXT,
+    lang TEXT NOT NULL,
+    n_keys INTEGER NOT NULL,
+    n_translated INTEGER NOT NULL,
+    n_errors INTEGER NOT NULL
+);
+";
+
+/// Statistics of a language in a project.
+#[derive(Debug, PartialEq)]
+struct LanguageStats {
+    /// Root directory of the project, if any.
+    project: Option<String>,
+    /// The language.
+    lang: String,
+    /// Number of keys in the locale file.
+    n_keys: usize,
+    /// Number of keys translated to the language.
+    n_translated: usize,
+    /// Number of errors affecting the language.
+    n_errors: usize,
+}
+
+/// The results of a check to record, possibly of multiple projects.
+#[derive(Debug, Default)]
+pub(crate) struct Run {
+    /// Statistics of every language in every project.
+    stats: Vec<LanguageStats>,
+    /// Number of errors.
+    n_errors: usize,
+    /// Number of warnings.
+    n_warnings: usize,
+}
+
+impl Run {
+    /// Creates an empty [`Run`].
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the results of a project.
+    pub(crate) fn record_project(
+        &mut self,
+        project: Option<&Path>,
+        localized_texts: &LocalizedTexts,
+        checker: &Checker,
+    ) {
+        let count = |severity: Severity, lang: Option<&str>| {
+            checker
+                .errors()
+                .filter(|(_, diagnostic)| diagnostic.severity == severity)
+                .filter(|(_, diagnostic)| match lang {
+                    Some(lang) => diagnostic.langs.iter().any(|l| &**l == lang),
+                    None => true,
+                })
+                .count()
+        };
+
+        for lang in localized_texts.languages() {
+            let n_translated = localized_texts
+                .texts
+                .values()
+                .filter(|t| match &*lang {
+                    "en" => t.en.is_some(),
+                    _ => t.others.contains_key(&lang),
+                })
+                .count();
+            self.stats.push(LanguageStats {
+                project: project.map(|project| project.display().to_string()),
+                lang: lang.to_string(),
+                n_keys: localized_texts.texts.len(),
+                n_translated,
+                n_errors: count(Severity::Error, Some(&lang)),
+            });
+        }
+        self.n_errors += count(Severity::Error, None);
+        self.n_warnings += count(Severity::Warning, None);
+    }
+
+    /// Returns the number of errors.
+    pub(crate) fn n_errors(&self) -> usize {
+        self.n_errors
+    }
+
+    /// Returns the number of warnings.
+    pub(crate) fn n_warnings(&self) -> usize {
+        self.n_warnings
+    }
+
+    /// Returns the languages recorded, in the order they are first recorded.
+    pub(crate) fn languages(&self) -> IndexSet<&str> {
+        self.stats.iter().map(|stats| &*stats.lang).collect()
+    }
+
+    /// Returns the coverage of `lang` and its errors, summed over the
+    /// projects, the coverage is `None` if there is no key.
+    pub(crate) fn point(&self, lang: &str) -> (Option<f64>, usize) {
+        let (mut n_keys, mut n_translated, mut n_errors) = (0, 0, 0);
+        for stats in self.stats.iter().filter(|stats| stats.lang == lang) {
+            n_keys += stats.n_keys;
+            n_translated += stats.n_translated;
+            n_errors += stats.n_errors;
+        }
+        let coverage = (n_keys != 0).then(|| n_translated as f64 / n_keys as f64);
+
+        (coverage, n_errors)
+    }
+}
+
+/// A run in a [`Trend`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct TrendPoint {
+    /// Hash of the commit.
+    pub(crate) commit: String,
+    /// When it was recorded, in seconds since the Unix epoch.
+    pub(crate) timestamp: u64,
+    /// Translation coverage of the language, between 0 and 1, `None` if the
+    /// locale file had no key.
+    pub(crate) coverage: Option<f64>,
+    /// Number of errors affecting the language.
+    pub(crate) n_errors: usize,
+}
+
+/// The evolution of a language across the recorded runs.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Trend {
+    /// The language.
+    pub(crate) lang: String,
+    /// The runs, from the oldest to the newest.
+    pub(crate) points: Vec<TrendPoint>,
+}
+
+impl Trend {
+    /// Renders the trend as a table, with the change since the previous run.
+    pub(crate) fn render(&self) -> String {
+        if self.points.is_empty() {
+            return "No run has been recorded\n".to_string();
+        }
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "Trend of `{}` over the last {} runs:",
+            self.lang,
+            self.points.len()
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "{:<8} {:<10} {:>8} {:>7} {:>6} {:>6}",
+            "commit", "date", "coverage", "change", "errors", "change"
+        )
+        .unwrap();
+        let mut prev: Option<&TrendPoint> = None;
+        for point in self.points.iter() {
+            let coverage = match point.coverage {
+                Some(coverage) => format!("{:.1}%", coverage * 100.0),
+                None => "-".to_string(),
+            };
+            let (coverage_change, errors_change) = match prev {
+                Some(prev) => (
+                    match (prev.coverage, point.coverage) {
+                        (Some(prev), Some(cur)) => format!("{:+.1}", (cur - prev) * 100.0),
+                        _ => String::new(),
+                    },
+                    format!("{:+}", point.n_errors as i64 - prev.n_errors as i64),
+                ),
+                None => (String::new(), String::new()),
+            };
+            let row = format!(
+                "{:<8} {:<10} {:>8} {:>7} {:>6} {:>6}",
+                &point.commit[..point.commit.len().min(8)],
+                format_date(point.timestamp),
+                coverage,
+                coverage_change,
+                point.n_errors,
+                errors_change
+            );
+            writeln!(out, "{}", row.trim_end()).unwrap();
+            prev = Some(point);
+        }
+
+        out
+    }
+}
+
+/// A regression found by the trend gates.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct Regression {
+    /// The regressed language.
+    pub(crate) lang: String,
+    /// What regressed.
+    pub(crate) message: String,
+}
+
+impl std::fmt::Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// The run-history database.
+pub(crate) struct History {
+    /// Connection to the database.
+    conn: Connection,
+}
+
+impl History {
+    /// Opens the database at `path`, it is created if `create` is true and it
+    /// does not exist.
+    pub(crate) fn open(path: &Path, create: bool) -> rusqlite::Result<Self> {
+        let mut flags = OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        if create {
+            flags |= OpenFlags::SQLITE_OPEN_CREATE;
+        }
+        let conn = Connection::open_with_flags(path, flags)?;
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self { conn })
+    }
+
+    /// Records `run` of `commit` at `timestamp`, in seconds since the Unix
+    /// epoch, replacing the run of `commit` recorded before, if any.
+    pub(crate) fn record(
+        &mut self,
+        commit: &str,
+        timestamp: u64,
+        run: &Run,
+    ) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM language_stats WHERE run_id IN (SELECT id FROM runs WHERE commit_hash = ?1)",
+            params![commit],
+        )?;
+        tx.execute("DELETE FROM runs WHERE commit_hash = ?1", params![commit])?;
+        tx.execute(
+            "INSERT INTO runs (commit_hash, timestamp, n_errors, n_warnings) VALUES (?1, ?2, ?3, ?4)",
+            params![commit, timestamp, run.n_errors, run.n_warnings],
+        )?;
+        let run_id = tx.last_insert_rowid();
+        {
+            let mut insert_stats = tx.prepare(
+                "INSERT INTO language_stats (run_id, project, lang, n_keys, n_translated, n_errors) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for stats in run.stats.iter() {
+                insert_stats.execute(params![
+                    run_id,
+                    stats.project,
+                    stats.lang,
+                    stats.n_keys,
+                    stats.n_translated,
+                    stats.n_errors
+                ])?;
+            }
+        }
+
+        tx.commit()
+    }
+
+    /// Returns the trend of `lang` over the `last` runs, summed over the
+    /// projects.
+    pub(crate) fn trend(&self, lang: &str, last: usize) -> rusqlite::Result<Trend> {
+        let mut points = self.points(lang, None, last)?;
+        points.reverse();
+
+        Ok(Trend {
+            lang: lang.to_string(),
+            points,
+        })
+    }
+
+    /// Returns the points of `lang` in the `limit` most recent runs, newest
+    /// first, the run of `exclude_commit` is skipped if given.
+    fn points(
+        &self,
+        lang: &str,
+        exclude_commit: Option<&str>,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<TrendPoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.commit_hash, r.timestamp,
+                 CAST(SUM(s.n_translated) AS REAL) / SUM(s.n_keys),
+                 COALESCE(SUM(s.n_errors), 0)
+             FROM runs r
+             LEFT JOIN language_stats s ON s.run_id = r.id AND s.lang = ?1
+             WHERE ?2 IS NULL OR r.commit_hash != ?2
+             GROUP BY r.id
+             ORDER BY r.timestamp DESC, r.id DESC
+             LIMIT ?3",
+        )?;
+        let points = stmt
+            .query_map(params![lang, exclude_commit, limit], |row| {
+                Ok(TrendPoint {
+                    commit: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    coverage: row.get(2)?,
+                    n_errors: row.get(3)?,
+                })
+            })?
+            .collect();
+
+        points
+    }
+
+    /// Returns the point of `lang` in the last run of another commit than
+    /// `commit`, `None` if there is no such run.
+    pub(crate) fn last_point(
+        &self,
+        lang: &str,
+        commit: &str,
+    ) -> rusqlite::Result<Option<TrendPoint>> {
+        Ok(self.points(lang, Some(commit), 1)?.pop())
+    }
+
+    /// Returns the languages of the last run of another commit than `commit`.
+    fn last_languages(&self, commit: &str) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT lang FROM language_stats
+             WHERE run_id = (
+                 SELECT id FROM runs WHERE commit_hash != ?1
+                 ORDER BY timestamp DESC, id DESC
+                 LIMIT 1
+             )",
+        )?;
+        let languages = stmt.query_map(params![commit], |row| row.get(0))?.collect();
+
+        languages
+    }
+
+    /// Returns the regressions of `run` of `commit` since the last run of
+    /// another commit recorded, according to `gates`.
+    ///
+    /// The languages in `gates.languages` are gated, or the tier-1 languages
+    /// in `tiers` if it is empty.
+    pub(crate) fn regressions(
+        &self,
+        commit: &str,
+        run: &Run,
+        gates: &TrendGatesConfig,
+        tiers: &LanguageTiers,
+    ) -> rusqlite::Result<Vec<Regression>> {
+        let languages: IndexSet<String> = if gates.languages.is_empty() {
+            // A language removed since the last run is a regression as well
+            let mut languages = run
+                .languages()
+                .into_iter()
+                .map(str::to_string)
+                .collect::<IndexSet<_>>();
+            languages.extend(self.last_languages(commit)?);
+            languages
+                .into_iter()
+                .filter(|lang| tiers.severity_of(lang) == Severity::Error)
+                .collect()
+        } else {
+            gates.languages.iter().cloned().collect()
+        };
+
+        let mut regressions = Vec::new();
+        for lang in languages {
+            let Some(prev) = self.last_point(&lang, commit)? else {
+                continue;
+            };
+            let prev_commit = &prev.commit[..prev.commit.len().min(8)];
+            let (coverage, n_errors) = run.point(&lang);
+
+            if let (Some(max_drop), Some(prev_coverage)) = (gates.max_coverage_drop, prev.coverage)
+            {
+                let drop = (prev_coverage - coverage.unwrap_or(0.0)) * 100.0;
+                if drop > max_drop {
+                    regressions.push(Regression {
+                        lang: lang.clone(),
+                        message: format!(
+                            "coverage of `{}` dropped by {:.1} points since the last recorded run ({}), more than {} allowed",
+                            lang, drop, prev_commit, max_drop
+                        ),
+                    });
+                }
+            }
+            if let Some(max_increase) = gates.max_error_increase {
+                let increase = n_errors.saturating_sub(prev.n_errors);
+                if increase > max_increase {
+                    regressions.push(Regression {
+                        lang: lang.clone(),
+                        message: format!(
+                            "errors of `{}` increased by {} since the last recorded run ({}), more than {} allowed",
+                            lang, increase, prev_commit, max_increase
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(regressions)
+    }
+}
+
+/// Formats `secs` since the Unix epoch as `YYYY-MM-DD`, in UTC.
+fn format_date(secs: u64) -> String {
+    // Converts days since the epoch to a date in the proleptic Gregorian
+    // calendar, see http://howardhinnant.github.io/date_algorithms.html
+    let days = secs / 86400 + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LanguageTiers;
+    use crate::locale_file_parser::Translations;
+    use crate::rules::missing_translations::MissingTranslations;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indexmap::IndexMap;
+    use indicatif::ProgressBar;
+
+    /// Checks `localized_texts` with the `MissingTranslations` rule.
+    fn run_of(localized_texts: &LocalizedTexts) -> Run {
+        let mut checker = Checker::new();
+        checker.register_rule(MissingTranslations::new(LanguageTiers::default()));
+        checker.check(
+            &CheckContext {
+                localized_texts,
+                locale_keys: &[],
+                set_locale_calls: &[],
+                concatenations: &[],
+                sink_literals: &[],
+            },
+            &mut Timings::new(),
+            &ProgressBar::hidden(),
+        );
+        let mut run = Run::new();
+        run.record_project(None, localized_texts, &checker);
+        run
+    }
+
+    fn translations(langs: &[&str]) -> Translations {
+        Translations {
+            en: Some("en".into()),
+            others: langs
+                .iter()
+                .map(|lang| ((*lang).into(), "translated".into()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_history_trend() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("history.db");
+        assert!(History::open(&db, false).is_err());
+        let mut history = History::open(&db, true).unwrap();
+
+        let v1 = LocalizedTexts {
+            texts: IndexMap::from([
+                ("a".into(), translations(&["de"])),
+                ("b".into(), translations(&["fr"])),
+            ]),
+        };
+        let v2 = LocalizedTexts {
+            texts: IndexMap::from([
+                ("a".into(), translations(&["de"])),
+                ("b".into(), translations(&["de", "fr"])),
+            ]),
+        };
+        history.record("1111111111", 0, &run_of(&v1)).unwrap();
+        history.record("2222222222", 86400, &run_of(&v1)).unwrap();
+        // Checking a commit again replaces its run
+        history.record("2222222222", 86400, &run_of(&v2)).unwrap();
+
+        let trend = history.trend("de", 30).unwrap();
+        assert_eq!(
+            trend.points,
+            [
+                TrendPoint {
+                    commit: "1111111111".into(),
+                    timestamp: 0,
+                    coverage: Some(0.5),
+                    n_errors: 1,
+                },
+                TrendPoint {
+                    commit: "2222222222".into(),
+                    timestamp: 86400,
+                    coverage: Some(1.0),
+                    n_errors: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            trend.render(),
+            "Trend of `de` over the last 2 runs:
+commit   date       coverage  change errors change
+11111111 1970-01-01    50.0%              1
+22222222 1970-01-02   100.0%   +50.0      0     -1
+"
+        );
+        assert_eq!(history.trend("de", 1).unwrap().points.len(), 1);
+        assert_eq!(history.trend("ja", 30).unwrap().points[0].coverage, None);
+    }
+
+    #[test]
+    fn test_history_regressions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = History::open(&dir.path().join("history.db"), true).unwrap();
+        let complete = LocalizedTexts {
+            texts: IndexMap::from([
+                ("a".into(), translations(&["de", "fr"])),
+                ("b".into(), translations(&["de", "fr"])),
+            ]),
+        };
+        let incomplete = LocalizedTexts {
+            texts: IndexMap::from([
+                ("a".into(), translations(&["de", "fr"])),
+                ("b".into(), translations(&["fr"])),
+            ]),
+        };
+        let gates = TrendGatesConfig {
+            max_coverage_drop: Some(2.0),
+            max_error_increase: Some(0),
+            languages: Vec::new(),
+        };
+        let tiers = LanguageTiers::default();
+
+        // Nothing to compare with
+        let run = run_of(&incomplete);
+        assert!(history
+            .regressions("2222222222", &run, &gates, &tiers)
+            .unwrap()
+            .is_empty());
+
+        history.record("1111111111", 0, &run_of(&complete)).unwrap();
+        history.record("2222222222", 1, &run).unwrap();
+        // The run of the same commit is not compared with
+        let regressions = history
+            .regressions("2222222222", &run, &gates, &tiers)
+            .unwrap();
+        assert!(regressions.iter().all(|regression| regression.lang == "de"));
+        assert_eq!(
+            regressions.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "coverage of `de` dropped by 50.0 points since the last recorded run (11111111), more than 2 allowed",
+                "errors of `de` increased by 1 since the last recorded run (11111111), more than 0 allowed",
+            ]
+        );
+
+        // Removing a language is a regression
+        let without_de = LocalizedTexts {
+            texts: IndexMap::from([
+                ("a".into(), translations(&["fr"])),
+                ("b".into(), translations(&["fr"])),
+            ]),
+        };
+        assert_eq!(
+            history
+                .regressions("3333333333", &run_of(&without_de), &gates, &tiers)
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["coverage of `de` dropped by 50.0 points since the last recorded run (22222222), more than 2 allowed"]
+        );
+
+        let tiers = LanguageTiers {
+            tier1: vec!["fr".into()],
+            tier2: vec!["de".into()],
+        };
+        assert!(history
+            .regressions("2222222222", &run, &gates, &tiers)
+            .unwrap()
+            .is_empty());
+        let gates = TrendGatesConfig {
+            languages: vec!["de".into()],
+            ..gates
+        };
+        assert_eq!(
+            history
+                .regressions("2222222222", &run, &gates, &tiers)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+    }
+}
@@ -0,0 +1,109 @@
+//! This file contains the `init` subcommand, which writes a starter config
+//! file.
+
+use crate::config::DEFAULT_CONFIG_FILE;
+use crate::error::Error;
+use std::path::Path;
+
+/// The starter config file, every option is commented out with its default
+/// value.
+const STARTER_CONFIG: &str = include_str!("starter_config.toml");
+
+/// A pre-commit (<https://pre-commit.com>) hook that runs the check.
+const PRE_COMMIT_HOOK: &str = r#"# .pre-commit-config.yaml
+repos:
+  - repo: local
+    hooks:
+      - id: topgrade-i18n-check
+        name: topgrade i18n check
+        entry: topgrade_i18n_locale_checker
+        language: system
+        files: '(\.rs|\.ya?ml|i18n-checker\.toml)$'
+        pass_filenames: false
+"#;
+
+/// A plain git hook that runs the check.
+const GIT_HOOK: &str = r#"#!/bin/sh
+# .git/hooks/pre-commit
+exec topgrade_i18n_locale_checker
+"#;
+
+/// Writes the starter config file to `i18n-checker.toml` under `dir`,
+/// an existing file is only overwritten if `force` is true.
+///
+/// Prints the pre-commit hook snippets if `pre_commit` is true.
+pub(crate) fn init(dir: &Path, force: bool, pre_commit: bool) -> Result<(), Error> {
+    let path = dir.join(DEFAULT_CONFIG_FILE);
+    if path.exists() && !force {
+        return Err(Error::Usage(format!(
+            "{} already exists, use --force to overwrite it",
+            path.display()
+        )));
+    }
+
+    std::fs::write(&path, STARTER_CONFIG).map_err(|error| Error::Write {
+        path: path.clone(),
+        error,
+    })?;
+    println!("Wrote {}", path.display());
+
+    if pre_commit {
+        println!();
+        println!("{}", PRE_COMMIT_HOOK);
+        println!("{}", GIT_HOOK);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_starter_config_is_the_default() {
+        let config: Config = toml::from_str(STARTER_CONFIG).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_commented_options_are_valid() {
+        // Uncomment the options, the examples should be valid too
+        let uncommented = STARTER_CONFIG
+            .lines()
+            .map(|line| line.strip_prefix("# ").unwrap_or(line))
+            .filter(|line| {
+                line.starts_with('[')
+                    || line.starts_with(|c: char| c.is_ascii_lowercase()) && line.contains(" = ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let config: Config = toml::from_str(&uncommented).unwrap();
+        assert_eq!(config.rules.too_many_placeholders.max, 4);
+        assert_eq!(config.languages.tiers.tier1, ["en", "zh_CN"]);
+    }
+
+    #[test]
+    fn test_init() {
+        let dir = tempdir().unwrap();
+        init(dir.path(), false, false).unwrap();
+        let contents = std::fs::read_to_string(dir.path().join(DEFAULT_CONFIG_FILE)).unwrap();
+        assert_eq!(contents, STARTER_CONFIG);
+
+        init(dir.path(), true, false).unwrap();
+    }
+
+    #[test]
+    fn test_init_does_not_overwrite() {
+        let dir = tempdir().unwrap();
+        init(dir.path(), false, false).unwrap();
+        let res = init(dir.path(), false, false);
+        assert!(
+            matches!(&res, Err(Error::Usage(message)) if message.contains("already exists")),
+            "{:?}",
+            res
+        );
+    }
+}
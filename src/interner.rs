@@ -0,0 +1,57 @@
+//! This file contains type [`Interner`], which deduplicates the allocations of
+//! the strings (locale keys, translations) used across a run.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A string interner.
+///
+/// It is shared by [`LocalizedTexts`], [`LocaleKey`] and the errors stored in
+/// [`Checker`] so that a string (e.g., a locale key) that appears in all of
+/// them will only be allocated once.
+///
+/// [`LocalizedTexts`]: crate::locale_file_parser::LocalizedTexts
+/// [`LocaleKey`]: crate::locale_key_collector::LocaleKey
+/// [`Checker`]: crate::checker::Checker
+#[derive(Debug, Default)]
+pub(crate) struct Interner {
+    /// Strings that have been interned.
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    /// Creates an empty interner.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared allocation of `str`, allocates one if `str` has not
+    /// been seen.
+    pub(crate) fn intern(&mut self, str: &str) -> Arc<str> {
+        match self.strings.get(str) {
+            Some(interned) => Arc::clone(interned),
+            None => {
+                let interned: Arc<str> = Arc::from(str);
+                self.strings.insert(Arc::clone(&interned));
+                interned
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_allocation() {
+        let mut interner = Interner::new();
+        let foo = interner.intern("foo");
+        let foo_again = interner.intern("foo");
+        let bar = interner.intern("bar");
+
+        assert!(Arc::ptr_eq(&foo, &foo_again));
+        assert!(!Arc::ptr_eq(&foo, &bar));
+        assert_eq!(interner.strings.len(), 2);
+    }
+}
@@ -0,0 +1,30 @@
+//! This file handles Ctrl-C, so that the diagnostics found so far can still be
+//! reported when users interrupt a long scan.
+
+use crate::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code used when this tool is interrupted, `128 + SIGINT` by convention.
+pub(crate) const EXIT_CODE_ON_INTERRUPT: i32 = 130;
+
+/// Set when Ctrl-C is pressed.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Installs the Ctrl-C handler.
+///
+/// The first Ctrl-C only sets a flag, the work in progress should check
+/// [`is_interrupted()`] and stop early. A second one terminates the process
+/// immediately.
+pub(crate) fn install_handler() -> Result<(), Error> {
+    ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(EXIT_CODE_ON_INTERRUPT);
+        }
+    })
+    .map_err(|e| Error::Other(format!("cannot set the Ctrl-C handler: {}", e)))
+}
+
+/// Returns true if Ctrl-C has been pressed.
+pub(crate) fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,334 @@
+//! This file contains `report --issues`, which renders one Markdown issue body
+//! per language with its missing and incorrect translations, ready to be
+//! posted as a "Help translate Topgrade into X" issue.
+
+use crate::checker::Checker;
+use crate::config::LanguageMaintainers;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use indexmap::IndexMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Name of the rule whose diagnostics are missing translations, diagnostics of
+/// other rules are incorrect translations.
+const MISSING_TRANSLATIONS: &str = "MissingTranslations";
+
+/// At most this many call sites are linked per key.
+const MAX_CALL_SITES: usize = 3;
+
+/// An issue asking for help with a language.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Issue {
+    /// The language.
+    pub(crate) lang: String,
+    /// Title of the issue.
+    pub(crate) title: String,
+    /// Body of the issue, in Markdown.
+    pub(crate) body: String,
+}
+
+/// Everything needed to render the issues of a project.
+#[derive(Clone, Copy)]
+pub(crate) struct IssueContext<'a> {
+    /// The locale file.
+    pub(crate) locale_file: &'a Path,
+    /// The parsed locale file.
+    pub(crate) localized_texts: &'a LocalizedTexts,
+    /// The `t!()` invocations found.
+    pub(crate) locale_keys: &'a [LocaleKey],
+    /// The checker that holds the diagnostics found.
+    pub(crate) checker: &'a Checker,
+    /// URL that file paths are appended to for links, e.g.,
+    /// `https://github.com/topgrade-rs/topgrade/blob/main/`, files are not
+    /// linked if `None`.
+    pub(crate) link_base: Option<&'a str>,
+    /// The maintainers, mentioned in the issues of their languages.
+    pub(crate) maintainers: &'a LanguageMaintainers,
+}
+
+/// A row of a table in an issue.
+struct Row<'a> {
+    /// The key.
+    key: &'a str,
+    /// What is wrong, `None` for a missing translation.
+    problem: Option<&'a str>,
+}
+
+impl IssueContext<'_> {
+    /// Renders an issue for every language, other than English, affected by
+    /// any diagnostic, in alphabetical order.
+    pub(crate) fn render_issues(&self) -> Vec<Issue> {
+        // `Language => (Missing, Incorrect)`
+        let mut by_lang: IndexMap<&str, (Vec<Row>, Vec<Row>)> = IndexMap::new();
+        for (rule, diagnostic) in self.checker.errors() {
+            for lang in diagnostic.langs.iter().filter(|lang| &***lang != "en") {
+                let (missing, incorrect) = by_lang.entry(lang).or_default();
+                if rule == MISSING_TRANSLATIONS {
+                    missing.push(Row {
+                        key: &diagnostic.key,
+                        problem: None,
+                    });
+                } else {
+                    incorrect.push(Row {
+                        key: &diagnostic.key,
+                        problem: Some(diagnostic.error_msg.as_deref().unwrap_or(rule)),
+                    });
+                }
+            }
+        }
+        by_lang.sort_keys();
+
+        by_lang
+            .into_iter()
+            .map(|(lang, (missing, incorrect))| Issue {
+                lang: lang.to_string(),
+                title: format!("Help translate Topgrade into {}", lang),
+                body: self.render_body(lang, &missing, &incorrect),
+            })
+            .collect()
+    }
+
+    /// Renders the body of the issue of `lang`.
+    fn render_body(&self, lang: &str, missing: &[Row], incorrect: &[Row]) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "Topgrade's `{}` translation has {} missing and {} incorrect translations, help is welcome!",
+            lang,
+            missing.len(),
+            incorrect.len()
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "Translations are in {}, add or fix the `{}` translation of every key below, the English text shows what it means.",
+            self.link(self.locale_file, None),
+            lang
+        )
+        .unwrap();
+
+        for (title, rows) in [
+            ("Missing translations", missing),
+            ("Incorrect translations", incorrect),
+        ] {
+            if rows.is_empty() {
+                continue;
+            }
+            writeln!(out).unwrap();
+            writeln!(out, "### {}", title).unwrap();
+            writeln!(out).unwrap();
+            if rows[0].problem.is_some() {
+                writeln!(out, "| Key | Problem | English | Used in |").unwrap();
+                writeln!(out, "| --- | --- | --- | --- |").unwrap();
+            } else {
+                writeln!(out, "| Key | English | Used in |").unwrap();
+                writeln!(out, "| --- | --- | --- |").unwrap();
+            }
+            for row in rows {
+                let english = self
+                    .localized_texts
+                    .texts
+                    .get(row.key)
+                    .and_then(|translations| translations.en.as_deref())
+                    .map(escape_cell)
+                    .unwrap_or_default();
+                let problem = match row.problem {
+                    Some(problem) => format!(" {} |", escape_cell(problem)),
+                    None => String::new(),
+                };
+                writeln!(
+                    out,
+                    "| {} |{} {} | {} |",
+                    code_span(row.key),
+                    problem,
+                    english,
+                    self.call_sites(row.key)
+                )
+                .unwrap();
+            }
+        }
+
+        let maintainers = self.maintainers.of(lang);
+        if !maintainers.is_empty() {
+            writeln!(out).unwrap();
+            writeln!(out, "cc {}", maintainers.join(" ")).unwrap();
+        }
+
+        out
+    }
+
+    /// Returns the links to the call sites of `key`, at most
+    /// [`MAX_CALL_SITES`] of them.
+    fn call_sites(&self, key: &str) -> String {
+        let call_sites = self
+            .locale_keys
+            .iter()
+            .filter(|locale_key| &*locale_key.key == key)
+            .collect::<Vec<_>>();
+        let mut links = call_sites
+            .iter()
+            .take(MAX_CALL_SITES)
+            .map(|locale_key| self.link(&locale_key.file, Some(locale_key.line)))
+            .collect::<Vec<_>>();
+        if call_sites.len() > MAX_CALL_SITES {
+            links.push(format!("and {} more", call_sites.len() - MAX_CALL_SITES));
+        }
+
+        links.join(", ")
+    }
+
+    /// Returns a Markdown link to `file`, at `line` if given, or its path if
+    /// there is no [`IssueContext::link_base`].
+    fn link(&self, file: &Path, line: Option<usize>) -> String {
+        let path = file.display().to_string();
+        let path = path.strip_prefix("./").unwrap_or(&path);
+        let text = match line {
+            Some(line) => format!("{}:{}", path, line),
+            None => path.to_string(),
+        };
+        match self.link_base {
+            Some(base) => {
+                let anchor = line.map(|line| format!("#L{}", line)).unwrap_or_default();
+                format!(
+                    "[`{}`]({}/{}{})",
+                    text,
+                    base.trim_end_matches('/'),
+                    path,
+                    anchor
+                )
+            }
+            None => format!("`{}`", text),
+        }
+    }
+}
+
+/// Escapes `text` so that it fits in a cell of a Markdown table.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Renders `text` as a code span that fits in a cell of a Markdown table.
+fn code_span(text: &str) -> String {
+    let text = escape_cell(text);
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LanguageTiers;
+    use crate::locale_file_parser::Translations;
+    use crate::rules::missing_translations::MissingTranslations;
+    use crate::rules::stub_translations::StubTranslations;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indicatif::ProgressBar;
+
+    #[test]
+    fn test_render_issues() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting {app}".into(),
+                    Translations {
+                        en: Some("Restarting %{app}".into()),
+                        others: IndexMap::from([("fr".into(), "TODO".into())]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "a|b".into(),
+                    Translations {
+                        en: Some("a|b".into()),
+                        others: IndexMap::from([
+                            ("de".into(), "a|b".into()),
+                            ("fr".into(), "a|b".into()),
+                        ]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let locale_keys = (1..=4)
+            .map(|line| LocaleKey {
+                key: "Restarting {app}".into(),
+                file: Path::new("./src/main.rs").into(),
+                line,
+                column: 0,
+                args: vec!["app".into()],
+                locale: None,
+                ignored: false,
+            })
+            .collect::<Vec<_>>();
+        let mut checker = Checker::new();
+        checker.register_rule(MissingTranslations::new(LanguageTiers::default()));
+        checker.register_rule(StubTranslations::new(&[]).unwrap());
+        checker.check(
+            &CheckContext {
+                localized_texts: &localized_texts,
+                locale_keys: &locale_keys,
+                set_locale_calls: &[],
+                concatenations: &[],
+                sink_literals: &[],
+            },
+            &mut Timings::new(),
+            &ProgressBar::hidden(),
+        );
+
+        let ctx = IssueContext {
+            locale_file: Path::new("locales/app.yml"),
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            checker: &checker,
+            link_base: Some("https://github.com/topgrade-rs/topgrade/blob/main/"),
+            maintainers: &LanguageMaintainers::from([("fr", &["@alice", "@bob"][..])]),
+        };
+        let issues = ctx.render_issues();
+        assert_eq!(
+            issues.iter().map(|issue| &*issue.title).collect::<Vec<_>>(),
+            [
+                "Help translate Topgrade into de",
+                "Help translate Topgrade into fr"
+            ]
+        );
+        assert_eq!(
+            issues[0].body,
+            "Topgrade's `de` translation has 1 missing and 0 incorrect translations, help is welcome!
+
+Translations are in [`locales/app.yml`](https://github.com/topgrade-rs/topgrade/blob/main/locales/app.yml), add or fix the `de` translation of every key below, the English text shows what it means.
+
+### Missing translations
+
+| Key | English | Used in |
+| --- | --- | --- |
+| `Restarting {app}` | Restarting %{app} | [`src/main.rs:1`](https://github.com/topgrade-rs/topgrade/blob/main/src/main.rs#L1), [`src/main.rs:2`](https://github.com/topgrade-rs/topgrade/blob/main/src/main.rs#L2), [`src/main.rs:3`](https://github.com/topgrade-rs/topgrade/blob/main/src/main.rs#L3), and 1 more |
+"
+        );
+
+        let ctx = IssueContext {
+            link_base: None,
+            ..ctx
+        };
+        let issues = ctx.render_issues();
+        assert!(issues[1].body.contains(
+            "| Key | Problem | English | Used in |
+| --- | --- | --- | --- |
+| `Restarting {app}` | "
+        ));
+        assert!(issues[1].body.contains("| `src/main.rs:1`, "));
+        assert!(!issues[1].body.contains("### Missing translations"));
+        assert!(issues[1].body.ends_with("|\n\ncc @alice @bob\n"));
+    }
+
+    #[test]
+    fn test_code_span() {
+        assert_eq!(code_span("a|b"), "`a\\|b`");
+        assert_eq!(code_span("a`b"), "`` a`b ``");
+    }
+}
@@ -0,0 +1,217 @@
+//! This file contains the JUnit XML output, which most CI systems render as
+//! test results.
+//!
+//! Every rule is a test suite, and every key a rule reports is a test case of
+//! it: errors are failures, warnings and informational diagnostics pass, with
+//! their messages as the output of the test case.
+
+use crate::editor::subject_of;
+use crate::report::{Report, ReportDiagnostic};
+use crate::rules::Severity;
+use indexmap::IndexMap;
+use std::fmt::Write;
+
+/// Renders the diagnostics of `report` as a JUnit XML report, suites and cases
+/// are in the order they are first reported.
+pub(crate) fn render(report: &Report) -> String {
+    // `RuleName => TestCaseName => Diagnostics`
+    let mut suites: IndexMap<&str, IndexMap<String, Vec<&ReportDiagnostic>>> = IndexMap::new();
+    for diagnostic in report.diagnostics.iter() {
+        let subject = subject_of(&diagnostic.key);
+        let name = match &diagnostic.project {
+            Some(project) => format!("{}: {}", project.display(), subject),
+            None => subject.into_owned(),
+        };
+        suites
+            .entry(&diagnostic.rule)
+            .or_default()
+            .entry(name)
+            .or_default()
+            .push(diagnostic);
+    }
+
+    let is_failure = |diagnostics: &[&ReportDiagnostic]| {
+        diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    };
+    let n_tests = suites.values().map(IndexMap::len).sum::<usize>();
+    let n_failures = suites
+        .values()
+        .flat_map(IndexMap::values)
+        .filter(|diagnostics| is_failure(diagnostics))
+        .count();
+
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        out,
+        r#"<testsuites name="topgrade-i18n-check" tests="{}" failures="{}">"#,
+        n_tests, n_failures
+    )
+    .unwrap();
+    for (rule, cases) in suites.iter() {
+        let n_failures = cases
+            .values()
+            .filter(|diagnostics| is_failure(diagnostics))
+            .count();
+        writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+            escape(rule),
+            cases.len(),
+            n_failures
+        )
+        .unwrap();
+        for (name, diagnostics) in cases.iter() {
+            let first = diagnostics[0];
+            writeln!(
+                out,
+                r#"    <testcase classname="{}" name="{}" file="{}" line="{}">"#,
+                escape(rule),
+                escape(name),
+                escape(&first.file.display().to_string()),
+                first.line
+            )
+            .unwrap();
+            let lines = diagnostics
+                .iter()
+                .map(|diagnostic| {
+                    let mut line = format!(
+                        "{}:{}:{}: {}",
+                        diagnostic.file.display(),
+                        diagnostic.line,
+                        diagnostic.column,
+                        diagnostic.severity.as_str()
+                    );
+                    if let Some(message) = &diagnostic.message {
+                        line.push_str(": ");
+                        line.push_str(message);
+                    }
+                    escape(&line)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if is_failure(diagnostics) {
+                let message = first.message.as_deref().unwrap_or(&first.key);
+                writeln!(
+                    out,
+                    r#"      <failure message="{}" type="{}">{}</failure>"#,
+                    escape(message),
+                    escape(&first.code),
+                    lines
+                )
+                .unwrap();
+            } else {
+                writeln!(out, "      <system-out>{}</system-out>", lines).unwrap();
+            }
+            writeln!(out, "    </testcase>").unwrap();
+        }
+        writeln!(out, "  </testsuite>").unwrap();
+    }
+    writeln!(out, "</testsuites>").unwrap();
+
+    out
+}
+
+/// Escapes `text` for XML text and attribute values, control characters XML
+/// does not allow are replaced with U+FFFD.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            c if c.is_control() || c == '\u{FFFE}' || c == '\u{FFFF}' => {
+                escaped.push(char::REPLACEMENT_CHARACTER)
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(rule: &str, key: &str, line: usize, severity: Severity) -> ReportDiagnostic {
+        ReportDiagnostic {
+            project: None,
+            rule: rule.into(),
+            code: "I18N001".into(),
+            severity,
+            in_source: false,
+            file: "locales/app.yml".into(),
+            line,
+            column: 1,
+            key: key.into(),
+            message: Some("Missing <de>".into()),
+            langs: vec!["de".into()],
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut report = Report::new();
+        report.diagnostics = vec![
+            diagnostic("MissingTranslations", "Restarting", 2, Severity::Error),
+            diagnostic("MissingTranslations", "Restarting", 2, Severity::Error),
+            diagnostic("MissingTranslations", "Pulling", 5, Severity::Warning),
+        ];
+
+        assert_eq!(
+            render(&report),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="topgrade-i18n-check" tests="2" failures="1">
+  <testsuite name="MissingTranslations" tests="2" failures="1">
+    <testcase classname="MissingTranslations" name="key &apos;Restarting&apos;" file="locales/app.yml" line="2">
+      <failure message="Missing &lt;de&gt;" type="I18N001">locales/app.yml:2:1: error: Missing &lt;de&gt;
+locales/app.yml:2:1: error: Missing &lt;de&gt;</failure>
+    </testcase>
+    <testcase classname="MissingTranslations" name="key &apos;Pulling&apos;" file="locales/app.yml" line="5">
+      <system-out>locales/app.yml:5:1: warning: Missing &lt;de&gt;</system-out>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+        assert_eq!(
+            render(&Report::new()),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"topgrade-i18n-check\" tests=\"0\" failures=\"0\">\n</testsuites>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_without_message() {
+        let mut report = Report::new();
+        let mut undefined = diagnostic("UseOfKeysDoNotExist", "Pull\u{7}ing", 3, Severity::Error);
+        undefined.message = None;
+        report.diagnostics = vec![undefined];
+
+        assert!(render(&report).contains(
+            "<failure message=\"Pull\u{FFFD}ing\" type=\"I18N001\">locales/app.yml:3:1: error</failure>"
+        ));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">&'"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;"
+        );
+        assert_eq!(escape("a\nb"), "a&#10;b");
+        assert_eq!(escape("a\r\tb"), "a&#13;&#9;b");
+        assert_eq!(
+            escape("a\u{1b}[0mb\0\u{FFFF}"),
+            "a\u{FFFD}[0mb\u{FFFD}\u{FFFD}"
+        );
+    }
+}
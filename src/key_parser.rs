@@ -0,0 +1,334 @@
+//! This file contains the parser of locale keys, i.e., the first argument of
+//! `t!()`, which are the English texts with placeholders written as `{name}`.
+//!
+//! The grammar of a key is:
+//!
+//! ```text
+//! key         = { text | escaped | placeholder | stray }
+//! escaped     = "{{" | "}}"             ; a literal `{` or `}`
+//! placeholder = "{" name "}"
+//! name        = 1*(any char except "{" and "}")
+//! stray       = "{" | "}"               ; neither of the above
+//! text        = 1*(any char except "{" and "}")
+//! ```
+//!
+//! Braces are matched from left to right, `{{` always escapes, so
+//! `{{{name}}}` is a literal `{`, placeholder `name`, and a literal `}`.
+//! Nested braces are not supported: in `{a{b}}`, the first `{` and the last
+//! `}` are stray and `b` is a placeholder. Stray braces are kept literally,
+//! but they are probably mistakes, the `AmbiguousBraces` rule reports them.
+//!
+//! ```
+//! use topgrade_i18n_locale_checker::key_parser::{placeholders_of_key, LocaleKeyParser, LocaleToken};
+//!
+//! let mut parser = LocaleKeyParser::new();
+//! parser.parse("Restarting {app} {{now}}");
+//! assert_eq!(
+//!     parser.tokens(),
+//!     [
+//!         LocaleToken::WithoutBrace("Restarting "),
+//!         LocaleToken::WithinBrace("app"),
+//!         LocaleToken::WithoutBrace(" "),
+//!         LocaleToken::Escaped("{"),
+//!         LocaleToken::WithoutBrace("now"),
+//!         LocaleToken::Escaped("}"),
+//!     ]
+//! );
+//! assert_eq!(placeholders_of_key("Restarting {app} {{now}}"), ["app"]);
+//! ```
+
+/// A locale token in the key.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LocaleToken<'token> {
+    /// It is not surrounded by a pair of braces
+    WithoutBrace(&'token str),
+    /// It is surrounded by a pair of braces
+    WithinBrace(&'token str),
+    /// A literal brace, `{` or `}`, written as `{{` or `}}`
+    Escaped(&'token str),
+    /// A brace, `{` or `}`, that is neither escaped nor a part of a
+    /// placeholder, e.g., the braces of `{}`
+    Stray(&'token str),
+}
+
+/// Key parser.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LocaleKeyParser<'input> {
+    /// tokens
+    tokens: Vec<LocaleToken<'input>>,
+}
+
+impl<'input> LocaleKeyParser<'input> {
+    /// Create a parser with nothing.
+    pub fn new() -> Self {
+        Self { tokens: Vec::new() }
+    }
+
+    /// Accesses the parsed tokens.
+    pub fn tokens(&self) -> &[LocaleToken<'input>] {
+        &self.tokens
+    }
+
+    /// Parses the `input`, stores the parsed tokens in `self`.
+    pub fn parse<'slf>(&'slf mut self, input: &'input str) {
+        let bytes = input.as_bytes();
+        // Start of the text not pushed yet
+        let mut text_start = 0;
+        let mut offset = 0;
+
+        while offset < bytes.len() {
+            let token = match bytes[offset] {
+                brace @ (b'{' | b'}') if bytes.get(offset + 1) == Some(&brace) => {
+                    let token = LocaleToken::Escaped(&input[offset..=offset]);
+                    Some((token, offset + 2))
+                }
+                b'{' => {
+                    // A placeholder if `}` comes before any other brace
+                    let name_end = input[offset + 1..]
+                        .find(['{', '}'])
+                        .map(|len| offset + 1 + len);
+                    match name_end {
+                        Some(end) if bytes[end] == b'}' && end > offset + 1 => {
+                            let token = LocaleToken::WithinBrace(&input[offset + 1..end]);
+                            Some((token, end + 1))
+                        }
+                        _ => Some((LocaleToken::Stray(&input[offset..=offset]), offset + 1)),
+                    }
+                }
+                b'}' => Some((LocaleToken::Stray(&input[offset..=offset]), offset + 1)),
+                _ => None,
+            };
+
+            match token {
+                Some((token, next_offset)) => {
+                    self.push_text(&input[text_start..offset]);
+                    self.tokens.push(token);
+                    offset = next_offset;
+                    text_start = offset;
+                }
+                None => offset += 1,
+            }
+        }
+        self.push_text(&input[text_start..]);
+    }
+
+    /// Pushes `text` as a [`LocaleToken::WithoutBrace`] unless it is empty.
+    fn push_text(&mut self, text: &'input str) {
+        if !text.is_empty() {
+            self.tokens.push(LocaleToken::WithoutBrace(text));
+        }
+    }
+}
+
+/// Returns the names of the placeholders in `key`, in the order they appear,
+/// duplicates are kept.
+///
+/// ```text
+/// "Restarting {app} in {dir}, {{literal}}" => ["app", "dir"]
+/// ```
+pub fn placeholders_of_key(key: &str) -> Vec<&str> {
+    let mut parser = LocaleKeyParser::new();
+    parser.parse(key);
+
+    parser
+        .tokens
+        .into_iter()
+        .filter_map(|token| match token {
+            LocaleToken::WithinBrace(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_brace() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("without_any_brace");
+
+        for token in parser.tokens() {
+            assert!(matches!(token, LocaleToken::WithoutBrace(_)));
+        }
+    }
+
+    #[test]
+    fn starts_with_brace() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("{brace}topgrade");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::WithinBrace("brace"),
+                LocaleToken::WithoutBrace("topgrade"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn ends_with_brace() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("topgrade{brace}");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::WithoutBrace("topgrade"),
+                LocaleToken::WithinBrace("brace"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn brace_in_the_middle() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("topgrade{brace}topgrade");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::WithoutBrace("topgrade"),
+                LocaleToken::WithinBrace("brace"),
+                LocaleToken::WithoutBrace("topgrade"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn continuous_braces() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("{brace}{brace}");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::WithinBrace("brace"),
+                LocaleToken::WithinBrace("brace"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn continuous_braces_in_the_middle() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("topgrade{brace}{brace}topgrade");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::WithoutBrace("topgrade"),
+                LocaleToken::WithinBrace("brace"),
+                LocaleToken::WithinBrace("brace"),
+                LocaleToken::WithoutBrace("topgrade"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn single_left_brace() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("{");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![LocaleToken::Stray("{")],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn mutliple_left_brace() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("x{x{x{");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::WithoutBrace("x"),
+                LocaleToken::Stray("{"),
+                LocaleToken::WithoutBrace("x"),
+                LocaleToken::Stray("{"),
+                LocaleToken::WithoutBrace("x"),
+                LocaleToken::Stray("{"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn a_pair_in_chaos() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("}{x{x}{{x{");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::Stray("}"),
+                LocaleToken::Stray("{"),
+                LocaleToken::WithoutBrace("x"),
+                LocaleToken::WithinBrace("x"),
+                LocaleToken::Escaped("{"),
+                LocaleToken::WithoutBrace("x"),
+                LocaleToken::Stray("{"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn escaped_braces() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("{{x}} {{{y}}}");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::Escaped("{"),
+                LocaleToken::WithoutBrace("x"),
+                LocaleToken::Escaped("}"),
+                LocaleToken::WithoutBrace(" "),
+                LocaleToken::Escaped("{"),
+                LocaleToken::WithinBrace("y"),
+                LocaleToken::Escaped("}"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn nested_and_empty_braces() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("{a{b}} {}");
+
+        let expected = LocaleKeyParser {
+            tokens: vec![
+                LocaleToken::Stray("{"),
+                LocaleToken::WithoutBrace("a"),
+                LocaleToken::WithinBrace("b"),
+                LocaleToken::Stray("}"),
+                LocaleToken::WithoutBrace(" "),
+                LocaleToken::Stray("{"),
+                LocaleToken::Stray("}"),
+            ],
+        };
+
+        assert_eq!(parser, expected);
+    }
+
+    #[test]
+    fn test_placeholders_of_key() {
+        assert_eq!(
+            placeholders_of_key("Restarting {app} in {dir}, {app} {{literal}} {}"),
+            ["app", "dir", "app"]
+        );
+        assert!(placeholders_of_key("Restarting").is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+//! The parsers of locale keys and translations used by `topgrade-i18n-check`,
+//! exposed so that Topgrade's build scripts and fuzz targets can reuse exactly
+//! the same parsing logic.
+//!
+//! * [`key_parser`] parses keys, i.e., the first argument of `t!()`.
+//! * [`placeholder`] extracts the placeholders from translations.
+
+pub mod key_parser;
+pub mod placeholder;
@@ -1,79 +1,314 @@
 //! This file contains type [`LocalizedTexts`] which represents a parsed locale
 //! file.
 
-use indexmap::IndexMap;
+use crate::config::{LanguageAliases, VariantsConfig};
+use crate::interner::Interner;
+use indexmap::{IndexMap, IndexSet};
 use serde_yaml_ng::Value as Yaml;
+use std::sync::Arc;
 
 /// Topgrade uses locale file version 2
-const LOCALE_FILE_VERSION: i64 = 2;
+pub(crate) const LOCALE_FILE_VERSION: i64 = 2;
+
+/// The CLDR plural categories, in their canonical order.
+pub(crate) const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// Plural forms of a translation: `Category => Translation`, in the order they
+/// are defined.
+pub(crate) type PluralForms = IndexMap<Arc<str>, Arc<str>>;
 
 /// Translations of various languages.
-#[derive(Debug, PartialEq)]
+///
+/// A plural key has plural forms in some languages, defined either as a
+/// mapping of plural categories under the language:
+///
+/// ```yaml
+/// "{count} updates":
+///   en:
+///     one: "%{count} update"
+///     other: "%{count} updates"
+/// ```
+///
+/// or as keys suffixed with the categories, e.g., `"{count} updates.one"` and
+/// `"{count} updates.other"`. The `other` form, or the first one if there is
+/// none, stands for the translation in [`Translations::en`] and
+/// [`Translations::others`], so that rules comparing texts check it.
+#[derive(Debug, Default, PartialEq)]
 pub(crate) struct Translations {
     /// English
-    pub(crate) en: Option<String>,
+    pub(crate) en: Option<Arc<str>>,
+    /// Other languages: `Language => Translation`
+    pub(crate) others: IndexMap<Arc<str>, Arc<str>>,
+    /// Plural forms: `Language => PluralForms`, English included, empty if
+    /// this is not a plural key.
+    pub(crate) plurals: IndexMap<Arc<str>, PluralForms>,
 }
 
 impl Translations {
     /// Construct a [`Translation`] from the given `translation_mapping`.
-    fn new(translation_yaml: Yaml) -> Self {
+    ///
+    /// Language names are canonicalized with `aliases`, returns why
+    /// `translation_yaml` is invalid if it is.
+    fn new(
+        translation_yaml: Yaml,
+        interner: &mut Interner,
+        aliases: &LanguageAliases,
+    ) -> Result<Self, String> {
         match translation_yaml {
-            Yaml::Null => Self { en: None },
-
-            Yaml::Mapping(mut translation_mapping) => {
-                let en = {
-                    let opt_en_yaml = translation_mapping.remove("en");
-                    opt_en_yaml.map(|opt_yaml| match opt_yaml {
-                        Yaml::String(en) => en,
-                        _ => panic!("Error: translation should be string"),
-                    })
-                };
+            Yaml::Null => Ok(Self::default()),
+
+            Yaml::Mapping(translation_mapping) => {
+                let mut translations = Self::default();
+
+                for (lang, translation) in translation_mapping {
+                    let lang = match lang {
+                        Yaml::String(lang) => lang,
+                        _ => return Err("language should be a string".to_string()),
+                    };
+                    let lang = interner.intern(aliases.canonicalize(&lang));
+                    if translations.get(&lang).is_some() {
+                        return Err(format!("duplicate translations for language {}", lang));
+                    }
 
-                Self { en }
+                    match translation {
+                        Yaml::String(translation) => {
+                            let translation = interner.intern(&translation);
+                            translations.insert(lang, translation);
+                        }
+                        Yaml::Mapping(forms) => {
+                            for (category, form) in forms {
+                                let category = match category {
+                                    Yaml::String(category)
+                                        if PLURAL_CATEGORIES.contains(&&*category) =>
+                                    {
+                                        interner.intern(&category)
+                                    }
+                                    category => {
+                                        return Err(format!(
+                                        "plural category {:?} of language {} should be one of {}",
+                                        category,
+                                        lang,
+                                        PLURAL_CATEGORIES.join(", ")
+                                    ))
+                                    }
+                                };
+                                let form = match form {
+                                    Yaml::String(form) => interner.intern(&form),
+                                    _ => return Err("translation should be string".to_string()),
+                                };
+                                translations.insert_plural_form(
+                                    Arc::clone(&lang),
+                                    category,
+                                    form,
+                                )?;
+                            }
+                        }
+                        _ => return Err("translation should be string".to_string()),
+                    }
+                }
+
+                Ok(translations)
             }
 
-            _ => panic!("Error: invalid format for translation"),
+            _ => Err("invalid format for translation".to_string()),
+        }
+    }
+
+    /// Returns the translation of `lang`.
+    pub(crate) fn get(&self, lang: &str) -> Option<&Arc<str>> {
+        match lang {
+            "en" => self.en.as_ref(),
+            _ => self.others.get(lang),
         }
     }
+
+    /// Returns true if this is a plural key.
+    pub(crate) fn is_plural(&self) -> bool {
+        !self.plurals.is_empty()
+    }
+
+    /// Sets the translation of `lang`.
+    fn insert(&mut self, lang: Arc<str>, translation: Arc<str>) {
+        if &*lang == "en" {
+            self.en = Some(translation);
+        } else {
+            self.others.insert(lang, translation);
+        }
+    }
+
+    /// Adds the plural form of `category` in `lang`, it stands for the
+    /// translation of `lang` if it is the `other` form or the first one.
+    ///
+    /// Returns an error if `lang` already has the form.
+    fn insert_plural_form(
+        &mut self,
+        lang: Arc<str>,
+        category: Arc<str>,
+        form: Arc<str>,
+    ) -> Result<(), String> {
+        let forms = self.plurals.entry(Arc::clone(&lang)).or_default();
+        if forms
+            .insert(Arc::clone(&category), Arc::clone(&form))
+            .is_some()
+        {
+            return Err(format!(
+                "duplicate plural form {} for language {}",
+                category, lang
+            ));
+        }
+        if forms.len() == 1 || &*category == "other" {
+            self.insert(lang, form);
+        }
+
+        Ok(())
+    }
 }
 
 /// Represents all the localized texts used by Topgrade.
 #[derive(Debug, PartialEq)]
 pub(crate) struct LocalizedTexts {
     /// Locale key => All the translations.
-    pub(crate) texts: IndexMap<String, Translations>,
+    pub(crate) texts: IndexMap<Arc<str>, Translations>,
 }
 
 impl LocalizedTexts {
     /// Construct a [`LocalizedTexts`] from the given parsed yaml file.
-    pub(crate) fn new(file_yaml: Yaml) -> Self {
+    ///
+    /// Keys and translations are interned with `interner`, language names are
+    /// canonicalized with `aliases`, and the names of key variants are checked
+    /// against `variants`.
+    ///
+    /// Returns why `file_yaml` is not a valid locale file if it is not.
+    pub(crate) fn new(
+        file_yaml: Yaml,
+        interner: &mut Interner,
+        aliases: &LanguageAliases,
+        variants: &VariantsConfig,
+    ) -> Result<Self, String> {
         let mut file_mapping = match file_yaml {
             Yaml::Mapping(mapping) => mapping,
-            _ => panic!("The outer level container should be a mapping"),
+            _ => return Err("the outer level container should be a mapping".to_string()),
         };
 
         let locale_file_version = file_mapping
-            .remove("_version")
-            .unwrap_or_else(|| panic!("Error: local file version key `_version` not found"))
+            .shift_remove("_version")
+            .ok_or("locale file version key `_version` not found")?
             .as_i64()
-            .expect("Error: locale file version number should be a number");
+            .ok_or("locale file version number should be a number")?;
         if locale_file_version != LOCALE_FILE_VERSION {
-            panic!("Error: locale file version should be 2");
+            return Err(format!(
+                "locale file version should be {}",
+                LOCALE_FILE_VERSION
+            ));
         }
 
-        let mut texts = IndexMap::with_capacity(file_mapping.len());
+        // Keys suffixed with `.other` are plural keys, `key.one` is only a
+        // plural form if `key.other` exists
+        let plural_keys = file_mapping
+            .keys()
+            .filter_map(|key| key.as_str()?.strip_suffix(".other"))
+            .map(str::to_string)
+            .collect::<IndexSet<_>>();
+
+        let mut texts: IndexMap<Arc<str>, Translations> =
+            IndexMap::with_capacity(file_mapping.len());
         for (key, translations_yaml) in file_mapping {
             let key = match key {
                 Yaml::String(key) => key,
-                _ => panic!("Error: locale translation key should be a string"),
+                _ => return Err("locale translation key should be a string".to_string()),
             };
 
-            let translations = Translations::new(translations_yaml);
+            let translations = Translations::new(translations_yaml, interner, aliases)
+                .map_err(|e| format!("key {}: {}", key, e))?;
 
-            texts.insert(key, translations);
+            let plural_form = key.rsplit_once('.').filter(|(key, category)| {
+                PLURAL_CATEGORIES.contains(category) && plural_keys.contains(*key)
+            });
+
+            let text_key = plural_form.map_or(&*key, |(key, _)| key);
+            if let (base, Some(name)) = variants.split(text_key) {
+                if !variants.names.is_empty() && !variants.names.iter().any(|n| n == name) {
+                    return Err(format!(
+                        "key {} is a variant of {} named {}, expect one of [{}] configured in `variants.names`",
+                        text_key,
+                        base,
+                        name,
+                        variants.names.join(", ")
+                    ));
+                }
+            }
+
+            match plural_form {
+                Some((key, category)) => {
+                    if translations.is_plural() {
+                        return Err(format!(
+                            "plural form {}.{} should not have plural forms",
+                            key, category
+                        ));
+                    }
+                    let plural = texts.entry(interner.intern(key)).or_default();
+                    if !plural.is_plural() && (plural.en.is_some() || !plural.others.is_empty()) {
+                        return Err(format!(
+                            "key {} is defined both with and without plural forms",
+                            key
+                        ));
+                    }
+                    let category = interner.intern(category);
+                    let en = translations.en.map(|en| (interner.intern("en"), en));
+                    for (lang, form) in en.into_iter().chain(translations.others) {
+                        plural.insert_plural_form(lang, Arc::clone(&category), form)?;
+                    }
+                }
+                None => {
+                    if texts.contains_key(&*key) {
+                        return Err(format!(
+                            "key {} is defined both with and without plural forms",
+                            key
+                        ));
+                    }
+                    texts.insert(interner.intern(&key), translations);
+                }
+            }
+        }
+
+        Ok(Self { texts })
+    }
+
+    /// Returns all the languages that appear in the locale file, English first
+    /// if it appears.
+    pub(crate) fn languages(&self) -> IndexSet<Arc<str>> {
+        let mut languages = IndexSet::new();
+        if self.texts.values().any(|t| t.en.is_some()) {
+            languages.insert("en".into());
         }
+        for translations in self.texts.values() {
+            languages.extend(translations.others.keys().cloned());
+        }
+
+        languages
+    }
+
+    /// Returns the translation coverage of every language: `(Language, Ratio)`,
+    /// where `Ratio` is the fraction of keys that have a translation in that
+    /// language.
+    pub(crate) fn coverage(&self) -> Vec<(String, f64)> {
+        let n_keys = self.texts.len();
+
+        self.languages()
+            .into_iter()
+            .map(|lang| {
+                let n_translated = self
+                    .texts
+                    .values()
+                    .filter(|t| match &*lang {
+                        "en" => t.en.is_some(),
+                        _ => t.others.contains_key(&lang),
+                    })
+                    .count();
 
-        Self { texts }
+                (lang.to_string(), n_translated as f64 / n_keys as f64)
+            })
+            .collect()
     }
 }
 
@@ -82,7 +317,6 @@ mod tests {
     use super::*;
 
     #[test]
-    #[should_panic(expected = "Error: locale translation key should be a string")]
     fn test_key_should_be_string() {
         let yaml_str = r#"
 _version: 2
@@ -90,11 +324,17 @@ _version: 2
   en: "en"
 "#;
         let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
-        LocalizedTexts::new(yaml);
+        let err = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &VariantsConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, "locale translation key should be a string");
     }
 
     #[test]
-    #[should_panic(expected = "Error: locale file version should be 2")]
     fn test_should_have_version_2() {
         let yaml_str = r#"
 _version: 1
@@ -102,18 +342,31 @@ _version: 1
 "with_en":
   en: "with_en""#;
         let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
-        LocalizedTexts::new(yaml);
+        let err = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &VariantsConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, "locale file version should be 2");
     }
 
     #[test]
-    #[should_panic(expected = "Error: local file version key `_version` not found")]
     fn test_version_not_found() {
         let yaml_str = r#"
 "with_no_en":
 "with_en":
   en: "with_en""#;
         let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
-        LocalizedTexts::new(yaml);
+        let err = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &VariantsConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, "locale file version key `_version` not found");
     }
 
     #[test]
@@ -124,20 +377,242 @@ _version: 2
 "with_en":
   en: "with_en""#;
         let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
-        let parsed = LocalizedTexts::new(yaml);
+        let parsed = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &VariantsConfig::default(),
+        )
+        .unwrap();
 
         let expected = LocalizedTexts {
             texts: IndexMap::from_iter(vec![
-                ("with_no_en".to_string(), Translations { en: None }),
+                ("with_no_en".into(), Translations::default()),
                 (
-                    "with_en".to_string(),
+                    "with_en".into(),
                     Translations {
-                        en: Some("with_en".to_string()),
+                        en: Some("with_en".into()),
+                        ..Default::default()
                     },
                 ),
             ]),
         };
 
         assert_eq!(parsed, expected);
+        assert_eq!(parsed.coverage(), [("en".to_string(), 0.5)]);
+    }
+
+    #[test]
+    fn test_language_aliases() {
+        let yaml_str = r#"
+_version: 2
+"foo":
+  en: "foo"
+  zh-CN: "zh_foo"
+"bar":
+  en: "bar"
+  zh_CN: "zh_bar"
+  pt_BR: "pt_bar"
+"#;
+        let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
+        let aliases = LanguageAliases::from([("zh-CN", "zh_CN"), ("pt_BR", "pt-BR")]);
+        let parsed = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &aliases,
+            &VariantsConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            parsed
+                .languages()
+                .iter()
+                .map(|lang| &**lang)
+                .collect::<Vec<_>>(),
+            ["en", "zh_CN", "pt-BR"]
+        );
+        assert_eq!(
+            parsed.coverage(),
+            [
+                ("en".to_string(), 1.0),
+                ("zh_CN".to_string(), 1.0),
+                ("pt-BR".to_string(), 0.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_languages_after_canonicalization() {
+        let yaml_str = r#"
+_version: 2
+"foo":
+  zh-CN: "foo"
+  zh_CN: "foo"
+"#;
+        let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
+        let aliases = LanguageAliases::from([("zh-CN", "zh_CN")]);
+        let err = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &aliases,
+            &VariantsConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, "key foo: duplicate translations for language zh_CN");
+    }
+
+    #[test]
+    fn test_plural_keys() {
+        let yaml_str = r#"
+_version: 2
+"{count} updates":
+  en:
+    one: "%{count} update"
+    other: "%{count} updates"
+  de:
+    other: "%{count} Updates"
+    one: "%{count} Update"
+  ja: "%{count} 件の更新"
+"{count} errors.one":
+  en: "%{count} error"
+"{count} errors.other":
+  en: "%{count} errors"
+  fr: "%{count} erreurs"
+"Version 1.one":
+  en: "Version 1.one"
+"#;
+        let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
+        let parsed = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &VariantsConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            parsed.texts.keys().map(|key| &**key).collect::<Vec<_>>(),
+            ["{count} updates", "{count} errors", "Version 1.one"]
+        );
+        let updates = &parsed.texts["{count} updates"];
+        assert!(updates.is_plural());
+        assert_eq!(updates.en.as_deref(), Some("%{count} updates"));
+        assert_eq!(updates.get("de").map(|de| &**de), Some("%{count} Updates"));
+        assert_eq!(updates.get("ja").map(|ja| &**ja), Some("%{count} 件の更新"));
+        assert_eq!(
+            updates
+                .plurals
+                .iter()
+                .map(|(lang, forms)| (&**lang, forms.keys().map(|c| &**c).collect::<Vec<_>>()))
+                .collect::<Vec<_>>(),
+            [("en", vec!["one", "other"]), ("de", vec!["other", "one"])]
+        );
+
+        let errors = &parsed.texts["{count} errors"];
+        assert_eq!(errors.en.as_deref(), Some("%{count} errors"));
+        assert_eq!(errors.plurals["en"]["one"].as_ref(), "%{count} error");
+        assert_eq!(errors.plurals["fr"].len(), 1);
+        assert!(!parsed.texts["Version 1.one"].is_plural());
+    }
+
+    #[test]
+    fn test_invalid_plural_category() {
+        let yaml_str = r#"
+_version: 2
+"{count} updates":
+  en:
+    some: "%{count} updates"
+"#;
+        let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
+        let err = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &VariantsConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "key {count} updates: plural category String(\"some\") of language en should be one of zero, one, two, few, many, other"
+        );
+    }
+
+    #[test]
+    fn test_plural_key_defined_twice() {
+        let yaml_str = r#"
+_version: 2
+"updates":
+  en: "updates"
+"updates.other":
+  en: "updates"
+"#;
+        let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
+        let err = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &VariantsConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "key updates is defined both with and without plural forms"
+        );
+    }
+
+    #[test]
+    fn test_variants() {
+        let yaml_str = r#"
+_version: 2
+"Welcome@masculine":
+  en: "Welcome"
+"Welcome@feminine.one":
+  en: "Welcome"
+"Welcome@feminine.other":
+  en: "Welcome"
+"#;
+        let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
+        let variants = VariantsConfig {
+            separator: Some("@".into()),
+            names: vec!["masculine".into(), "feminine".into()],
+        };
+        let parsed = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &variants,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parsed.texts.keys().map(|key| &**key).collect::<Vec<_>>(),
+            ["Welcome@masculine", "Welcome@feminine"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_variant() {
+        let yaml_str = r#"
+_version: 2
+"Welcome@femenine":
+  en: "Welcome"
+"#;
+        let yaml: Yaml = serde_yaml_ng::from_str(yaml_str).unwrap();
+        let variants = VariantsConfig {
+            separator: Some("@".into()),
+            names: vec!["masculine".into(), "feminine".into()],
+        };
+        let err = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &LanguageAliases::default(),
+            &variants,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "key Welcome@femenine is a variant of Welcome named femenine, expect one of [masculine, feminine] configured in `variants.names`"
+        );
     }
 }
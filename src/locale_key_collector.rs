@@ -1,139 +1,934 @@
 //! This files contains a `LocaleKeyCollector` type that finds the invocation
 //! of `rust_i18n::t!()` in Topgrade's source code and extracts the locale
-//! key.
+//! key, it also finds the calls to `rust_i18n::set_locale()`, the places
+//! where the results of `t!()` are concatenated with other text, and the
+//! string literals passed to functions and macros.
 
-use proc_macro2::TokenTree;
+use crate::crash;
+use crate::error::Error;
+use crate::interner::Interner;
+use crate::interrupt;
+use crate::logging;
+use crate::macro_matcher::{MacroFamily, MacroMatcher};
+use crate::source::Source;
+use crate::timings::Timings;
+use indicatif::ProgressBar;
+use proc_macro2::{TokenStream, TokenTree};
 use std::borrow::Cow;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::visit::Visit;
+use syn::visit_mut::VisitMut;
+use syn::Token;
 
 /// A collector that finds the invocation of `rust_i18n::t!()` macro and collects
 /// its locale key.
 #[derive(Debug)]
-pub(crate) struct LocaleKeyCollector<'path> {
+pub(crate) struct LocaleKeyCollector {
     /// Collected locale keys.
-    locale_keys: Vec<LocaleKey<'path>>,
+    locale_keys: Vec<LocaleKey>,
+    /// Collected `set_locale()` calls.
+    set_locale_calls: Vec<SetLocaleCall>,
+    /// Collected concatenations around `t!()` results.
+    concatenations: Vec<Concatenation>,
+    /// Collected string literals passed to functions and macros.
+    sink_literals: Vec<SinkLiteral>,
+    /// Whether to collect [`SinkLiteral`]s, which only the rules about
+    /// hard-coded strings need.
+    collect_sink_literals: bool,
+    /// Files skipped rather than parsed, see [`skip_reason()`].
+    skipped_files: Vec<PathBuf>,
+    /// Matchers of the translation macros to collect.
+    matchers: Vec<&'static dyn MacroMatcher>,
 }
 
-impl<'path> LocaleKeyCollector<'path> {
-    /// Creates a new collector with keys set empty.
-    pub(crate) fn new() -> Self {
+impl LocaleKeyCollector {
+    /// Creates a new collector with keys set empty, which collects the
+    /// translation macros of the `macros` i18n stacks.
+    pub(crate) fn new(macros: &[MacroFamily]) -> Self {
         Self {
             locale_keys: Vec::new(),
+            set_locale_calls: Vec::new(),
+            concatenations: Vec::new(),
+            sink_literals: Vec::new(),
+            collect_sink_literals: false,
+            skipped_files: Vec::new(),
+            matchers: macros.iter().map(|family| family.matcher()).collect(),
         }
     }
 
+    /// Makes it collect the string literals passed to functions and macros if
+    /// `collect` is true, they are not collected by default.
+    pub(crate) fn with_sink_literals(mut self, collect: bool) -> Self {
+        self.collect_sink_literals = collect;
+        self
+    }
+
     /// Collects the invocation of `t!()` from `files`.
-    pub(crate) fn collect(&mut self, files: &'path [Cow<'path, Path>]) {
+    ///
+    /// Files are read from `source`, keys are interned with `interner`, time
+    /// spent on parsing every file will be recorded in `timings`, and every
+    /// parsed file advances `progress`.
+    ///
+    /// Files larger than `max_file_size` bytes, containing NUL bytes or not
+    /// valid UTF-8 are skipped with a note, see [`skip_reason()`].
+    ///
+    /// Stops early, with the invocations found so far kept, if the user presses
+    /// Ctrl-C.
+    ///
+    /// Fails if a file cannot be read or parsed, or if a `t!()` invocation is
+    /// malformed, e.g., its key is not a string literal.
+    pub(crate) fn collect(
+        &mut self,
+        files: &[Cow<'_, Path>],
+        source: &Source,
+        max_file_size: usize,
+        interner: &mut Interner,
+        timings: &mut Timings,
+        progress: &ProgressBar,
+    ) -> Result<(), Error> {
+        // Reused across files to avoid allocating a buffer for every file.
+        let mut buf = Vec::new();
+
         for file in files {
-            let str = std::fs::read_to_string(file)
-                .unwrap_or_else(|err| panic!("failed to read file {}: {}", file.display(), err));
-            let parsed_file = syn::parse_file(&str)
-                .unwrap_or_else(|e| panic!("failed to parse file {} due to {}", file.display(), e));
+            if interrupt::is_interrupted() {
+                break;
+            }
+
+            progress.set_message(file.display().to_string());
+            source.read(file, &mut buf)?;
+            let str = match skip_reason(&buf, max_file_size) {
+                Some(reason) => {
+                    tracing::info!(file = %file.display(), reason, "file skipped");
+                    if !logging::is_json() {
+                        progress.suspend(|| {
+                            eprintln!("Note: skipped {}, {}", file.display(), reason);
+                        });
+                    }
+                    self.skipped_files.push(file.to_path_buf());
+                    progress.inc(1);
+                    continue;
+                }
+                None => std::str::from_utf8(&buf).expect("checked by skip_reason()"),
+            };
+            crash::set_current_file(Some(file));
+            let parse_start = Instant::now();
+            let mut parsed_file = syn::parse_file(str).map_err(|error| {
+                crash::set_current_file(None);
+                Error::RustSyntax {
+                    path: file.to_path_buf(),
+                    error,
+                }
+            })?;
+            timings.record_file(file, parse_start.elapsed());
 
             let mut single_file_collector = SingleFileLocalenKeyCollector {
-                file,
+                file: Arc::from(file.as_ref()),
                 locale_keys: Vec::new(),
+                set_locale_calls: Vec::new(),
+                concatenations: Vec::new(),
+                sink_literals: Vec::new(),
+                collect_sink_literals: self.collect_sink_literals,
+                str_bindings: HashMap::new(),
+                ignored_lines: ignored_lines(str),
+                matchers: self.matchers.clone(),
+                interner,
+                error: None,
             };
 
-            single_file_collector.visit_file(&parsed_file);
+            single_file_collector.visit_file_mut(&mut parsed_file);
+            crash::set_current_file(None);
+            if let Some(error) = single_file_collector.error {
+                return Err(error);
+            }
 
-            self.locale_keys
-                .extend(single_file_collector.locale_keys.into_iter());
+            self.locale_keys.extend(single_file_collector.locale_keys);
+            self.set_locale_calls
+                .extend(single_file_collector.set_locale_calls);
+            self.concatenations
+                .extend(single_file_collector.concatenations);
+            self.sink_literals
+                .extend(single_file_collector.sink_literals);
+            progress.inc(1);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the keys of `expanded_keys`, collected from expanded code, that
+    /// no `t!()` invocation in the source code has, i.e., the ones generated by
+    /// macros, returns how many were added.
+    pub(crate) fn add_expanded_keys(&mut self, expanded_keys: Vec<LocaleKey>) -> usize {
+        let known = self
+            .locale_keys
+            .iter()
+            .map(|locale_key| Arc::clone(&locale_key.key))
+            .collect::<HashSet<_>>();
+        let n_keys = self.locale_keys.len();
+        self.locale_keys.extend(
+            expanded_keys
+                .into_iter()
+                .filter(|locale_key| !known.contains(&locale_key.key)),
+        );
+
+        self.locale_keys.len() - n_keys
+    }
+
+    /// Marks the invocations whose keys `is_ignored` as ignored, like with an
+    /// `// i18n-checker: ignore` comment.
+    pub(crate) fn ignore_keys(&mut self, is_ignored: impl Fn(&str) -> bool) {
+        for locale_key in self.locale_keys.iter_mut() {
+            if is_ignored(&locale_key.key) {
+                locale_key.ignored = true;
+            }
         }
     }
 
     /// Gets the reference to the collected locale keys.
-    pub(crate) fn locale_keys(&self) -> &[LocaleKey<'path>] {
+    pub(crate) fn locale_keys(&self) -> &[LocaleKey] {
         &self.locale_keys
     }
+
+    /// Gets the reference to the collected `set_locale()` calls.
+    pub(crate) fn set_locale_calls(&self) -> &[SetLocaleCall] {
+        &self.set_locale_calls
+    }
+
+    /// Gets the reference to the collected concatenations around `t!()`
+    /// results.
+    pub(crate) fn concatenations(&self) -> &[Concatenation] {
+        &self.concatenations
+    }
+
+    /// Gets the reference to the collected string literals passed to
+    /// functions and macros.
+    pub(crate) fn sink_literals(&self) -> &[SinkLiteral] {
+        &self.sink_literals
+    }
+
+    /// Gets the reference to the files skipped rather than parsed, the keys
+    /// they use are unknown.
+    pub(crate) fn skipped_files(&self) -> &[PathBuf] {
+        &self.skipped_files
+    }
+}
+
+/// Returns why a Rust file whose contents are `contents` should not be parsed,
+/// `None` if it should be.
+///
+/// Files that are too large, binary, or not UTF-8 are most likely generated,
+/// parsing them is slow at best and panics at worst.
+pub(crate) fn skip_reason(contents: &[u8], max_file_size: usize) -> Option<String> {
+    if contents.len() > max_file_size {
+        Some(format!(
+            "it is larger than {} bytes, raise `scan.max_file_size` in the config to check it",
+            max_file_size
+        ))
+    } else if contents.contains(&0) {
+        Some("it contains NUL bytes, which suggests a binary file".to_string())
+    } else if std::str::from_utf8(contents).is_err() {
+        Some("it is not valid UTF-8".to_string())
+    } else {
+        None
+    }
+}
+
+/// The comment that makes the rules about `t!()` invocations skip the ones on
+/// its line, or on the next line if it is on a line of its own.
+const IGNORE_COMMENT: &str = "i18n-checker: ignore";
+
+/// Returns the lines, starting from 1, of the Rust file `contents` whose
+/// `t!()` invocations are ignored with an [`IGNORE_COMMENT`].
+///
+/// A comment on a line of its own applies to the next line that is not a
+/// comment, so that it can be followed by the reason.
+fn ignored_lines(contents: &str) -> HashSet<usize> {
+    let is_ignore_comment = |line: &str| {
+        line.match_indices("//")
+            .any(|(idx, _)| line[idx + 2..].trim_start().starts_with(IGNORE_COMMENT))
+    };
+
+    let mut ignored_lines = HashSet::new();
+    // Whether a comment-only line above ignores the current line
+    let mut pending = false;
+    for (idx, line) in contents.lines().enumerate() {
+        if line.trim_start().starts_with("//") {
+            pending |= is_ignore_comment(line);
+            continue;
+        }
+        if pending || is_ignore_comment(line) {
+            ignored_lines.insert(idx + 1);
+        }
+        pending = false;
+    }
+
+    ignored_lines
 }
 
 /// Collector that is responsible for a single file.
 ///
 /// # NOTE
 /// This is a workaround to enable us to have the file path info while
-/// invoking `visit_macro()`.
-struct SingleFileLocalenKeyCollector<'path> {
+/// invoking `visit_macro_mut()`.
+///
+/// It visits the AST mutably so that the tokens of a `t!()` invocation can be
+/// moved out rather than cloned, the AST is discarded after the visit anyway.
+struct SingleFileLocalenKeyCollector<'interner> {
     /// File path.
-    file: &'path Path,
+    file: Arc<Path>,
     /// Keys collected from `file`.
-    locale_keys: Vec<LocaleKey<'path>>,
+    locale_keys: Vec<LocaleKey>,
+    /// `set_locale()` calls collected from `file`.
+    set_locale_calls: Vec<SetLocaleCall>,
+    /// Concatenations around `t!()` results collected from `file`.
+    concatenations: Vec<Concatenation>,
+    /// String literals passed to functions and macros collected from `file`.
+    sink_literals: Vec<SinkLiteral>,
+    /// Whether to collect `sink_literals`.
+    collect_sink_literals: bool,
+    /// `Name => Value` of the variables and constants in scope that are bound
+    /// to string literals, used to trace the `locale` argument of `t!()`.
+    str_bindings: HashMap<String, String>,
+    /// Lines of `file` whose `t!()` invocations are ignored, see
+    /// [`ignored_lines()`].
+    ignored_lines: HashSet<usize>,
+    /// Matchers of the translation macros to collect, `t!()` in the docs of
+    /// this type stands for any of them.
+    matchers: Vec<&'static dyn MacroMatcher>,
+    /// Interner used to intern the collected keys.
+    interner: &'interner mut Interner,
+    /// The first malformed `t!()` invocation found, the rest of `file` is not
+    /// collected once it is set.
+    error: Option<Error>,
+}
+
+impl<'interner> SingleFileLocalenKeyCollector<'interner> {
+    /// Returns the matcher of the translation macro that `path` invokes, if
+    /// any.
+    fn matcher(&self, path: &syn::Path) -> Option<&'static dyn MacroMatcher> {
+        self.matchers
+            .iter()
+            .copied()
+            .find(|matcher| matcher.matches(path))
+    }
+
+    /// Returns the translation macro invocation that `expr` evaluates, looking
+    /// through references, parentheses and method calls like `.to_string()`.
+    fn t_invocation<'expr>(&self, expr: &'expr syn::Expr) -> Option<&'expr syn::Macro> {
+        match expr {
+            syn::Expr::Macro(expr) => self.matcher(&expr.mac.path).map(|_| &expr.mac),
+            syn::Expr::Reference(expr) => self.t_invocation(&expr.expr),
+            syn::Expr::Paren(expr) => self.t_invocation(&expr.expr),
+            syn::Expr::MethodCall(expr) => self.t_invocation(&expr.receiver),
+            _ => None,
+        }
+    }
+
+    /// Constructs a `LocaleKey` from the given `t!()` invocation, whose macro
+    /// is matched by `matcher`.
+    ///
+    /// Fails if the invocation has no key, or if it is not a string literal.
+    fn locale_key(
+        &mut self,
+        mac: &mut syn::Macro,
+        matcher: &dyn MacroMatcher,
+    ) -> Result<LocaleKey, Error> {
+        let span = mac.span();
+        let start = span.start();
+        let line = start.line;
+        let column = start.column;
+        let invocation_error = |message: String| Error::Invocation {
+            path: self.file.to_path_buf(),
+            line,
+            column,
+            message,
+        };
+
+        // The AST won't be used after visiting, take the tokens so that no
+        // clone is needed.
+        let token_stream = std::mem::take(&mut mac.tokens);
+        let args = split_args(token_stream);
+        let Some(key_arg) = args.get(matcher.key_index()) else {
+            return Err(invocation_error(format!(
+                "{}!() needs at least {} argument(s)",
+                matcher.name(),
+                matcher.key_index() + 1
+            )));
+        };
+        let key = match matcher.key(key_arg).map(syn::Lit::new) {
+            Some(syn::Lit::Str(literal)) => self.interner.intern(&literal.value()),
+            _ => {
+                return Err(invocation_error(format!(
+                    "the {} argument to {}!() should be a string literal",
+                    ["first", "second"]
+                        .get(matcher.key_index())
+                        .unwrap_or(&"key"),
+                    matcher.name()
+                )))
+            }
+        };
+        let (args, locale) = self.named_args(args.into_iter().skip(matcher.key_index() + 1));
+
+        Ok(LocaleKey {
+            key,
+            file: Arc::clone(&self.file),
+            line,
+            column,
+            args,
+            locale,
+            ignored: self.ignored_lines.contains(&line),
+        })
+    }
+
+    /// Records that the result of the `t!()` invocation `mac` is concatenated
+    /// with other text.
+    fn push_concatenation(&mut self, mac: &syn::Macro, kind: ConcatenationKind) {
+        let Some(matcher) = self.matcher(&mac.path) else {
+            return;
+        };
+        let args = split_args(mac.tokens.clone());
+        let Some(syn::Lit::Str(literal)) = args
+            .get(matcher.key_index())
+            .and_then(|arg| matcher.key(arg))
+            .map(syn::Lit::new)
+        else {
+            return;
+        };
+        let start = mac.span().start();
+        self.concatenations.push(Concatenation {
+            kind,
+            key: self.interner.intern(&literal.value()),
+            file: Arc::clone(&self.file),
+            line: start.line,
+            column: start.column,
+        });
+    }
+
+    /// Records the string literal `literal` passed to `sink`.
+    fn push_sink_literal(&mut self, sink: &str, literal: &syn::LitStr) {
+        let start = literal.span().start();
+        self.sink_literals.push(SinkLiteral {
+            sink: self.interner.intern(sink),
+            text: literal.value(),
+            file: Arc::clone(&self.file),
+            line: start.line,
+            column: start.column,
+        });
+    }
+
+    /// Checks if the results of `t!()` invocations in `exprs`, the arguments
+    /// of the format-like macro `path`, are concatenated with other text, that
+    /// is, formatted along with string literals, or surrounded by words in the
+    /// format string.
+    fn check_format_args(&mut self, path: &syn::Path, exprs: &Punctuated<syn::Expr, Token![,]>) {
+        // Skip the destination of `write!()`
+        let skip = usize::from(path.is_ident("write") || path.is_ident("writeln"));
+        let mut exprs = exprs.iter().skip(skip);
+        let format_string = match exprs.next() {
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(format_string),
+                ..
+            })) => Some(format_string.value()),
+            _ => None,
+        };
+
+        let mut positional_args = Vec::new();
+        // `name = value`
+        let mut named_args = Vec::new();
+        for expr in exprs {
+            match expr {
+                syn::Expr::Assign(assign) => {
+                    if let syn::Expr::Path(name) = &*assign.left {
+                        if let Some(name) = name.path.get_ident() {
+                            named_args.push((name.to_string(), &*assign.right));
+                        }
+                    }
+                }
+                arg => positional_args.push(arg),
+            }
+        }
+        let args = positional_args
+            .iter()
+            .copied()
+            .chain(named_args.iter().map(|(_, arg)| *arg));
+
+        let has_str_literal = args.clone().any(|arg| {
+            matches!(
+                arg,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(_),
+                    ..
+                })
+            )
+        });
+        if has_str_literal {
+            let macs = args
+                .filter_map(|arg| self.t_invocation(arg))
+                .collect::<Vec<_>>();
+            for mac in macs {
+                self.push_concatenation(mac, ConcatenationKind::FormatArgs);
+            }
+        }
+
+        let Some(format_string) = format_string else {
+            return;
+        };
+        let (placeholders, text) = parse_format_string(&format_string);
+        let has_words = text
+            .split(|c: char| !c.is_alphabetic())
+            .any(|word| word.chars().count() >= 2);
+        if !has_words {
+            return;
+        }
+        let mut next_positional = 0;
+        // An argument can be referenced more than once
+        let mut surrounded_args: Vec<&syn::Expr> = Vec::new();
+        for placeholder in placeholders {
+            let arg = match placeholder {
+                FormatPlaceholder::Next => {
+                    next_positional += 1;
+                    positional_args.get(next_positional - 1).copied()
+                }
+                FormatPlaceholder::Index(idx) => positional_args.get(idx).copied(),
+                FormatPlaceholder::Name(name) => named_args
+                    .iter()
+                    .find(|(arg_name, _)| *arg_name == name)
+                    .map(|(_, arg)| *arg),
+            };
+            if let Some(arg) = arg {
+                if !surrounded_args.iter().any(|seen| std::ptr::eq(*seen, arg)) {
+                    surrounded_args.push(arg);
+                }
+            }
+        }
+        let macs = surrounded_args
+            .into_iter()
+            .filter_map(|arg| self.t_invocation(arg))
+            .collect::<Vec<_>>();
+        for mac in macs {
+            self.push_concatenation(mac, ConcatenationKind::FormatString);
+        }
+    }
+
+    /// Extracts the names of the named arguments (`name = value` or
+    /// `name => value`) from the arguments after the locale key, `arg_tokens`,
+    /// along with the value of the `locale` argument if it is a string literal
+    /// or a variable bound to one.
+    fn named_args(
+        &mut self,
+        arg_tokens: impl Iterator<Item = Vec<TokenTree>>,
+    ) -> (Vec<Arc<str>>, Option<Arc<str>>) {
+        let mut args = Vec::new();
+        let mut locale = None;
+
+        for arg_tokens in arg_tokens {
+            if let [TokenTree::Ident(name), TokenTree::Punct(eq), value @ ..] =
+                arg_tokens.as_slice()
+            {
+                if eq.as_char() == '=' {
+                    let name = name.to_string();
+                    if name == "locale" {
+                        locale = self
+                            .str_value(value)
+                            .map(|value| self.interner.intern(&value));
+                    }
+                    args.push(self.interner.intern(&name));
+                }
+            }
+        }
+
+        (args, locale)
+    }
+
+    /// Returns the string that the value of a named argument, `tokens`, is,
+    /// if it is a string literal or a variable bound to one, possibly
+    /// referenced, the `>` of `=>` is skipped.
+    fn str_value(&self, mut tokens: &[TokenTree]) -> Option<String> {
+        while let [TokenTree::Punct(punct), rest @ ..] = tokens {
+            if !matches!(punct.as_char(), '>' | '&') {
+                return None;
+            }
+            tokens = rest;
+        }
+
+        match tokens {
+            [TokenTree::Literal(literal)] => match syn::Lit::new(literal.clone()) {
+                syn::Lit::Str(literal) => Some(literal.value()),
+                _ => None,
+            },
+            [TokenTree::Ident(name)] => self.str_bindings.get(&name.to_string()).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Records that `name` is bound to `value`, if it is a string literal, or
+    /// forgets a previous binding otherwise, as it is shadowed.
+    fn bind(&mut self, name: String, value: Option<&syn::Expr>) {
+        match value.and_then(str_literal) {
+            Some(literal) => {
+                self.str_bindings.insert(name, literal.value());
+            }
+            None => {
+                self.str_bindings.remove(&name);
+            }
+        }
+    }
 }
 
-impl<'ast, 'path> Visit<'ast> for SingleFileLocalenKeyCollector<'path> {
-    fn visit_macro(&mut self, i: &'ast syn::Macro) {
-        let path_segments = &i.path.segments;
-        let path_segments_len = path_segments.len();
+impl<'interner> VisitMut for SingleFileLocalenKeyCollector<'interner> {
+    fn visit_item_fn_mut(&mut self, i: &mut syn::ItemFn) {
+        // Bindings of a function are out of scope after it
+        let str_bindings = self.str_bindings.clone();
+        syn::visit_mut::visit_item_fn_mut(self, i);
+        self.str_bindings = str_bindings;
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, i: &mut syn::ImplItemFn) {
+        let str_bindings = self.str_bindings.clone();
+        syn::visit_mut::visit_impl_item_fn_mut(self, i);
+        self.str_bindings = str_bindings;
+    }
+
+    fn visit_item_const_mut(&mut self, i: &mut syn::ItemConst) {
+        self.bind(i.ident.to_string(), Some(&i.expr));
+        syn::visit_mut::visit_item_const_mut(self, i);
+    }
+
+    fn visit_local_mut(&mut self, i: &mut syn::Local) {
+        // Visit the initializer first, it cannot see the new binding
+        syn::visit_mut::visit_local_mut(self, i);
+        let pat = match &i.pat {
+            // let locale: &str = ...
+            syn::Pat::Type(pat) => &*pat.pat,
+            pat => pat,
+        };
+        if let syn::Pat::Ident(pat) = pat {
+            let value = i.init.as_ref().map(|init| &*init.expr);
+            self.bind(pat.ident.to_string(), value);
+        }
+    }
+
+    fn visit_macro_mut(&mut self, i: &mut syn::Macro) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if let Some(matcher) = self.matcher(&i.path) {
+            match self.locale_key(i, matcher) {
+                Ok(locale_key) => self.locale_keys.push(locale_key),
+                Err(error) => self.error = Some(error),
+            }
+        } else if let Some(last_segment) = i.path.segments.last() {
+            let name = last_segment.ident.to_string();
+            if FORMAT_MACROS.contains(&name.as_str()) {
+                if let Ok(mut args) =
+                    i.parse_body_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)
+                {
+                    self.check_format_args(&i.path, &args);
+                    // The arguments are parsed from a copy of the tokens,
+                    // which the visitor does not walk into
+                    for arg in args.iter_mut() {
+                        self.visit_expr_mut(arg);
+                    }
+                }
+            }
+
+            // Only the top-level tokens, nested calls are unknown without
+            // parsing the body
+            if self.collect_sink_literals {
+                let sink = format!("{}!", name);
+                for token in i.tokens.clone() {
+                    if let TokenTree::Literal(literal) = token {
+                        if let syn::Lit::Str(literal) = syn::Lit::new(literal) {
+                            self.push_sink_literal(&sink, &literal);
+                        }
+                    }
+                }
+            }
+        }
+
+        syn::visit_mut::visit_macro_mut(self, i);
+    }
+
+    fn visit_expr_binary_mut(&mut self, i: &mut syn::ExprBinary) {
+        if let syn::BinOp::Add(_) = i.op {
+            for operand in [&*i.left, &*i.right] {
+                if let Some(mac) = self.t_invocation(operand) {
+                    self.push_concatenation(mac, ConcatenationKind::AddOperator);
+                }
+            }
+        }
+
+        syn::visit_mut::visit_expr_binary_mut(self, i);
+    }
+
+    fn visit_expr_call_mut(&mut self, i: &mut syn::ExprCall) {
+        if let syn::Expr::Path(func) = &*i.func {
+            let path_segments = &func.path.segments;
+            let is_set_locale_call = path_segments
+                .last()
+                .is_some_and(|last_segment| last_segment.ident == "set_locale")
+                && match path_segments.len() {
+                    // call: set_locale()
+                    1 => true,
+                    // call: rust_i18n::set_locale()
+                    2 => path_segments.get(0).expect("len == 2").ident == "rust_i18n",
+                    _ => false,
+                };
 
-        let last_segment = path_segments
-            .last()
-            .expect("macro invocation should have at least 1 path segment");
-        if last_segment.ident == "t" {
-            // invocation: t!()
-            if path_segments_len == 1 {
-                self.locale_keys.push(LocaleKey::new(i, self.file));
+            // Only string literals are checked, other arguments are unknown
+            // until runtime.
+            if let (
+                true,
+                Some(syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(locale),
+                    ..
+                })),
+            ) = (is_set_locale_call, i.args.first())
+            {
+                let start = i.span().start();
+                let locale = self.interner.intern(&locale.value());
+                self.set_locale_calls.push(SetLocaleCall {
+                    locale,
+                    file: Arc::clone(&self.file),
+                    line: start.line,
+                    column: start.column,
+                });
             }
+        }
 
-            if path_segments_len == 2 {
-                let first_segment = path_segments.get(0).expect("len == 2");
-                // invocation: rust_i18n::t!()
-                if first_segment.ident == "rust_i18n" {
-                    self.locale_keys.push(LocaleKey::new(i, self.file));
+        if let (true, syn::Expr::Path(func)) = (self.collect_sink_literals, &*i.func) {
+            if let Some(last_segment) = func.path.segments.last() {
+                let sink = last_segment.ident.to_string();
+                for literal in i.args.iter().filter_map(str_literal) {
+                    self.push_sink_literal(&sink, literal);
                 }
             }
         }
 
-        syn::visit::visit_macro(self, i);
+        syn::visit_mut::visit_expr_call_mut(self, i);
+    }
+
+    fn visit_expr_method_call_mut(&mut self, i: &mut syn::ExprMethodCall) {
+        if self.collect_sink_literals {
+            let sink = i.method.to_string();
+            for literal in i.args.iter().filter_map(str_literal) {
+                self.push_sink_literal(&sink, literal);
+            }
+        }
+
+        syn::visit_mut::visit_expr_method_call_mut(self, i);
+    }
+}
+
+/// Macros that take a format string and arguments.
+const FORMAT_MACROS: [&str; 9] = [
+    "format",
+    "format_args",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "write",
+    "writeln",
+    "panic",
+];
+
+/// Splits the tokens of a macro invocation into its comma-separated
+/// arguments, nested commas are hidden in groups.
+fn split_args(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut args = vec![Vec::new()];
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => args.push(Vec::new()),
+            _ => args.last_mut().expect("never empty").push(token),
+        }
+    }
+    if args.last().is_some_and(Vec::is_empty) {
+        args.pop();
+    }
+
+    args
+}
+
+/// Returns the string literal that `expr` is, looking through references and
+/// conversions like `"text".to_string()`.
+pub(crate) fn str_literal(expr: &syn::Expr) -> Option<&syn::LitStr> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(literal),
+            ..
+        }) => Some(literal),
+        syn::Expr::Reference(expr) => str_literal(&expr.expr),
+        syn::Expr::MethodCall(expr) if expr.args.is_empty() => str_literal(&expr.receiver),
+        _ => None,
+    }
+}
+
+/// A placeholder in a format string.
+#[derive(Debug, PartialEq)]
+enum FormatPlaceholder<'a> {
+    /// `{}`, the next positional argument.
+    Next,
+    /// `{0}`
+    Index(usize),
+    /// `{name}`
+    Name(&'a str),
+}
+
+/// Splits `format_string` into its placeholders and the text around them,
+/// with `{{` and `}}` unescaped.
+fn parse_format_string(format_string: &str) -> (Vec<FormatPlaceholder<'_>>, String) {
+    let mut placeholders = Vec::new();
+    let mut text = String::new();
+    let mut rest = format_string;
+
+    while let Some(idx) = rest.find(['{', '}']) {
+        text.push_str(&rest[..idx]);
+        let brace = &rest[idx..idx + 1];
+        rest = &rest[idx + 1..];
+        if let Some(after_escaped) = rest.strip_prefix(brace) {
+            text.push_str(brace);
+            rest = after_escaped;
+            continue;
+        }
+        if brace == "}" {
+            // Unbalanced, let rustc complain about it
+            continue;
+        }
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        // Strip the format spec, e.g., `:?`
+        let arg = rest[..end].split(':').next().unwrap_or_default().trim();
+        placeholders.push(if arg.is_empty() {
+            FormatPlaceholder::Next
+        } else if let Ok(idx) = arg.parse() {
+            FormatPlaceholder::Index(idx)
+        } else {
+            FormatPlaceholder::Name(arg)
+        });
+        rest = &rest[end + 1..];
     }
+    text.push_str(rest);
+
+    (placeholders, text)
 }
 
 /// Info about a locale key.
 #[derive(Debug, PartialEq)]
-pub(crate) struct LocaleKey<'path> {
+pub(crate) struct LocaleKey {
     /// Locale key.
-    pub(crate) key: String,
+    pub(crate) key: Arc<str>,
     /// path of the file where the `t!()` macro is invoked.
-    pub(crate) file: &'path Path,
+    pub(crate) file: Arc<Path>,
     /// Line number of the start of invocation, starts from 1.
     pub(crate) line: usize,
     /// Column number of the start of invocation, starts from 0.
     pub(crate) column: usize,
+    /// Names of the named arguments, e.g., `["app"]` for
+    /// `t!("Restarting {app}", app = name)`.
+    pub(crate) args: Vec<Arc<str>>,
+    /// The locale passed with `locale = "zh_CN"`, if it is a string literal
+    /// or a variable bound to one, `None` if there is no such argument or it
+    /// is unknown until runtime.
+    pub(crate) locale: Option<Arc<str>>,
+    /// Whether the invocation is ignored with an [`IGNORE_COMMENT`], rules
+    /// about `t!()` invocations skip it.
+    pub(crate) ignored: bool,
 }
 
-impl<'path> LocaleKey<'path> {
-    /// Constructs a `LocaleKey` from the given info.
-    fn new(mac: &syn::Macro, file: &'path Path) -> Self {
-        let token_stream = mac.tokens.clone();
+/// Info about a `set_locale()` call whose argument is a string literal.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SetLocaleCall {
+    /// The locale set.
+    pub(crate) locale: Arc<str>,
+    /// path of the file where `set_locale()` is called.
+    pub(crate) file: Arc<Path>,
+    /// Line number of the start of the call, starts from 1.
+    pub(crate) line: usize,
+    /// Column number of the start of the call, starts from 0.
+    pub(crate) column: usize,
+}
 
-        let mut token_tree_iter = token_stream.into_iter();
-        let translation_key = token_tree_iter
-            .next()
-            .expect("t!() needs at least 1 argument");
-        let key = match translation_key {
-            TokenTree::Literal(literal) => literal.to_string().trim_matches('"').to_string(),
-            _ => panic!("The first argument to t!() should be a string literal"),
-        };
+/// How the result of `t!()` is concatenated with other text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConcatenationKind {
+    /// Formatted along with string literals, e.g.,
+    /// `format!("{}{}", t!("a"), ": details")`.
+    FormatArgs,
+    /// Concatenated with `+`, e.g., `s + &t!("a")`.
+    AddOperator,
+    /// Surrounded by words in the format string, e.g.,
+    /// `format!("Failed to {}: error", t!("update"))`.
+    FormatString,
+}
 
-        let span = mac.span();
-        let start = span.start();
-        let line = start.line;
-        let column = start.column;
+/// Info about a place where the result of `t!()` is concatenated with other
+/// text, which defeats translation.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Concatenation {
+    /// How it is concatenated.
+    pub(crate) kind: ConcatenationKind,
+    /// Locale key of the `t!()` invocation.
+    pub(crate) key: Arc<str>,
+    /// path of the file where the `t!()` macro is invoked.
+    pub(crate) file: Arc<Path>,
+    /// Line number of the start of invocation, starts from 1.
+    pub(crate) line: usize,
+    /// Column number of the start of invocation, starts from 0.
+    pub(crate) column: usize,
+}
 
-        Self {
-            key,
-            file,
-            line,
-            column,
-        }
-    }
+/// Info about a string literal passed to a function or a macro, the sink,
+/// which may be a user-facing string not passed through `t!()`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SinkLiteral {
+    /// Name of the function or method, or the macro followed by `!`, e.g.,
+    /// `println!`, paths are stripped.
+    pub(crate) sink: Arc<str>,
+    /// Value of the string literal.
+    pub(crate) text: String,
+    /// path of the file where the string literal is.
+    pub(crate) file: Arc<Path>,
+    /// Line number of the start of the string literal, starts from 1.
+    pub(crate) line: usize,
+    /// Column number of the start of the string literal, starts from 0.
+    pub(crate) column: usize,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::macro_matcher::{Fluent, Gettext, RustI18n};
+
+    /// Collects the invocations of the translation macros of `matchers` from
+    /// `contents`, the contents of `foo.rs`.
+    fn collect(
+        contents: &str,
+        matchers: &[&'static dyn MacroMatcher],
+    ) -> SingleFileLocalenKeyCollector<'static> {
+        let mut collector = SingleFileLocalenKeyCollector {
+            file: Path::new("foo.rs").into(),
+            locale_keys: Vec::new(),
+            set_locale_calls: Vec::new(),
+            concatenations: Vec::new(),
+            sink_literals: Vec::new(),
+            collect_sink_literals: true,
+            str_bindings: HashMap::new(),
+            ignored_lines: ignored_lines(contents),
+            matchers: matchers.to_vec(),
+            // Leaked so that the collector can outlive this function
+            interner: Box::leak(Box::new(Interner::new())),
+            error: None,
+        };
+        collector.visit_file_mut(&mut syn::parse_file(contents).unwrap());
+        collector
+    }
 
     #[test]
     fn test_single_file_collector_works() {
@@ -142,43 +937,397 @@ mod tests {
 foo::bar::t!("not a key");
 ::foo::bar::t!("not a key");
 "#;
-        let path = PathBuf::from("foo.rs");
-        let mut collector = SingleFileLocalenKeyCollector {
-            file: &path,
-            locale_keys: Vec::new(),
-        };
-        collector.visit_file(&syn::parse_file(&file_contents).unwrap());
+        let collector = collect(file_contents, &[&RustI18n]);
 
         assert_eq!(
             collector.locale_keys,
             vec![
                 LocaleKey {
-                    key: "first_key".to_string(),
-                    file: Path::new("foo.rs"),
+                    key: "first_key".into(),
+                    file: Path::new("foo.rs").into(),
                     line: 1,
-                    column: 0
+                    column: 0,
+                    args: Vec::new(),
+                    locale: None,
+                    ignored: false,
                 },
                 LocaleKey {
-                    key: "second_key".to_string(),
-                    file: Path::new("foo.rs"),
+                    key: "second_key".into(),
+                    file: Path::new("foo.rs").into(),
+                    line: 2,
+                    column: 1,
+                    args: Vec::new(),
+                    locale: None,
+                    ignored: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_file_collector_interns_keys() {
+        let file_contents = r#"t!("key");
+t!("key");
+"#;
+        let collector = collect(file_contents, &[&RustI18n]);
+
+        assert_eq!(collector.locale_keys.len(), 2);
+        assert!(Arc::ptr_eq(
+            &collector.locale_keys[0].key,
+            &collector.locale_keys[1].key
+        ));
+    }
+
+    #[test]
+    fn test_single_file_collector_args() {
+        let file_contents = r#"t!("{app} {count}", app = f(a, b), count => 1, locale = "en");
+t!("key",);
+"#;
+        let collector = collect(file_contents, &[&RustI18n]);
+
+        let args = collector
+            .locale_keys
+            .iter()
+            .map(|locale_key| locale_key.args.iter().map(|arg| &**arg).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(args, [vec!["app", "count", "locale"], vec![]]);
+    }
+
+    #[test]
+    fn test_single_file_collector_locale_overrides() {
+        let file_contents = r#"const DEFAULT: &str = "de";
+fn main() {
+    t!("a", locale = "zh-CN");
+    let locale = "fr";
+    t!("b", locale => locale);
+    t!("c", locale = &DEFAULT);
+    let locale: String = detect();
+    t!("d", locale = locale);
+    t!("e");
+}
+fn other() {
+    t!("f", locale = locale);
+}
+"#;
+        let collector = collect(file_contents, &[&RustI18n]);
+
+        let locales = collector
+            .locale_keys
+            .iter()
+            .map(|locale_key| locale_key.locale.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locales,
+            [Some("zh-CN"), Some("fr"), Some("de"), None, None, None]
+        );
+    }
+
+    #[test]
+    fn test_single_file_collector_set_locale_calls() {
+        let file_contents = r#"fn main() {
+    rust_i18n::set_locale("zh-CN");
+    set_locale("de");
+    set_locale(&locale);
+    foo::set_locale("not a locale");
+}
+"#;
+        let collector = collect(file_contents, &[&RustI18n]);
+
+        assert_eq!(
+            collector.set_locale_calls,
+            vec![
+                SetLocaleCall {
+                    locale: "zh-CN".into(),
+                    file: Path::new("foo.rs").into(),
                     line: 2,
-                    column: 1
+                    column: 4
+                },
+                SetLocaleCall {
+                    locale: "de".into(),
+                    file: Path::new("foo.rs").into(),
+                    line: 3,
+                    column: 4
                 },
             ]
         );
     }
 
     #[test]
-    #[should_panic(expected = "The first argument to t!() should be a string literal")]
+    fn test_parse_format_string() {
+        assert_eq!(
+            parse_format_string("{{a}} {} {1:?} {name:>5}!"),
+            (
+                vec![
+                    FormatPlaceholder::Next,
+                    FormatPlaceholder::Index(1),
+                    FormatPlaceholder::Name("name"),
+                ],
+                String::from("{a}   !")
+            )
+        );
+    }
+
+    #[test]
+    fn test_single_file_collector_concatenations() {
+        let file_contents = r#"fn main() {
+    let a = format!("{}{}", t!("a"), ": details");
+    let b = s + &t!("b");
+    let c = t!("c").to_string() + "!";
+    writeln!(f, "{}", t!("d"));
+    writeln!(f, "{} {}", t!("e"), "?");
+    println!("Error: {0:?} {}", t!("f"), x);
+    println!("{{Error}}: {} {name}", x, name = t!("g"));
+    println!("{}\n", t!("h"));
+}
+"#;
+        let collector = collect(file_contents, &[&RustI18n]);
+
+        let concatenations = collector
+            .concatenations
+            .iter()
+            .map(|concatenation| (concatenation.kind, &*concatenation.key, concatenation.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            concatenations,
+            [
+                (ConcatenationKind::FormatArgs, "a", 2),
+                (ConcatenationKind::AddOperator, "b", 3),
+                (ConcatenationKind::AddOperator, "c", 4),
+                (ConcatenationKind::FormatArgs, "e", 6),
+                (ConcatenationKind::FormatString, "f", 7),
+                (ConcatenationKind::FormatString, "g", 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_file_collector_format_macros() {
+        let file_contents = r#"fn main() {
+    println!("{}", t!("k"));
+    let s = format!("{}", t!("l"));
+    writeln!(f, "{}: {}", t!("m", name = x), format!("{}", t!("n")));
+    eprintln!("{}", t!("o")); // i18n-checker: ignore
+}
+"#;
+        let collector = collect(file_contents, &[&RustI18n]);
+
+        let keys = collector
+            .locale_keys
+            .iter()
+            .map(|key| {
+                (
+                    &*key.key,
+                    key.line,
+                    key.column,
+                    key.args.iter().map(|arg| &**arg).collect::<Vec<_>>(),
+                    key.ignored,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                ("k", 2, 19, vec![], false),
+                ("l", 3, 26, vec![], false),
+                ("m", 4, 26, vec!["name"], false),
+                ("n", 4, 59, vec![], false),
+                ("o", 5, 20, vec![], true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_file_collector_sink_literals() {
+        let file_contents = r#"fn main() {
+    println!("Hello {}", t!("world"));
+    print_separator("Summary".to_string());
+    terminal::print_separator(t!("Summary"));
+    x.context(&"Failed");
+}
+"#;
+        let collector = collect(file_contents, &[&RustI18n]);
+
+        let sink_literals = collector
+            .sink_literals
+            .iter()
+            .map(|literal| (&*literal.sink, literal.text.as_str(), literal.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sink_literals,
+            [
+                ("println!", "Hello {}", 2),
+                ("print_separator", "Summary", 3),
+                ("context", "Failed", 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_file_collector_macro_families() {
+        let file_contents = r#"fn main() {
+    fl!(LOADER, "hello-user", name = user);
+    i18n_embed_fl::fl!(loader(), "goodbye");
+    tr!("menu" => "Open");
+    tr!("Open {}", file);
+    t!("Not collected");
+}
+"#;
+        let collector = collect(file_contents, &[&Fluent, &Gettext]);
+
+        assert_eq!(
+            collector
+                .locale_keys
+                .iter()
+                .map(|locale_key| (&*locale_key.key, locale_key.args.clone()))
+                .collect::<Vec<_>>(),
+            [
+                ("hello-user", vec!["name".into()]),
+                ("goodbye", Vec::new()),
+                ("Open", Vec::new()),
+                ("Open {}", Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
     fn test_single_file_collector_locale_key_is_not_string_literal() {
         let file_contents = r#"
 t!(key);
 "#;
-        let path = PathBuf::from("foo.rs");
-        let mut collector = SingleFileLocalenKeyCollector {
-            file: &path,
-            locale_keys: Vec::new(),
+        let collector = collect(file_contents, &[&RustI18n]);
+        assert_eq!(
+            collector.error.unwrap().to_string(),
+            "foo.rs:2:1: the first argument to t!() should be a string literal"
+        );
+    }
+
+    #[test]
+    fn test_ignored_lines() {
+        let contents = r#"fn main() {
+    t!("a"); // i18n-checker: ignore
+    // i18n-checker: ignore
+    // the key is added by the plugin
+    t!("b");
+    t!("c");
+    t!("https://example.com"); // not i18n-checker: ignore
+    /// i18n-checker: ignore
+    t!("d");
+}
+"#;
+        let mut ignored_lines = ignored_lines(contents).into_iter().collect::<Vec<_>>();
+        ignored_lines.sort_unstable();
+        assert_eq!(ignored_lines, [2, 5]);
+    }
+
+    #[test]
+    fn test_skip_reason() {
+        assert_eq!(skip_reason(b"fn main() {}", 100), None);
+        assert!(skip_reason(b"fn main() {}", 4)
+            .unwrap()
+            .starts_with("it is larger than 4 bytes"));
+        assert!(skip_reason(b"\0asm", 100).unwrap().contains("NUL"));
+        assert!(skip_reason(b"\xff", 100).unwrap().contains("UTF-8"));
+    }
+
+    #[test]
+    fn test_add_expanded_keys() {
+        let locale_key = |key: &str, file: &str| LocaleKey {
+            key: key.into(),
+            file: Path::new(file).into(),
+            line: 1,
+            column: 0,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
         };
-        collector.visit_file(&syn::parse_file(&file_contents).unwrap());
+        let mut collector = LocaleKeyCollector::new(&[MacroFamily::RustI18n]);
+        collector.locale_keys.push(locale_key("Pulling", "foo.rs"));
+
+        let n_keys = collector.add_expanded_keys(vec![
+            locale_key("Pulling", "<cargo expand>"),
+            locale_key("Generated", "<cargo expand>"),
+        ]);
+        assert_eq!(n_keys, 1);
+        assert_eq!(
+            collector.locale_keys(),
+            [
+                locale_key("Pulling", "foo.rs"),
+                locale_key("Generated", "<cargo expand>")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_skips_suspicious_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let code = dir.path().join("code.rs");
+        let blob = dir.path().join("blob.rs");
+        std::fs::write(&code, "fn f() { t!(\"Restarting\"); }\n").unwrap();
+        std::fs::write(&blob, b"fn f() {\0").unwrap();
+
+        let mut collector = LocaleKeyCollector::new(&[MacroFamily::RustI18n]);
+        collector
+            .collect(
+                &[Cow::Owned(code), Cow::Owned(blob.clone())],
+                &Source::WorkTree,
+                1024,
+                &mut Interner::new(),
+                &mut Timings::new(),
+                &ProgressBar::hidden(),
+            )
+            .unwrap();
+        assert_eq!(collector.locale_keys().len(), 1);
+        assert_eq!(collector.skipped_files(), [blob]);
+    }
+
+    #[test]
+    fn test_collect_sink_literals_on_demand() {
+        let dir = tempfile::tempdir().unwrap();
+        let code = dir.path().join("code.rs");
+        std::fs::write(&code, "fn f() { println!(\"Hello\"); }\n").unwrap();
+
+        for collect in [false, true] {
+            let mut collector =
+                LocaleKeyCollector::new(&[MacroFamily::RustI18n]).with_sink_literals(collect);
+            collector
+                .collect(
+                    &[Cow::Borrowed(code.as_path())],
+                    &Source::WorkTree,
+                    1024,
+                    &mut Interner::new(),
+                    &mut Timings::new(),
+                    &ProgressBar::hidden(),
+                )
+                .unwrap();
+            assert_eq!(collector.sink_literals().len(), usize::from(collect));
+        }
+    }
+
+    #[test]
+    fn test_collect_fails_on_syntax_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let broken = dir.path().join("broken.rs");
+        std::fs::write(&broken, "fn f() { t!(\"Restarting\") }\n}\n").unwrap();
+
+        let mut collector = LocaleKeyCollector::new(&[MacroFamily::RustI18n]);
+        let res = collector.collect(
+            &[Cow::Owned(broken)],
+            &Source::WorkTree,
+            1024,
+            &mut Interner::new(),
+            &mut Timings::new(),
+            &ProgressBar::hidden(),
+        );
+        assert!(matches!(res, Err(Error::RustSyntax { .. })));
+
+        let res = collector.collect(
+            &[Cow::Borrowed(dir.path().join("missing.rs").as_path())],
+            &Source::WorkTree,
+            1024,
+            &mut Interner::new(),
+            &mut Timings::new(),
+            &ProgressBar::hidden(),
+        );
+        assert!(matches!(res, Err(Error::Read { .. })));
     }
 }
@@ -0,0 +1,223 @@
+//! This file contains the keys lockfile, `i18n-keys.lock`, which lists every
+//! key of the locale file with its placeholder signature, i.e., the
+//! placeholders its English translation interpolates.
+//!
+//! `--write-lock` writes it and `--check-lock` fails if it is outdated, so
+//! that adding, removing or changing the placeholders of a key shows up as a
+//! lockfile change in code review.
+//!
+//! The lockfile is a TOML table `key = [placeholders]`, sorted by key with one
+//! key per line so that diffs are minimal.
+
+use crate::config::PlaceholderSyntax;
+use crate::locale_file_parser::LocalizedTexts;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use topgrade_i18n_locale_checker::key_parser::placeholders_of_key;
+use topgrade_i18n_locale_checker::placeholder::placeholders_of_translation;
+
+/// Name of the lockfile, in the project root, or in the current directory if
+/// there is no root.
+pub(crate) const LOCKFILE: &str = "i18n-keys.lock";
+
+/// Comment at the top of the lockfile.
+const HEADER: &str = "# Generated by `topgrade-i18n-check --write-lock`, do not edit.
+# Every key of the locale file with the placeholders of its English translation,
+# update it with `--write-lock` whenever they change.
+";
+
+/// Returns the lockfile of the project whose root is `root`.
+pub(crate) fn lockfile_path(root: Option<&Path>) -> PathBuf {
+    root.unwrap_or(Path::new("")).join(LOCKFILE)
+}
+
+/// The keys of a locale file with their placeholder signatures.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Lock {
+    /// `Key => Placeholders`, placeholders are sorted and deduplicated.
+    keys: BTreeMap<String, Vec<String>>,
+}
+
+/// A difference between a lockfile and the locale file.
+#[derive(Debug, PartialEq)]
+pub(crate) enum LockChange {
+    /// A key is not in the lockfile.
+    Added(String),
+    /// A key of the lockfile is gone.
+    Removed(String),
+    /// The placeholders of a key changed: `(Key, Locked, Current)`.
+    Changed(String, Vec<String>, Vec<String>),
+}
+
+impl LockChange {
+    /// Returns the key that changed.
+    fn key(&self) -> &str {
+        match self {
+            LockChange::Added(key) | LockChange::Removed(key) | LockChange::Changed(key, ..) => key,
+        }
+    }
+}
+
+impl fmt::Display for LockChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockChange::Added(key) => write!(f, "+ {:?}", key),
+            LockChange::Removed(key) => write!(f, "- {:?}", key),
+            LockChange::Changed(key, locked, current) => write!(
+                f,
+                "~ {:?}: [{}] -> [{}]",
+                key,
+                locked.join(", "),
+                current.join(", ")
+            ),
+        }
+    }
+}
+
+impl Lock {
+    /// Computes the lock of `localized_texts`, placeholders are written in
+    /// `syntax`.
+    ///
+    /// Keys without an English translation are signed with the placeholders of
+    /// the key.
+    pub(crate) fn new(localized_texts: &LocalizedTexts, syntax: PlaceholderSyntax) -> Self {
+        let keys = localized_texts
+            .texts
+            .iter()
+            .map(|(key, translations)| {
+                let mut placeholders = match &translations.en {
+                    Some(en) => placeholders_of_translation(en, syntax),
+                    None => placeholders_of_key(key),
+                }
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>();
+                placeholders.sort();
+                placeholders.dedup();
+                (key.to_string(), placeholders)
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    /// Parses a lockfile.
+    pub(crate) fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents).map(|keys| Self { keys })
+    }
+
+    /// Renders the lockfile.
+    pub(crate) fn render(&self) -> String {
+        let table = toml::to_string(&self.keys).expect("lock should be serializable");
+        format!("{}\n{}", HEADER, table)
+    }
+
+    /// Returns what changed from the `locked` keys, sorted by key.
+    pub(crate) fn changes_from(&self, locked: &Lock) -> Vec<LockChange> {
+        let mut changes = Vec::new();
+        for (key, placeholders) in self.keys.iter() {
+            match locked.keys.get(key) {
+                None => changes.push(LockChange::Added(key.clone())),
+                Some(locked) if locked != placeholders => changes.push(LockChange::Changed(
+                    key.clone(),
+                    locked.clone(),
+                    placeholders.clone(),
+                )),
+                Some(_) => {}
+            }
+        }
+        for key in locked.keys.keys() {
+            if !self.keys.contains_key(key) {
+                changes.push(LockChange::Removed(key.clone()));
+            }
+        }
+        changes.sort_by(|a, b| a.key().cmp(b.key()));
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::Translations;
+    use indexmap::IndexMap;
+
+    fn localized_texts(texts: &[(&str, Option<&str>)]) -> LocalizedTexts {
+        LocalizedTexts {
+            texts: texts
+                .iter()
+                .map(|(key, en)| {
+                    (
+                        (*key).into(),
+                        Translations {
+                            en: en.map(Into::into),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect::<IndexMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_lock() {
+        let lock = Lock::new(
+            &localized_texts(&[
+                (
+                    "Restarting {app}",
+                    Some("Restarting %{app} in %{dir}, %{app}"),
+                ),
+                ("Done", Some("Done")),
+                ("Pulling {repo}", None),
+            ]),
+            PlaceholderSyntax::default(),
+        );
+        let rendered = lock.render();
+        assert_eq!(
+            rendered,
+            format!(
+                "{}
+Done = []
+\"Pulling {{repo}}\" = [\"repo\"]
+\"Restarting {{app}}\" = [\"app\", \"dir\"]
+",
+                HEADER
+            )
+        );
+        assert_eq!(Lock::parse(&rendered).unwrap(), lock);
+        assert!(Lock::parse("Done = 1").is_err());
+
+        let current = Lock::new(
+            &localized_texts(&[
+                ("Restarting {app}", Some("Restarting %{app}")),
+                ("Pulling {repo}", None),
+                ("Cleaning", Some("Cleaning")),
+            ]),
+            PlaceholderSyntax::default(),
+        );
+        let changes = current.changes_from(&lock);
+        assert_eq!(
+            changes,
+            [
+                LockChange::Added("Cleaning".into()),
+                LockChange::Removed("Done".into()),
+                LockChange::Changed(
+                    "Restarting {app}".into(),
+                    vec!["app".into(), "dir".into()],
+                    vec!["app".into()]
+                ),
+            ]
+        );
+        assert_eq!(
+            changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "+ \"Cleaning\"",
+                "- \"Done\"",
+                "~ \"Restarting {app}\": [app, dir] -> [app]"
+            ]
+        );
+        assert!(current.changes_from(&current).is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+//! This file contains the internal logging of this tool.
+//!
+//! Events about its progress, e.g., phases and parsed files, and failures are
+//! emitted with `tracing`. They are dropped by default, `--log-format json`
+//! writes them to stderr as JSON lines, so that CI systems can index them.
+
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How the internal events are logged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum LogFormat {
+    /// Only warnings and errors are printed, for humans.
+    #[default]
+    Text,
+    /// Every event is written to stderr as a JSON line, human-readable
+    /// messages and progress bars are suppressed.
+    Json,
+}
+
+/// Set when JSON logging is enabled.
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Sets up logging in `format`, should be called once, before anything is
+/// logged.
+///
+/// Panics are logged by the panic hook, see [`crate::crash`].
+pub(crate) fn init(format: LogFormat) {
+    if format != LogFormat::Json {
+        return;
+    }
+
+    JSON.store(true, Ordering::SeqCst);
+    tracing_subscriber::fmt()
+        .json()
+        .flatten_event(true)
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Returns true if events are logged as JSON, in which case human-readable
+/// messages should not be printed to stderr.
+pub(crate) fn is_json() -> bool {
+    JSON.load(Ordering::SeqCst)
+}
+
+/// Returns the message `panic!()` is called with, `payload` is the payload of
+/// the panic.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message() {
+        let payload = std::panic::catch_unwind(|| panic!("Error: {}", 42)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "Error: 42");
+
+        let payload = std::panic::catch_unwind(|| panic!("Error")).unwrap_err();
+        assert_eq!(panic_message(&*payload), "Error");
+    }
+}
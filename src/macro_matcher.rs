@@ -0,0 +1,179 @@
+//! This file contains the `MacroMatcher` trait, which tells the collector
+//! which macro invocations are translations and where their keys are, along
+//! with the matchers of the supported i18n stacks:
+//!
+//! * `t!("key", name = value)` of rust-i18n, which Topgrade uses.
+//! * `fl!(loader, "message-id", name = value)` of i18n-embed with Fluent.
+//! * `tr!("msgid {}", value)` of the `tr` crate with gettext, possibly with a
+//!   context, `tr!("context" => "msgid")`.
+
+use proc_macro2::{Literal, TokenTree};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Matches the invocations of the translation macro of an i18n stack.
+pub(crate) trait MacroMatcher: Debug + Sync {
+    /// Name of the macro, e.g., `t` for `t!()`.
+    fn name(&self) -> &'static str;
+
+    /// Crate the macro can also be invoked through, e.g., `rust_i18n` for
+    /// `rust_i18n::t!()`.
+    fn crate_name(&self) -> &'static str;
+
+    /// Index of the argument that is the key, e.g., 1 for `fl!()`, whose first
+    /// argument is the loader.
+    fn key_index(&self) -> usize {
+        0
+    }
+
+    /// Returns true if `path` invokes the macro, as `name!()` or
+    /// `crate_name::name!()`.
+    fn matches(&self, path: &syn::Path) -> bool {
+        let path_segments = &path.segments;
+        let Some(last_segment) = path_segments.last() else {
+            return false;
+        };
+
+        last_segment.ident == self.name()
+            && match path_segments.len() {
+                1 => true,
+                2 => path_segments.get(0).expect("len == 2").ident == self.crate_name(),
+                _ => false,
+            }
+    }
+
+    /// Returns the key from the tokens of its argument, `arg`, if it is a
+    /// literal.
+    fn key(&self, arg: &[TokenTree]) -> Option<Literal> {
+        match arg.first() {
+            Some(TokenTree::Literal(literal)) => Some(literal.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// `t!()` of rust-i18n.
+#[derive(Debug)]
+pub(crate) struct RustI18n;
+
+impl MacroMatcher for RustI18n {
+    fn name(&self) -> &'static str {
+        "t"
+    }
+
+    fn crate_name(&self) -> &'static str {
+        "rust_i18n"
+    }
+}
+
+/// `fl!()` of i18n-embed, whose first argument is the Fluent loader.
+#[derive(Debug)]
+pub(crate) struct Fluent;
+
+impl MacroMatcher for Fluent {
+    fn name(&self) -> &'static str {
+        "fl"
+    }
+
+    fn crate_name(&self) -> &'static str {
+        "i18n_embed_fl"
+    }
+
+    fn key_index(&self) -> usize {
+        1
+    }
+}
+
+/// `tr!()` of the `tr` crate, with gettext.
+#[derive(Debug)]
+pub(crate) struct Gettext;
+
+impl MacroMatcher for Gettext {
+    fn name(&self) -> &'static str {
+        "tr"
+    }
+
+    fn crate_name(&self) -> &'static str {
+        "tr"
+    }
+
+    /// The key is the msgid, which follows the context, if any:
+    /// `tr!("context" => "msgid")`.
+    fn key(&self, arg: &[TokenTree]) -> Option<Literal> {
+        match arg {
+            [TokenTree::Literal(_), TokenTree::Punct(eq), TokenTree::Punct(gt), TokenTree::Literal(msgid)]
+                if eq.as_char() == '=' && gt.as_char() == '>' =>
+            {
+                Some(msgid.clone())
+            }
+            [TokenTree::Literal(msgid), ..] => Some(msgid.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// An i18n stack whose translation macro is collected, set in `scan.macros`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum MacroFamily {
+    /// `t!()` of rust-i18n.
+    RustI18n,
+    /// `fl!()` of i18n-embed.
+    Fluent,
+    /// `tr!()` of gettext.
+    Gettext,
+}
+
+impl MacroFamily {
+    /// Returns the matcher of the macro.
+    pub(crate) fn matcher(self) -> &'static dyn MacroMatcher {
+        match self {
+            MacroFamily::RustI18n => &RustI18n,
+            MacroFamily::Fluent => &Fluent,
+            MacroFamily::Gettext => &Gettext,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::TokenStream;
+
+    fn key(matcher: &dyn MacroMatcher, arg: &str) -> Option<String> {
+        let arg = arg
+            .parse::<TokenStream>()
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        matcher.key(&arg).map(|literal| literal.to_string())
+    }
+
+    #[test]
+    fn test_matches() {
+        let path = |path: &str| syn::parse_str::<syn::Path>(path).unwrap();
+        assert!(RustI18n.matches(&path("t")));
+        assert!(RustI18n.matches(&path("rust_i18n::t")));
+        assert!(!RustI18n.matches(&path("foo::t")));
+        assert!(!RustI18n.matches(&path("fl")));
+        assert!(Fluent.matches(&path("i18n_embed_fl::fl")));
+        assert!(Gettext.matches(&path("tr")));
+    }
+
+    #[test]
+    fn test_key() {
+        assert_eq!(
+            key(&RustI18n, r#""Restarting""#).as_deref(),
+            Some(r#""Restarting""#)
+        );
+        assert_eq!(key(&RustI18n, "KEY"), None);
+        assert_eq!(
+            key(&Gettext, r#""menu" => "Open""#).as_deref(),
+            Some(r#""Open""#)
+        );
+        assert_eq!(
+            key(&Gettext, r#""Open {}""#).as_deref(),
+            Some(r#""Open {}""#)
+        );
+    }
+}
@@ -1,53 +1,1533 @@
 #![cfg(unix)]
 
+mod baseline;
 mod checker;
 mod cli_opt;
+mod codegen;
+mod compare;
+mod config;
+mod crash;
+mod docs;
+mod doctor;
+mod editor;
+mod error;
+mod expand;
+mod explain;
+mod export;
+mod fix;
+mod glossary;
+mod history;
+mod init;
+mod interner;
+mod interrupt;
+mod issues;
+mod junit;
 mod locale_file_parser;
 mod locale_key_collector;
+mod lockfile;
+mod logging;
+mod macro_matcher;
+mod metrics;
+mod notify;
+mod porcelain;
+mod progress;
+mod project;
+mod repl;
+mod report;
+mod rule_list;
 mod rules;
+mod sarif;
+mod source;
+mod timings;
+mod todo;
+mod usage_map;
 
+use crate::baseline::Baseline;
 use crate::checker::Checker;
-use crate::cli_opt::Cli;
+use crate::cli_opt::{
+    Cli, CodegenArgs, Command, CompareArgs, ExplainKeyArgs, ExportArgs, ListKeysArgs,
+    MergeReportsArgs, ReplArgs, ReportArgs, TodoArgs, TrendArgs,
+};
+use crate::codegen::TestFile;
+use crate::compare::Comparison;
+use crate::config::Config;
+use crate::editor::OutputFormat;
+use crate::error::{Error, EXIT_CODE_ON_ERROR, EXIT_CODE_ON_FAILURE};
+use crate::expand::CARGO_EXPAND_OUTPUT;
+use crate::explain::KeyExplanation;
+use crate::export::{ExportFormat, SqliteExport};
+use crate::glossary::Glossary;
+use crate::history::{History, Regression, Run};
+use crate::interner::Interner;
+use crate::issues::IssueContext;
 use crate::locale_file_parser::LocalizedTexts;
 use crate::locale_key_collector::LocaleKeyCollector;
-use crate::rules::key_and_eng_matches::KeyEngMatches;
+use crate::lockfile::{lockfile_path, Lock};
+use crate::metrics::Metrics;
+use crate::notify::Summary;
+use crate::project::Project;
+use crate::repl::Repl;
+use crate::report::Report;
+use crate::rules::ambiguous_braces::AmbiguousBraces;
+use crate::rules::ansi_escape_sequences::AnsiEscapeSequences;
+use crate::rules::bidi_control_characters::BidiControlCharacters;
+use crate::rules::concatenated_translations::ConcatenatedTranslations;
+use crate::rules::emoji_policy::EmojiPolicy;
+use crate::rules::error_messages::ErrorMessages;
+use crate::rules::glossary_terms::GlossaryTerms;
+use crate::rules::hard_coded_strings::HardCodedStrings;
+use crate::rules::key_and_eng_matches::{derived_en, KeyEngMatches};
+use crate::rules::markup_parity::MarkupParity;
 use crate::rules::missing_translations::MissingTranslations;
+use crate::rules::numbers_and_units::NumbersAndUnits;
+use crate::rules::percent_brace_in_keys::PercentBraceInKeys;
+use crate::rules::placeholder_consistency::PlaceholderConsistency;
+use crate::rules::plural_categories::PluralCategories;
+use crate::rules::pseudo_text::PseudoText;
+use crate::rules::quoting_style::QuotingStyle;
+use crate::rules::raw_braces_in_english::RawBracesInEnglish;
+use crate::rules::stub_translations::StubTranslations;
+use crate::rules::suspicious_whitespace::SuspiciousWhitespace;
+use crate::rules::too_many_placeholders::TooManyPlaceholders;
+use crate::rules::trailing_newline::TrailingNewline;
+use crate::rules::unicode_normalization::UnicodeNormalization;
+use crate::rules::unused_locale_keys::UnusedLocaleKeys;
+use crate::rules::urls_preserved::UrlsPreserved;
 use crate::rules::use_of_keys_do_not_exist::UseOfKeysDoNotExist;
+use crate::rules::use_of_locales_do_not_exist::UseOfLocalesDoNotExist;
+use crate::rules::words_around_translations::WordsAroundTranslations;
+use crate::rules::{CheckContext, Rule, RULES};
+use crate::source::Source;
+use crate::timings::Timings;
+use crate::todo::Todo;
+use crate::usage_map::{UsageMap, UsageMapFormat};
 use clap::Parser;
-use serde_yaml_ng::from_reader;
+use indexmap::IndexMap;
+use serde_yaml_ng::from_str;
 use serde_yaml_ng::Value as Yaml;
-use std::fs::File;
-
-const EXIT_CODE_ON_ERROR: i32 = 1;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 fn main() {
     let cli = Cli::parse();
+    logging::init(cli.log_format());
+    crash::install_hook(
+        cli.command_name(),
+        cli.bug_report_dir().map(Path::to_path_buf),
+    );
+
+    // Validates the config file itself, so an invalid config file is one of
+    // its findings rather than an error.
+    if let Some(Command::Doctor(args)) = cli.command() {
+        let project_config = cli
+            .config_file()
+            .and_then(|path| Config::load(path).ok())
+            .map(|config| config.project)
+            .unwrap_or_default();
+        if doctor::doctor(&args.projects(&project_config), cli.config_file()) {
+            std::process::exit(EXIT_CODE_ON_ERROR);
+        }
+        return;
+    }
+
+    let has_error = run(&cli).unwrap_or_else(|e| {
+        tracing::error!(error = %e, "failed");
+        if !logging::is_json() {
+            eprintln!("Error: {}", e);
+        }
+        std::process::exit(EXIT_CODE_ON_FAILURE);
+    });
+
+    if interrupt::is_interrupted() {
+        std::process::exit(interrupt::EXIT_CODE_ON_INTERRUPT);
+    }
+    if has_error {
+        std::process::exit(EXIT_CODE_ON_ERROR);
+    }
+}
+
+/// Runs the subcommand specified by `cli`, or checks the projects if there
+/// is none, returns true if any error is found.
+fn run(cli: &Cli) -> Result<bool, Error> {
+    let config = cli.config()?;
+
+    if let Some(format) = cli.print_config() {
+        println!("{}", config.render(format).trim_end());
+        return Ok(false);
+    }
+
+    interrupt::install_handler()?;
+
+    match cli.command() {
+        Some(Command::Compare(args)) => compare(args, &config),
+        Some(Command::ListKeys(args)) => list_keys(args, &config),
+        Some(Command::ExplainKey(args)) => explain_key(args, &config),
+        Some(Command::Repl(args)) => repl(args, &config),
+        Some(Command::Export(args)) => export(args, &config),
+        Some(Command::Report(args)) => report(args, &config),
+        Some(Command::Todo(args)) => todo(args, &config),
+        Some(Command::Codegen(args)) => codegen(args, &config),
+        Some(Command::Trend(args)) => trend(args, &config),
+        Some(Command::MergeReports(args)) => merge_reports(args, &config),
+        Some(Command::Docs(args)) => {
+            match args.out_dir() {
+                Some(out_dir) => docs::write_files(out_dir, &RULES)?,
+                None => print!("{}", docs::render_page(&RULES)),
+            }
+            Ok(false)
+        }
+        Some(Command::Rules) => {
+            print!("{}", rule_list::render(&RULES, &config.rules));
+            Ok(false)
+        }
+        Some(Command::Doctor(_)) => unreachable!("handled above"),
+        Some(Command::Init(args)) => {
+            init::init(Path::new(""), args.force(), args.pre_commit())?;
+            Ok(false)
+        }
+        None => check(cli, &config),
+    }
+}
+
+/// Tells the user that the report is incomplete because of Ctrl-C.
+///
+/// Printed to stderr if `machine_readable` is true so that the output format
+/// is not broken.
+fn report_interruption(machine_readable: bool) {
+    const MSG: &str = "Interrupted, the report above is incomplete!";
+    if machine_readable {
+        eprintln!("{}", MSG);
+    } else {
+        println!("{}", MSG);
+    }
+}
+
+/// Checks the projects specified by `cli`, returns true if any error is found.
+fn check(cli: &Cli, config: &Config) -> Result<bool, Error> {
+    if cli.output().is_some() && !cli.format().is_document() {
+        return Err(Error::Usage(
+            "--output is only supported by --format json, sarif and junit".to_string(),
+        ));
+    }
+    let source = cli.source()?;
+    if let Source::GitRev(git_rev) = &source {
+        if !cli.machine_readable() {
+            println!("Revision {}:", git_rev.rev());
+        }
+    }
+
+    // Without the file, the current diagnostics are recorded as the baseline
+    let mut recording_baseline = false;
+    let mut baseline = match cli.baseline() {
+        Some(path) if path.exists() => {
+            let contents = std::fs::read_to_string(path).map_err(|error| Error::Read {
+                path: path.to_path_buf(),
+                error,
+            })?;
+            Baseline::parse(&contents).map_err(|e| Error::Invalid {
+                what: "baseline",
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?
+        }
+        Some(_) => {
+            recording_baseline = true;
+            Baseline::new()
+        }
+        None => Baseline::new(),
+    };
+
+    let mut metrics = Metrics::new();
+    let mut report = Report::new();
+    let mut run = Run::new();
+    // `ProjectRoot => UsageMap`
+    let mut usage_maps = IndexMap::new();
+    let mut has_error = false;
+    for project in cli.projects(&config.project) {
+        if let Some(root) = project.root() {
+            if !cli.machine_readable() {
+                println!("Project {}:", root.display());
+            }
+        }
+
+        let mut timings = Timings::new();
+        let start = Instant::now();
+        let ProjectCheck {
+            localized_texts,
+            collector,
+            mut checker,
+        } = check_project(
+            &project,
+            &source,
+            config,
+            CheckOptions {
+                locale_only: cli.locale_only(),
+                allow_empty: cli.allow_empty(),
+                // Progress bars are noise for machine-readable output
+                show_progress: !cli.machine_readable(),
+            },
+            &mut timings,
+        )?;
+        let duration = start.elapsed();
+
+        if recording_baseline {
+            baseline.record(project.root(), &checker);
+        }
+        let n_suppressed = baseline.suppress(project.root(), &mut checker);
+        if n_suppressed != 0 {
+            tracing::info!(
+                suppressed = n_suppressed,
+                "diagnostics suppressed by the baseline"
+            );
+            if !logging::is_json() && !recording_baseline {
+                eprintln!(
+                    "Note: {} diagnostics suppressed by the baseline",
+                    n_suppressed
+                );
+            }
+        }
+
+        let mut locale_file_contents = String::new();
+        if cli.format() != OutputFormat::Text || cli.report_file().is_some() {
+            source.read_to_string(project.locale_file(), &mut locale_file_contents)?;
+        }
+        if cli.porcelain() {
+            porcelain::write_diagnostics(
+                &mut std::io::stdout().lock(),
+                project.root(),
+                &checker,
+                cli.nul_terminated(),
+            )
+            .map_err(stdout_error)?;
+        } else {
+            match cli.format() {
+                OutputFormat::Text => {
+                    checker.report_to_user(cli.layout(), &config.languages.maintainers)
+                }
+                // Printed as a single document once every project is checked
+                format if format.is_document() => {}
+                format => editor::write_diagnostics(
+                    &mut std::io::stdout().lock(),
+                    format,
+                    &checker,
+                    project.locale_file(),
+                    &locale_file_contents,
+                )
+                .map_err(stdout_error)?,
+            }
+        }
+
+        if cli.timings() {
+            timings.report_to_user();
+        }
+
+        metrics.record_project(
+            project.root(),
+            &localized_texts,
+            collector.locale_keys(),
+            &checker,
+            duration,
+        );
+        report.record_project(
+            project.root(),
+            &localized_texts,
+            collector.locale_keys(),
+            &checker,
+            project.locale_file(),
+            &locale_file_contents,
+        );
+        run.record_project(project.root(), &localized_texts, &checker);
+
+        if cli.write_lock() || cli.check_lock() {
+            has_error |= update_lock(cli, project.root(), &localized_texts, config)?;
+        }
+
+        if cli.fix() || cli.fix_unused() {
+            fix_locale_file(cli, &project, &source, &localized_texts, &collector, config)?;
+        }
+
+        if cli.emit_usage_map().is_some() {
+            usage_maps.insert(
+                project.root().map(Path::to_path_buf),
+                UsageMap::new(&localized_texts, collector.locale_keys()),
+            );
+        }
+
+        tracing::info!(
+            locale_file = %project.locale_file().display(),
+            diagnostics = checker.errors().count(),
+            duration_ms = duration.as_secs_f64() * 1000.0,
+            "project checked"
+        );
+        has_error |= checker.has_error();
+
+        if interrupt::is_interrupted() {
+            report_interruption(cli.machine_readable());
+            break;
+        }
+    }
+
+    if let Some(usage_map_file) = cli.emit_usage_map() {
+        // With multiple projects, usage maps are keyed by the project roots.
+        let json = if usage_maps.len() == 1 {
+            serde_json::to_string_pretty(&usage_maps[0])
+        } else {
+            let usage_maps = usage_maps
+                .iter()
+                .map(|(root, usage_map)| {
+                    let root = root.as_deref().expect("multiple projects have roots");
+                    (root.display().to_string(), usage_map)
+                })
+                .collect::<IndexMap<_, _>>();
+            serde_json::to_string_pretty(&usage_maps)
+        }
+        .expect("usage map should be serializable");
+        write_file(usage_map_file, json)?;
+    }
+
+    // An interrupted check, or one of a single buffer, is incomplete, recording
+    // it would skew the trend
+    let mut regressions = Vec::new();
+    let incomplete = interrupt::is_interrupted() || cli.stdin_source().is_some();
+    if let (Some(history_file), false) = (&config.history.file, incomplete) {
+        regressions = record_run(history_file, &source, &run, config)?;
+        has_error |= !regressions.is_empty();
+    }
+
+    if let (Some(baseline_file), true, false) = (cli.baseline(), recording_baseline, incomplete) {
+        write_file(baseline_file, baseline.render())?;
+        tracing::info!(baseline = %baseline_file.display(), diagnostics = baseline.len(), "baseline recorded");
+        if !logging::is_json() {
+            eprintln!(
+                "Recorded {} diagnostics to the baseline {}",
+                baseline.len(),
+                baseline_file.display()
+            );
+        }
+    }
+
+    if let Some(url) = cli.notify_webhook() {
+        notify(url, cli, &source, &run, !has_error, regressions, config);
+    }
+
+    if let Some(metrics_file) = cli.metrics_file() {
+        write_file(metrics_file, metrics.render())?;
+    }
+
+    if cli.format().is_document() {
+        let document = report.render_as(cli.format());
+        match cli.output() {
+            Some(output) => write_file(output, document)?,
+            None => println!("{}", document.trim_end()),
+        }
+    }
+    if let Some(report_file) = cli.report_file() {
+        write_file(report_file, report.render())?;
+    }
+
+    Ok(has_error)
+}
+
+/// Writes `contents` to the file at `path`, replaced if it exists.
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), Error> {
+    std::fs::write(path, contents).map_err(|error| Error::Write {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Converts an error writing to stdout to [`Error`].
+fn stdout_error(error: std::io::Error) -> Error {
+    Error::Write {
+        path: PathBuf::from("stdout"),
+        error,
+    }
+}
+
+/// Merges the reports given by `args`, writes the merged one if requested and
+/// prints its diagnostics, returns true if it has any error.
+fn merge_reports(args: &MergeReportsArgs, config: &Config) -> Result<bool, Error> {
+    let reports = args
+        .reports()
+        .iter()
+        .map(|path| {
+            let contents = std::fs::read_to_string(path).map_err(|error| Error::Read {
+                path: path.to_path_buf(),
+                error,
+            })?;
+            Report::parse(&contents).map_err(|e| Error::Invalid {
+                what: "report",
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let merged = Report::merge(reports);
+
+    if let Some(output) = args.output() {
+        write_file(output, merged.render())?;
+    }
+
+    if args.format() == OutputFormat::Text {
+        let mut checkers = merged.checkers();
+        if checkers.is_empty() {
+            checkers.insert(None, Checker::new());
+        }
+        for (project, checker) in checkers {
+            if let Some(project) = project {
+                println!("Project {}:", project.display());
+            }
+            checker.report_to_user(args.layout(), &config.languages.maintainers);
+        }
+    } else if args.format().is_document() {
+        println!("{}", merged.render_as(args.format()).trim_end());
+    } else {
+        merged
+            .write_diagnostics(&mut std::io::stdout().lock(), args.format())
+            .map_err(stdout_error)?;
+    }
+
+    Ok(merged.has_error())
+}
+
+/// Records `run` of the commit checked out in `source` in the history file,
+/// returns the regressions found by the trend gates in `config`.
+fn record_run(
+    history_file: &Path,
+    source: &Source,
+    run: &Run,
+    config: &Config,
+) -> Result<Vec<Regression>, Error> {
+    let Some(commit) = source.commit() else {
+        if !logging::is_json() {
+            eprintln!("Warning: not in a git repository, the check is not recorded in the history");
+        }
+        return Ok(Vec::new());
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let regressions = History::open(history_file, true)
+        .and_then(|mut history| {
+            let regressions = history.regressions(
+                &commit,
+                run,
+                &config.history.gates,
+                &config.languages.tiers,
+            )?;
+            history.record(&commit, timestamp, run)?;
+            Ok(regressions)
+        })
+        .map_err(|e| {
+            Error::Other(format!(
+                "cannot record the check in the history file {} due to error {}",
+                history_file.display(),
+                e
+            ))
+        })?;
+    tracing::info!(commit, "run recorded");
+
+    for regression in regressions.iter() {
+        tracing::error!(error = %regression, "trend regression");
+        if !logging::is_json() {
+            eprintln!("Error: {}", regression);
+        }
+    }
+
+    Ok(regressions)
+}
+
+/// Posts a summary of `run` to the webhook at `url`, the coverage changes are
+/// computed against the history file, if any.
+///
+/// A failure is only warned about, as the check itself is done.
+fn notify(
+    url: &str,
+    cli: &Cli,
+    source: &Source,
+    run: &Run,
+    passed: bool,
+    regressions: Vec<Regression>,
+    config: &Config,
+) {
+    let history = config
+        .history
+        .file
+        .as_deref()
+        .and_then(|history_file| History::open(history_file, false).ok());
+    let commit = source.commit();
+    let last_point = |lang: &str| match (&history, &commit) {
+        (Some(history), Some(commit)) => history.last_point(lang, commit).ok().flatten(),
+        _ => None,
+    };
+    let summary = Summary::new(
+        run,
+        last_point,
+        passed,
+        regressions,
+        &config.languages.maintainers,
+        cli.report_url(),
+    );
+
+    match notify::post(url, &summary.payload(cli.notify_format())) {
+        Ok(()) => tracing::info!("notification posted"),
+        Err(e) => {
+            tracing::warn!(error = %e, "notification failed");
+            if !logging::is_json() {
+                eprintln!("Warning: cannot post the notification due to error {}", e);
+            }
+        }
+    }
+}
+
+/// Renders the issue bodies of every project, returns false as they are
+/// meant for translators rather than a check.
+fn report(args: &ReportArgs, config: &Config) -> Result<bool, Error> {
+    let source = Source::WorkTree;
+    let projects = args.projects(&config.project);
+
+    for project in projects.iter() {
+        let options = CheckOptions {
+            locale_only: args.locale_only(),
+            allow_empty: true,
+            show_progress: true,
+        };
+        let ProjectCheck {
+            localized_texts,
+            collector,
+            checker,
+        } = check_project(project, &source, config, options, &mut Timings::new())?;
+        let issues = IssueContext {
+            locale_file: project.locale_file(),
+            localized_texts: &localized_texts,
+            locale_keys: collector.locale_keys(),
+            checker: &checker,
+            link_base: args.link_base(),
+            maintainers: &config.languages.maintainers,
+        }
+        .render_issues();
+
+        match args.out_dir() {
+            Some(out_dir) => {
+                let dir = match project.root().and_then(Path::file_name) {
+                    Some(name) if projects.len() > 1 => out_dir.join(name),
+                    _ => out_dir.to_path_buf(),
+                };
+                std::fs::create_dir_all(&dir).map_err(|error| Error::Write {
+                    path: dir.clone(),
+                    error,
+                })?;
+                for issue in issues {
+                    let file = dir.join(format!("{}.md", issue.lang));
+                    write_file(&file, &issue.body)?;
+                    println!("{}: {}", file.display(), issue.title);
+                }
+            }
+            None => {
+                if let Some(root) = project.root() {
+                    println!("Project {}:", root.display());
+                }
+                for issue in issues {
+                    println!("# {}\n\n{}\n---\n", issue.title, issue.body);
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Writes the TODO files of every project, returns false as they are meant
+/// for translators rather than a check.
+fn todo(args: &TodoArgs, config: &Config) -> Result<bool, Error> {
+    let source = Source::WorkTree;
+    let projects = args.projects(&config.project);
+
+    for project in projects.iter() {
+        let options = CheckOptions {
+            locale_only: args.locale_only(),
+            allow_empty: true,
+            show_progress: true,
+        };
+        let ProjectCheck {
+            localized_texts,
+            collector,
+            ..
+        } = check_project(project, &source, config, options, &mut Timings::new())?;
+        let dir = match project.root().and_then(Path::file_name) {
+            Some(name) if projects.len() > 1 => args.out_dir().join(name),
+            _ => args.out_dir().to_path_buf(),
+        };
+        let written = Todo {
+            locale_file: project.locale_file(),
+            localized_texts: &localized_texts,
+            locale_keys: collector.locale_keys(),
+            maintainers: &config.languages.maintainers,
+        }
+        .write_files(&dir)?;
+        for file in written {
+            println!("{}", file.display());
+        }
+    }
+
+    Ok(false)
+}
+
+/// Fixes the locale file of `project`, whose parsed contents are
+/// `localized_texts`, for the `t!()` invocations `collector` found in its Rust
+/// files, as requested by `--fix` and `--fix-unused`.
+///
+/// With `--fix-unused`, fails without changing anything if any Rust file was
+/// skipped, or the check was interrupted, as keys it does not know are used
+/// would be removed. Rust files that cannot be parsed already failed the check.
+fn fix_locale_file(
+    cli: &Cli,
+    project: &Project,
+    source: &Source,
+    localized_texts: &LocalizedTexts,
+    collector: &LocaleKeyCollector,
+    config: &Config,
+) -> Result<(), Error> {
+    let locale_file = project.locale_file();
+    if cli.fix_unused() {
+        if !config
+            .rules
+            .selection()
+            .is_enabled(UnusedLocaleKeys::name(), true)
+        {
+            return Err(Error::Usage(format!(
+                "--fix-unused does not remove keys when `{}` is disabled, the keys are not checked",
+                UnusedLocaleKeys::name()
+            )));
+        }
+        if !collector.skipped_files().is_empty() {
+            let files = collector
+                .skipped_files()
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect::<Vec<_>>();
+            return Err(Error::Usage(format!(
+                "--fix-unused does not remove keys when Rust files are skipped, the keys they use are unknown: {}",
+                files.join(", ")
+            )));
+        }
+        // Possible with `--allow-empty`
+        if collector.locale_keys().is_empty() {
+            return Err(Error::Usage(
+                "--fix-unused does not remove keys when no `t!()` invocation is found, every key would be removed"
+                    .to_string(),
+            ));
+        }
+        if interrupt::is_interrupted() {
+            return Err(Error::Usage(
+                "--fix-unused does not remove keys when the check is interrupted, the keys the Rust files not scanned use are unknown"
+                    .to_string(),
+            ));
+        }
+    }
+    let locale_keys = collector.locale_keys();
+    let mut original = String::new();
+    source.read_to_string(locale_file, &mut original)?;
+    let mut contents = original.clone();
+    // What was done, e.g., `removed 2 unused keys`
+    let mut changes = Vec::new();
+
+    if cli.fix() {
+        // English translations `KeyEngMatches` reports, rewritten in place
+        let english_fixes = KeyEngMatches::new(
+            config.placeholders.syntax,
+            config.rules.key_eng_matches.clone(),
+            config.variants.clone(),
+        )
+        .english_fixes(localized_texts);
+        let mut n_rewritten = 0;
+        for (key, en) in english_fixes {
+            match fix::set_english(&contents, key, &en) {
+                Some(fixed) => {
+                    contents = fixed;
+                    n_rewritten += 1;
+                }
+                None => {
+                    tracing::warn!(key, "cannot rewrite the English translation");
+                    if !logging::is_json() {
+                        eprintln!(
+                            "Warning: cannot rewrite the English translation of key '{}' in {}, it is not a block mapping",
+                            key,
+                            locale_file.display()
+                        );
+                    }
+                }
+            }
+        }
+        changes.push(format!("rewrote {} English translations", n_rewritten));
+    }
+
+    if cli.fix_unused() {
+        let mut n_removed = 0;
+        for key in
+            UnusedLocaleKeys::new(config.variants.clone()).unused_keys(localized_texts, locale_keys)
+        {
+            match fix::remove_key(&contents, key) {
+                Some(fixed) => {
+                    contents = fixed;
+                    n_removed += 1;
+                }
+                None => {
+                    tracing::warn!(key = &**key, "cannot remove the unused key");
+                    if !logging::is_json() {
+                        eprintln!(
+                            "Warning: cannot remove the unused key '{}' from {}, it is not found",
+                            key,
+                            locale_file.display()
+                        );
+                    }
+                }
+            }
+        }
+        changes.push(format!("removed {} unused keys", n_removed));
+    }
+
+    if cli.fix() {
+        let mut missing_keys = UseOfKeysDoNotExist::new(config.variants.clone())
+            .missing_keys(localized_texts, locale_keys)
+            .into_iter()
+            .map(|locale_key| &*locale_key.key)
+            .collect::<Vec<_>>();
+        // Keys are usually used more than once, stubs are in order of first use
+        let mut seen = HashSet::new();
+        missing_keys.retain(|key| seen.insert(*key));
+        let stubs = missing_keys
+            .iter()
+            .map(|key| (*key, derived_en(key, config.placeholders.syntax)))
+            .collect::<Vec<_>>();
+        contents = fix::append_stubs(&contents, &stubs);
+        changes.push(format!(
+            "added {} stub entries for missing keys",
+            stubs.len()
+        ));
+    }
+
+    if contents == original {
+        return Ok(());
+    }
+    if cli.dry_run() {
+        print!("{}", fix::diff(locale_file, &original, &contents));
+        return Ok(());
+    }
+    write_file(locale_file, contents)?;
+
+    tracing::info!(locale_file = %locale_file.display(), changes = ?changes, "locale file fixed");
+    if !logging::is_json() {
+        eprintln!("Fixed {}: {}", locale_file.display(), changes.join(", "));
+    }
+
+    Ok(())
+}
 
-    let locale_file = File::open(cli.locale_file()).unwrap_or_else(|e| {
-        panic!(
-            "Error: cannot open the specified file {} due to error {:?}",
-            cli.locale_file().display(),
+/// Writes or checks the lockfile of the project whose root is `root`, returns
+/// true if `--check-lock` found it missing or outdated.
+fn update_lock(
+    cli: &Cli,
+    root: Option<&Path>,
+    localized_texts: &LocalizedTexts,
+    config: &Config,
+) -> Result<bool, Error> {
+    let lock = Lock::new(localized_texts, config.placeholders.syntax);
+    let path = lockfile_path(root);
+
+    if cli.write_lock() {
+        write_file(&path, lock.render())?;
+        return Ok(false);
+    }
+
+    let locked = match std::fs::read_to_string(&path) {
+        Ok(contents) => Lock::parse(&contents).map_err(|e| Error::Invalid {
+            what: "lockfile",
+            path: path.clone(),
+            message: e.to_string(),
+        })?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Lock::default(),
+        Err(error) => return Err(Error::Read { path, error }),
+    };
+    let changes = lock.changes_from(&locked);
+    if changes.is_empty() {
+        return Ok(false);
+    }
+
+    tracing::error!(
+        lockfile = %path.display(),
+        changes = changes.len(),
+        "lockfile is outdated"
+    );
+    if !logging::is_json() {
+        eprintln!(
+            "Error: {} is outdated, run with --write-lock and commit it, the keys changed:",
+            path.display()
+        );
+        for change in changes {
+            eprintln!("  {}", change);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Generates the code asked for, returns false as there is nothing to check.
+fn codegen(args: &CodegenArgs, config: &Config) -> Result<bool, Error> {
+    let projects = args.projects(&config.project);
+    let [project] = projects.as_slice() else {
+        return Err(Error::Usage(
+            "codegen generates the code of a single project, pass one --project".to_string(),
+        ));
+    };
+    let options = CheckOptions {
+        locale_only: args.locale_only(),
+        allow_empty: true,
+        show_progress: true,
+    };
+    let ProjectCheck {
+        localized_texts,
+        collector,
+        ..
+    } = check_project(
+        project,
+        &Source::WorkTree,
+        config,
+        options,
+        &mut Timings::new(),
+    )?;
+    let rendered = TestFile {
+        localized_texts: &localized_texts,
+        locale_keys: collector.locale_keys(),
+        tiers: &config.languages.tiers,
+        variants: &config.variants,
+    }
+    .render();
+
+    match args.output() {
+        Some(output) => {
+            write_file(output, rendered)?;
+            println!("{}", output.display());
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(false)
+}
+
+/// Prints the trend of a language across the runs in the history file,
+/// returns false as there is nothing to check.
+fn trend(args: &TrendArgs, config: &Config) -> Result<bool, Error> {
+    let Some(history_file) = &config.history.file else {
+        return Err(Error::Usage(
+            "no history file, set `history.file` in the config file or pass --history-file"
+                .to_string(),
+        ));
+    };
+    let trend = History::open(history_file, false)
+        .and_then(|history| history.trend(args.lang(), args.last()))
+        .map_err(|e| {
+            Error::Other(format!(
+                "cannot read the history file {} due to error {}",
+                history_file.display(),
+                e
+            ))
+        })?;
+    print!("{}", trend.render());
+
+    Ok(false)
+}
+
+/// Compares the check results of two revisions, returns true if the new
+/// revision introduces any error.
+fn compare(args: &CompareArgs, config: &Config) -> Result<bool, Error> {
+    let from = args.from();
+    let to = args.to();
+
+    let mut has_introduced_error = false;
+    for project in args.projects(&config.project) {
+        if let Some(root) = project.root() {
+            println!("Project {}:", root.display());
+        }
+
+        let options = CheckOptions {
+            locale_only: args.locale_only(),
+            allow_empty: true,
+            show_progress: true,
+        };
+        let mut timings = Timings::new();
+        let from = check_project(&project, &from, config, options, &mut timings)?;
+        let to = check_project(&project, &to, config, options, &mut timings)?;
+
+        let comparison = Comparison::new(
+            (&from.localized_texts, &from.checker),
+            (&to.localized_texts, &to.checker),
+        );
+        comparison.report_to_user();
+
+        has_introduced_error |= comparison.has_introduced_error();
+
+        if interrupt::is_interrupted() {
+            report_interruption(false);
+            break;
+        }
+    }
+
+    Ok(has_introduced_error)
+}
+
+/// Prints everything known about a key in every project, returns true if
+/// any error is about the key.
+fn explain_key(args: &ExplainKeyArgs, config: &Config) -> Result<bool, Error> {
+    let source = Source::WorkTree;
+
+    let mut has_error = false;
+    for project in args.projects(&config.project) {
+        if let Some(root) = project.root() {
+            println!("Project {}:", root.display());
+        }
+
+        let options = CheckOptions {
+            locale_only: args.locale_only(),
+            allow_empty: true,
+            show_progress: true,
+        };
+        let ProjectCheck {
+            localized_texts,
+            collector,
+            checker,
+        } = check_project(&project, &source, config, options, &mut Timings::new())?;
+
+        let mut locale_file_contents = String::new();
+        source.read_to_string(project.locale_file(), &mut locale_file_contents)?;
+        let explanation = KeyExplanation {
+            key: args.key(),
+            locale_file: project.locale_file(),
+            locale_file_contents: &locale_file_contents,
+            localized_texts: &localized_texts,
+            locale_keys: collector.locale_keys(),
+            checker: &checker,
+            syntax: config.placeholders.syntax,
+        };
+        print!("{}", explanation.render());
+
+        has_error |= explanation.has_error();
+    }
+
+    Ok(has_error)
+}
+
+/// Checks every project once and answers the queries read from stdin,
+/// returns false as the REPL does not report errors by itself.
+fn repl(args: &ReplArgs, config: &Config) -> Result<bool, Error> {
+    let source = Source::WorkTree;
+
+    for project in args.projects(&config.project) {
+        if let Some(root) = project.root() {
+            println!("Project {}:", root.display());
+        }
+
+        let options = CheckOptions {
+            locale_only: args.locale_only(),
+            allow_empty: true,
+            show_progress: true,
+        };
+        let ProjectCheck {
+            localized_texts,
+            collector,
+            checker,
+        } = check_project(&project, &source, config, options, &mut Timings::new())?;
+
+        let mut locale_file_contents = String::new();
+        source.read_to_string(project.locale_file(), &mut locale_file_contents)?;
+        let repl = Repl {
+            model: KeyExplanation {
+                key: "",
+                locale_file: project.locale_file(),
+                locale_file_contents: &locale_file_contents,
+                localized_texts: &localized_texts,
+                locale_keys: collector.locale_keys(),
+                checker: &checker,
+                syntax: config.placeholders.syntax,
+            },
+        };
+        println!("Type `help` for the available queries.");
+        repl.run(std::io::stdin().lock(), std::io::stdout().lock())?;
+    }
+
+    Ok(false)
+}
+
+/// Writes the model of every project to the file specified by `args`,
+/// returns false as the diagnostics are exported rather than reported.
+fn export(args: &ExportArgs, config: &Config) -> Result<bool, Error> {
+    let source = Source::WorkTree;
+    let output = args.output();
+    let fail = |e: rusqlite::Error| {
+        Error::Other(format!(
+            "cannot export to {} due to error {}",
+            output.display(),
             e
+        ))
+    };
+
+    let mut export = match args.format() {
+        ExportFormat::Sqlite => SqliteExport::create(output)?,
+    };
+    for project in args.projects(&config.project) {
+        let options = CheckOptions {
+            locale_only: args.locale_only(),
+            allow_empty: true,
+            show_progress: true,
+        };
+        let ProjectCheck {
+            localized_texts,
+            collector,
+            checker,
+        } = check_project(&project, &source, config, options, &mut Timings::new())?;
+
+        export
+            .add_project(
+                project.root(),
+                project.locale_file(),
+                &localized_texts,
+                collector.locale_keys(),
+                &checker,
+            )
+            .map_err(fail)?;
+
+        if interrupt::is_interrupted() {
+            report_interruption(true);
+            break;
+        }
+    }
+
+    Ok(false)
+}
+
+/// Prints every key used by `t!()` along with its locations, returns false
+/// as there is nothing to check.
+fn list_keys(args: &ListKeysArgs, config: &Config) -> Result<bool, Error> {
+    let source = args.source();
+    let files = project::rust_files(args.rust_src_to_check(), &source, &config.scan)?;
+
+    let progress = progress::new(
+        files.len(),
+        "Scanning",
+        args.format() == UsageMapFormat::Text,
+    );
+    let mut collector = LocaleKeyCollector::new(&config.scan.macros);
+    collector.collect(
+        &files,
+        &source,
+        config.scan.max_file_size,
+        &mut Interner::new(),
+        &mut Timings::new(),
+        &progress,
+    )?;
+    progress.finish_and_clear();
+
+    UsageMap::from_locale_keys(collector.locale_keys()).report_to_user(args.format());
+    if interrupt::is_interrupted() {
+        report_interruption(args.format() != UsageMapFormat::Text);
+    }
+
+    Ok(false)
+}
+
+/// Results of checking a project.
+struct ProjectCheck {
+    /// The parsed locale file.
+    localized_texts: LocalizedTexts,
+    /// The collector that holds the `t!()` invocations found.
+    collector: LocaleKeyCollector,
+    /// The checker that holds the errors found.
+    checker: Checker,
+}
+
+/// Options of [`check_project()`].
+#[derive(Debug, Clone, Copy)]
+struct CheckOptions {
+    /// If true, Rust files won't be scanned, and only the rules that check the
+    /// locale file itself will be applied.
+    locale_only: bool,
+    /// If false, fail with [`Error::EmptyScan`] right after scanning the Rust
+    /// files if no Rust file is found or no `t!()` invocation is collected.
+    allow_empty: bool,
+    /// Show progress bars on stderr, if it is a TTY.
+    show_progress: bool,
+}
+
+/// Adds the keys generated by macros to `collector`, from the output of
+/// `cargo expand` and the expanded files, as configured in `config.scan`.
+fn collect_expanded_keys(
+    project: &Project,
+    config: &Config,
+    collector: &mut LocaleKeyCollector,
+    interner: &mut Interner,
+) -> Result<(), Error> {
+    let mut expanded = Vec::new();
+    if config.scan.cargo_expand {
+        let contents = expand::cargo_expand(project.root())?;
+        expanded.push((PathBuf::from(CARGO_EXPAND_OUTPUT), contents));
+    }
+    for file in config.scan.expanded_files.iter() {
+        let contents = std::fs::read_to_string(file).map_err(|error| Error::Read {
+            path: file.clone(),
+            error,
+        })?;
+        expanded.push((file.clone(), contents));
+    }
+
+    for (file, contents) in expanded {
+        let n_keys =
+            collector.add_expanded_keys(expand::expanded_keys(&file, &contents, interner)?);
+        tracing::info!(file = %file.display(), keys = n_keys, "expanded code collected");
+    }
+
+    Ok(())
+}
+
+/// Checks `project`, whose files are read from `source`, with `config`.
+///
+/// Fails if the locale file or a Rust file cannot be read or parsed.
+fn check_project(
+    project: &Project,
+    source: &Source,
+    config: &Config,
+    options: CheckOptions,
+    timings: &mut Timings,
+) -> Result<ProjectCheck, Error> {
+    let mut locale_file = String::new();
+    source.read_to_string(project.locale_file(), &mut locale_file)?;
+
+    let mut interner = Interner::new();
+
+    let mut localized_texts = timings.time("YAML parse", || {
+        let contents: Yaml = from_str(&locale_file).map_err(|error| Error::Yaml {
+            path: project.locale_file().to_path_buf(),
+            error,
+        })?;
+        LocalizedTexts::new(
+            contents,
+            &mut interner,
+            &config.languages.aliases,
+            &config.variants,
         )
-    });
+        .map_err(|message| Error::LocaleFile {
+            path: project.locale_file().to_path_buf(),
+            message,
+        })
+    })?;
 
-    let contents: Yaml = from_reader(&locale_file).unwrap();
-    let localized_texts = LocalizedTexts::new(contents);
+    let is_ignored_key = |key: &str| {
+        let base = config.variants.split(key).0;
+        config
+            .rules
+            .ignore_keys
+            .iter()
+            .any(|ignored| ignored == key || ignored == base)
+    };
+    localized_texts.texts.retain(|key, _| !is_ignored_key(key));
 
-    let rust_files_to_check = cli.rust_src_to_check();
-    let mut collector = LocaleKeyCollector::new();
-    collector.collect(&rust_files_to_check);
+    // With stdin, only the rules about the buffer are relevant
+    let buffer_only = matches!(source, Source::Stdin(_));
+    let mut checker = Checker::with_selection(config.rules.selection())?;
+    // Only the rules about hard-coded strings need the string literals passed
+    // to functions and macros, which are most of the string literals
+    let needs_sink_literals = [HardCodedStrings::name(), ErrorMessages::name()]
+        .into_iter()
+        .any(|name| checker.is_enabled(name, config.rules.runs_by_default(name)));
+    let mut collector =
+        LocaleKeyCollector::new(&config.scan.macros).with_sink_literals(needs_sink_literals);
+    let mut n_rust_files = 0;
+    if !options.locale_only {
+        let rust_files_to_check = timings.time("walkdir", || {
+            project.rust_src_to_check(source, &config.scan)
+        })?;
+        n_rust_files = rust_files_to_check.len();
+        let progress = progress::new(rust_files_to_check.len(), "Scanning", options.show_progress);
+        let collect_start = Instant::now();
+        collector.collect(
+            &rust_files_to_check,
+            source,
+            config.scan.max_file_size,
+            &mut interner,
+            timings,
+            &progress,
+        )?;
+        timings.record_phase("collect", collect_start.elapsed());
+        progress.finish_and_clear();
 
-    let mut checker = Checker::new();
-    checker.register_rule(MissingTranslations);
-    checker.register_rule(KeyEngMatches);
-    checker.register_rule(UseOfKeysDoNotExist);
+        // Expanded code is of the whole crate rather than the buffer
+        if !buffer_only {
+            let expand_start = Instant::now();
+            collect_expanded_keys(project, config, &mut collector, &mut interner)?;
+            timings.record_phase("expand", expand_start.elapsed());
+        }
+    }
+    collector.ignore_keys(is_ignored_key);
+    // Before any rule runs, an empty scan would report every key as unused
+    if !options.locale_only
+        && !options.allow_empty
+        && (n_rust_files == 0 || collector.locale_keys().is_empty())
+    {
+        return Err(Error::EmptyScan {
+            paths: project.rust_src().to_vec(),
+            n_rust_files,
+        });
+    }
 
-    checker.check(&localized_texts, collector.locale_keys());
+    // Rules that only need the locale file, which are not about the buffer
+    if !buffer_only {
+        checker.register_rule(MissingTranslations::new(config.languages.tiers.clone()));
+        checker.register_rule(PluralCategories::new(
+            config.languages.tiers.clone(),
+            config.placeholders.syntax,
+        ));
+        checker.register_rule(KeyEngMatches::new(
+            config.placeholders.syntax,
+            config.rules.key_eng_matches.clone(),
+            config.variants.clone(),
+        ));
+        checker.register_rule(PercentBraceInKeys);
+        checker.register_rule(AmbiguousBraces);
+        checker.register_rule(RawBracesInEnglish::new(config.placeholders.syntax));
+        checker.register_rule(PlaceholderConsistency::new(config.placeholders.syntax));
+        checker.register_rule(TooManyPlaceholders::new(
+            config.rules.too_many_placeholders.max,
+        ));
+        checker.register_rule(QuotingStyle::new(
+            config.placeholders.syntax,
+            &config.rules.quoting_style.languages,
+        )?);
+        checker.register_rule(MarkupParity);
+        checker.register_rule(StubTranslations::new(
+            &config.rules.stub_translations.patterns,
+        )?);
+        checker.register_rule(PseudoText::new(&config.rules.pseudo_text.patterns)?);
+        checker.register_rule(SuspiciousWhitespace::new(
+            &config.rules.suspicious_whitespace.space_before_punctuation,
+        ));
+        checker.register_rule(TrailingNewline);
+        if let Some(glossary_file) = &config.rules.glossary.file {
+            if checker.is_enabled(GlossaryTerms::name(), true) {
+                checker.register_rule(GlossaryTerms::new(&Glossary::load(glossary_file)?));
+            }
+        }
+        checker.register_rule(NumbersAndUnits);
+        checker.register_rule(UrlsPreserved);
+        checker.register_rule(EmojiPolicy::new(config.rules.emoji_policy.mode));
+        checker.register_rule(BidiControlCharacters);
+        checker.register_rule(AnsiEscapeSequences);
+        checker.register_rule(UnicodeNormalization::new(
+            config.rules.unicode_normalization.form,
+        ));
+    }
+    // Rules that need the Rust files
+    if !options.locale_only {
+        checker.register_rule(UseOfKeysDoNotExist::new(config.variants.clone()));
+        checker.register_rule(ConcatenatedTranslations);
+        checker.register_rule(WordsAroundTranslations);
+        if checker.is_enabled(
+            HardCodedStrings::name(),
+            config.rules.runs_by_default(HardCodedStrings::name()),
+        ) {
+            checker.register_rule(HardCodedStrings::new(&config.rules.hard_coded_strings)?);
+        }
+        if checker.is_enabled(
+            ErrorMessages::name(),
+            config.rules.runs_by_default(ErrorMessages::name()),
+        ) {
+            checker.register_rule(ErrorMessages::new(&config.rules.error_messages));
+        }
+        checker.register_rule(UseOfLocalesDoNotExist::new(
+            config.languages.aliases.clone(),
+        ));
+        // Every key but the ones of the buffer would be reported
+        if !buffer_only {
+            checker.register_rule(UnusedLocaleKeys::new(config.variants.clone()));
+        }
+    }
 
-    checker.report_to_user();
+    let progress = progress::new(checker.n_rules(), "Checking", options.show_progress);
+    let ctx = CheckContext {
+        localized_texts: &localized_texts,
+        locale_keys: collector.locale_keys(),
+        set_locale_calls: collector.set_locale_calls(),
+        concatenations: collector.concatenations(),
+        sink_literals: collector.sink_literals(),
+    };
+    checker.check(&ctx, timings, &progress);
+    progress.finish_and_clear();
 
-    if checker.has_error() {
-        std::process::exit(EXIT_CODE_ON_ERROR);
+    Ok(ProjectCheck {
+        localized_texts,
+        collector,
+        checker,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::ffi::OsStr;
+
+    /// Runs `--fix-unused`, along with the options `args`, on a project whose
+    /// locale file is `locale_file` and Rust files are `rust_files`, returns
+    /// the fixed locale file.
+    fn fix_unused(
+        locale_file: &str,
+        rust_files: &[(&str, &[u8])],
+        args: &[&str],
+    ) -> Result<String, Error> {
+        let dir = tempfile::tempdir().unwrap();
+        let locale_path = dir.path().join("app.yml");
+        std::fs::write(&locale_path, locale_file).unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        for (name, contents) in rust_files {
+            std::fs::write(src.join(name), contents).unwrap();
+        }
+
+        let cli = Cli::parse_from(
+            ["checker", "--fix-unused"]
+                .iter()
+                .chain(args)
+                .map(OsStr::new)
+                .chain([locale_path.as_os_str(), src.as_os_str()]),
+        );
+        let config = cli.config().unwrap();
+        let project = &cli.projects(&config.project)[0];
+        let ProjectCheck {
+            localized_texts,
+            collector,
+            ..
+        } = check_project(
+            project,
+            &Source::WorkTree,
+            &config,
+            CheckOptions {
+                locale_only: false,
+                allow_empty: cli.allow_empty(),
+                show_progress: false,
+            },
+            &mut Timings::new(),
+        )?;
+        fix_locale_file(
+            &cli,
+            project,
+            &Source::WorkTree,
+            &localized_texts,
+            &collector,
+            &config,
+        )?;
+
+        Ok(std::fs::read_to_string(&locale_path).unwrap())
+    }
+
+    const LOCALE_FILE: &str = r#"_version: 2
+Used in println:
+  en: Used in println
+Used in format:
+  en: Used in format
+Unused:
+  en: Unused
+"#;
+
+    #[test]
+    fn test_fix_unused_keeps_keys_used_in_format_macros() {
+        let main_rs = br#"fn main() {
+    println!("{}", t!("Used in println"));
+    let s = format!("{}", t!("Used in format"));
+}
+"#;
+        assert_eq!(
+            fix_unused(LOCALE_FILE, &[("main.rs", main_rs)], &[]).unwrap(),
+            "_version: 2\nUsed in println:\n  en: Used in println\nUsed in format:\n  en: Used in format\n"
+        );
+    }
+
+    #[test]
+    fn test_fix_unused_keeps_escaped_and_raw_keys() {
+        let locale_file = r#"_version: 2
+'Say "hi"':
+  en: Say "hi"
+Raw:
+  en: Raw
+Unused:
+  en: Unused
+"#;
+        let main_rs = br##"fn main() {
+    println!("{}", t!("Say \"hi\""));
+    println!("{}", t!(r"Raw"));
+}
+"##;
+        assert_eq!(
+            fix_unused(locale_file, &[("main.rs", main_rs)], &[]).unwrap(),
+            "_version: 2\n'Say \"hi\"':\n  en: Say \"hi\"\nRaw:\n  en: Raw\n"
+        );
+    }
+
+    #[test]
+    fn test_fix_unused_refuses_with_skipped_files() {
+        let main_rs = br#"fn main() {
+    println!("{}", t!("Used in println"));
+}
+"#;
+        let error = fix_unused(
+            LOCALE_FILE,
+            &[("main.rs", main_rs), ("blob.rs", b"fn f() {\0")],
+            &[],
+        )
+        .unwrap_err();
+        assert!(matches!(&error, Error::Usage(message) if message.ends_with("blob.rs")));
+    }
+
+    #[test]
+    fn test_empty_scan_fails_before_fixing() {
+        let error = fix_unused(LOCALE_FILE, &[], &[]).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::EmptyScan {
+                n_rust_files: 0,
+                ..
+            }
+        ));
+
+        let error = fix_unused(LOCALE_FILE, &[("main.rs", b"fn main() {}\n")], &[]).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::EmptyScan {
+                n_rust_files: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_fix_unused_refuses_with_empty_scan() {
+        for rust_files in [&[][..], &[("main.rs", &b"fn main() {}\n"[..])]] {
+            let error = fix_unused(LOCALE_FILE, rust_files, &["--allow-empty"]).unwrap_err();
+            assert!(matches!(&error, Error::Usage(message) if message.starts_with("--fix-unused")));
+        }
+    }
+
+    #[test]
+    fn test_fix_unused_refuses_with_disabled_rule() {
+        let main_rs = br#"fn main() {
+    println!("{}", t!("Used in println"));
+}
+"#;
+        let error = fix_unused(
+            LOCALE_FILE,
+            &[("main.rs", main_rs)],
+            &["--disable-rule", "UnusedLocaleKeys"],
+        )
+        .unwrap_err();
+        assert!(matches!(&error, Error::Usage(message) if message.contains("UnusedLocaleKeys")));
     }
 }
@@ -0,0 +1,229 @@
+//! This file contains type [`Metrics`], which renders the check results in the
+//! Prometheus/OpenMetrics text format.
+
+use crate::checker::Checker;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use crate::rules::Severity;
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// A metric family: `(Help, Samples)`, where a sample is `(Labels, Value)`.
+type Family = (&'static str, Vec<(Vec<(&'static str, String)>, f64)>);
+
+/// Metrics of one or more projects.
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    /// `MetricName => Family`
+    families: IndexMap<&'static str, Family>,
+}
+
+impl Metrics {
+    /// Creates an empty [`Metrics`].
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the metrics of a project.
+    ///
+    /// If `project` is `Some`, samples will be labeled with it.
+    pub(crate) fn record_project(
+        &mut self,
+        project: Option<&Path>,
+        localized_texts: &LocalizedTexts,
+        locale_keys: &[LocaleKey],
+        checker: &Checker,
+        duration: Duration,
+    ) {
+        let base_labels = match project {
+            Some(project) => vec![("project", project.display().to_string())],
+            None => Vec::new(),
+        };
+        let labels = |extra: Option<(&'static str, String)>| {
+            let mut labels = base_labels.clone();
+            labels.extend(extra);
+            labels
+        };
+
+        for lang in localized_texts.languages() {
+            let n_missing = localized_texts
+                .texts
+                .values()
+                .filter(|t| match &*lang {
+                    "en" => t.en.is_none(),
+                    _ => !t.others.contains_key(&lang),
+                })
+                .count();
+            self.record(
+                "i18n_missing_translations",
+                "Number of keys that have no translation in the language.",
+                labels(Some(("lang", lang.to_string()))),
+                n_missing as f64,
+            );
+        }
+
+        let used_keys = locale_keys
+            .iter()
+            .map(|locale_key| &*locale_key.key)
+            .collect::<HashSet<_>>();
+        let n_unused = localized_texts
+            .texts
+            .keys()
+            .filter(|key| !used_keys.contains(&***key))
+            .count();
+        self.record(
+            "i18n_unused_keys",
+            "Number of keys in the locale file that are not used by any t!() invocation.",
+            labels(None),
+            n_unused as f64,
+        );
+
+        for (severity, name) in [
+            (Severity::Error, "error"),
+            (Severity::Warning, "warning"),
+            (Severity::Info, "info"),
+        ] {
+            let n = checker
+                .errors()
+                .filter(|(_, diagnostic)| diagnostic.severity == severity)
+                .count();
+            self.record(
+                "i18n_diagnostics",
+                "Number of diagnostics reported by the rules.",
+                labels(Some(("severity", name.to_string()))),
+                n as f64,
+            );
+        }
+
+        self.record(
+            "i18n_check_duration_seconds",
+            "Time spent on checking the project.",
+            labels(None),
+            duration.as_secs_f64(),
+        );
+    }
+
+    /// Records a sample.
+    fn record(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: Vec<(&'static str, String)>,
+        value: f64,
+    ) {
+        self.families
+            .entry(name)
+            .or_insert_with(|| (help, Vec::new()))
+            .1
+            .push((labels, value));
+    }
+
+    /// Renders the metrics in the OpenMetrics text format.
+    pub(crate) fn render(&self) -> String {
+        let mut ret = String::new();
+
+        for (name, (help, samples)) in self.families.iter() {
+            writeln!(ret, "# HELP {} {}", name, help).unwrap();
+            writeln!(ret, "# TYPE {} gauge", name).unwrap();
+            for (labels, value) in samples {
+                ret.push_str(name);
+                if !labels.is_empty() {
+                    let labels = labels
+                        .iter()
+                        .map(|(label, value)| format!("{}=\"{}\"", label, escape_label(value)))
+                        .collect::<Vec<_>>();
+                    write!(ret, "{{{}}}", labels.join(",")).unwrap();
+                }
+                writeln!(ret, " {}", value).unwrap();
+            }
+        }
+        ret.push_str("# EOF\n");
+
+        ret
+    }
+}
+
+/// Escapes a label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::Translations;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_render() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Used".into(),
+                    Translations {
+                        en: Some("Used".into()),
+                        others: IndexMap::from([("de".into(), "Benutzt".into())]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Unused".into(),
+                    Translations {
+                        en: Some("Unused".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let locale_keys = vec![LocaleKey {
+            key: "Used".into(),
+            file: Arc::from(Path::new("foo.rs")),
+            line: 1,
+            column: 0,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
+        }];
+
+        let mut metrics = Metrics::new();
+        metrics.record_project(
+            Some(Path::new("topgrade")),
+            &localized_texts,
+            &locale_keys,
+            &Checker::new(),
+            Duration::from_millis(1500),
+        );
+
+        assert_eq!(
+            metrics.render(),
+            r#"# HELP i18n_missing_translations Number of keys that have no translation in the language.
+# TYPE i18n_missing_translations gauge
+i18n_missing_translations{project="topgrade",lang="en"} 0
+i18n_missing_translations{project="topgrade",lang="de"} 1
+# HELP i18n_unused_keys Number of keys in the locale file that are not used by any t!() invocation.
+# TYPE i18n_unused_keys gauge
+i18n_unused_keys{project="topgrade"} 1
+# HELP i18n_diagnostics Number of diagnostics reported by the rules.
+# TYPE i18n_diagnostics gauge
+i18n_diagnostics{project="topgrade",severity="error"} 0
+i18n_diagnostics{project="topgrade",severity="warning"} 0
+i18n_diagnostics{project="topgrade",severity="info"} 0
+# HELP i18n_check_duration_seconds Time spent on checking the project.
+# TYPE i18n_check_duration_seconds gauge
+i18n_check_duration_seconds{project="topgrade"} 1.5
+# EOF
+"#
+        );
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+}
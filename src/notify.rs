@@ -0,0 +1,295 @@
+//! This file contains the webhook notifications, which post a summary of a
+//! run, i.e., the error counts, the coverage changes and a link to the report,
+//! so that translation coordinators hear about regressions without watching CI.
+//!
+//! The summary is posted with `curl`, as JSON in the format of the webhook.
+
+use crate::config::LanguageMaintainers;
+use crate::history::{Regression, Run, TrendPoint};
+use serde::Serialize;
+use serde_json::{json, Value as Json};
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+/// Payload format of the webhook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum WebhookFormat {
+    /// The summary as a JSON object, for custom integrations.
+    #[default]
+    Json,
+    /// A Slack incoming webhook, `{"text": ...}`.
+    Slack,
+    /// A Matrix webhook bridge, `{"msgtype": "m.text", "body": ...}`.
+    Matrix,
+}
+
+/// Translation coverage of a language.
+#[derive(Debug, PartialEq, Serialize)]
+struct Coverage {
+    /// The language.
+    lang: String,
+    /// Coverage, between 0 and 1, `None` if the locale file has no key.
+    coverage: Option<f64>,
+    /// Change since the last recorded run of another commit, in percentage
+    /// points, `None` if unknown.
+    change: Option<f64>,
+    /// Maintainers of the language.
+    maintainers: Vec<String>,
+}
+
+/// Summary of a run.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct Summary {
+    /// False if the check failed.
+    passed: bool,
+    /// Number of errors.
+    n_errors: usize,
+    /// Number of warnings.
+    n_warnings: usize,
+    /// Coverage of every language.
+    coverage: Vec<Coverage>,
+    /// Regressions found by the trend gates.
+    regressions: Vec<Regression>,
+    /// Maintainers of the regressed languages, to mention.
+    mentions: Vec<String>,
+    /// Where the full report can be found, e.g., the CI run.
+    report_url: Option<String>,
+}
+
+impl Summary {
+    /// Summarizes `run`, `last_point` returns the point of a language in the
+    /// last recorded run, if known, to compute the coverage changes, the
+    /// `maintainers` of the regressed languages are mentioned.
+    pub(crate) fn new(
+        run: &Run,
+        last_point: impl Fn(&str) -> Option<TrendPoint>,
+        passed: bool,
+        regressions: Vec<Regression>,
+        maintainers: &LanguageMaintainers,
+        report_url: Option<String>,
+    ) -> Self {
+        let coverage = run
+            .languages()
+            .into_iter()
+            .map(|lang| {
+                let (coverage, _) = run.point(lang);
+                let change = match (last_point(lang).and_then(|point| point.coverage), coverage) {
+                    (Some(prev), Some(cur)) => Some((cur - prev) * 100.0),
+                    _ => None,
+                };
+                Coverage {
+                    lang: lang.to_string(),
+                    coverage,
+                    change,
+                    maintainers: maintainers.of(lang).to_vec(),
+                }
+            })
+            .collect();
+        let mentions = maintainers.of_any(regressions.iter().map(|regression| &*regression.lang));
+
+        Self {
+            passed,
+            n_errors: run.n_errors(),
+            n_warnings: run.n_warnings(),
+            coverage,
+            regressions,
+            mentions,
+            report_url,
+        }
+    }
+
+    /// Renders the summary as a message for humans.
+    fn text(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "topgrade-i18n-check {}: {} errors, {} warnings",
+            if self.passed { "passed" } else { "failed" },
+            self.n_errors,
+            self.n_warnings
+        )
+        .unwrap();
+        let coverage = self
+            .coverage
+            .iter()
+            .map(|coverage| {
+                let mut s = format!("{} ", coverage.lang);
+                match coverage.coverage {
+                    Some(ratio) => write!(s, "{:.1}%", ratio * 100.0).unwrap(),
+                    None => s.push('-'),
+                }
+                if let Some(change) = coverage.change {
+                    write!(s, " ({:+.1})", change).unwrap();
+                }
+                s
+            })
+            .collect::<Vec<_>>();
+        if !coverage.is_empty() {
+            writeln!(out, "Coverage: {}", coverage.join(", ")).unwrap();
+        }
+        if !self.regressions.is_empty() {
+            writeln!(out, "Regressions:").unwrap();
+            for regression in self.regressions.iter() {
+                writeln!(out, "- {}", regression).unwrap();
+            }
+        }
+        if !self.mentions.is_empty() {
+            writeln!(out, "cc {}", self.mentions.join(" ")).unwrap();
+        }
+        if let Some(report_url) = &self.report_url {
+            writeln!(out, "Report: {}", report_url).unwrap();
+        }
+
+        out.trim_end().to_string()
+    }
+
+    /// Returns the payload to post in `format`.
+    pub(crate) fn payload(&self, format: WebhookFormat) -> Json {
+        match format {
+            WebhookFormat::Json => {
+                serde_json::to_value(self).expect("summary should be serializable")
+            }
+            WebhookFormat::Slack => json!({ "text": self.text() }),
+            WebhookFormat::Matrix => json!({ "msgtype": "m.text", "body": self.text() }),
+        }
+    }
+}
+
+/// Returns the URL of the CI run, which has the full report, if this runs on
+/// GitHub Actions, `vars` should be [`std::env::vars()`] except in tests.
+pub(crate) fn ci_run_url(vars: impl IntoIterator<Item = (String, String)>) -> Option<String> {
+    let (mut server, mut repository, mut run_id) = (None, None, None);
+    for (name, value) in vars {
+        match name.as_str() {
+            "GITHUB_SERVER_URL" => server = Some(value),
+            "GITHUB_REPOSITORY" => repository = Some(value),
+            "GITHUB_RUN_ID" => run_id = Some(value),
+            _ => {}
+        }
+    }
+
+    Some(format!(
+        "{}/{}/actions/runs/{}",
+        server?, repository?, run_id?
+    ))
+}
+
+/// Posts `payload` to the webhook at `url`, returns why it failed, if it did.
+pub(crate) fn post(url: &str, payload: &Json) -> Result<(), String> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--max-time",
+            "30",
+            "--header",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            "--output",
+            "/dev/null",
+            "--",
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to execute curl due to error {:?}", e))?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(payload.to_string().as_bytes())
+        .map_err(|e| format!("failed to write to curl due to error {:?}", e))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("failed to wait for curl due to error {:?}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> Summary {
+        Summary {
+            passed: false,
+            n_errors: 3,
+            n_warnings: 1,
+            coverage: vec![
+                Coverage {
+                    lang: "de".into(),
+                    coverage: Some(0.8),
+                    change: Some(-2.5),
+                    maintainers: vec!["@alice".into()],
+                },
+                Coverage {
+                    lang: "fr".into(),
+                    coverage: Some(1.0),
+                    change: None,
+                    maintainers: Vec::new(),
+                },
+            ],
+            regressions: vec![Regression {
+                lang: "de".into(),
+                message: "coverage of `de` dropped".into(),
+            }],
+            mentions: vec!["@alice".into()],
+            report_url: Some("https://ci/1".into()),
+        }
+    }
+
+    #[test]
+    fn test_payload() {
+        let text = "topgrade-i18n-check failed: 3 errors, 1 warnings
+Coverage: de 80.0% (-2.5), fr 100.0%
+Regressions:
+- coverage of `de` dropped
+cc @alice
+Report: https://ci/1";
+        assert_eq!(
+            summary().payload(WebhookFormat::Slack),
+            json!({ "text": text })
+        );
+        assert_eq!(
+            summary().payload(WebhookFormat::Matrix),
+            json!({ "msgtype": "m.text", "body": text })
+        );
+        assert_eq!(
+            summary().payload(WebhookFormat::Json),
+            json!({
+                "passed": false,
+                "n_errors": 3,
+                "n_warnings": 1,
+                "coverage": [
+                    { "lang": "de", "coverage": 0.8, "change": -2.5, "maintainers": ["@alice"] },
+                    { "lang": "fr", "coverage": 1.0, "change": null, "maintainers": [] },
+                ],
+                "regressions": [{ "lang": "de", "message": "coverage of `de` dropped" }],
+                "mentions": ["@alice"],
+                "report_url": "https://ci/1",
+            })
+        );
+    }
+
+    #[test]
+    fn test_ci_run_url() {
+        assert_eq!(ci_run_url(Vec::new()), None);
+        let vars = [
+            ("GITHUB_SERVER_URL", "https://github.com"),
+            ("GITHUB_REPOSITORY", "topgrade-rs/topgrade"),
+            ("GITHUB_RUN_ID", "42"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        assert_eq!(
+            ci_run_url(vars).as_deref(),
+            Some("https://github.com/topgrade-rs/topgrade/actions/runs/42")
+        );
+    }
+}
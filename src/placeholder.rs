@@ -0,0 +1,138 @@
+//! This file contains helpers to extract the placeholders (the named or
+//! positional arguments, e.g., `%{app}`) from translations.
+//!
+//! A placeholder in a translation is the start of the [`PlaceholderSyntax`],
+//! e.g., `%{`, followed by a name valid in that syntax and `}`:
+//!
+//! ```text
+//! placeholder = start name "}"
+//! start       = "%{" | "{"      ; depends on the syntax
+//! name        = 1*(any char except "{" and "}")   ; `{name}` and `%{name}`
+//!             | 1*DIGIT                           ; `{0}`
+//! ```
+//!
+//! Anything else, e.g., an unterminated placeholder, is text. Unlike keys, see
+//! [`crate::key_parser`], translations have no escapes.
+//!
+//! ```
+//! use topgrade_i18n_locale_checker::placeholder::{placeholders_of_translation, PlaceholderSyntax};
+//!
+//! assert_eq!(
+//!     placeholders_of_translation("Restarting %{app}", PlaceholderSyntax::PercentBrace),
+//!     ["app"]
+//! );
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// How placeholders are written in translations, configured with an example
+/// placeholder, e.g., `syntax = "{name}"`.
+///
+/// Placeholders are always written as `{name}` in keys.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum PlaceholderSyntax {
+    /// `%{name}`, the rust-i18n default.
+    #[default]
+    #[serde(rename = "%{name}")]
+    PercentBrace,
+    /// `{name}`
+    #[serde(rename = "{name}")]
+    Brace,
+    /// `{0}`, positional arguments.
+    #[serde(rename = "{0}")]
+    Positional,
+}
+
+impl PlaceholderSyntax {
+    /// Start of a placeholder.
+    fn start(self) -> &'static str {
+        match self {
+            PlaceholderSyntax::PercentBrace => "%{",
+            PlaceholderSyntax::Brace | PlaceholderSyntax::Positional => "{",
+        }
+    }
+
+    /// Returns true if `name` is a valid placeholder name in this syntax.
+    fn is_valid_name(self, name: &str) -> bool {
+        match self {
+            PlaceholderSyntax::PercentBrace | PlaceholderSyntax::Brace => {
+                !name.is_empty() && !name.contains('{')
+            }
+            PlaceholderSyntax::Positional => {
+                !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit())
+            }
+        }
+    }
+
+    /// Renders the placeholder `name` in this syntax, e.g., `app` is rendered
+    /// as `%{app}` in [`PlaceholderSyntax::PercentBrace`].
+    pub fn format(self, name: &str) -> String {
+        format!("{}{}{}", self.start(), name, PLACEHOLDER_END)
+    }
+}
+
+/// End of a placeholder.
+const PLACEHOLDER_END: char = '}';
+
+/// Returns the names of the placeholders in `translation` written in `syntax`,
+/// in the order they appear, duplicates are kept.
+///
+/// ```text
+/// "Restarting %{app} in %{dir}" => ["app", "dir"]
+/// ```
+///
+/// An unterminated placeholder, or one whose name is invalid in `syntax`
+/// (e.g., `{app}` in [`PlaceholderSyntax::Positional`]), is not a placeholder.
+pub fn placeholders_of_translation(translation: &str, syntax: PlaceholderSyntax) -> Vec<&str> {
+    let mut placeholders = Vec::new();
+    let mut rest = translation;
+
+    while let Some(start) = rest.find(syntax.start()) {
+        rest = &rest[start + syntax.start().len()..];
+        let Some(end) = rest.find(PLACEHOLDER_END) else {
+            break;
+        };
+        let name = &rest[..end];
+        if syntax.is_valid_name(name) {
+            placeholders.push(name);
+            rest = &rest[end + 1..];
+        }
+    }
+
+    placeholders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders_of_translation() {
+        let syntax = PlaceholderSyntax::PercentBrace;
+        assert_eq!(
+            placeholders_of_translation("Restarting %{app} in %{dir}, %{app}", syntax),
+            ["app", "dir", "app"]
+        );
+        assert!(placeholders_of_translation("Restarting {app}", syntax).is_empty());
+        assert_eq!(
+            placeholders_of_translation("%{a}%{b} %{unterminated", syntax),
+            ["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_placeholder_syntaxes() {
+        assert_eq!(
+            placeholders_of_translation("Restarting {app} in {{dir}", PlaceholderSyntax::Brace),
+            ["app", "dir"]
+        );
+        assert_eq!(
+            placeholders_of_translation("{1} {app} {0}", PlaceholderSyntax::Positional),
+            ["1", "0"]
+        );
+
+        assert_eq!(PlaceholderSyntax::PercentBrace.format("app"), "%{app}");
+        assert_eq!(PlaceholderSyntax::Brace.format("app"), "{app}");
+        assert_eq!(PlaceholderSyntax::Positional.format("0"), "{0}");
+    }
+}
@@ -0,0 +1,204 @@
+//! This file contains the porcelain output format, which is meant to be
+//! consumed by shell scripts.
+//!
+//! Every diagnostic is printed as a record, which consists of the following
+//! fields:
+//!
+//! ```text
+//! <severity> <rule> <project> <languages> <file> <line> <column> <key> <message>
+//! ```
+//!
+//! * `severity`: `error`, `warning` or `info`
+//! * `project`: root of the project, empty if it is not specified via `--project`
+//! * `languages`: affected languages separated by `,`, can be empty
+//! * `file`, `line`, `column`: where the diagnostic is found in the Rust source
+//!   files, line and column count from 1, all empty for the diagnostics about
+//!   the locale file
+//! * `key`: the locale key, can be empty if the location is enough, e.g., for a
+//!   hard-coded string
+//! * `message`: can be empty
+//!
+//! By default, fields are separated by tabs and records are terminated by
+//! `\n`, backslashes, tabs, carriage returns and newlines in the fields are
+//! escaped as `\\`, `\t`, `\r` and `\n`. With `-z`, fields are printed
+//! verbatim and every field, including the last one of a record, is terminated
+//! by NUL, a record is always nine fields.
+//!
+//! This format is stable: existing fields will not be changed or reordered, new
+//! fields, if any, will only be appended to the end of a record.
+
+use crate::checker::Checker;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Writes the diagnostics found by `checker` in the porcelain format.
+///
+/// Records are sorted by severity, then by rule name, so that the output is
+/// deterministic.
+pub(crate) fn write_diagnostics(
+    out: &mut impl Write,
+    project: Option<&Path>,
+    checker: &Checker,
+    nul_terminated: bool,
+) -> io::Result<()> {
+    let mut diagnostics = checker.errors().collect::<Vec<_>>();
+    diagnostics
+        .sort_by(|(rule_a, a), (rule_b, b)| (a.severity, rule_a, a).cmp(&(b.severity, rule_b, b)));
+
+    let project = project
+        .map(|root| root.to_string_lossy())
+        .unwrap_or_default();
+    let (separator, terminator) = if nul_terminated {
+        ("\0", b'\0')
+    } else {
+        ("\t", b'\n')
+    };
+    let field = |value: &'_ str| -> String {
+        if nul_terminated {
+            value.to_string()
+        } else {
+            escape(value).into_owned()
+        }
+    };
+
+    for (rule, diagnostic) in diagnostics {
+        let langs = diagnostic
+            .langs
+            .iter()
+            .map(|lang| &**lang)
+            .collect::<Vec<_>>()
+            .join(",");
+        let (file, line, column) = diagnostic
+            .location
+            .as_ref()
+            .map(|location| {
+                (
+                    location.file.to_string_lossy(),
+                    location.line.to_string(),
+                    (location.column + 1).to_string(),
+                )
+            })
+            .unwrap_or_default();
+        let record = [
+            diagnostic.severity.as_str(),
+            rule,
+            &project,
+            &langs,
+            &file,
+            &line,
+            &column,
+            &diagnostic.key,
+            diagnostic.error_msg.as_deref().unwrap_or_default(),
+        ]
+        .map(field)
+        .join(separator);
+
+        out.write_all(record.as_bytes())?;
+        out.write_all(&[terminator])?;
+    }
+
+    Ok(())
+}
+
+/// Escapes backslashes, tabs, carriage returns and newlines in `value`.
+fn escape(value: &str) -> Cow<'_, str> {
+    if !value.contains(['\\', '\t', '\r', '\n']) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 2);
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{KeyEngMatchesConfig, PlaceholderSyntax};
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use crate::locale_key_collector::LocaleKey;
+    use crate::rules::key_and_eng_matches::KeyEngMatches;
+    use crate::rules::use_of_keys_do_not_exist::UseOfKeysDoNotExist;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indexmap::IndexMap;
+    use indicatif::ProgressBar;
+
+    fn checker() -> Checker {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Tab\there".into(),
+                    Translations {
+                        en: Some("mismatch".into()),
+                        ..Default::default()
+                    },
+                ),
+                ("Restarting".into(), Translations::default()),
+            ]),
+        };
+        let locale_keys = [LocaleKey {
+            key: "Say\r\n".into(),
+            file: Path::new("src/main.rs").into(),
+            line: 3,
+            column: 4,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
+        }];
+        let mut checker = Checker::new();
+        checker.register_rule(KeyEngMatches::new(
+            PlaceholderSyntax::default(),
+            KeyEngMatchesConfig::default(),
+            Default::default(),
+        ));
+        checker.register_rule(UseOfKeysDoNotExist::new(Default::default()));
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+        checker.check(&ctx, &mut Timings::new(), &ProgressBar::hidden());
+        checker
+    }
+
+    #[test]
+    fn test_porcelain() {
+        let mut out = Vec::new();
+        write_diagnostics(&mut out, None, &checker(), false).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "error\tKeyEngMatches\t\ten\t\t\t\tRestarting\tMissing English translation\n\
+             error\tKeyEngMatches\t\ten\t\t\t\tTab\\there\t\n\
+             error\tUseOfKeysDoNotExist\t\t\tsrc/main.rs\t3\t5\tSay\\r\\n\t\n"
+        );
+
+        let mut out = Vec::new();
+        write_diagnostics(&mut out, Some(Path::new("foo")), &checker(), true).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "error\0KeyEngMatches\0foo\0en\0\0\0\0Restarting\0Missing English translation\0\
+             error\0KeyEngMatches\0foo\0en\0\0\0\0Tab\there\0\0\
+             error\0UseOfKeysDoNotExist\0foo\0\0src/main.rs\x003\x005\0Say\r\n\0\0"
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("foo"), "foo");
+        assert_eq!(escape("a\tb\nc\\d"), "a\\tb\\nc\\\\d");
+        assert_eq!(escape("a\r\nb"), "a\\r\\nb");
+    }
+}
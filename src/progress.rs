@@ -0,0 +1,28 @@
+//! This file contains helpers to create progress bars, which are shown while
+//! scanning large source trees so that this tool won't look hung.
+
+use crate::logging;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Creates a progress bar of `len` steps, with `prefix` describing the phase.
+///
+/// Progress bars are drawn to stderr, they are hidden if `visible` is false,
+/// stderr is not a TTY, or stderr is used by JSON logs.
+pub(crate) fn new(len: usize, prefix: &'static str, visible: bool) -> ProgressBar {
+    let draw_target = if visible && !logging::is_json() {
+        // This target is hidden automatically if stderr is not a TTY.
+        ProgressDrawTarget::stderr()
+    } else {
+        ProgressDrawTarget::hidden()
+    };
+
+    let progress = ProgressBar::with_draw_target(Some(len as u64), draw_target);
+    progress.set_style(
+        ProgressStyle::with_template("{prefix:>10} [{bar:30}] {pos}/{len} {wide_msg}")
+            .expect("template should be valid")
+            .progress_chars("=> "),
+    );
+    progress.set_prefix(prefix);
+
+    progress
+}
@@ -0,0 +1,579 @@
+//! This file contains type [`Project`], a locale file along with the Rust files
+//! that use it.
+
+use crate::config::ScanConfig;
+use crate::error::Error;
+use crate::logging;
+use crate::source::Source;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Path to the locale file, relative to the project root.
+const PROJECT_LOCALE_FILE: &str = "locales/app.yml";
+/// Path to the Rust source directory, relative to the project root.
+const PROJECT_RUST_SRC: &str = "src";
+
+/// A project to check.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Project {
+    /// Project root, `None` if the project is specified by `--locale-file` and
+    /// `--rust-src-to-check` rather than `--project`.
+    root: Option<PathBuf>,
+    /// The path to the locale file.
+    locale_file: PathBuf,
+    /// Rust files to check, can be directories.
+    rust_src_to_check: Vec<PathBuf>,
+}
+
+impl Project {
+    /// Creates a project from the given locale file and Rust files.
+    pub(crate) fn new(locale_file: PathBuf, rust_src_to_check: Vec<PathBuf>) -> Self {
+        Self {
+            root: None,
+            locale_file,
+            rust_src_to_check,
+        }
+    }
+
+    /// Creates a project from its root directory, the locale file and Rust
+    /// files are expected to be `<root>/locales/app.yml` and `<root>/src`.
+    pub(crate) fn from_root(root: PathBuf) -> Self {
+        Self {
+            locale_file: root.join(PROJECT_LOCALE_FILE),
+            rust_src_to_check: vec![root.join(PROJECT_RUST_SRC)],
+            root: Some(root),
+        }
+    }
+
+    /// Accesses the project root.
+    pub(crate) fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Accesses the locale file.
+    pub(crate) fn locale_file(&self) -> &Path {
+        &self.locale_file
+    }
+
+    /// Accesses the Rust files to check as specified, directories are not
+    /// walked.
+    pub(crate) fn rust_src(&self) -> &[PathBuf] {
+        &self.rust_src_to_check
+    }
+
+    /// Returns the Rust files to check, see [`rust_files()`].
+    pub(crate) fn rust_src_to_check(
+        &self,
+        source: &Source,
+        scan: &ScanConfig,
+    ) -> Result<Vec<Cow<'_, Path>>, Error> {
+        rust_files(&self.rust_src_to_check, source, scan)
+    }
+}
+
+/// Detects the locale file of the project at `root`: `<root>/locales/app.yml`,
+/// or the single YAML file under `<root>/locales`.
+///
+/// Returns `None` if there is no such file, or there are multiple YAML files.
+pub(crate) fn detect_locale_file(root: &Path) -> Option<PathBuf> {
+    let locale_file = root.join(PROJECT_LOCALE_FILE);
+    if locale_file.is_file() {
+        return Some(locale_file);
+    }
+
+    let locale_dir = locale_file.parent().expect("it has a parent");
+    let mut yaml_files = std::fs::read_dir(locale_dir)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension == "yml" || extension == "yaml")
+        });
+    let yaml_file = yaml_files.next()?;
+    if yaml_files.next().is_some() {
+        return None;
+    }
+
+    Some(yaml_file)
+}
+
+/// Detects the Rust source directory of the project at `root`, `<root>/src`.
+///
+/// Returns `None` if it is not a directory.
+pub(crate) fn detect_rust_src(root: &Path) -> Option<PathBuf> {
+    let rust_src = root.join(PROJECT_RUST_SRC);
+
+    rust_src.is_dir().then_some(rust_src)
+}
+
+/// Flattens the input paths and returns it.
+///
+/// For directories, it will walk through the directory and get all the Rust
+/// files.
+///
+/// Symlinks will be silently ignored, unless `scan.follow_symlinks` is true, in
+/// which case links that lead to a directory already visited, e.g., a loop, are
+/// skipped with a warning.
+///
+/// Directories that `scan` ignores, e.g., `target`, are skipped unless they are
+/// in `paths`.
+///
+/// When `source` is a git revision, files are listed from that revision, when
+/// it is stdin, its buffer is the only file.
+///
+/// Fails with [`Error::TooDeep`] or [`Error::TooManyFiles`] if the limits in
+/// `scan` are exceeded, which usually means the paths are wrong.
+pub(crate) fn rust_files<'paths>(
+    paths: &'paths [PathBuf],
+    source: &Source,
+    scan: &ScanConfig,
+) -> Result<Vec<Cow<'paths, Path>>, Error> {
+    let (rust_files_to_check, skipped_links) = walk(paths, source, scan)?;
+    for link in skipped_links {
+        tracing::warn!(link = %link.display(), "symlink skipped");
+        if !logging::is_json() {
+            eprintln!(
+                "Warning: skipped symlink {}, it leads to a directory that has already been visited",
+                link.display()
+            );
+        }
+    }
+
+    Ok(rust_files_to_check)
+}
+
+/// Does the work of [`rust_files()`], returns the Rust files along with the
+/// symlinks skipped.
+fn walk<'paths>(
+    paths: &'paths [PathBuf],
+    source: &Source,
+    scan: &ScanConfig,
+) -> Result<(Vec<Cow<'paths, Path>>, Vec<PathBuf>), Error> {
+    if let Source::Stdin(buffer) = source {
+        return Ok((vec![Cow::Owned(buffer.path().to_path_buf())], Vec::new()));
+    }
+    if let Source::GitRev(git_rev) = source {
+        let files = git_rev.list_files(paths)?;
+        if files.len() > scan.max_files {
+            return Err(too_many_files(paths, scan));
+        }
+
+        let rust_files_to_check = files
+            .into_iter()
+            .filter(is_rust_file)
+            .filter(|file| !in_ignored_dir(file, paths, scan))
+            .map(Cow::Owned)
+            .collect();
+        return Ok((rust_files_to_check, Vec::new()));
+    }
+
+    let mut rust_files_to_check = Vec::with_capacity(paths.len());
+    // Links skipped by the walker threads, along with the ones reported by
+    // jwalk itself
+    let skipped_links = Arc::new(Mutex::new(Vec::new()));
+    // `(Device, Inode)` of the directories visited, to break symlink loops
+    let visited_dirs = Arc::new(Mutex::new(HashSet::new()));
+    let mut n_files = 0;
+
+    for entry_path in paths.iter() {
+        let entry_metadata = if scan.follow_symlinks {
+            std::fs::metadata(entry_path)
+        } else {
+            std::fs::symlink_metadata(entry_path)
+        }
+        .map_err(|error| Error::Read {
+            path: entry_path.clone(),
+            error,
+        })?;
+
+        if entry_metadata.is_file() {
+            n_files += 1;
+            if is_rust_file(entry_path) {
+                rust_files_to_check.push(Cow::Borrowed(entry_path.as_path()));
+            }
+        } else if entry_metadata.is_dir() {
+            let dir = entry_path;
+            // Directories are read in parallel, this prunes them on the walker
+            // threads before they are read
+            let process_read_dir = {
+                let scan = scan.clone();
+                let visited_dirs = Arc::clone(&visited_dirs);
+                let skipped_links = Arc::clone(&skipped_links);
+                move |_: Option<usize>,
+                      _: &Path,
+                      _: &mut (),
+                      children: &mut Vec<jwalk::Result<jwalk::DirEntry<((), ())>>>| {
+                    children.retain(|child| {
+                        let Ok(child) = child else {
+                            return true;
+                        };
+                        if !child.file_type().is_dir() {
+                            return true;
+                        }
+                        // The specified directory is walked even if it is
+                        // ignored
+                        if child.depth() > 0 && scan.is_ignored_dir(child.file_name()) {
+                            return false;
+                        }
+                        if !scan.follow_symlinks {
+                            return true;
+                        }
+
+                        let child_path = child.path();
+                        let id = std::fs::metadata(&child_path)
+                            .ok()
+                            .as_ref()
+                            .and_then(file_id);
+                        let first_visit = match id {
+                            Some(id) => visited_dirs
+                                .lock()
+                                .expect("lock should not be poisoned")
+                                .insert(id),
+                            None => true,
+                        };
+                        if !first_visit {
+                            skipped_links
+                                .lock()
+                                .expect("lock should not be poisoned")
+                                .push(child_path);
+                        }
+                        first_visit
+                    });
+                }
+            };
+            // One more level so that exceeding the limit can be noticed
+            let walk_dir = jwalk::WalkDir::new(dir)
+                .skip_hidden(false)
+                .max_depth(scan.max_depth + 1)
+                .follow_links(scan.follow_symlinks)
+                .process_read_dir(process_read_dir);
+
+            for res_entry in walk_dir {
+                let entry = match res_entry {
+                    Ok(entry) => entry,
+                    // jwalk detects the links to an ancestor itself
+                    Err(e) if e.loop_ancestor().is_some() => {
+                        skipped_links
+                            .lock()
+                            .expect("lock should not be poisoned")
+                            .extend(e.path().map(Path::to_path_buf));
+                        continue;
+                    }
+                    Err(e) => {
+                        return Err(Error::Read {
+                            path: e.path().map_or_else(|| dir.clone(), Path::to_path_buf),
+                            error: e.into(),
+                        })
+                    }
+                };
+
+                let entry_path = entry.path();
+                if entry.depth() > scan.max_depth {
+                    return Err(Error::TooDeep {
+                        path: entry_path,
+                        dir: dir.clone(),
+                        max_depth: scan.max_depth,
+                    });
+                }
+
+                if entry.file_type().is_file() {
+                    n_files += 1;
+                    if n_files > scan.max_files {
+                        return Err(too_many_files(paths, scan));
+                    }
+                    if is_rust_file(&entry_path) {
+                        rust_files_to_check.push(Cow::Owned(entry_path));
+                    }
+                }
+            }
+        }
+    }
+
+    let skipped_links =
+        std::mem::take(&mut *skipped_links.lock().expect("lock should not be poisoned"));
+    Ok((rust_files_to_check, skipped_links))
+}
+
+/// Returns true if `file`, listed under one of `paths`, is in a directory
+/// that `scan` ignores, the directories in `paths` themselves are not ignored.
+fn in_ignored_dir(file: &Path, paths: &[PathBuf], scan: &ScanConfig) -> bool {
+    let relative = paths
+        .iter()
+        .find_map(|path| file.strip_prefix(path).ok())
+        .unwrap_or(file);
+
+    relative
+        .parent()
+        .is_some_and(|dir| dir.iter().any(|name| scan.is_ignored_dir(name)))
+}
+
+/// Returns `(Device, Inode)` of the file whose metadata is `metadata`, which
+/// identifies the file no matter which path leads to it.
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Returns `(Device, Inode)` of the file whose metadata is `metadata`, not
+/// available on this platform, where only the loops detected by jwalk are
+/// broken.
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Returns the error that more than `scan.max_files` files are found under
+/// `paths`.
+fn too_many_files(paths: &[PathBuf], scan: &ScanConfig) -> Error {
+    Error::TooManyFiles {
+        paths: paths.to_vec(),
+        max_files: scan.max_files,
+    }
+}
+
+/// Returns if the given path points to a Rust file by checking its file extension.
+fn is_rust_file<P: AsRef<Path> + ?Sized>(file_path: &P) -> bool {
+    const RUST_FILE_EXTENSION: &str = "rs";
+
+    if let Some(extension) = file_path.as_ref().extension() {
+        if extension == RUST_FILE_EXTENSION {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_project_rust_src_to_check() {
+        let root_tempdir = tempdir().unwrap();
+        let root_tempdir_path = root_tempdir.path();
+
+        let file_foo = root_tempdir_path.join("foo");
+        std::fs::File::create(&file_foo).unwrap();
+        let file_bar_rs = root_tempdir_path.join("bar.rs");
+        std::fs::File::create(&file_bar_rs).unwrap();
+        let dir_baz = root_tempdir_path.join("baz");
+        std::fs::create_dir(&dir_baz).unwrap();
+        let file_qux_rs_under_dir_baz = dir_baz.join("qux.rs");
+        std::fs::File::create(&file_qux_rs_under_dir_baz).unwrap();
+
+        let project = Project::new(
+            // This field won't be used so let's give it a NULL value
+            PathBuf::new(),
+            vec![file_foo.clone(), file_bar_rs.clone(), dir_baz.clone()],
+        );
+
+        let flattened = project
+            .rust_src_to_check(&Source::WorkTree, &ScanConfig::default())
+            .unwrap();
+        assert_eq!(
+            flattened,
+            [file_bar_rs.clone(), file_qux_rs_under_dir_baz.clone()]
+        );
+
+        let file_quux_rs_under_dir_baz = dir_baz.join("quux");
+        std::fs::File::create(&file_quux_rs_under_dir_baz).unwrap();
+
+        let flattened = project
+            .rust_src_to_check(&Source::WorkTree, &ScanConfig::default())
+            .unwrap();
+        assert_eq!(
+            flattened,
+            [file_bar_rs.clone(), file_qux_rs_under_dir_baz.clone()]
+        );
+    }
+
+    #[test]
+    fn test_rust_files_max_depth() {
+        let root_tempdir = tempdir().unwrap();
+        let root = root_tempdir.path();
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::File::create(root.join("a/b/c.rs")).unwrap();
+
+        let scan = ScanConfig {
+            max_depth: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            rust_files(&[root.to_path_buf()], &Source::WorkTree, &scan).unwrap(),
+            [root.join("a/b/c.rs")]
+        );
+
+        let scan = ScanConfig {
+            max_depth: 2,
+            ..Default::default()
+        };
+        let error = rust_files(&[root.to_path_buf()], &Source::WorkTree, &scan).unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::TooDeep { path, dir, max_depth: 2 } if path == &root.join("a/b/c.rs") && dir == root
+        ));
+        assert!(error.to_string().contains("Raise `scan.max_depth`"));
+    }
+
+    #[test]
+    fn test_rust_files_max_files() {
+        let root_tempdir = tempdir().unwrap();
+        let root = root_tempdir.path();
+        for file in ["a.rs", "b.rs", "c.txt"] {
+            std::fs::File::create(root.join(file)).unwrap();
+        }
+
+        let scan = ScanConfig {
+            max_files: 2,
+            ..Default::default()
+        };
+        let error = rust_files(&[root.to_path_buf()], &Source::WorkTree, &scan).unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::TooManyFiles { paths, max_files: 2 } if paths == &[root.to_path_buf()]
+        ));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "more than 2 files found under [{}], is the path right? Raise `scan.max_files` in the config if it is intended",
+                root.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_rust_files_default_ignores() {
+        let root_tempdir = tempdir().unwrap();
+        let root = root_tempdir.path();
+        for dir in [
+            "src",
+            "target/debug",
+            "node_modules",
+            "vendor/dep",
+            "third_party",
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            std::fs::File::create(root.join(dir).join("lib.rs")).unwrap();
+        }
+
+        let paths = [root.to_path_buf()];
+        let mut scan = ScanConfig::default();
+        let mut files = rust_files(&paths, &Source::WorkTree, &scan).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [root.join("src/lib.rs"), root.join("third_party/lib.rs")]
+        );
+
+        scan.vendor_dirs = vec!["vendor".to_string(), "third_party".to_string()];
+        assert_eq!(
+            rust_files(&paths, &Source::WorkTree, &scan).unwrap(),
+            [root.join("src/lib.rs")]
+        );
+
+        // Specified directories are never ignored
+        let target = [root.join("target")];
+        assert_eq!(
+            rust_files(&target, &Source::WorkTree, &scan).unwrap(),
+            [root.join("target/debug/lib.rs")]
+        );
+
+        scan.default_ignores = false;
+        assert_eq!(
+            rust_files(&paths, &Source::WorkTree, &scan).unwrap().len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_in_ignored_dir() {
+        let scan = ScanConfig::default();
+        let paths = [PathBuf::from("target")];
+        assert!(in_ignored_dir(Path::new("src/target/lib.rs"), &[], &scan));
+        assert!(!in_ignored_dir(Path::new("target/lib.rs"), &paths, &scan));
+        assert!(!in_ignored_dir(Path::new("target.rs"), &[], &scan));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rust_files_follow_symlinks() {
+        let root_tempdir = tempdir().unwrap();
+        let root = root_tempdir.path();
+        std::fs::create_dir(root.join("a")).unwrap();
+        std::fs::File::create(root.join("a/lib.rs")).unwrap();
+        // A loop, and another link to a directory visited
+        std::os::unix::fs::symlink(root, root.join("a/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("a"), root.join("b")).unwrap();
+
+        let paths = [root.to_path_buf()];
+        let scan = ScanConfig::default();
+        assert_eq!(
+            walk(&paths, &Source::WorkTree, &scan).unwrap(),
+            (vec![Cow::Owned(root.join("a/lib.rs"))], Vec::new())
+        );
+
+        let scan = ScanConfig {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let (rust_files_to_check, skipped_links) = walk(&paths, &Source::WorkTree, &scan).unwrap();
+        // `a` and `b` are the same directory, which one is walked depends on
+        // the order of the entries
+        assert_eq!(rust_files_to_check.len(), 1);
+        assert!(rust_files_to_check[0].ends_with("lib.rs"));
+        assert_eq!(skipped_links.len(), 2);
+        assert!(skipped_links
+            .iter()
+            .all(|link| link.ends_with("loop") || link.ends_with("a") || link.ends_with("b")));
+    }
+
+    #[test]
+    fn test_detect_locale_file() {
+        let root_tempdir = tempdir().unwrap();
+        let root = root_tempdir.path();
+        assert_eq!(detect_locale_file(root), None);
+
+        let locale_dir = root.join("locales");
+        std::fs::create_dir(&locale_dir).unwrap();
+        std::fs::File::create(locale_dir.join("README.md")).unwrap();
+        std::fs::File::create(locale_dir.join("topgrade.yaml")).unwrap();
+        assert_eq!(
+            detect_locale_file(root),
+            Some(locale_dir.join("topgrade.yaml"))
+        );
+
+        std::fs::File::create(locale_dir.join("other.yml")).unwrap();
+        assert_eq!(detect_locale_file(root), None);
+
+        std::fs::File::create(locale_dir.join("app.yml")).unwrap();
+        assert_eq!(detect_locale_file(root), Some(locale_dir.join("app.yml")));
+    }
+
+    #[test]
+    fn test_detect_rust_src() {
+        let root_tempdir = tempdir().unwrap();
+        let root = root_tempdir.path();
+        assert_eq!(detect_rust_src(root), None);
+
+        std::fs::create_dir(root.join("src")).unwrap();
+        assert_eq!(detect_rust_src(root), Some(root.join("src")));
+    }
+
+    #[test]
+    fn test_project_from_root() {
+        let project = Project::from_root(PathBuf::from("topgrade"));
+        assert_eq!(project.root(), Some(Path::new("topgrade")));
+        assert_eq!(project.locale_file(), Path::new("topgrade/locales/app.yml"));
+        assert_eq!(project.rust_src_to_check, [PathBuf::from("topgrade/src")]);
+    }
+}
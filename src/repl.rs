@@ -0,0 +1,400 @@
+//! This file contains the `repl` subcommand, which answers queries against a
+//! checked project without re-parsing it between questions.
+
+use crate::error::Error;
+use crate::explain::KeyExplanation;
+use regex::Regex;
+use std::fmt::Write as _;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Help message of the REPL.
+const HELP: &str = "\
+Queries:
+  key <pattern>     explain the keys matching <pattern>, `*` matches anything
+  unused            list the keys that have no call site
+  file <path>       list the call sites and diagnostics in <path>
+  coverage <lang>   show the translation coverage of <lang>
+  help              show this message
+  quit              leave the REPL
+";
+
+/// Everything the REPL can query, built once per project.
+///
+/// It is a [`KeyExplanation`] without a specific key, `key` is ignored.
+pub(crate) struct Repl<'a> {
+    /// The checked project.
+    pub(crate) model: KeyExplanation<'a>,
+}
+
+impl Repl<'_> {
+    /// Reads queries from `input` and writes the answers to `output` until
+    /// `quit` or EOF.
+    ///
+    /// Fails if `input` cannot be read.
+    pub(crate) fn run(&self, input: impl BufRead, mut output: impl Write) -> Result<(), Error> {
+        let mut lines = input.lines();
+        loop {
+            write!(output, "> ").unwrap();
+            output.flush().unwrap();
+
+            let Some(line) = lines.next() else {
+                writeln!(output).unwrap();
+                break;
+            };
+            let line = line.map_err(|error| Error::Read {
+                path: PathBuf::from("stdin"),
+                error,
+            })?;
+            match self.answer(&line) {
+                Some(answer) => write!(output, "{}", answer).unwrap(),
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Answers a single query, returns `None` if the REPL should be left.
+    pub(crate) fn answer(&self, query: &str) -> Option<String> {
+        let query = query.trim();
+        let (command, arg) = match query.split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, unquote(arg.trim())),
+            None => (query, ""),
+        };
+
+        let answer = match (command, arg) {
+            ("", _) => String::new(),
+            ("quit" | "exit", _) => return None,
+            ("help", _) => HELP.to_string(),
+            ("key", pattern) if !pattern.is_empty() => self.key(pattern),
+            ("unused", "") => self.unused(),
+            ("file", path) if !path.is_empty() => self.file(Path::new(path)),
+            ("coverage", lang) if !lang.is_empty() => self.coverage(lang),
+            _ => format!(
+                "Unknown query {:?}, type `help` for the available queries\n",
+                query
+            ),
+        };
+
+        Some(answer)
+    }
+
+    /// Explains every key, defined or used, that matches the glob `pattern`.
+    fn key(&self, pattern: &str) -> String {
+        let regex = glob_to_regex(pattern);
+        let mut keys = self
+            .model
+            .localized_texts
+            .texts
+            .keys()
+            .map(|key| &**key)
+            .chain(
+                self.model
+                    .locale_keys
+                    .iter()
+                    .map(|locale_key| &*locale_key.key),
+            )
+            .filter(|key| regex.is_match(key))
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.dedup();
+
+        if keys.is_empty() {
+            return format!("No key matches {:?}\n", pattern);
+        }
+
+        keys.into_iter()
+            .map(|key| KeyExplanation { key, ..self.model }.render())
+            .collect()
+    }
+
+    /// Lists the keys defined in the locale file but never used by `t!()`.
+    fn unused(&self) -> String {
+        let unused = self
+            .model
+            .localized_texts
+            .texts
+            .keys()
+            .filter(|key| {
+                !self
+                    .model
+                    .locale_keys
+                    .iter()
+                    .any(|locale_key| locale_key.key == **key)
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = String::new();
+        for key in unused.iter() {
+            writeln!(out, "  {}", key).unwrap();
+        }
+        writeln!(out, "{} unused key(s)", unused.len()).unwrap();
+        out
+    }
+
+    /// Lists the `t!()` invocations and the diagnostics located in `path`.
+    fn file(&self, path: &Path) -> String {
+        let mut out = String::new();
+
+        let mut call_sites = self
+            .model
+            .locale_keys
+            .iter()
+            .filter(|locale_key| locale_key.file.ends_with(path))
+            .collect::<Vec<_>>();
+        call_sites.sort_unstable_by_key(|locale_key| (locale_key.line, locale_key.column));
+        if call_sites.is_empty() {
+            writeln!(out, "Call sites: none").unwrap();
+        } else {
+            writeln!(out, "Call sites:").unwrap();
+            for call_site in call_sites {
+                writeln!(
+                    out,
+                    "  {}:{} {}",
+                    call_site.line, call_site.column, call_site.key
+                )
+                .unwrap();
+            }
+        }
+
+        let diagnostics = self
+            .model
+            .checker
+            .errors()
+            .filter(|(_, diagnostic)| {
+                diagnostic
+                    .location
+                    .as_ref()
+                    .is_some_and(|location| location.file.ends_with(path))
+            })
+            .collect::<Vec<_>>();
+        if !diagnostics.is_empty() {
+            writeln!(out, "Diagnostics:").unwrap();
+            for (rule, diagnostic) in diagnostics {
+                write!(
+                    out,
+                    "  {} {}: {}",
+                    diagnostic.severity.as_str(),
+                    rule,
+                    diagnostic.headline()
+                )
+                .unwrap();
+                if let Some(error_msg) = &diagnostic.error_msg {
+                    write!(out, ": {}", error_msg).unwrap();
+                }
+                writeln!(out).unwrap();
+            }
+        }
+
+        out
+    }
+
+    /// Shows the translation coverage of `lang`, and the keys it misses.
+    fn coverage(&self, lang: &str) -> String {
+        let localized_texts = self.model.localized_texts;
+        if !localized_texts
+            .languages()
+            .iter()
+            .any(|language| &**language == lang)
+        {
+            return format!("Language {:?} does not appear in the locale file\n", lang);
+        }
+
+        let missing = localized_texts
+            .texts
+            .iter()
+            .filter(|(_, translations)| match lang {
+                "en" => translations.en.is_none(),
+                _ => !translations.others.contains_key(lang),
+            })
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        let n_keys = localized_texts.texts.len();
+        let n_translated = n_keys - missing.len();
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{}: {:.1}% ({}/{} keys)",
+            lang,
+            n_translated as f64 / n_keys as f64 * 100.0,
+            n_translated,
+            n_keys
+        )
+        .unwrap();
+        if !missing.is_empty() {
+            writeln!(out, "Missing:").unwrap();
+            for key in missing {
+                writeln!(out, "  {}", key).unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Removes the double or single quotes around `arg`, if any.
+fn unquote(arg: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(unquoted) = arg
+            .strip_prefix(quote)
+            .and_then(|arg| arg.strip_suffix(quote))
+        {
+            return unquoted;
+        }
+    }
+    arg
+}
+
+/// Converts a glob `pattern`, where `*` matches anything, to an anchored
+/// regex.
+fn glob_to_regex(pattern: &str) -> Regex {
+    let parts = pattern.split('*').map(regex::escape).collect::<Vec<_>>();
+    Regex::new(&format!("^{}$", parts.join(".*"))).expect("escaped")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::Checker;
+    use crate::config::PlaceholderSyntax;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use crate::locale_key_collector::LocaleKey;
+    use crate::rules::use_of_keys_do_not_exist::UseOfKeysDoNotExist;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indexmap::IndexMap;
+    use indicatif::ProgressBar;
+
+    fn locale_key(key: &str, file: &str, line: usize) -> LocaleKey {
+        LocaleKey {
+            key: key.into(),
+            file: Path::new(file).into(),
+            line,
+            column: 4,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
+        }
+    }
+
+    #[test]
+    fn test_answer() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting".into(),
+                    Translations {
+                        en: Some("Restarting".into()),
+                        others: IndexMap::from([("de".into(), "Neustart".into())]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating".into(),
+                    Translations {
+                        en: Some("Updating".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let locale_keys = vec![
+            locale_key("Restarting", "src/steps/git.rs", 10),
+            locale_key("Pulling", "src/steps/git.rs", 3),
+        ];
+        let mut checker = Checker::new();
+        checker.register_rule(UseOfKeysDoNotExist::new(Default::default()));
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+        checker.check(&ctx, &mut Timings::new(), &ProgressBar::hidden());
+
+        let repl = Repl {
+            model: KeyExplanation {
+                key: "",
+                locale_file: Path::new("locales/app.yml"),
+                locale_file_contents: "",
+                localized_texts: &localized_texts,
+                locale_keys: &locale_keys,
+                checker: &checker,
+                syntax: PlaceholderSyntax::default(),
+            },
+        };
+
+        assert_eq!(repl.answer("quit"), None);
+        assert_eq!(repl.answer("  ").as_deref(), Some(""));
+        assert_eq!(
+            repl.answer("key \"Nothing*\"").as_deref(),
+            Some("No key matches \"Nothing*\"\n")
+        );
+        assert!(repl
+            .answer("key \"Restart*\"")
+            .unwrap()
+            .starts_with("Key \"Restarting\"\n"));
+        assert_eq!(
+            repl.answer("unused").as_deref(),
+            Some("  Updating\n1 unused key(s)\n")
+        );
+        assert_eq!(
+            repl.answer("file git.rs").as_deref(),
+            Some(
+                "Call sites:
+  3:4 Pulling
+  10:4 Restarting
+Diagnostics:
+  error UseOfKeysDoNotExist: file 'src/steps/git.rs' / line '3' / column '4' / key 'Pulling'
+"
+            )
+        );
+        assert_eq!(
+            repl.answer("coverage de").as_deref(),
+            Some("de: 50.0% (1/2 keys)\nMissing:\n  Updating\n")
+        );
+        assert_eq!(
+            repl.answer("coverage fr").as_deref(),
+            Some("Language \"fr\" does not appear in the locale file\n")
+        );
+        assert!(repl
+            .answer("frobnicate")
+            .unwrap()
+            .starts_with("Unknown query"));
+    }
+
+    #[test]
+    fn test_run_stops_at_quit() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::new(),
+        };
+        let checker = Checker::new();
+        let repl = Repl {
+            model: KeyExplanation {
+                key: "",
+                locale_file: Path::new("locales/app.yml"),
+                locale_file_contents: "",
+                localized_texts: &localized_texts,
+                locale_keys: &[],
+                checker: &checker,
+                syntax: PlaceholderSyntax::default(),
+            },
+        };
+
+        let mut output = Vec::new();
+        repl.run("unused\nquit\nunused\n".as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "> 0 unused key(s)\n> ");
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let regex = glob_to_regex("Restart*{app}");
+        assert!(regex.is_match("Restarting {app}"));
+        assert!(!regex.is_match("Not Restarting {app}"));
+        assert!(glob_to_regex("a.b").is_match("a.b"));
+        assert!(!glob_to_regex("a.b").is_match("axb"));
+    }
+}
@@ -0,0 +1,463 @@
+//! This file contains type [`Report`], the diagnostics of a check with their
+//! locations in JSON, which `--format json` prints and `--report-file` writes.
+//!
+//! `merge-reports` combines the reports of sharded CI jobs, e.g., one job per
+//! project, into one, and renders the merged result in any output format, so
+//! that a CI matrix can check in parallel and still report once.
+
+use crate::checker::Checker;
+use crate::editor::{self, Location, OutputFormat};
+use crate::junit;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use crate::rules::{code_of, Diagnostic, Errors, Severity, SourceLocation, RULES};
+use crate::sarif;
+use indexmap::{IndexMap, IndexSet};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Version of the report format, bumped on incompatible changes.
+pub(crate) const REPORT_VERSION: u32 = 1;
+
+/// The diagnostics of one or more projects.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct Report {
+    /// Version of the format, [`REPORT_VERSION`].
+    pub(crate) version: u32,
+    /// Statistics of the check.
+    pub(crate) stats: Stats,
+    /// Diagnostics, in the order they are found.
+    pub(crate) diagnostics: Vec<ReportDiagnostic>,
+}
+
+/// Statistics of a [`Report`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct Stats {
+    /// Number of projects checked.
+    pub(crate) projects: usize,
+    /// Number of keys in the locale files.
+    pub(crate) locale_keys: usize,
+    /// Number of `t!()` invocations collected.
+    pub(crate) key_uses: usize,
+    /// Number of diagnostics with severity [`Severity::Error`].
+    pub(crate) errors: usize,
+    /// Number of diagnostics with severity [`Severity::Warning`].
+    pub(crate) warnings: usize,
+    /// Number of diagnostics with severity [`Severity::Info`].
+    pub(crate) infos: usize,
+}
+
+/// A diagnostic along with the rule that found it and where it points to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub(crate) struct ReportDiagnostic {
+    /// Root of the project, if any.
+    pub(crate) project: Option<PathBuf>,
+    /// Name of the rule.
+    pub(crate) rule: String,
+    /// Code of the rule, e.g., `I18N003`.
+    pub(crate) code: String,
+    /// Severity.
+    pub(crate) severity: Severity,
+    /// True if it is found in the Rust files, `file` is then a Rust file
+    /// rather than the locale file.
+    pub(crate) in_source: bool,
+    /// The file it points to.
+    pub(crate) file: PathBuf,
+    /// Line number, starts from 1.
+    pub(crate) line: usize,
+    /// Column number, starts from 1.
+    pub(crate) column: usize,
+    /// Key of the diagnostic, see [`Diagnostic::key`], it does not include
+    /// the location.
+    pub(crate) key: String,
+    /// Message reported to users, if any.
+    pub(crate) message: Option<String>,
+    /// Languages affected, empty if it is not about any specific language.
+    pub(crate) langs: Vec<String>,
+}
+
+impl ReportDiagnostic {
+    /// Converts it back to a [`Diagnostic`].
+    fn diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            location: self.in_source.then(|| SourceLocation {
+                file: self.file.as_path().into(),
+                line: self.line,
+                column: self.column - 1,
+            }),
+            key: self.key.as_str().into(),
+            error_msg: self.message.clone(),
+            severity: self.severity,
+            langs: self.langs.iter().map(|lang| lang.as_str().into()).collect(),
+        }
+    }
+}
+
+impl Report {
+    /// Creates an empty [`Report`].
+    pub(crate) fn new() -> Self {
+        Self {
+            version: REPORT_VERSION,
+            stats: Stats::default(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Records the diagnostics found by `checker` in a project, diagnostics
+    /// about a locale key point at `locale_file`, whose contents are
+    /// `locale_file_contents`.
+    pub(crate) fn record_project(
+        &mut self,
+        project: Option<&Path>,
+        localized_texts: &LocalizedTexts,
+        locale_keys: &[LocaleKey],
+        checker: &Checker,
+        locale_file: &Path,
+        locale_file_contents: &str,
+    ) {
+        for (rule, diagnostic) in checker.errors() {
+            let location = Location::of(diagnostic, locale_file, locale_file_contents);
+            self.diagnostics.push(ReportDiagnostic {
+                project: project.map(Path::to_path_buf),
+                rule: rule.to_string(),
+                code: code_of(rule).to_string(),
+                severity: diagnostic.severity,
+                in_source: diagnostic.location.is_some(),
+                file: location.file.into_owned(),
+                line: location.line,
+                column: location.column,
+                key: diagnostic.key.to_string(),
+                message: diagnostic.error_msg.clone(),
+                langs: diagnostic
+                    .langs
+                    .iter()
+                    .map(|lang| lang.to_string())
+                    .collect(),
+            });
+        }
+        self.stats.projects += 1;
+        self.stats.locale_keys += localized_texts.texts.len();
+        self.stats.key_uses += locale_keys.len();
+        self.count_severities();
+    }
+
+    /// Merges `reports`: their diagnostics are concatenated with the
+    /// identical ones deduplicated, and their statistics are summed, except
+    /// the numbers of diagnostics, which are counted after deduplication.
+    pub(crate) fn merge(reports: impl IntoIterator<Item = Report>) -> Self {
+        let mut merged = Self::new();
+        let mut diagnostics = IndexSet::new();
+        for report in reports {
+            merged.stats.projects += report.stats.projects;
+            merged.stats.locale_keys += report.stats.locale_keys;
+            merged.stats.key_uses += report.stats.key_uses;
+            diagnostics.extend(report.diagnostics);
+        }
+        merged.diagnostics = diagnostics.into_iter().collect();
+        merged.count_severities();
+
+        merged
+    }
+
+    /// Updates the numbers of diagnostics of every severity.
+    fn count_severities(&mut self) {
+        let count = |severity| {
+            self.diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        self.stats.errors = count(Severity::Error);
+        self.stats.warnings = count(Severity::Warning);
+        self.stats.infos = count(Severity::Info);
+    }
+
+    /// Return true if there is any error.
+    pub(crate) fn has_error(&self) -> bool {
+        self.stats.errors != 0
+    }
+
+    /// Parses a report.
+    ///
+    /// Returns an error if it is not valid JSON, of another version, or has
+    /// diagnostics of unknown rules.
+    pub(crate) fn parse(contents: &str) -> Result<Self, String> {
+        let report = serde_json::from_str::<Self>(contents).map_err(|e| e.to_string())?;
+        if report.version != REPORT_VERSION {
+            return Err(format!(
+                "unsupported version {}, expected {}",
+                report.version, REPORT_VERSION
+            ));
+        }
+        if let Some(diagnostic) = report
+            .diagnostics
+            .iter()
+            .find(|diagnostic| !RULES.iter().any(|doc| doc.name == diagnostic.rule))
+        {
+            return Err(format!("unknown rule {}", diagnostic.rule));
+        }
+
+        Ok(report)
+    }
+
+    /// Renders the report in JSON.
+    pub(crate) fn render(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report should be serializable")
+    }
+
+    /// Renders the report in `format`, which should be a document, see
+    /// [`OutputFormat::is_document()`].
+    pub(crate) fn render_as(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Json => self.render(),
+            OutputFormat::Sarif => sarif::render(self),
+            OutputFormat::Junit => junit::render(self),
+            _ => unreachable!("the {:?} format is not a document", format),
+        }
+    }
+
+    /// Returns a checker per project that has found the diagnostics of the
+    /// project, so that they can be reported as a check does, projects are in
+    /// the order they first appear.
+    pub(crate) fn checkers(&self) -> IndexMap<Option<&Path>, Checker> {
+        // `Project => Errors`
+        let mut errors: IndexMap<Option<&Path>, Errors> = IndexMap::new();
+        for diagnostic in self.diagnostics.iter() {
+            errors
+                .entry(diagnostic.project.as_deref())
+                .or_default()
+                .entry(rule_name(&diagnostic.rule))
+                .or_default()
+                .push(diagnostic.diagnostic());
+        }
+
+        errors
+            .into_iter()
+            .map(|(project, errors)| (project, Checker::with_errors(errors)))
+            .collect()
+    }
+
+    /// Writes the diagnostics in `format`, which should be one meant for
+    /// editors, see [`editor::write_diagnostics()`].
+    pub(crate) fn write_diagnostics(
+        &self,
+        out: &mut impl Write,
+        format: OutputFormat,
+    ) -> io::Result<()> {
+        let diagnostics = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic, diagnostic.diagnostic()))
+            .collect::<Vec<_>>();
+        let located = diagnostics
+            .iter()
+            .map(|(report_diagnostic, diagnostic)| {
+                let location = Location {
+                    file: Cow::Borrowed(report_diagnostic.file.as_path()),
+                    line: report_diagnostic.line,
+                    column: report_diagnostic.column,
+                    subject: editor::subject_of(&report_diagnostic.key),
+                };
+                (location, report_diagnostic.rule.as_str(), diagnostic)
+            })
+            .collect();
+
+        editor::write_located(out, format, located)
+    }
+}
+
+/// Returns the name of the rule `rule` as stored by the checker.
+///
+/// Panics if there is no such rule, which [`Report::parse()`] rules out.
+fn rule_name(rule: &str) -> &'static str {
+    RULES
+        .iter()
+        .find(|doc| doc.name == rule)
+        .map(|doc| doc.name)
+        .unwrap_or_else(|| panic!("the report contains unknown rule {}", rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::use_of_keys_do_not_exist::UseOfKeysDoNotExist;
+    use crate::rules::CheckContext;
+    use crate::timings::Timings;
+    use indicatif::ProgressBar;
+
+    fn diagnostic(project: &str, key: &str, severity: Severity) -> ReportDiagnostic {
+        ReportDiagnostic {
+            project: Some(project.into()),
+            rule: "UseOfKeysDoNotExist".into(),
+            code: "I18N003".into(),
+            severity,
+            in_source: true,
+            file: "src/main.rs".into(),
+            line: 3,
+            column: 5,
+            key: key.to_string(),
+            message: None,
+            langs: Vec::new(),
+        }
+    }
+
+    fn report(diagnostics: Vec<ReportDiagnostic>) -> Report {
+        let mut report = Report {
+            stats: Stats {
+                projects: 1,
+                locale_keys: 10,
+                key_uses: 20,
+                ..Default::default()
+            },
+            diagnostics,
+            ..Report::new()
+        };
+        report.count_severities();
+        report
+    }
+
+    #[test]
+    fn test_merge() {
+        let a = report(vec![
+            diagnostic("foo", "Pulling", Severity::Error),
+            diagnostic("foo", "Cleaning", Severity::Warning),
+        ]);
+        let b = report(vec![
+            diagnostic("bar", "Pulling", Severity::Error),
+            diagnostic("foo", "Pulling", Severity::Error),
+        ]);
+        let merged = Report::merge([a, b]);
+        assert_eq!(
+            merged.stats,
+            Stats {
+                projects: 2,
+                locale_keys: 20,
+                key_uses: 40,
+                errors: 2,
+                warnings: 1,
+                infos: 0,
+            }
+        );
+        assert_eq!(
+            merged.diagnostics,
+            [
+                diagnostic("foo", "Pulling", Severity::Error),
+                diagnostic("foo", "Cleaning", Severity::Warning),
+                diagnostic("bar", "Pulling", Severity::Error),
+            ]
+        );
+        assert!(merged.has_error());
+        assert_eq!(Report::parse(&merged.render()).unwrap(), merged);
+
+        let checkers = merged.checkers();
+        assert_eq!(
+            checkers.keys().collect::<Vec<_>>(),
+            [&Some(Path::new("foo")), &Some(Path::new("bar"))]
+        );
+        assert_eq!(checkers[0].errors().count(), 2);
+        assert!(!Report::merge([]).has_error());
+    }
+
+    #[test]
+    fn test_record_project() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::new(),
+        };
+        let locale_keys = vec![LocaleKey {
+            key: "Pulling".into(),
+            file: Path::new("src/steps/git.rs").into(),
+            line: 10,
+            column: 4,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
+        }];
+        let mut checker = Checker::new();
+        checker.register_rule(UseOfKeysDoNotExist::new(Default::default()));
+        checker.check(
+            &CheckContext {
+                localized_texts: &localized_texts,
+                locale_keys: &locale_keys,
+                set_locale_calls: &[],
+                concatenations: &[],
+                sink_literals: &[],
+            },
+            &mut Timings::new(),
+            &ProgressBar::hidden(),
+        );
+
+        let mut report = Report::new();
+        report.record_project(
+            None,
+            &localized_texts,
+            &locale_keys,
+            &checker,
+            Path::new("locales/app.yml"),
+            "",
+        );
+        let json = serde_json::from_str::<serde_json::Value>(&report.render()).unwrap();
+        let diagnostic = &json["diagnostics"][0];
+        assert_eq!(diagnostic["key"], "Pulling");
+        assert_eq!(diagnostic["in_source"], true);
+        assert_eq!(diagnostic["file"], "src/steps/git.rs");
+        assert_eq!(diagnostic["line"], 10);
+        assert_eq!(diagnostic["column"], 5);
+
+        let checkers = report.checkers();
+        assert_eq!(
+            checkers[&None].errors().map(|(_, d)| d).collect::<Vec<_>>(),
+            checker.errors().map(|(_, d)| d).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(Report::parse("{").is_err());
+        let mut report = report(Vec::new());
+        report.version = REPORT_VERSION + 1;
+        assert_eq!(
+            Report::parse(&report.render()).unwrap_err(),
+            format!(
+                "unsupported version {}, expected {}",
+                REPORT_VERSION + 1,
+                REPORT_VERSION
+            )
+        );
+
+        let mut unknown_rule = self::report(vec![diagnostic("a", "foo", Severity::Error)]);
+        unknown_rule.diagnostics[0].rule = "NoSuchRule".into();
+        assert_eq!(
+            Report::parse(&unknown_rule.render()).unwrap_err(),
+            "unknown rule NoSuchRule"
+        );
+    }
+
+    #[test]
+    fn test_write_diagnostics() {
+        let report = report(vec![
+            diagnostic("foo", "Pulling", Severity::Error),
+            ReportDiagnostic {
+                in_source: false,
+                file: "locales/app.yml".into(),
+                line: 2,
+                column: 1,
+                key: "Restarting".into(),
+                rule: "KeyEngMatches".into(),
+                code: "I18N002".into(),
+                message: Some("does not match".into()),
+                ..diagnostic("foo", "", Severity::Warning)
+            },
+        ]);
+        let mut out = Vec::new();
+        report
+            .write_diagnostics(&mut out, OutputFormat::Gcc)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "locales/app.yml:2:1: warning: [I18N002] key 'Restarting': does not match\n\
+             src/main.rs:3:5: error: [I18N003] key 'Pulling'\n"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! This file contains the `rules` subcommand, which lists the rules.
+
+use crate::config::RulesConfig;
+use crate::rules::RuleDoc;
+use std::fmt::Write;
+
+/// Returns `yes` or `no`.
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Renders one row per rule in `docs`: its name, which `--enable-rule` and
+/// `--disable-rule` accept, code, whether it runs with the default config and
+/// with `config`, and description.
+pub(crate) fn render(docs: &[&RuleDoc], config: &RulesConfig) -> String {
+    let default_config = RulesConfig::default();
+    let selection = config.selection();
+    let name_width = docs.iter().map(|doc| doc.name.len()).max().unwrap_or(0);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "{:name_width$}  {:7}  {:7}  {:7}  Description",
+        "Rule", "Code", "Default", "Enabled"
+    )
+    .unwrap();
+    for doc in docs {
+        let enabled = selection.is_enabled(doc.name, config.runs_by_default(doc.name));
+        writeln!(
+            out,
+            "{:name_width$}  {:7}  {:7}  {:7}  {}",
+            doc.name,
+            doc.code,
+            yes_no(default_config.runs_by_default(doc.name)),
+            yes_no(enabled),
+            doc.description
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::hard_coded_strings;
+    use crate::rules::missing_translations;
+
+    #[test]
+    fn test_render() {
+        let docs = [&missing_translations::DOC, &hard_coded_strings::DOC];
+        let config = RulesConfig {
+            enabled: vec!["HardCodedStrings".into()],
+            ..Default::default()
+        };
+
+        let out = render(&docs, &config);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Rule                 Code     Default  Enabled  Description"));
+        assert!(lines[1].starts_with("MissingTranslations  I18N001  yes      no       Checks"));
+        assert!(lines[2].starts_with("HardCodedStrings     I18N024  no       yes      Checks"));
+    }
+}
@@ -0,0 +1,107 @@
+//! A rule that checks if any key contains braces that are neither escaped nor
+//! a part of a placeholder.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use topgrade_i18n_locale_checker::key_parser::{LocaleKeyParser, LocaleToken};
+
+/// Checks if any key contains stray braces, e.g., `{}`, an unmatched `{`, or
+/// the outer braces of `{a{b}}`.
+///
+/// They are kept literally, but it is unclear whether a placeholder or a
+/// literal brace is meant, literal braces should be escaped as `{{` and `}}`.
+/// Problems are reported as warnings.
+pub(crate) struct AmbiguousBraces;
+
+/// Documentation of [`AmbiguousBraces`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "AmbiguousBraces",
+    code: "I18N026",
+    description: "Checks if any key contains braces that are neither escaped as `{{`/`}}` nor a part of a `{name}` placeholder.",
+    rationale: "Such braces, e.g., `{}`, an unmatched `{`, or nested braces, are kept literally, but it is unclear whether a placeholder or a literal brace is meant. Problems are reported as warnings.",
+    options: &[],
+    example_syntax: "yaml",
+    failing: r#""Options {}":
+  en: Options {}
+"#,
+    passing: r#""Options {{}}":
+  en: Options {}
+"#,
+};
+
+impl Rule for AmbiguousBraces {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for key in ctx.localized_texts.texts.keys() {
+            let mut parser = LocaleKeyParser::new();
+            parser.parse(key);
+            let stray_braces = parser
+                .tokens()
+                .iter()
+                .filter_map(|token| match token {
+                    LocaleToken::Stray(brace) => Some(format!("`{}`", brace)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if stray_braces.is_empty() {
+                continue;
+            }
+
+            diagnostics.push(Diagnostic {
+                location: None,
+                key: key.clone(),
+                error_msg: Some(format!(
+                    "Ambiguous braces [{}], write `{{{{` and `}}}}` for literal braces, or `{{name}}` for a placeholder",
+                    stray_braces.join(", ")
+                )),
+                severity: Severity::Warning,
+                langs: Vec::new(),
+            });
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                ("Options {}".into(), Translations::default()),
+                ("Options {{}} {app}".into(), Translations::default()),
+                ("Set {a{b}}".into(), Translations::default()),
+            ]),
+        };
+        let rule = AmbiguousBraces;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic {
+                location: None,
+                key: "Options {}".into(),
+                error_msg: Some("Ambiguous braces [`{`, `}`], write `{{` and `}}` for literal braces, or `{name}` for a placeholder".into()),
+                severity: Severity::Warning,
+                langs: Vec::new(),
+            },
+            Diagnostic {
+                location: None,
+                key: "Set {a{b}}".into(),
+                error_msg: Some("Ambiguous braces [`{`, `}`], write `{{` and `}}` for literal braces, or `{name}` for a placeholder".into()),
+                severity: Severity::Warning,
+                langs: Vec::new(),
+            },
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,150 @@
+//! A rule that checks if there are ANSI escape sequences in translations.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use std::sync::Arc;
+
+/// The escape character that starts an ANSI escape sequence.
+const ESC: char = '\u{1B}';
+
+/// The 8-bit Control Sequence Introducer, equivalent to `ESC [`.
+const CSI: char = '\u{9B}';
+
+/// Returns the ANSI escape sequences in `text`, in the order they appear,
+/// duplicates are removed, `ESC` is rendered as `\e` so that they are visible.
+fn escape_sequences(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let mut sequence = match c {
+            ESC => String::from("\\e"),
+            CSI => String::from("\\e["),
+            _ => continue,
+        };
+        let is_control_sequence = c == CSI || chars.next_if_eq(&'[').is_some();
+        if c == ESC && is_control_sequence {
+            sequence.push('[');
+        }
+        if is_control_sequence {
+            // Parameter and intermediate bytes, then a final byte
+            for c in chars.by_ref() {
+                sequence.push(c);
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else if let Some(c) = chars.next() {
+            sequence.push(c);
+        }
+
+        if !found.contains(&sequence) {
+            found.push(sequence);
+        }
+    }
+
+    found
+}
+
+/// Checks if there are ANSI escape sequences, e.g., colors, in the
+/// translations.
+///
+/// Styling should come from Topgrade's code, not the locale file, and escapes
+/// present in some languages but not others break alignment.
+pub(crate) struct AnsiEscapeSequences;
+
+/// Documentation of [`AnsiEscapeSequences`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "AnsiEscapeSequences",
+    code: "I18N018",
+    description: "Checks if there are ANSI escape sequences, e.g., colors, in the translations.",
+    rationale: "Styling should come from the code, not the locale file, and escapes present in some languages but not others break alignment.",
+    options: &[],
+    example_syntax: "yaml",
+    failing: r#"Failed:
+  en: Failed
+  de: "\e[31mFehlgeschlagen\e[0m"
+"#,
+    passing: "Failed:
+  en: Failed
+  de: Fehlgeschlagen
+",
+};
+
+impl Rule for AnsiEscapeSequences {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let en = translations.en.iter().map(|en| ("en".into(), en));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation));
+            for (lang, translation) in en.chain(others) {
+                let sequences = escape_sequences(translation);
+                if sequences.is_empty() {
+                    continue;
+                }
+
+                diagnostics.push(
+                    Diagnostic::error(
+                        key.clone(),
+                        Some(format!(
+                            "ANSI escape sequences [{}] in the translation",
+                            sequences.join(", ")
+                        )),
+                    )
+                    .with_langs(vec![lang]),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_escape_sequences() {
+        assert_eq!(
+            escape_sequences("\u{1B}[1;31mError\u{1B}[0m \u{1B}[1;31m"),
+            ["\\e[1;31m", "\\e[0m"]
+        );
+        assert_eq!(escape_sequences("\u{9B}32mOK"), ["\\e[32m"]);
+        assert_eq!(escape_sequences("\u{1B}7 \u{1B}"), ["\\e7", "\\e"]);
+        assert!(escape_sequences("[0m").is_empty());
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Error".into(),
+                Translations {
+                    en: Some("Error".into()),
+                    others: IndexMap::from([("de".into(), "\u{1B}[31mFehler\u{1B}[0m".into())]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = AnsiEscapeSequences;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic::error(
+            "Error".into(),
+            Some("ANSI escape sequences [\\e[31m, \\e[0m] in the translation".into()),
+        )
+        .with_langs(vec!["de".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,145 @@
+//! A rule that checks if there are Unicode bidirectional control characters in
+//! the locale file.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use std::sync::Arc;
+
+/// Unicode bidirectional control characters.
+///
+/// They change how the text around them is rendered, which makes terminals
+/// display garbled output, and can be used to make the text in a translation
+/// PR look different from what it actually is ("Trojan Source").
+const BIDI_CONTROL_CHARACTERS: [char; 12] = [
+    '\u{061C}', // ARABIC LETTER MARK
+    '\u{200E}', // LEFT-TO-RIGHT MARK
+    '\u{200F}', // RIGHT-TO-LEFT MARK
+    '\u{202A}', // LEFT-TO-RIGHT EMBEDDING
+    '\u{202B}', // RIGHT-TO-LEFT EMBEDDING
+    '\u{202C}', // POP DIRECTIONAL FORMATTING
+    '\u{202D}', // LEFT-TO-RIGHT OVERRIDE
+    '\u{202E}', // RIGHT-TO-LEFT OVERRIDE
+    '\u{2066}', // LEFT-TO-RIGHT ISOLATE
+    '\u{2067}', // RIGHT-TO-LEFT ISOLATE
+    '\u{2068}', // FIRST STRONG ISOLATE
+    '\u{2069}', // POP DIRECTIONAL ISOLATE
+];
+
+/// Returns an error message listing the bidirectional control characters in
+/// `text`, `None` if there is none.
+fn error_msg(text: &str, location: &str) -> Option<String> {
+    let mut found = Vec::new();
+    for c in text.chars().filter(|c| BIDI_CONTROL_CHARACTERS.contains(c)) {
+        let code_point = format!("U+{:04X}", c as u32);
+        if !found.contains(&code_point) {
+            found.push(code_point);
+        }
+    }
+    if found.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "Bidirectional control characters [{}] in {}",
+        found.join(", "),
+        location
+    ))
+}
+
+/// Checks if there are bidirectional control characters in the keys and
+/// translations.
+pub(crate) struct BidiControlCharacters;
+
+/// Documentation of [`BidiControlCharacters`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "BidiControlCharacters",
+    code: "I18N005",
+    description: "Checks if keys or translations contain Unicode bidirectional control characters.",
+    rationale: r#"They make terminals display garbled output, and can make the text in a translation PR look different from what it actually is ("Trojan Source")."#,
+    options: &[],
+    example_syntax: "yaml",
+    failing: r#"Restarting:
+  en: Restarting
+  ar: "\u202Eإعادة التشغيل"
+"#,
+    passing: "Restarting:
+  en: Restarting
+  ar: إعادة التشغيل
+",
+};
+
+impl Rule for BidiControlCharacters {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            if let Some(error_msg) = error_msg(key, "the key") {
+                diagnostics.push(Diagnostic::error(key.clone(), Some(error_msg)));
+            }
+
+            let en = translations.en.iter().map(|en| ("en".into(), en));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation));
+            for (lang, translation) in en.chain(others) {
+                if let Some(error_msg) = error_msg(translation, "the translation") {
+                    diagnostics.push(
+                        Diagnostic::error(key.clone(), Some(error_msg)).with_langs(vec![lang]),
+                    );
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting\u{202E}".into(),
+                    Translations {
+                        en: Some("Restarting".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating".into(),
+                    Translations {
+                        en: Some("Updating".into()),
+                        others: IndexMap::from([("ar".into(), "\u{2067}تحديث\u{2069}".into())]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let rule = BidiControlCharacters;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "Restarting\u{202E}".into(),
+                Some("Bidirectional control characters [U+202E] in the key".into()),
+            ),
+            Diagnostic::error(
+                "Updating".into(),
+                Some("Bidirectional control characters [U+2067, U+2069] in the translation".into()),
+            )
+            .with_langs(vec!["ar".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,103 @@
+//! A rule that checks if Topgrade concatenates the results of `t!()` with
+//! other text.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity, SourceLocation};
+use crate::locale_key_collector::ConcatenationKind;
+use std::sync::Arc;
+
+/// Checks if the result of any `t!()` invocation is concatenated with other
+/// text, e.g., `format!("{}{}", t!("a"), ": details")` or `s + &t!("a")`.
+///
+/// The concatenated text won't be translated, and its position can't change
+/// per language, it should be moved into the key. Problems are reported as
+/// warnings.
+pub(crate) struct ConcatenatedTranslations;
+
+/// Documentation of [`ConcatenatedTranslations`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "ConcatenatedTranslations",
+    code: "I18N022",
+    description: "Checks if the result of any `t!()` invocation is concatenated with other text.",
+    rationale: "The concatenated text won't be translated, and its position can't change per language, it should be moved into the key. Problems are reported as warnings.",
+    options: &[],
+    example_syntax: "rust",
+    failing: r#"let msg = t!("Failed") + ": " + &reason;
+"#,
+    passing: r#"let msg = t!("Failed: {reason}", reason = reason);
+"#,
+};
+
+impl Rule for ConcatenatedTranslations {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        ctx.concatenations
+            .iter()
+            .filter_map(|concatenation| {
+                let how = match concatenation.kind {
+                    ConcatenationKind::FormatArgs => "formatted along with string literals",
+                    ConcatenationKind::AddOperator => "concatenated with `+`",
+                    // Reported by `WordsAroundTranslations`
+                    ConcatenationKind::FormatString => return None,
+                };
+                Some(Diagnostic {
+                    location: Some(SourceLocation {
+                        file: Arc::clone(&concatenation.file),
+                        line: concatenation.line,
+                        column: concatenation.column,
+                    }),
+                    key: Arc::clone(&concatenation.key),
+                    error_msg: Some(format!(
+                        "The result of t!() is {}, move the text into the key",
+                        how
+                    )),
+                    severity: Severity::Warning,
+                    langs: Vec::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::LocalizedTexts;
+    use crate::locale_key_collector::Concatenation;
+    use indexmap::IndexMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::new(),
+        };
+        let concatenations = vec![Concatenation {
+            kind: ConcatenationKind::AddOperator,
+            key: "Restarting".into(),
+            file: Path::new("foo.rs").into(),
+            line: 1,
+            column: 1,
+        }];
+        let rule = ConcatenatedTranslations;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &concatenations,
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic {
+            location: Some(SourceLocation {
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 1,
+            }),
+            key: "Restarting".into(),
+            error_msg: Some(
+                "The result of t!() is concatenated with `+`, move the text into the key".into(),
+            ),
+            severity: Severity::Warning,
+            langs: Vec::new(),
+        }];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,199 @@
+//! A rule that enforces the configured emoji policy on translations.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::config::EmojiPolicyMode;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Code point ranges of emoji, an approximation that covers the pictographs,
+/// symbols and flags commonly rendered as emoji.
+const EMOJI_RANGES: [RangeInclusive<char>; 6] = [
+    '\u{231A}'..='\u{231B}',   // Watch, hourglass
+    '\u{23E9}'..='\u{23FA}',   // Media control symbols
+    '\u{2600}'..='\u{27BF}',   // Miscellaneous symbols, dingbats
+    '\u{2B50}'..='\u{2B55}',   // Stars, circles
+    '\u{1F000}'..='\u{1FAFF}', // Pictographs, emoticons, flags, etc.
+    '\u{FE0F}'..='\u{FE0F}',   // Emoji presentation selector
+];
+
+/// Returns the emoji in `text`, sorted, duplicates are kept.
+fn emoji(text: &str) -> Vec<char> {
+    let mut emoji = text
+        .chars()
+        // The presentation selector is not an emoji itself
+        .filter(|c| *c != '\u{FE0F}' && EMOJI_RANGES.iter().any(|range| range.contains(c)))
+        .collect::<Vec<_>>();
+    emoji.sort_unstable();
+
+    emoji
+}
+
+/// Formats `emoji` as `[🚀, ✅]`.
+fn format_emoji(emoji: &[char]) -> String {
+    let emoji = emoji.iter().map(char::to_string).collect::<Vec<_>>();
+
+    format!("[{}]", emoji.join(", "))
+}
+
+/// Enforces the emoji policy, emoji width wrecks column alignment in some
+/// terminals.
+///
+/// * [`EmojiPolicyMode::Forbid`]: no translation should contain emoji.
+/// * [`EmojiPolicyMode::MatchEnglish`]: every translation should use the same
+///   emoji as English, keys without an English translation are skipped.
+pub(crate) struct EmojiPolicy {
+    /// The policy.
+    mode: EmojiPolicyMode,
+}
+
+impl EmojiPolicy {
+    /// Creates the rule that enforces the policy `mode`.
+    pub(crate) fn new(mode: EmojiPolicyMode) -> Self {
+        Self { mode }
+    }
+}
+
+/// Documentation of [`EmojiPolicy`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "EmojiPolicy",
+    code: "I18N020",
+    description: "Enforces the emoji policy: either no translation contains emoji, or every translation uses the same emoji as English.",
+    rationale: "Emoji width wrecks column alignment in some terminals.",
+    options: &[
+        ("rules.emoji_policy.mode", "`match_english` (default) or `forbid`."),
+    ],
+    example_syntax: "yaml",
+    failing: "Done:
+  en: Done ✅
+  de: Fertig 🎉
+",
+    passing: "Done:
+  en: Done ✅
+  de: Fertig ✅
+",
+};
+
+impl Rule for EmojiPolicy {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            match self.mode {
+                EmojiPolicyMode::Forbid => {
+                    let en = translations.en.iter().map(|en| ("en".into(), en));
+                    let others = translations
+                        .others
+                        .iter()
+                        .map(|(lang, translation)| (Arc::clone(lang), translation));
+                    for (lang, translation) in en.chain(others) {
+                        let found = emoji(translation);
+                        if !found.is_empty() {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    key.clone(),
+                                    Some(format!("Emoji {} are forbidden", format_emoji(&found))),
+                                )
+                                .with_langs(vec![lang]),
+                            );
+                        }
+                    }
+                }
+                EmojiPolicyMode::MatchEnglish => {
+                    let Some(en) = &translations.en else {
+                        continue;
+                    };
+                    let expected = emoji(en);
+
+                    for (lang, translation) in translations.others.iter() {
+                        let found = emoji(translation);
+                        if found != expected {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    key.clone(),
+                                    Some(format!(
+                                        "Emoji differ from English: expected {}, found {}",
+                                        format_emoji(&expected),
+                                        format_emoji(&found)
+                                    )),
+                                )
+                                .with_langs(vec![Arc::clone(lang)]),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    fn localized_texts() -> LocalizedTexts {
+        LocalizedTexts {
+            texts: IndexMap::from([(
+                "Done ✅".into(),
+                Translations {
+                    en: Some("Done ✅".into()),
+                    others: IndexMap::from([
+                        ("de".into(), "✅ Fertig".into()),
+                        ("fr".into(), "Terminé 🚀".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_emoji() {
+        assert_eq!(emoji("🚀 Done ✅, ⚠\u{FE0F} 1 warning"), ['⚠', '✅', '🚀']);
+        assert!(emoji("Résumé → ½ «»").is_empty());
+    }
+
+    #[test]
+    fn test_forbid() {
+        let localized_texts = localized_texts();
+        let rule = EmojiPolicy::new(EmojiPolicyMode::Forbid);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error("Done ✅".into(), Some("Emoji [✅] are forbidden".into()))
+                .with_langs(vec!["en".into()]),
+            Diagnostic::error("Done ✅".into(), Some("Emoji [✅] are forbidden".into()))
+                .with_langs(vec!["de".into()]),
+            Diagnostic::error("Done ✅".into(), Some("Emoji [🚀] are forbidden".into()))
+                .with_langs(vec!["fr".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_match_english() {
+        let localized_texts = localized_texts();
+        let rule = EmojiPolicy::new(EmojiPolicyMode::MatchEnglish);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic::error(
+            "Done ✅".into(),
+            Some("Emoji differ from English: expected [✅], found [🚀]".into()),
+        )
+        .with_langs(vec!["fr".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
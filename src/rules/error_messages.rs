@@ -0,0 +1,135 @@
+//! A rule that checks if Topgrade constructs errors with English messages that
+//! are not passed through `t!()`.
+
+use super::hard_coded_strings::count_words;
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity, SourceLocation};
+use crate::config::ErrorMessagesConfig;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Checks if any string literal passed to the functions and macros that
+/// construct errors, e.g., `panic!()`, `anyhow::bail!()` and `Err()`, looks
+/// like an English message.
+///
+/// Error messages are user-visible in Topgrade, but teams may choose not to
+/// localize them, so they are classified separately from
+/// [`HardCodedStrings`], with their own severity and allowlist. The rule is
+/// opt-in.
+///
+/// [`HardCodedStrings`]: super::hard_coded_strings::HardCodedStrings
+pub(crate) struct ErrorMessages {
+    /// Severity of the problems found.
+    severity: Severity,
+    /// Error messages that are fine to be hard-coded.
+    allowlist: HashSet<String>,
+    /// Functions and macros that construct errors.
+    sinks: HashSet<String>,
+    /// A string literal with fewer words than this is not a message.
+    min_words: usize,
+}
+
+impl ErrorMessages {
+    /// Creates the rule with its `config`.
+    pub(crate) fn new(config: &ErrorMessagesConfig) -> Self {
+        Self {
+            severity: config.severity,
+            allowlist: config.allowlist.iter().cloned().collect(),
+            sinks: config.sinks.iter().cloned().collect(),
+            min_words: config.min_words,
+        }
+    }
+}
+
+/// Documentation of [`ErrorMessages`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "ErrorMessages",
+    code: "I18N025",
+    description: "Checks if any string literal passed to the functions and macros that construct errors looks like an English message.",
+    rationale: "Error messages are user-visible, but teams may choose not to localize them, so they are classified separately from `HardCodedStrings`. The rule is opt-in.",
+    options: &[
+        ("rules.error_messages.enabled", "The rule is disabled unless this is true."),
+        ("rules.error_messages.severity", "Severity of the problems found, `warning` by default."),
+        ("rules.error_messages.allowlist", "Error messages that are fine to be hard-coded."),
+        ("rules.error_messages.sinks", "Functions, or macros followed by `!`, that construct errors."),
+        ("rules.error_messages.min_words", "A string literal with fewer words is not considered a message, 2 by default."),
+    ],
+    example_syntax: "rust",
+    failing: r#"bail!("Cannot find the config file");
+"#,
+    passing: r#"bail!(t!("Cannot find the config file"));
+"#,
+};
+
+impl Rule for ErrorMessages {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        ctx.sink_literals
+            .iter()
+            .filter(|literal| {
+                self.sinks.contains(&*literal.sink)
+                    && !self.allowlist.contains(&literal.text)
+                    && count_words(&literal.text) >= self.min_words
+            })
+            .map(|literal| Diagnostic {
+                location: Some(SourceLocation {
+                    file: Arc::clone(&literal.file),
+                    line: literal.line,
+                    column: literal.column,
+                }),
+                key: "".into(),
+                error_msg: Some(format!(
+                    "Error message {:?} passed to {} is not localized",
+                    literal.text, literal.sink
+                )),
+                severity: self.severity,
+                langs: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::LocalizedTexts;
+    use crate::locale_key_collector::SinkLiteral;
+    use indexmap::IndexMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::new(),
+        };
+        let sink_literal = |sink: &str, text: &str, line| SinkLiteral {
+            sink: sink.into(),
+            text: text.into(),
+            file: Path::new("foo.rs").into(),
+            line,
+            column: 4,
+        };
+        let sink_literals = vec![
+            sink_literal("bail!", "Failed to update {}", 1),
+            sink_literal("Err", "unreachable", 2),
+            sink_literal("println!", "Press any key to continue", 3),
+            sink_literal("panic!", "Not a directory", 4),
+        ];
+        let rule = ErrorMessages::new(&ErrorMessagesConfig {
+            severity: Severity::Error,
+            allowlist: vec!["Not a directory".to_string()],
+            ..Default::default()
+        });
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &sink_literals,
+        });
+        let expected_diagnostics = vec![Diagnostic::error(
+            "".into(),
+            Some("Error message \"Failed to update {}\" passed to bail! is not localized".into()),
+        )
+        .at(Path::new("foo.rs").into(), 1, 4)];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,223 @@
+//! A rule that checks if translations follow the glossary.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use crate::glossary::Glossary;
+use regex::Regex;
+use std::sync::Arc;
+
+/// A glossary term, with the regex that finds it in English translations.
+struct Term {
+    /// Matches the English term as a whole word, case-insensitively.
+    regex: Regex,
+    /// The English term.
+    en: String,
+    /// If true, the term should never be translated.
+    keep: bool,
+    /// `Language => Required translation`
+    translations: Vec<(String, String)>,
+}
+
+impl Term {
+    /// Returns the text a translation in `lang` should contain, `None` if the
+    /// glossary does not say.
+    fn required(&self, lang: &str) -> Option<&str> {
+        if self.keep {
+            return Some(&self.en);
+        }
+
+        self.translations
+            .iter()
+            .find(|(l, _)| l == lang)
+            .map(|(_, translation)| translation.as_str())
+    }
+}
+
+/// Checks if every translation whose English translation contains a glossary
+/// term uses the required translation of that term, or keeps it as is if it
+/// should never be translated.
+///
+/// Terms are matched case-insensitively, as whole words in English and as
+/// substrings in translations so that inflected forms are accepted. Keys
+/// without an English translation are skipped, problems are reported as
+/// warnings.
+pub(crate) struct GlossaryTerms {
+    /// The terms of the glossary.
+    terms: Vec<Term>,
+}
+
+impl GlossaryTerms {
+    /// Creates the rule that enforces `glossary`.
+    pub(crate) fn new(glossary: &Glossary) -> Self {
+        let terms = glossary
+            .terms
+            .iter()
+            .map(|term| Term {
+                regex: Regex::new(&format!(r"(?i)\b{}\b", regex::escape(&term.en)))
+                    .expect("an escaped term is a valid regex"),
+                en: term.en.clone(),
+                keep: term.keep,
+                translations: term
+                    .translations
+                    .iter()
+                    .map(|(lang, translation)| (lang.clone(), translation.clone()))
+                    .collect(),
+            })
+            .collect();
+
+        Self { terms }
+    }
+}
+
+/// Documentation of [`GlossaryTerms`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "GlossaryTerms",
+    code: "I18N021",
+    description: "Checks if every translation whose English translation contains a glossary term uses the required translation of that term, or keeps it as is if it should never be translated.",
+    rationale: "Consistent terminology makes the output predictable. Problems are reported as warnings, and the rule only runs if a glossary is configured.",
+    options: &[
+        ("rules.glossary.file", "Path to the glossary file, relative to the config file."),
+    ],
+    example_syntax: "yaml",
+    failing: "# glossary: step -> de: Schritt
+Running step:
+  en: Running step
+  de: Führe Stufe aus
+",
+    passing: "Running step:
+  en: Running step
+  de: Führe Schritt aus
+",
+};
+
+impl Rule for GlossaryTerms {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let Some(en) = &translations.en else {
+                continue;
+            };
+            let terms = self
+                .terms
+                .iter()
+                .filter(|term| term.regex.is_match(en))
+                .collect::<Vec<_>>();
+            if terms.is_empty() {
+                continue;
+            }
+
+            for (lang, translation) in translations.others.iter() {
+                let translation = translation.to_lowercase();
+                for term in terms.iter() {
+                    let Some(required) = term.required(lang) else {
+                        continue;
+                    };
+                    if translation.contains(&required.to_lowercase()) {
+                        continue;
+                    }
+
+                    let error_msg = if term.keep {
+                        format!("Glossary: \"{}\" should not be translated", term.en)
+                    } else {
+                        format!(
+                            "Glossary: \"{}\" should be translated as \"{}\"",
+                            term.en, required
+                        )
+                    };
+                    diagnostics.push(Diagnostic {
+                        location: None,
+                        key: key.clone(),
+                        error_msg: Some(error_msg),
+                        severity: Severity::Warning,
+                        langs: vec![Arc::clone(lang)],
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glossary::GlossaryTerm;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_rule_works() {
+        let glossary = Glossary {
+            terms: vec![
+                GlossaryTerm {
+                    en: "step".into(),
+                    keep: false,
+                    translations: HashMap::from([("de".into(), "Schritt".into())]),
+                },
+                GlossaryTerm {
+                    en: "Topgrade".into(),
+                    keep: true,
+                    translations: HashMap::new(),
+                },
+            ],
+        };
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Topgrade skipped the Step".into(),
+                    Translations {
+                        en: Some("Topgrade skipped the Step".into()),
+                        others: IndexMap::from([
+                            ("de".into(), "Topgrade hat den Schritt übersprungen".into()),
+                            ("fr".into(), "TopMise a sauté l'étape".into()),
+                        ]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Steps".into(),
+                    Translations {
+                        en: Some("Steps".into()),
+                        others: IndexMap::from([("de".into(), "Etappen".into())]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Run the step".into(),
+                    Translations {
+                        en: Some("Run the step".into()),
+                        others: IndexMap::from([("de".into(), "Führe die Stufe aus".into())]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let rule = GlossaryTerms::new(&glossary);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic {
+                location: None,
+                key: "Topgrade skipped the Step".into(),
+                error_msg: Some("Glossary: \"Topgrade\" should not be translated".into()),
+                severity: Severity::Warning,
+                langs: vec!["fr".into()],
+            },
+            Diagnostic {
+                location: None,
+                key: "Run the step".into(),
+                error_msg: Some("Glossary: \"step\" should be translated as \"Schritt\"".into()),
+                severity: Severity::Warning,
+                langs: vec!["de".into()],
+            },
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,203 @@
+//! A rule that checks if Topgrade prints user-facing English strings that are
+//! not passed through `t!()`.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity, SourceLocation};
+use crate::config::HardCodedStringsConfig;
+use crate::error::Error;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Returns the number of words in `text`, outside of format placeholders.
+pub(crate) fn count_words(text: &str) -> usize {
+    let mut n_words = 0;
+    let mut in_placeholder = false;
+    let mut word = String::new();
+    for c in text.chars().chain([' ']) {
+        match c {
+            '{' => in_placeholder = true,
+            '}' => in_placeholder = false,
+            _ if in_placeholder => {}
+            c if c.is_alphabetic() || c == '\'' => {
+                word.push(c);
+                continue;
+            }
+            _ => {}
+        }
+
+        if word.chars().any(char::is_alphabetic) {
+            n_words += 1;
+        }
+        word.clear();
+    }
+
+    n_words
+}
+
+/// Checks if any string literal passed to the functions and macros that print
+/// user-facing text, the sinks, looks like an English sentence, which should
+/// be passed through `t!()` instead.
+///
+/// A string literal looks like a sentence if it has enough words and matches
+/// none of the ignore patterns, e.g., URLs. As it is a heuristic, the rule is
+/// opt-in, the sinks and heuristics are configurable, literals can be
+/// allowlisted, and problems are reported as warnings.
+pub(crate) struct HardCodedStrings {
+    /// String literals that are fine to be hard-coded.
+    allowlist: HashSet<String>,
+    /// Functions and macros that print user-facing text.
+    sinks: HashSet<String>,
+    /// A string literal with fewer words than this is not a sentence.
+    min_words: usize,
+    /// String literals that match any of them are ignored.
+    ignore_patterns: Vec<Regex>,
+}
+
+impl HardCodedStrings {
+    /// Creates the rule with its `config`, fails if any of the ignore patterns
+    /// is not a valid regular expression.
+    pub(crate) fn new(config: &HardCodedStringsConfig) -> Result<Self, Error> {
+        let ignore_patterns = config
+            .ignore_patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    Error::Usage(format!(
+                        "invalid hard-coded string ignore pattern {:?} in `rules.hard_coded_strings.ignore_patterns`: {}",
+                        pattern, e
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            allowlist: config.allowlist.iter().cloned().collect(),
+            sinks: config.sinks.iter().cloned().collect(),
+            min_words: config.min_words,
+            ignore_patterns,
+        })
+    }
+
+    /// Returns true if `text` looks like a user-facing English sentence.
+    fn looks_like_sentence(&self, text: &str) -> bool {
+        !self.allowlist.contains(text)
+            && !self
+                .ignore_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(text))
+            && count_words(text) >= self.min_words
+    }
+}
+
+/// Documentation of [`HardCodedStrings`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "HardCodedStrings",
+    code: "I18N024",
+    description: "Checks if any string literal passed to the functions and macros that print user-facing text looks like an English sentence.",
+    rationale: "User-facing text should be passed through `t!()` so that it can be translated. As it is a heuristic, the rule is opt-in and problems are reported as warnings.",
+    options: &[
+        ("rules.hard_coded_strings.enabled", "The rule is disabled unless this is true."),
+        ("rules.hard_coded_strings.allowlist", "String literals that are fine to be hard-coded."),
+        ("rules.hard_coded_strings.sinks", "Functions and methods, or macros followed by `!`, that print user-facing text."),
+        ("rules.hard_coded_strings.min_words", "A string literal with fewer words is not considered a sentence, 2 by default."),
+        ("rules.hard_coded_strings.ignore_patterns", "Regular expressions, string literals that match any of them are ignored."),
+    ],
+    example_syntax: "rust",
+    failing: r#"println!("Updating the system");
+"#,
+    passing: r#"println!("{}", t!("Updating the system"));
+"#,
+};
+
+impl Rule for HardCodedStrings {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        ctx.sink_literals
+            .iter()
+            .filter(|literal| {
+                self.sinks.contains(&*literal.sink) && self.looks_like_sentence(&literal.text)
+            })
+            .map(|literal| Diagnostic {
+                location: Some(SourceLocation {
+                    file: Arc::clone(&literal.file),
+                    line: literal.line,
+                    column: literal.column,
+                }),
+                key: "".into(),
+                error_msg: Some(format!(
+                    "Hard-coded string {:?} passed to {}, it should be passed through t!()",
+                    literal.text, literal.sink
+                )),
+                severity: Severity::Warning,
+                langs: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::LocalizedTexts;
+    use crate::locale_key_collector::SinkLiteral;
+    use indexmap::IndexMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_looks_like_sentence() {
+        let rule = HardCodedStrings::new(&HardCodedStringsConfig::default()).unwrap();
+        assert!(rule.looks_like_sentence("Failed to update {app}"));
+        assert!(rule.looks_like_sentence("Don't panic"));
+        assert!(!rule.looks_like_sentence("{} {}"));
+        assert!(!rule.looks_like_sentence("{app}: {error}"));
+        assert!(!rule.looks_like_sentence("Summary"));
+        assert!(!rule.looks_like_sentence("See https://github.com/topgrade-rs"));
+        assert!(!rule.looks_like_sentence("brew --version"));
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::new(),
+        };
+        let sink_literal = |sink: &str, text: &str, line| SinkLiteral {
+            sink: sink.into(),
+            text: text.into(),
+            file: Path::new("foo.rs").into(),
+            line,
+            column: 4,
+        };
+        let sink_literals = vec![
+            sink_literal("println!", "Press any key to continue", 1),
+            sink_literal("println!", "Topgrade Summary", 2),
+            sink_literal("debug!", "Running the step", 3),
+            sink_literal("print_separator", "{}", 4),
+        ];
+        let rule = HardCodedStrings::new(&HardCodedStringsConfig {
+            allowlist: vec!["Topgrade Summary".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &sink_literals,
+        });
+        let expected_diagnostics = vec![Diagnostic {
+            location: Some(SourceLocation {
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 4,
+            }),
+            key: "".into(),
+            error_msg: Some(
+                "Hard-coded string \"Press any key to continue\" passed to println!, it should be passed through t!()"
+                    .into(),
+            ),
+            severity: Severity::Warning,
+            langs: Vec::new(),
+        }];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -1,270 +1,207 @@
-use super::Rule;
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use crate::config::{KeyEngMatchesConfig, KeyEngMatchesMode, PlaceholderSyntax, VariantsConfig};
 use crate::locale_file_parser::LocalizedTexts;
-use crate::locale_key_collector::LocaleKey;
-use parser::{LocaleKeyParser, LocaleToken};
-use std::collections::HashMap;
+use topgrade_i18n_locale_checker::key_parser::{placeholders_of_key, LocaleKeyParser, LocaleToken};
+use topgrade_i18n_locale_checker::placeholder::placeholders_of_translation;
 
 /// A rules that enforces a locale's key matches its English translation.
 ///
 /// This is not requested by rust-i18n (The i18n framework Topgrade uses), it is
 /// simply our convention.
-pub(crate) struct KeyEngMatches;
-
-impl Rule for KeyEngMatches {
-    fn check(
-        &self,
-        localized_texts: &LocalizedTexts,
-        _locale_keys: &[LocaleKey],
-        errors: &mut HashMap<String, Vec<(String, Option<String>)>>,
-    ) {
-        for (key, translations) in localized_texts.texts.iter() {
-            let en = &translations.en;
-
-            if en.is_none() {
-                Self::report_error(
-                    key.clone(),
-                    Some("Missing English translation".into()),
-                    errors,
-                );
-                return;
-            }
-
-            let mut parser = LocaleKeyParser::new();
-            parser.parse(key);
-            let expected = key_to_en(&parser);
-
-            let en = en.as_ref().unwrap();
-
-            if en != &expected {
-                Self::report_error(key.clone(), None, errors)
-            }
-        }
-    }
+///
+/// Placeholders are written as `{name}` in keys, and in the configured syntax
+/// in the English translations, e.g., `%{name}`.
+///
+/// In [`KeyEngMatchesMode::Placeholders`] mode, only the placeholders have to
+/// match, the wording can differ.
+///
+/// The English translation of a key variant, e.g., `Welcome@feminine`, should
+/// match the key it is a variant of, `Welcome`.
+pub(crate) struct KeyEngMatches {
+    /// How placeholders are written in translations.
+    syntax: PlaceholderSyntax,
+    /// Options of this rule.
+    config: KeyEngMatchesConfig,
+    /// How key variants are defined.
+    variants: VariantsConfig,
 }
 
-mod parser {
-    const LEFT_BRACE: &str = "{";
-    const RIGHT_BRACE: &str = "}";
-
-    /// A locale token in the key.
-    #[derive(Debug, PartialEq, Eq)]
-    pub(crate) enum LocaleToken<'token> {
-        /// It is not surrounded by a pair of braces
-        WithoutBrace(&'token str),
-        /// It is surrounded by a pair of braces
-        WithinBrace(&'token str),
+impl KeyEngMatches {
+    /// Creates the rule with the placeholder `syntax` used in translations, its
+    /// `config`, and the key `variants` convention.
+    pub(crate) fn new(
+        syntax: PlaceholderSyntax,
+        config: KeyEngMatchesConfig,
+        variants: VariantsConfig,
+    ) -> Self {
+        Self {
+            syntax,
+            config,
+            variants,
+        }
     }
 
-    /// Key parser.
-    #[derive(Debug, PartialEq, Eq)]
-    pub(crate) struct LocaleKeyParser<'input> {
-        /// tokens
-        tokens: Vec<LocaleToken<'input>>,
+    /// Returns the English translation expected of `key` in
+    /// [`KeyEngMatchesMode::Exact`] mode, derived from the key it is a variant
+    /// of if it is one.
+    fn expected_en(&self, key: &str) -> String {
+        derived_en(self.variants.split(key).0, self.syntax)
     }
 
-    impl<'input> LocaleKeyParser<'input> {
-        /// Create a parser with nothing.
-        pub(crate) fn new() -> Self {
-            Self { tokens: Vec::new() }
-        }
-
-        /// Accesses the parsed tokens.
-        pub(crate) fn tokens(&self) -> &[LocaleToken<'input>] {
-            &self.tokens
+    /// Returns `(Key, ExpectedEnglish)` of the keys of `localized_texts` whose
+    /// English translation is missing or differs from the expected one, which
+    /// can be fixed by writing the expected one back.
+    ///
+    /// Empty in [`KeyEngMatchesMode::Placeholders`] mode, where the wording is
+    /// up to the translator, plural keys are skipped as their English
+    /// translation is a set of forms.
+    pub(crate) fn english_fixes<'t>(
+        &self,
+        localized_texts: &'t LocalizedTexts,
+    ) -> Vec<(&'t str, String)> {
+        if self.config.mode != KeyEngMatchesMode::Exact {
+            return Vec::new();
         }
 
-        /// Parses the `input`, stores the parsed tokens in `self`.
-        pub(crate) fn parse<'slf>(&'slf mut self, input: &'input str) {
-            let len = input.len();
-            let mut start_offset = 0;
-
-            while start_offset < len {
-                let opt_left_brace_location = input[start_offset..].find(LEFT_BRACE);
-
-                match opt_left_brace_location {
-                    None => {
-                        self.tokens
-                            .push(LocaleToken::WithoutBrace(&input[start_offset..]));
-                        return;
-                    }
-                    Some(mut left_brace_location) => {
-                        left_brace_location += start_offset;
-
-                        let opt_right_brace_location =
-                            input[left_brace_location..].find(RIGHT_BRACE);
-
-                        match opt_right_brace_location {
-                            None => {
-                                self.tokens
-                                    .push(LocaleToken::WithoutBrace(&input[start_offset..]));
-                                return;
-                            }
-                            Some(mut right_brace_location) => {
-                                right_brace_location += left_brace_location;
-                                // handle the part without brace
-                                if left_brace_location != start_offset {
-                                    self.tokens.push(LocaleToken::WithoutBrace(
-                                        &input[start_offset..left_brace_location],
-                                    ));
-                                }
-
-                                self.tokens.push(LocaleToken::WithinBrace(
-                                    &input[left_brace_location + 1..=right_brace_location - 1],
-                                ));
-
-                                start_offset = right_brace_location + 1;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        localized_texts
+            .texts
+            .iter()
+            .filter(|(_, translations)| !translations.is_plural())
+            .filter_map(|(key, translations)| {
+                let expected = self.expected_en(key);
+                (translations.en.as_deref() != Some(expected.as_str())).then(|| (&**key, expected))
+            })
+            .collect()
     }
+}
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-
-        #[test]
-        fn no_brace() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("without_any_brace");
-
-            for token in parser.tokens() {
-                assert!(matches!(token, LocaleToken::WithoutBrace(_)));
-            }
-        }
-
-        #[test]
-        fn starts_with_brace() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("{brace}topgrade");
-
-            let expected = LocaleKeyParser {
-                tokens: vec![
-                    LocaleToken::WithinBrace("brace"),
-                    LocaleToken::WithoutBrace("topgrade"),
-                ],
-            };
-
-            assert_eq!(parser, expected);
-        }
-
-        #[test]
-        fn ends_with_brace() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("topgrade{brace}");
-
-            let expected = LocaleKeyParser {
-                tokens: vec![
-                    LocaleToken::WithoutBrace("topgrade"),
-                    LocaleToken::WithinBrace("brace"),
-                ],
-            };
-
-            assert_eq!(parser, expected);
-        }
-
-        #[test]
-        fn brace_in_the_middle() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("topgrade{brace}topgrade");
-
-            let expected = LocaleKeyParser {
-                tokens: vec![
-                    LocaleToken::WithoutBrace("topgrade"),
-                    LocaleToken::WithinBrace("brace"),
-                    LocaleToken::WithoutBrace("topgrade"),
-                ],
-            };
-
-            assert_eq!(parser, expected);
-        }
-
-        #[test]
-        fn continuous_braces() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("{brace}{brace}");
+/// Documentation of [`KeyEngMatches`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "KeyEngMatches",
+    code: "I18N002",
+    description: "Checks if the English translation of every key matches the key, with placeholders written in the configured syntax.",
+    rationale: "Keys are the English texts by convention, so reading a `t!()` call tells what users see. A drifted English translation makes the code lie.",
+    options: &[
+        ("rules.key_eng_matches.mode", "`exact` (default) compares the whole text, `placeholders` only compares the placeholders."),
+        ("rules.key_eng_matches.lenient_formatting", "In `exact` mode, report differences only in whitespace and trailing punctuation as warnings."),
+    ],
+    example_syntax: "yaml",
+    failing: r#""Restarting {app}":
+  en: Restarting the app
+"#,
+    passing: r#""Restarting {app}":
+  en: Restarting %{app}
+"#,
+};
 
-            let expected = LocaleKeyParser {
-                tokens: vec![
-                    LocaleToken::WithinBrace("brace"),
-                    LocaleToken::WithinBrace("brace"),
-                ],
-            };
+impl Rule for KeyEngMatches {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
 
-            assert_eq!(parser, expected);
-        }
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let en = &translations.en;
 
-        #[test]
-        fn continuous_braces_in_the_middle() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("topgrade{brace}{brace}topgrade");
-
-            let expected = LocaleKeyParser {
-                tokens: vec![
-                    LocaleToken::WithoutBrace("topgrade"),
-                    LocaleToken::WithinBrace("brace"),
-                    LocaleToken::WithinBrace("brace"),
-                    LocaleToken::WithoutBrace("topgrade"),
-                ],
-            };
-
-            assert_eq!(parser, expected);
-        }
+            if en.is_none() {
+                diagnostics.push(
+                    Diagnostic::error(key.clone(), Some("Missing English translation".into()))
+                        .with_langs(vec!["en".into()]),
+                );
+                return diagnostics;
+            }
 
-        #[test]
-        fn single_left_brace() {
+            // A variant is compared with the key it is a variant of
+            let (text_key, _) = self.variants.split(key);
             let mut parser = LocaleKeyParser::new();
-            parser.parse("{");
+            parser.parse(text_key);
 
-            let expected = LocaleKeyParser {
-                tokens: vec![LocaleToken::WithoutBrace("{")],
-            };
+            let en = en.as_ref().unwrap();
 
-            assert_eq!(parser, expected);
+            match self.config.mode {
+                KeyEngMatchesMode::Exact => {
+                    let expected = self.expected_en(key);
+                    if **en != *expected {
+                        let mut diagnostic =
+                            Diagnostic::error(key.clone(), None).with_langs(vec!["en".into()]);
+                        if self.config.lenient_formatting
+                            && normalize_formatting(en) == normalize_formatting(&expected)
+                        {
+                            diagnostic.severity = Severity::Warning;
+                            diagnostic.error_msg =
+                                Some("Differs only in whitespace or trailing punctuation".into());
+                        }
+                        diagnostics.push(diagnostic);
+                    }
+                }
+                KeyEngMatchesMode::Placeholders => {
+                    let mut expected = placeholders_of_key(text_key);
+                    expected.sort_unstable();
+                    let mut found = placeholders_of_translation(en, self.syntax);
+                    found.sort_unstable();
+                    if found != expected {
+                        let expected = expected
+                            .iter()
+                            .map(|placeholder| self.syntax.format(placeholder))
+                            .collect::<Vec<_>>();
+                        diagnostics.push(
+                            Diagnostic::error(
+                                key.clone(),
+                                Some(format!(
+                                    "English translation should contain exactly the placeholders [{}]",
+                                    expected.join(", ")
+                                )),
+                            )
+                            .with_langs(vec!["en".into()]),
+                        );
+                    }
+                }
+            }
         }
 
-        #[test]
-        fn mutliple_left_brace() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("x{x{x{");
+        diagnostics
+    }
+}
 
-            let expected = LocaleKeyParser {
-                tokens: vec![LocaleToken::WithoutBrace("x{x{x{")],
-            };
+/// Helper function to collapse runs of whitespace into a single space, and
+/// remove leading/trailing whitespace and trailing punctuation.
+fn normalize_formatting(text: &str) -> String {
+    const TRAILING_PUNCTUATION: [char; 7] = ['.', ',', ':', ';', '!', '?', '…'];
 
-            assert_eq!(parser, expected);
-        }
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
 
-        #[test]
-        fn a_pair_in_chaos() {
-            let mut parser = LocaleKeyParser::new();
-            parser.parse("}{x{x}{{x{");
+    collapsed
+        .trim_end_matches(|c: char| c.is_whitespace() || TRAILING_PUNCTUATION.contains(&c))
+        .to_string()
+}
 
-            let expected = LocaleKeyParser {
-                tokens: vec![
-                    LocaleToken::WithoutBrace("}"),
-                    LocaleToken::WithinBrace("x{x"),
-                    LocaleToken::WithoutBrace("{{x{"),
-                ],
-            };
+/// Returns the English translation derived from `key`, with placeholders
+/// written in `syntax`, i.e., what [`KeyEngMatches`] expects in
+/// [`KeyEngMatchesMode::Exact`] mode.
+pub(crate) fn derived_en(key: &str, syntax: PlaceholderSyntax) -> String {
+    let mut parser = LocaleKeyParser::new();
+    parser.parse(key);
 
-            assert_eq!(parser, expected);
-        }
-    }
+    key_to_en(&parser, syntax)
 }
 
 /// Helper function to convert a locale key to its English translation by
-/// prepending a `%` to the tokens serrounded by `{}`.
-fn key_to_en(parser: &parser::LocaleKeyParser<'_>) -> String {
+/// rendering the tokens serrounded by `{}` as placeholders in `syntax`, e.g.,
+/// prepending a `%` to them.
+///
+/// Escaped braces are literal braces, which are written as is with `%{name}`
+/// placeholders, and stay escaped with the other syntaxes, where a single
+/// brace would start a placeholder. Stray braces are written as is.
+fn key_to_en(parser: &LocaleKeyParser<'_>, syntax: PlaceholderSyntax) -> String {
     let mut ret = String::new();
     for token in parser.tokens() {
         match token {
-            LocaleToken::WithinBrace(str) => {
-                std::fmt::write(&mut ret, format_args!("%{{{}}}", str)).unwrap()
+            LocaleToken::WithinBrace(str) => ret.push_str(&syntax.format(str)),
+            LocaleToken::Escaped(brace) => {
+                ret.push_str(brace);
+                if syntax != PlaceholderSyntax::PercentBrace {
+                    ret.push_str(brace);
+                }
             }
-            LocaleToken::WithoutBrace(str) => {
+            LocaleToken::WithoutBrace(str) | LocaleToken::Stray(str) => {
                 std::fmt::write(&mut ret, format_args!("{}", str)).unwrap()
             }
         }
@@ -276,16 +213,37 @@ fn key_to_en(parser: &parser::LocaleKeyParser<'_>) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::locale_file_parser::Translations;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
     use indexmap::IndexMap;
-    use parser::LocaleKeyParser;
 
     #[test]
     fn preprend_percent_works() {
         let mut parser = LocaleKeyParser::new();
         parser.parse("hello, {topgrade}");
 
-        assert_eq!(key_to_en(&parser).as_str(), "hello, %{topgrade}");
+        assert_eq!(
+            key_to_en(&parser, PlaceholderSyntax::PercentBrace).as_str(),
+            "hello, %{topgrade}"
+        );
+        assert_eq!(
+            key_to_en(&parser, PlaceholderSyntax::Brace).as_str(),
+            "hello, {topgrade}"
+        );
+    }
+
+    #[test]
+    fn key_to_en_works_with_escaped_braces() {
+        let mut parser = LocaleKeyParser::new();
+        parser.parse("{{x}} {y}");
+
+        assert_eq!(
+            key_to_en(&parser, PlaceholderSyntax::PercentBrace).as_str(),
+            "{x} %{y}"
+        );
+        assert_eq!(
+            key_to_en(&parser, PlaceholderSyntax::Brace).as_str(),
+            "{{x}} {y}"
+        );
     }
 
     #[test]
@@ -293,25 +251,35 @@ mod tests {
         let mut parser = LocaleKeyParser::new();
         parser.parse("hello, topgrade");
 
-        assert_eq!(key_to_en(&parser).as_str(), "hello, topgrade");
+        assert_eq!(
+            key_to_en(&parser, PlaceholderSyntax::PercentBrace).as_str(),
+            "hello, topgrade"
+        );
     }
 
     #[test]
     fn test_rule_works_missing_en_translation() {
         let localized_texts = LocalizedTexts {
-            texts: IndexMap::from([("Restarting".into(), Translations { en: None })]),
+            texts: IndexMap::from([("Restarting".into(), Translations::default())]),
         };
-        let rule = KeyEngMatches;
-        let mut errors = HashMap::new();
-        rule.check(&localized_texts, &[], &mut errors);
-        let expected_errors = HashMap::from([(
-            <KeyEngMatches as Rule>::name().to_string(),
-            vec![(
-                "Restarting".to_string(),
-                Some("Missing English translation".into()),
-            )],
-        )]);
-        assert_eq!(errors, expected_errors);
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig::default(),
+            VariantsConfig::default(),
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic::error(
+            "Restarting".into(),
+            Some("Missing English translation".into()),
+        )
+        .with_langs(vec!["en".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
     }
 
     #[test]
@@ -321,17 +289,25 @@ mod tests {
                 "Restarting".into(),
                 Translations {
                     en: Some("buz".into()),
+                    ..Default::default()
                 },
             )]),
         };
-        let rule = KeyEngMatches;
-        let mut errors = HashMap::new();
-        rule.check(&localized_texts, &[], &mut errors);
-        let expected_errors = HashMap::from([(
-            <KeyEngMatches as Rule>::name().to_string(),
-            vec![("Restarting".to_string(), None)],
-        )]);
-        assert_eq!(errors, expected_errors);
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig::default(),
+            VariantsConfig::default(),
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics =
+            vec![Diagnostic::error("Restarting".into(), None).with_langs(vec!["en".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
     }
 
     #[test]
@@ -341,17 +317,156 @@ mod tests {
                 "Restarting {app}".into(),
                 Translations {
                     en: Some("Restarting {app}".into()),
+                    ..Default::default()
                 },
             )]),
         };
-        let rule = KeyEngMatches;
-        let mut errors = HashMap::new();
-        rule.check(&localized_texts, &[], &mut errors);
-        let expected_errors = HashMap::from([(
-            <KeyEngMatches as Rule>::name().to_string(),
-            vec![("Restarting {app}".to_string(), None)],
-        )]);
-        assert_eq!(errors, expected_errors);
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig::default(),
+            VariantsConfig::default(),
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics =
+            vec![Diagnostic::error("Restarting {app}".into(), None).with_langs(vec!["en".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_rule_works_with_variants() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Welcome {name}@feminine".into(),
+                    Translations {
+                        en: Some("Welcome %{name}".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Welcome {name}@masculine".into(),
+                    Translations {
+                        en: Some("Welcome".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig::default(),
+            VariantsConfig {
+                separator: Some("@".into()),
+                names: Vec::new(),
+            },
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic::error("Welcome {name}@masculine".into(), None)
+            .with_langs(vec!["en".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_rule_works_in_placeholders_mode() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting {app}".into(),
+                    Translations {
+                        en: Some("Now restarting %{app}".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating {app}".into(),
+                    Translations {
+                        en: Some("Updating %{application}".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig {
+                mode: KeyEngMatchesMode::Placeholders,
+                ..Default::default()
+            },
+            VariantsConfig::default(),
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic::error(
+            "Updating {app}".into(),
+            Some("English translation should contain exactly the placeholders [%{app}]".into()),
+        )
+        .with_langs(vec!["en".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_rule_works_with_lenient_formatting() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting {app}".into(),
+                    Translations {
+                        en: Some("Restarting  %{app}...".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating {app}".into(),
+                    Translations {
+                        en: Some("Upgrading %{app}".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig {
+                lenient_formatting: true,
+                ..Default::default()
+            },
+            VariantsConfig::default(),
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic {
+                location: None,
+                key: "Restarting {app}".into(),
+                error_msg: Some("Differs only in whitespace or trailing punctuation".into()),
+                severity: Severity::Warning,
+                langs: vec!["en".into()],
+            },
+            Diagnostic::error("Updating {app}".into(), None).with_langs(vec!["en".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
     }
 
     #[test]
@@ -361,27 +476,101 @@ mod tests {
                 "Restarting {app}".into(),
                 Translations {
                     en: Some("Restarting %{app}".into()),
+                    ..Default::default()
                 },
             )]),
         };
-        let rule = KeyEngMatches;
-        let mut errors = HashMap::new();
-        rule.check(&localized_texts, &[], &mut errors);
-        let expected_errors = HashMap::new();
-        assert_eq!(errors, expected_errors);
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig::default(),
+            VariantsConfig::default(),
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics: Vec<Diagnostic> = Vec::new();
+        assert_eq!(diagnostics, expected_diagnostics);
 
         let localized_texts = LocalizedTexts {
             texts: IndexMap::from([(
                 "Restarting".into(),
                 Translations {
                     en: Some("Restarting".into()),
+                    ..Default::default()
                 },
             )]),
         };
-        let rule = KeyEngMatches;
-        let mut errors = HashMap::new();
-        rule.check(&localized_texts, &[], &mut errors);
-        let expected_errors = HashMap::new();
-        assert_eq!(errors, expected_errors);
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig::default(),
+            VariantsConfig::default(),
+        );
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics: Vec<Diagnostic> = Vec::new();
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_english_fixes() {
+        let translations = |en: Option<&str>| Translations {
+            en: en.map(Into::into),
+            ..Default::default()
+        };
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                ("Restarting {app}".into(), translations(Some("Restart"))),
+                ("Pulling".into(), translations(None)),
+                ("Done".into(), translations(Some("Done"))),
+                ("Welcome@feminine".into(), translations(Some("Welcome!"))),
+                (
+                    "{count} updates".into(),
+                    Translations {
+                        plurals: IndexMap::from([(
+                            "en".into(),
+                            IndexMap::from([("one".into(), "One update".into())]),
+                        )]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let variants = VariantsConfig {
+            separator: Some("@".into()),
+            names: Vec::new(),
+        };
+
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig::default(),
+            variants.clone(),
+        );
+        assert_eq!(
+            rule.english_fixes(&localized_texts),
+            [
+                ("Restarting {app}", "Restarting %{app}".to_string()),
+                ("Pulling", "Pulling".to_string()),
+                ("Welcome@feminine", "Welcome".to_string()),
+            ]
+        );
+
+        let rule = KeyEngMatches::new(
+            PlaceholderSyntax::PercentBrace,
+            KeyEngMatchesConfig {
+                mode: KeyEngMatchesMode::Placeholders,
+                ..Default::default()
+            },
+            variants,
+        );
+        assert!(rule.english_fixes(&localized_texts).is_empty());
     }
 }
@@ -0,0 +1,275 @@
+//! A rule that checks if the markup in translations matches the English one.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// A markup token.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Markup<'text> {
+    /// `` ` ``
+    Backtick,
+    /// `**`
+    Bold,
+    /// `<name ...>`
+    OpenTag(&'text str),
+    /// `</name>`
+    CloseTag(&'text str),
+    /// `<name ... />`
+    SelfClosingTag(&'text str),
+}
+
+impl std::fmt::Display for Markup<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Markup::Backtick => write!(f, "`"),
+            Markup::Bold => write!(f, "**"),
+            Markup::OpenTag(name) => write!(f, "<{}>", name),
+            Markup::CloseTag(name) => write!(f, "</{}>", name),
+            Markup::SelfClosingTag(name) => write!(f, "<{}/>", name),
+        }
+    }
+}
+
+/// Returns the markup tokens in `text`, in the order they appear.
+///
+/// Tag attributes are ignored, a `<` that is not followed by a tag name (e.g.,
+/// `a < b`) is not markup, neither is anything in a code span (e.g.,
+/// `` `<path>` ``).
+fn markup_of(text: &str) -> Vec<Markup<'_>> {
+    let mut markup = Vec::new();
+    let mut rest = text;
+
+    while let Some(idx) = rest.find(['`', '*', '<']) {
+        rest = &rest[idx..];
+        if let Some(after) = rest.strip_prefix('`') {
+            markup.push(Markup::Backtick);
+            rest = after;
+            // Contents of a code span are literal
+            if let Some(end) = rest.find('`') {
+                markup.push(Markup::Backtick);
+                rest = &rest[end + 1..];
+            }
+        } else if let Some(after) = rest.strip_prefix("**") {
+            markup.push(Markup::Bold);
+            rest = after;
+        } else if let Some((tag, after)) = parse_tag(rest) {
+            markup.push(tag);
+            rest = after;
+        } else {
+            // A single `*` or `<` that is not markup
+            rest = &rest[1..];
+        }
+    }
+
+    markup
+}
+
+/// Parses the HTML-like tag at the start of `text`, returns the tag and the
+/// text after it.
+fn parse_tag(text: &str) -> Option<(Markup<'_>, &str)> {
+    let inner = text.strip_prefix('<')?;
+    let end = inner.find('>')?;
+    let (inner, after) = (&inner[..end], &inner[end + 1..]);
+
+    let (is_close, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let (is_self_closing, inner) = match inner.strip_suffix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let name_len = inner
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(inner.len());
+    let name = &inner[..name_len];
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let tag = match (is_close, is_self_closing) {
+        (false, false) => Markup::OpenTag(name),
+        (true, false) => Markup::CloseTag(name),
+        (false, true) => Markup::SelfClosingTag(name),
+        (true, true) => return None,
+    };
+
+    Some((tag, after))
+}
+
+/// Returns true if the `markup` is balanced: backticks and `**` are paired,
+/// and tags are properly closed.
+fn is_balanced(markup: &[Markup<'_>]) -> bool {
+    let mut stack = Vec::new();
+    for token in markup {
+        match token {
+            Markup::Backtick | Markup::Bold | Markup::OpenTag(_) => {
+                if stack.last() == Some(&token) && !matches!(token, Markup::OpenTag(_)) {
+                    stack.pop();
+                } else {
+                    stack.push(token);
+                }
+            }
+            Markup::CloseTag(name) => {
+                if stack.pop() != Some(&Markup::OpenTag(name)) {
+                    return false;
+                }
+            }
+            Markup::SelfClosingTag(_) => {}
+        }
+    }
+
+    stack.is_empty()
+}
+
+/// Counts the occurrences of every markup token.
+fn count<'text>(markup: &[Markup<'text>]) -> BTreeMap<Markup<'text>, usize> {
+    let mut counts = BTreeMap::new();
+    for token in markup {
+        *counts.entry(token.clone()).or_default() += 1;
+    }
+
+    counts
+}
+
+/// Formats `markup` as `` [`, **] ``.
+fn format_markup(markup: &[Markup<'_>]) -> String {
+    let markup = markup.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    format!("[{}]", markup.join(", "))
+}
+
+/// Checks if the markup (backticks, `**bold**`, HTML-like tags such as `<b>`)
+/// is balanced in every translation, and if every non-English translation
+/// uses the same markup as the English one.
+///
+/// Broken markup renders raw tags to users.
+pub(crate) struct MarkupParity;
+
+/// Documentation of [`MarkupParity`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "MarkupParity",
+    code: "I18N008",
+    description: "Checks if the markup, i.e., backticks, `**bold**` and HTML-like tags, is balanced in every translation and the same as in English.",
+    rationale: "Broken markup renders raw tags to users.",
+    options: &[],
+    example_syntax: "yaml",
+    failing: "Run `topgrade`:
+  en: Run `topgrade`
+  de: Führe `topgrade aus
+",
+    passing: "Run `topgrade`:
+  en: Run `topgrade`
+  de: Führe `topgrade` aus
+",
+};
+
+impl Rule for MarkupParity {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let en_markup = translations.en.as_deref().map(markup_of);
+
+            let en = translations.en.iter().map(|en| ("en".into(), en));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation));
+            for (lang, translation) in en.chain(others) {
+                let markup = markup_of(translation);
+
+                let error_msg = if !is_balanced(&markup) {
+                    format!("Unbalanced markup: {}", format_markup(&markup))
+                } else {
+                    match &en_markup {
+                        Some(en_markup) if count(en_markup) != count(&markup) => format!(
+                            "Markup does not match English: expected {}, found {}",
+                            format_markup(en_markup),
+                            format_markup(&markup)
+                        ),
+                        _ => continue,
+                    }
+                };
+
+                diagnostics
+                    .push(Diagnostic::error(key.clone(), Some(error_msg)).with_langs(vec![lang]));
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_markup_of() {
+        assert_eq!(
+            markup_of("Run `topgrade` **now**, a < b <br/> <a href=\"x\">link</a> 2 * 3"),
+            [
+                Markup::Backtick,
+                Markup::Backtick,
+                Markup::Bold,
+                Markup::Bold,
+                Markup::SelfClosingTag("br"),
+                Markup::OpenTag("a"),
+                Markup::CloseTag("a"),
+            ]
+        );
+        assert!(is_balanced(&markup_of("<b>`x`</b> **y**")));
+        assert_eq!(
+            markup_of("Use `<path>`"),
+            [Markup::Backtick, Markup::Backtick]
+        );
+        assert!(!is_balanced(&markup_of("<b>`x</b>`")));
+        assert!(!is_balanced(&markup_of("**y")));
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Run `topgrade` <b>now</b>".into(),
+                Translations {
+                    en: Some("Run `topgrade` <b>now</b>".into()),
+                    others: IndexMap::from([
+                        ("de".into(), "<b>Jetzt</b> `topgrade` ausführen".into()),
+                        ("fr".into(), "Lancez `topgrade <b>maintenant</b>".into()),
+                        ("es".into(), "Ejecute topgrade <b>ahora</b>".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = MarkupParity;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "Run `topgrade` <b>now</b>".into(),
+                Some("Unbalanced markup: [`, <b>, </b>]".into()),
+            )
+            .with_langs(vec!["fr".into()]),
+            Diagnostic::error(
+                "Run `topgrade` <b>now</b>".into(),
+                Some(
+                    "Markup does not match English: expected [`, `, <b>, </b>], found [<b>, </b>]"
+                        .into(),
+                ),
+            )
+            .with_langs(vec!["es".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -1,96 +1,147 @@
-use super::Rule;
-use crate::locale_file_parser::LocalizedTexts;
-use crate::locale_key_collector::LocaleKey;
-use bitflags::bitflags;
-use std::collections::HashMap;
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use crate::config::LanguageTiers;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
-bitflags! {
-    /// A bitflag represent the missing languages, every language would take 1 bit.
-    ///
-    /// When a language is missing, its bit will be set to 1.
-    #[derive(Debug, PartialEq, Eq)]
-    struct MissingLanguages: u8 {
-        const En = 0b_0000_0001;
+/// Returns a error message describing the missing languages.
+fn error_msg(missing_langs: &[Arc<str>]) -> String {
+    let mut str = String::from("Missing translations for ");
+    str.push('[');
+    for (idx, lang) in missing_langs.iter().enumerate() {
+        if idx != 0 {
+            str.push_str(", ");
+        }
+        if &**lang == "en" {
+            str.push_str("English")
+        } else {
+            str.push_str(lang)
+        }
     }
+    str.push(']');
+
+    str
 }
 
-impl MissingLanguages {
-    /// Returns a error message describing the missing languages.
-    fn error_msg(&self) -> String {
-        let mut str = String::from("Missing translations for ");
-        str.push('[');
-        for lang in self.iter() {
-            if lang == MissingLanguages::En {
-                str.push_str("English")
-            }
-        }
-        str.push(']');
+/// A rule that checks if there is any key that misses some translations.
+///
+/// A key misses a translation if it has no translation in a language that is
+/// used in the locale file. The severity depends on the tier of the missing
+/// language.
+pub(crate) struct MissingTranslations {
+    /// Language tiers.
+    tiers: LanguageTiers,
+}
 
-        str
+impl MissingTranslations {
+    /// Creates the rule with the given language `tiers`.
+    pub(crate) fn new(tiers: LanguageTiers) -> Self {
+        Self { tiers }
     }
 }
 
-/// A rule that checks if there is any key that misses some translations.
-pub(crate) struct MissingTranslations;
+/// Documentation of [`MissingTranslations`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "MissingTranslations",
+    code: "I18N001",
+    description: "Checks if any key misses translations in some of the languages used in the locale file.",
+    rationale: "Users of a language with a missing translation see the English text, or the key. Missing tier-1 languages are errors, tier-2 ones are warnings, others are informational.",
+    options: &[
+        ("languages.tiers.tier1", "Languages whose missing translations are errors, every language is tier 1 if no tier is configured."),
+        ("languages.tiers.tier2", "Languages whose missing translations are warnings."),
+    ],
+    example_syntax: "yaml",
+    failing: "Restarting:
+  en: Restarting
+  # de is used by other keys but missing here
+",
+    passing: "Restarting:
+  en: Restarting
+  de: Neustart
+",
+};
 
 impl Rule for MissingTranslations {
-    fn check(
-        &self,
-        localized_texts: &LocalizedTexts,
-        _locale_keys: &[LocaleKey],
-        errors: &mut HashMap<String, Vec<(String, Option<String>)>>,
-    ) {
-        for (key, translations) in localized_texts.texts.iter() {
-            let mut missing_langs = MissingLanguages::empty();
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let languages = ctx.localized_texts.languages();
 
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            // Missing languages grouped by severity
+            let mut missing_langs: BTreeMap<Severity, Vec<Arc<str>>> = BTreeMap::new();
+
+            // English is always checked, even if no key has it.
             if translations.en.is_none() {
-                missing_langs.insert(MissingLanguages::En);
+                missing_langs
+                    .entry(self.tiers.severity_of("en"))
+                    .or_default()
+                    .push("en".into());
+            }
+            for lang in languages.iter().filter(|lang| &***lang != "en") {
+                if !translations.others.contains_key(lang) {
+                    missing_langs
+                        .entry(self.tiers.severity_of(lang))
+                        .or_default()
+                        .push(Arc::clone(lang));
+                }
             }
 
-            if !missing_langs.is_empty() {
-                Self::report_error(key.clone(), Some(missing_langs.error_msg()), errors);
+            for (severity, langs) in missing_langs {
+                diagnostics.push(Diagnostic {
+                    location: None,
+                    key: key.clone(),
+                    error_msg: Some(error_msg(&langs)),
+                    severity,
+                    langs,
+                });
             }
         }
+
+        diagnostics
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::locale_file_parser::Translations;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
     use indexmap::IndexMap;
 
     #[test]
     fn test_missing_en() {
         let localized_texts = LocalizedTexts {
             texts: IndexMap::from([
-                ("Restarting {app}".into(), Translations { en: None }),
-                ("Restarting {topgrade}".into(), Translations { en: None }),
+                ("Restarting {app}".into(), Translations::default()),
+                ("Restarting {topgrade}".into(), Translations::default()),
                 (
                     "Restarting {ba}".into(),
                     Translations {
                         en: Some("Restarting %{ba}".into()),
+                        ..Default::default()
                     },
                 ),
             ]),
         };
-        let mut errors = HashMap::new();
-        let rule = MissingTranslations;
-        rule.check(&localized_texts, &[], &mut errors);
-        let expected_errors = HashMap::from([(
-            <MissingTranslations as Rule>::name().to_string(),
-            vec![
-                (
-                    "Restarting {app}".to_string(),
-                    Some("Missing translations for [English]".into()),
-                ),
-                (
-                    "Restarting {topgrade}".to_string(),
-                    Some("Missing translations for [English]".into()),
-                ),
-            ],
-        )]);
-        assert_eq!(errors, expected_errors);
+        let rule = MissingTranslations::new(LanguageTiers::default());
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "Restarting {app}".into(),
+                Some("Missing translations for [English]".into()),
+            )
+            .with_langs(vec!["en".into()]),
+            Diagnostic::error(
+                "Restarting {topgrade}".into(),
+                Some("Missing translations for [English]".into()),
+            )
+            .with_langs(vec!["en".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
     }
 
     #[test]
@@ -101,26 +152,95 @@ mod tests {
                     "Restarting {app}".into(),
                     Translations {
                         en: Some("whatever".into()),
+                        ..Default::default()
                     },
                 ),
                 (
                     "Restarting {topgrade}".into(),
                     Translations {
                         en: Some("wahtever".into()),
+                        ..Default::default()
                     },
                 ),
                 (
                     "Restarting {ba}".into(),
                     Translations {
                         en: Some("Restarting %{ba}".into()),
+                        ..Default::default()
                     },
                 ),
             ]),
         };
-        let mut errors = HashMap::new();
-        let rule = MissingTranslations;
-        rule.check(&localized_texts, &[], &mut errors);
-        let expected_errors = HashMap::new();
-        assert_eq!(errors, expected_errors);
+        let rule = MissingTranslations::new(LanguageTiers::default());
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics: Vec<Diagnostic> = Vec::new();
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_missing_translations_respect_tiers() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting".into(),
+                    Translations {
+                        en: Some("Restarting".into()),
+                        others: IndexMap::from([
+                            ("de".into(), "Neustart".into()),
+                            ("zh_CN".into(), "重启".into()),
+                            ("fr".into(), "Redémarrage".into()),
+                        ]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating".into(),
+                    Translations {
+                        en: Some("Updating".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let tiers = LanguageTiers {
+            tier1: vec!["en".into(), "zh_CN".into()],
+            tier2: vec!["de".into()],
+        };
+        let rule = MissingTranslations::new(tiers);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "Updating".into(),
+                Some("Missing translations for [zh_CN]".into()),
+            )
+            .with_langs(vec!["zh_CN".into()]),
+            Diagnostic {
+                location: None,
+                key: "Updating".into(),
+                error_msg: Some("Missing translations for [de]".into()),
+                severity: Severity::Warning,
+                langs: vec!["de".into()],
+            },
+            Diagnostic {
+                location: None,
+                key: "Updating".into(),
+                error_msg: Some("Missing translations for [fr]".into()),
+                severity: Severity::Info,
+                langs: vec!["fr".into()],
+            },
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
     }
 }
@@ -1,15 +1,248 @@
+pub(crate) mod ambiguous_braces;
+pub(crate) mod ansi_escape_sequences;
+pub(crate) mod bidi_control_characters;
+pub(crate) mod concatenated_translations;
+pub(crate) mod emoji_policy;
+pub(crate) mod error_messages;
+pub(crate) mod glossary_terms;
+pub(crate) mod hard_coded_strings;
 pub(crate) mod key_and_eng_matches;
+pub(crate) mod markup_parity;
 pub(crate) mod missing_translations;
+pub(crate) mod numbers_and_units;
+pub(crate) mod percent_brace_in_keys;
+pub(crate) mod placeholder_consistency;
+pub(crate) mod plural_categories;
+pub(crate) mod pseudo_text;
+pub(crate) mod quoting_style;
+pub(crate) mod raw_braces_in_english;
+pub(crate) mod stub_translations;
+pub(crate) mod suspicious_whitespace;
+pub(crate) mod too_many_placeholders;
+pub(crate) mod trailing_newline;
+pub(crate) mod unicode_normalization;
+pub(crate) mod unused_locale_keys;
+pub(crate) mod urls_preserved;
 pub(crate) mod use_of_keys_do_not_exist;
+pub(crate) mod use_of_locales_do_not_exist;
+pub(crate) mod words_around_translations;
 
-use crate::LocalizedTexts;
-use std::collections::{hash_map::Entry, HashMap};
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::{Concatenation, LocaleKey, SetLocaleCall, SinkLiteral};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Errors found by the rules, in the order the rules are registered.
+///
+/// `IndexMap<RuleName, Vec<Diagnostic>>`
+pub(crate) type Errors = IndexMap<&'static str, Vec<Diagnostic>>;
+
+/// Documentation of every rule, in the order the rules are introduced, so
+/// that their codes are sorted.
+pub(crate) const RULES: [&RuleDoc; 28] = [
+    &missing_translations::DOC,
+    &key_and_eng_matches::DOC,
+    &use_of_keys_do_not_exist::DOC,
+    &use_of_locales_do_not_exist::DOC,
+    &bidi_control_characters::DOC,
+    &unicode_normalization::DOC,
+    &placeholder_consistency::DOC,
+    &markup_parity::DOC,
+    &numbers_and_units::DOC,
+    &urls_preserved::DOC,
+    &percent_brace_in_keys::DOC,
+    &raw_braces_in_english::DOC,
+    &stub_translations::DOC,
+    &pseudo_text::DOC,
+    &too_many_placeholders::DOC,
+    &suspicious_whitespace::DOC,
+    &quoting_style::DOC,
+    &ansi_escape_sequences::DOC,
+    &trailing_newline::DOC,
+    &emoji_policy::DOC,
+    &glossary_terms::DOC,
+    &concatenated_translations::DOC,
+    &words_around_translations::DOC,
+    &hard_coded_strings::DOC,
+    &error_messages::DOC,
+    &ambiguous_braces::DOC,
+    &plural_categories::DOC,
+    &unused_locale_keys::DOC,
+];
+
+/// Documentation of a rule, rendered by the `docs` subcommand.
+///
+/// Every rule defines its documentation as `DOC` next to its implementation,
+/// so that the published documentation is generated from the code.
+#[derive(Debug)]
+pub(crate) struct RuleDoc {
+    /// Name of the rule, same as [`Rule::name()`].
+    pub(crate) name: &'static str,
+    /// Stable code of the rule, e.g., `I18N003`.
+    ///
+    /// Codes are meant to be referenced from outside, e.g., in editors, so a
+    /// code is never changed or reused, new rules get new codes.
+    pub(crate) code: &'static str,
+    /// What the rule checks, in a sentence.
+    pub(crate) description: &'static str,
+    /// Why the rule exists.
+    pub(crate) rationale: &'static str,
+    /// Config options of the rule: `(Option, Description)`.
+    pub(crate) options: &'static [(&'static str, &'static str)],
+    /// Language of the examples, `yaml` for the locale file, `rust` for the
+    /// Rust files.
+    pub(crate) example_syntax: &'static str,
+    /// An example that the rule reports.
+    pub(crate) failing: &'static str,
+    /// The fixed example, which passes the rule.
+    pub(crate) passing: &'static str,
+}
+
+/// Returns the code of `rule`, see [`RuleDoc::code`].
+pub(crate) fn code_of(rule: &str) -> &'static str {
+    RULES
+        .iter()
+        .find(|doc| doc.name == rule)
+        .map(|doc| doc.code)
+        .unwrap_or_else(|| panic!("rule {} has no documentation", rule))
+}
+
+/// Everything a rule can inspect.
+///
+/// Rules only have immutable access to it, so they can run concurrently.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CheckContext<'a> {
+    /// The parsed locale file.
+    pub(crate) localized_texts: &'a LocalizedTexts,
+    /// The `t!()` invocations found in the Rust files.
+    pub(crate) locale_keys: &'a [LocaleKey],
+    /// The `set_locale()` calls found in the Rust files.
+    pub(crate) set_locale_calls: &'a [SetLocaleCall],
+    /// The concatenations around `t!()` results found in the Rust files.
+    pub(crate) concatenations: &'a [Concatenation],
+    /// The string literals passed to functions and macros found in the Rust
+    /// files.
+    pub(crate) sink_literals: &'a [SinkLiteral],
+}
+
+impl<'a> CheckContext<'a> {
+    /// Returns the `t!()` invocations that rules about them check, i.e., the
+    /// ones not ignored with an `// i18n-checker: ignore` comment.
+    pub(crate) fn checked_locale_keys(&self) -> impl Iterator<Item = &'a LocaleKey> {
+        self.locale_keys
+            .iter()
+            .filter(|locale_key| !locale_key.ignored)
+    }
+}
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    /// Fails the check.
+    Error,
+    /// Printed, but does not fail the check.
+    Warning,
+    /// For information only.
+    Info,
+}
+
+impl Severity {
+    /// Returns the lowercase name of this severity.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+/// Where a [`Diagnostic`] found in the Rust files points to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct SourceLocation {
+    /// The Rust file.
+    pub(crate) file: Arc<Path>,
+    /// Line number, starts from 1.
+    pub(crate) line: usize,
+    /// Column number, starts from 0.
+    pub(crate) column: usize,
+}
+
+/// Something found by a rule.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Diagnostic {
+    /// Where it is found in the Rust files, `None` if it is about the locale
+    /// file.
+    pub(crate) location: Option<SourceLocation>,
+    /// The locale key, or whatever the rule uses to identify the problem, can
+    /// be empty if `location` is enough, e.g., a hard-coded string.
+    pub(crate) key: Arc<str>,
+    /// Optional message that will be reported to users.
+    pub(crate) error_msg: Option<String>,
+    /// Severity.
+    pub(crate) severity: Severity,
+    /// Languages affected, empty if it is not about any specific language.
+    pub(crate) langs: Vec<Arc<str>>,
+}
+
+impl Diagnostic {
+    /// Creates a [`Diagnostic`] with severity [`Severity::Error`].
+    pub(crate) fn error(key: Arc<str>, error_msg: Option<String>) -> Self {
+        Self {
+            location: None,
+            key,
+            error_msg,
+            severity: Severity::Error,
+            langs: Vec::new(),
+        }
+    }
+
+    /// Sets the affected languages.
+    pub(crate) fn with_langs(mut self, langs: Vec<Arc<str>>) -> Self {
+        self.langs = langs;
+        self
+    }
+
+    /// Points it at `line` and `column` of the Rust file `file`, see
+    /// [`SourceLocation`].
+    pub(crate) fn at(mut self, file: Arc<Path>, line: usize, column: usize) -> Self {
+        self.location = Some(SourceLocation { file, line, column });
+        self
+    }
+
+    /// Returns what it is about in the human-readable reports: the key,
+    /// prefixed with the location in the Rust files if any, e.g.,
+    /// `file 'src/main.rs' / line '1' / column '0' / key 'Restarting'`.
+    pub(crate) fn headline(&self) -> Cow<'_, str> {
+        let Some(location) = &self.location else {
+            return Cow::Borrowed(&self.key);
+        };
+
+        let mut headline = format!(
+            "file '{}' / line '{}' / column '{}'",
+            location.file.display(),
+            location.line,
+            location.column
+        );
+        if !self.key.is_empty() {
+            write!(headline, " / key '{}'", self.key).unwrap();
+        }
+
+        Cow::Owned(headline)
+    }
+}
 
 /// Represents a rule that Topgrade's locale file should obey.
 ///
-/// Implementations should implement the [`check()`] method, and invoke
-/// [`report_error()`] when find any errors.
-pub(crate) trait Rule {
+/// Implementations should implement the [`check()`] method, and return the
+/// diagnostics found. Rules are independent of each other and may run
+/// concurrently.
+pub(crate) trait Rule: Send + Sync {
     /// Name of this rule.
     fn name() -> &'static str
     where
@@ -23,31 +256,124 @@ pub(crate) trait Rule {
         }
     }
 
-    /// Implementations should invoke this when found an error.
-    ///
-    /// When `error_msg` is `Some`, it will be stored and reported to users as well.
-    fn report_error(
-        key: String,
-        error_msg: Option<String>,
-        errors: &mut HashMap<String, Vec<(String, Option<String>)>>,
-    ) where
-        Self: Sized, // remove it from the vtable to make `trait Rule` object safe.
-    {
-        match errors.entry(Self::name().to_string()) {
-            Entry::Occupied(mut o) => {
-                o.get_mut().push((key, error_msg));
-            }
-            Entry::Vacant(v) => {
-                v.insert(vec![(key, error_msg)]);
-            }
+    /// Begin the check, returns the diagnostics found.
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_rule_docs() {
+        let names = RULES.iter().map(|doc| doc.name).collect::<HashSet<_>>();
+        assert_eq!(names.len(), RULES.len());
+
+        for (idx, doc) in RULES.iter().enumerate() {
+            assert_eq!(doc.code, format!("I18N{:03}", idx + 1));
+            assert!(matches!(doc.example_syntax, "yaml" | "rust"));
+        }
+
+        for (doc, name) in [
+            (
+                missing_translations::DOC,
+                missing_translations::MissingTranslations::name(),
+            ),
+            (
+                key_and_eng_matches::DOC,
+                key_and_eng_matches::KeyEngMatches::name(),
+            ),
+            (
+                use_of_keys_do_not_exist::DOC,
+                use_of_keys_do_not_exist::UseOfKeysDoNotExist::name(),
+            ),
+            (
+                use_of_locales_do_not_exist::DOC,
+                use_of_locales_do_not_exist::UseOfLocalesDoNotExist::name(),
+            ),
+            (
+                bidi_control_characters::DOC,
+                bidi_control_characters::BidiControlCharacters::name(),
+            ),
+            (
+                unicode_normalization::DOC,
+                unicode_normalization::UnicodeNormalization::name(),
+            ),
+            (
+                placeholder_consistency::DOC,
+                placeholder_consistency::PlaceholderConsistency::name(),
+            ),
+            (markup_parity::DOC, markup_parity::MarkupParity::name()),
+            (
+                numbers_and_units::DOC,
+                numbers_and_units::NumbersAndUnits::name(),
+            ),
+            (urls_preserved::DOC, urls_preserved::UrlsPreserved::name()),
+            (
+                percent_brace_in_keys::DOC,
+                percent_brace_in_keys::PercentBraceInKeys::name(),
+            ),
+            (
+                raw_braces_in_english::DOC,
+                raw_braces_in_english::RawBracesInEnglish::name(),
+            ),
+            (
+                stub_translations::DOC,
+                stub_translations::StubTranslations::name(),
+            ),
+            (pseudo_text::DOC, pseudo_text::PseudoText::name()),
+            (
+                too_many_placeholders::DOC,
+                too_many_placeholders::TooManyPlaceholders::name(),
+            ),
+            (
+                suspicious_whitespace::DOC,
+                suspicious_whitespace::SuspiciousWhitespace::name(),
+            ),
+            (quoting_style::DOC, quoting_style::QuotingStyle::name()),
+            (
+                ansi_escape_sequences::DOC,
+                ansi_escape_sequences::AnsiEscapeSequences::name(),
+            ),
+            (
+                trailing_newline::DOC,
+                trailing_newline::TrailingNewline::name(),
+            ),
+            (emoji_policy::DOC, emoji_policy::EmojiPolicy::name()),
+            (glossary_terms::DOC, glossary_terms::GlossaryTerms::name()),
+            (
+                concatenated_translations::DOC,
+                concatenated_translations::ConcatenatedTranslations::name(),
+            ),
+            (
+                words_around_translations::DOC,
+                words_around_translations::WordsAroundTranslations::name(),
+            ),
+            (
+                hard_coded_strings::DOC,
+                hard_coded_strings::HardCodedStrings::name(),
+            ),
+            (error_messages::DOC, error_messages::ErrorMessages::name()),
+            (
+                ambiguous_braces::DOC,
+                ambiguous_braces::AmbiguousBraces::name(),
+            ),
+            (
+                plural_categories::DOC,
+                plural_categories::PluralCategories::name(),
+            ),
+            (
+                unused_locale_keys::DOC,
+                unused_locale_keys::UnusedLocaleKeys::name(),
+            ),
+        ] {
+            assert_eq!(doc.name, name);
         }
     }
 
-    /// Begin the check.
-    fn check(
-        &self,
-        localized_texts: &LocalizedTexts,
-        locale_keys: &[crate::locale_key_collector::LocaleKey],
-        errors: &mut HashMap<String, Vec<(String, Option<String>)>>,
-    );
+    #[test]
+    fn test_code_of() {
+        assert_eq!(code_of("UseOfKeysDoNotExist"), "I18N003");
+    }
 }
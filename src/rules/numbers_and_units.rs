@@ -0,0 +1,188 @@
+//! A rule that checks if the numbers and units in English are preserved in the
+//! other translations.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use std::sync::Arc;
+
+/// Unit tokens that should not be translated.
+const UNITS: [&str; 16] = [
+    "B", "KB", "MB", "GB", "TB", "KiB", "MiB", "GiB", "TiB", "ms", "ns", "Hz", "kHz", "MHz", "GHz",
+    "%",
+];
+
+/// Returns the numbers and units in `text`, sorted.
+///
+/// A number is a run of ASCII digits that is not part of a word (e.g., `x86`
+/// is not a number), and may contain a decimal separator, `1,5` and `1.5` are
+/// considered equal. Units are the ones in [`UNITS`], as whole words.
+fn numbers_and_units(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let chars = text.char_indices().collect::<Vec<_>>();
+    let mut idx = 0;
+    while idx < chars.len() {
+        let (start, c) = chars[idx];
+        let prev_is_word_char = idx > 0 && is_word_char(chars[idx - 1].1);
+
+        if c.is_ascii_digit() && !prev_is_word_char {
+            let mut number = String::new();
+            while idx < chars.len() {
+                let c = chars[idx].1;
+                let is_separator = (c == '.' || c == ',')
+                    && chars.get(idx + 1).is_some_and(|(_, c)| c.is_ascii_digit());
+                if c.is_ascii_digit() {
+                    number.push(c);
+                } else if is_separator {
+                    number.push('.');
+                } else {
+                    break;
+                }
+                idx += 1;
+            }
+            // Part of a word, e.g., `2nd`
+            if !chars.get(idx).is_some_and(|(_, c)| c.is_alphabetic()) {
+                found.push(number);
+            }
+            continue;
+        }
+
+        if c == '%' {
+            // Not the start of a `%{placeholder}`
+            if chars.get(idx + 1).map(|(_, c)| *c) != Some('{') {
+                found.push(c.to_string());
+            }
+            idx += 1;
+            continue;
+        }
+
+        if is_word_char(c) && !prev_is_word_char {
+            let end = chars[idx..]
+                .iter()
+                .position(|(_, c)| !is_word_char(*c))
+                .map_or(chars.len(), |len| idx + len);
+            let end_byte = chars.get(end).map_or(text.len(), |(offset, _)| *offset);
+            let word = &text[start..end_byte];
+            if UNITS.contains(&word) {
+                found.push(word.to_string());
+            }
+            idx = end;
+            continue;
+        }
+
+        idx += 1;
+    }
+
+    found.sort();
+    found
+}
+
+/// Checks if every non-English translation contains the same numbers and
+/// units (e.g., `5`, `MB`) as the English translation, a cheap detector for
+/// semantically broken translations.
+///
+/// As it is a heuristic, problems are reported as warnings.
+pub(crate) struct NumbersAndUnits;
+
+/// Documentation of [`NumbersAndUnits`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "NumbersAndUnits",
+    code: "I18N009",
+    description: "Checks if every non-English translation contains the same numbers and units as the English translation.",
+    rationale: "It is a cheap detector for semantically broken translations. As it is a heuristic, problems are reported as warnings.",
+    options: &[],
+    example_syntax: "yaml",
+    failing: "Waiting 5 seconds:
+  en: Waiting 5 seconds
+  de: Warte 3 Sekunden
+",
+    passing: "Waiting 5 seconds:
+  en: Waiting 5 seconds
+  de: Warte 5 Sekunden
+",
+};
+
+impl Rule for NumbersAndUnits {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let Some(en) = &translations.en else {
+                continue;
+            };
+            let expected = numbers_and_units(en);
+
+            for (lang, translation) in translations.others.iter() {
+                let found = numbers_and_units(translation);
+                if found != expected {
+                    diagnostics.push(Diagnostic {
+                        location: None,
+                        key: key.clone(),
+                        error_msg: Some(format!(
+                            "Numbers or units differ from English: expected [{}], found [{}]",
+                            expected.join(", "),
+                            found.join(", ")
+                        )),
+                        severity: Severity::Warning,
+                        langs: vec![Arc::clone(lang)],
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_numbers_and_units() {
+        assert_eq!(
+            numbers_and_units("Waiting 5 seconds, 1.5 MB left, 100 % done"),
+            ["%", "1.5", "100", "5", "MB"]
+        );
+        assert_eq!(numbers_and_units("1,5 MB"), ["1.5", "MB"]);
+        assert!(numbers_and_units("x86 2nd MBs B2 %{app}").is_empty());
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Waiting 5 seconds".into(),
+                Translations {
+                    en: Some("Waiting 5 seconds, 1.5 MB".into()),
+                    others: IndexMap::from([
+                        ("de".into(), "Warte 5 Sekunden, 1,5 MB".into()),
+                        ("fr".into(), "Attendre 50 secondes, 1,5 Mo".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = NumbersAndUnits;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic {
+            location: None,
+            key: "Waiting 5 seconds".into(),
+            error_msg: Some(
+                "Numbers or units differ from English: expected [1.5, 5, MB], found [1.5, 50]"
+                    .into(),
+            ),
+            severity: Severity::Warning,
+            langs: vec!["fr".into()],
+        }];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
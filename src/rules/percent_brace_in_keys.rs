@@ -0,0 +1,100 @@
+//! A rule that checks if any key contains the `%{...}` placeholder syntax.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::config::PlaceholderSyntax;
+use topgrade_i18n_locale_checker::placeholder::placeholders_of_translation;
+
+/// Checks if any key contains `%{...}`.
+///
+/// Placeholders in keys are written as `{...}`, a key with `%{...}` is usually
+/// the English text pasted into the key position, from which [`KeyEngMatches`]
+/// derives nonsense, and the call sites won't match.
+///
+/// [`KeyEngMatches`]: super::key_and_eng_matches::KeyEngMatches
+pub(crate) struct PercentBraceInKeys;
+
+/// Documentation of [`PercentBraceInKeys`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "PercentBraceInKeys",
+    code: "I18N011",
+    description: "Checks if any key contains `%{...}`.",
+    rationale: "Placeholders in keys are written as `{...}`, a key with `%{...}` is usually the English text pasted into the key position, the call sites won't match it.",
+    options: &[],
+    example_syntax: "yaml",
+    failing: r#""Restarting %{app}":
+  en: Restarting %{app}
+"#,
+    passing: r#""Restarting {app}":
+  en: Restarting %{app}
+"#,
+};
+
+impl Rule for PercentBraceInKeys {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        ctx.localized_texts
+            .texts
+            .keys()
+            .filter_map(|key| {
+                let placeholders = placeholders_of_translation(key, PlaceholderSyntax::PercentBrace);
+                if placeholders.is_empty() {
+                    return None;
+                }
+
+                let placeholders = placeholders
+                    .iter()
+                    .map(|placeholder| PlaceholderSyntax::PercentBrace.format(placeholder))
+                    .collect::<Vec<_>>();
+                Some(Diagnostic::error(
+                    key.clone(),
+                    Some(format!(
+                        "The key contains [{}], placeholders in keys should be written as `{{...}}`",
+                        placeholders.join(", ")
+                    )),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                ("Restarting %{app}".into(), Translations::default()),
+                ("Restarting {app}".into(), Translations::default()),
+                ("100%{ok}".into(), Translations::default()),
+            ]),
+        };
+        let rule = PercentBraceInKeys;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "Restarting %{app}".into(),
+                Some(
+                    "The key contains [%{app}], placeholders in keys should be written as `{...}`"
+                        .into(),
+                ),
+            ),
+            Diagnostic::error(
+                "100%{ok}".into(),
+                Some(
+                    "The key contains [%{ok}], placeholders in keys should be written as `{...}`"
+                        .into(),
+                ),
+            ),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
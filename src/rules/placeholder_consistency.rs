@@ -0,0 +1,236 @@
+//! A rule that checks if translations use the same placeholders as English.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use crate::config::PlaceholderSyntax;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use topgrade_i18n_locale_checker::key_parser::placeholders_of_key;
+use topgrade_i18n_locale_checker::placeholder::placeholders_of_translation;
+
+/// Counts the occurrences of every placeholder.
+fn count(placeholders: &[&str]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for placeholder in placeholders {
+        *counts.entry(placeholder.to_string()).or_default() += 1;
+    }
+
+    counts
+}
+
+/// Formats `placeholders` as `[%{a}, %{b}]`, in `syntax`.
+fn format_placeholders(placeholders: &[&str], syntax: PlaceholderSyntax) -> String {
+    let placeholders = placeholders
+        .iter()
+        .map(|placeholder| syntax.format(placeholder))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", placeholders.join(", "))
+}
+
+/// Checks if every non-English translation uses the same placeholders as the
+/// English translation.
+///
+/// Placeholders are compared as multisets:
+///
+/// * A translation that drops, repeats, or adds a placeholder is an error.
+/// * A translation that uses the same placeholders in a different order is
+///   fine, word order differs between languages, it is reported as
+///   informational.
+///
+/// Translations of a key without an English translation are compared with the
+/// placeholders of the key, which rust-i18n falls back to.
+pub(crate) struct PlaceholderConsistency {
+    /// How placeholders are written in translations.
+    syntax: PlaceholderSyntax,
+}
+
+impl PlaceholderConsistency {
+    /// Creates the rule with the placeholder `syntax` used in translations.
+    pub(crate) fn new(syntax: PlaceholderSyntax) -> Self {
+        Self { syntax }
+    }
+}
+
+/// Documentation of [`PlaceholderConsistency`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "PlaceholderConsistency",
+    code: "I18N007",
+    description: "Checks if every non-English translation uses the same placeholders as the English translation, or as the key if there is none.",
+    rationale: "A dropped placeholder loses information, an added or misspelled one is printed literally. Reordered placeholders are fine as word order differs between languages, they are reported as informational.",
+    options: &[
+        ("placeholders.syntax", "How placeholders are written in translations, `%{name}` (default), `{name}` or `{0}`."),
+    ],
+    example_syntax: "yaml",
+    failing: r#""Restarting {app}":
+  en: Restarting %{app}
+  de: Neustart
+"#,
+    passing: r#""Restarting {app}":
+  en: Restarting %{app}
+  de: "%{app} wird neu gestartet"
+"#,
+};
+
+impl Rule for PlaceholderConsistency {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let en_placeholders = match &translations.en {
+                Some(en) => placeholders_of_translation(en, self.syntax),
+                None => placeholders_of_key(key),
+            };
+            let en_counts = count(&en_placeholders);
+
+            for (lang, translation) in translations.others.iter() {
+                let placeholders = placeholders_of_translation(translation, self.syntax);
+                if placeholders == en_placeholders {
+                    continue;
+                }
+                let counts = count(&placeholders);
+
+                if counts == en_counts {
+                    diagnostics.push(Diagnostic {
+                        location: None,
+                        key: key.clone(),
+                        error_msg: Some(format!(
+                            "Placeholders are reordered: {}",
+                            format_placeholders(&placeholders, self.syntax)
+                        )),
+                        severity: Severity::Info,
+                        langs: vec![Arc::clone(lang)],
+                    });
+                    continue;
+                }
+
+                let mut missing = Vec::new();
+                let mut repeated = Vec::new();
+                let mut unknown = Vec::new();
+                for (placeholder, &en_count) in en_counts.iter() {
+                    match counts.get(placeholder).copied().unwrap_or(0) {
+                        n if n < en_count => missing.push(placeholder.as_str()),
+                        n if n > en_count => repeated.push(placeholder.as_str()),
+                        _ => {}
+                    }
+                }
+                for placeholder in counts.keys() {
+                    if !en_counts.contains_key(placeholder) {
+                        unknown.push(placeholder.as_str());
+                    }
+                }
+
+                let problems = [
+                    ("missing", missing),
+                    ("repeated", repeated),
+                    ("unknown", unknown),
+                ]
+                .into_iter()
+                .filter(|(_, placeholders)| !placeholders.is_empty())
+                .map(|(kind, placeholders)| {
+                    format!(
+                        "{} {}",
+                        kind,
+                        format_placeholders(&placeholders, self.syntax)
+                    )
+                })
+                .collect::<Vec<_>>();
+
+                diagnostics.push(
+                    Diagnostic::error(
+                        key.clone(),
+                        Some(format!("Placeholders mismatch: {}", problems.join(", "))),
+                    )
+                    .with_langs(vec![Arc::clone(lang)]),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Copying {src} to {dst}".into(),
+                Translations {
+                    en: Some("Copying %{src} to %{dst}".into()),
+                    others: IndexMap::from([
+                        ("de".into(), "Kopiere %{src} nach %{dst}".into()),
+                        ("ja".into(), "%{dst} に %{src} をコピー".into()),
+                        ("fr".into(), "Copie de %{src} vers %{src}".into()),
+                        ("es".into(), "Copiando %{src} a %{destino}".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = PlaceholderConsistency::new(PlaceholderSyntax::PercentBrace);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic {
+                location: None,
+                key: "Copying {src} to {dst}".into(),
+                error_msg: Some("Placeholders are reordered: [%{dst}, %{src}]".into()),
+                severity: Severity::Info,
+                langs: vec!["ja".into()],
+            },
+            Diagnostic::error(
+                "Copying {src} to {dst}".into(),
+                Some("Placeholders mismatch: missing [%{dst}], repeated [%{src}]".into()),
+            )
+            .with_langs(vec!["fr".into()]),
+            Diagnostic::error(
+                "Copying {src} to {dst}".into(),
+                Some("Placeholders mismatch: missing [%{dst}], unknown [%{destino}]".into()),
+            )
+            .with_langs(vec!["es".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_no_english_translation() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Pulling {repo}".into(),
+                Translations {
+                    others: IndexMap::from([
+                        ("de".into(), "Hole %{repo}".into()),
+                        ("fr".into(), "Récupération".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = PlaceholderConsistency::new(PlaceholderSyntax::PercentBrace);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        assert_eq!(
+            diagnostics,
+            [Diagnostic::error(
+                "Pulling {repo}".into(),
+                Some("Placeholders mismatch: missing [%{repo}]".into()),
+            )
+            .with_langs(vec!["fr".into()])]
+        );
+    }
+}
@@ -0,0 +1,471 @@
+//! A rule that checks if the plural keys have the plural forms every language
+//! needs, if they interpolate `count`, and if they are used with a `count`.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::config::{LanguageTiers, PlaceholderSyntax};
+use crate::locale_file_parser::PLURAL_CATEGORIES;
+use std::sync::Arc;
+use topgrade_i18n_locale_checker::placeholder::placeholders_of_translation;
+
+/// The CLDR cardinal plural categories of languages: `(Languages,
+/// Categories)`, languages that CLDR gives unusual combinations, e.g., `shi`,
+/// are not listed.
+const CLDR_CATEGORIES: [(&[&str], &[&str]); 10] = [
+    (
+        &[
+            "bm", "bo", "dz", "id", "ig", "ii", "ja", "jv", "kde", "kea", "km", "ko", "lkt", "lo",
+            "ms", "my", "nqo", "sah", "ses", "sg", "su", "th", "to", "tpi", "vi", "wo", "yo",
+            "yue", "zh",
+        ],
+        &["other"],
+    ),
+    (
+        &[
+            "af", "ak", "am", "as", "asa", "ast", "az", "bem", "bez", "bg", "bho", "bn", "brx",
+            "ce", "ceb", "cgg", "chr", "ckb", "da", "de", "doi", "dv", "ee", "el", "en", "eo",
+            "et", "eu", "fa", "ff", "fi", "fil", "fo", "fur", "fy", "gl", "gsw", "gu", "ha", "haw",
+            "hi", "hu", "hy", "ia", "io", "is", "jgo", "jmc", "ka", "kab", "kaj", "kcg", "kk",
+            "kkj", "kl", "kn", "ks", "ksb", "ku", "ky", "lb", "lg", "ln", "mas", "mg", "mgo", "mk",
+            "ml", "mn", "mr", "nah", "nb", "nd", "ne", "nl", "nn", "nnh", "no", "nr", "nso", "ny",
+            "nyn", "om", "or", "os", "pa", "pap", "ps", "rm", "rof", "rwk", "saq", "sc", "sd",
+            "sdh", "seh", "si", "sn", "so", "sq", "ss", "ssy", "st", "sv", "sw", "syr", "ta", "te",
+            "teo", "ti", "tig", "tk", "tl", "tn", "tr", "ts", "ug", "ur", "uz", "ve", "vo", "vun",
+            "wa", "wae", "xh", "xog", "zu",
+        ],
+        &["one", "other"],
+    ),
+    (&["ca", "es", "fr", "it", "pt"], &["one", "many", "other"]),
+    (&["lv", "prg"], &["zero", "one", "other"]),
+    (&["bs", "hr", "ro", "sh", "sr"], &["one", "few", "other"]),
+    (
+        &["he", "iu", "naq", "se", "sma", "smj", "smn", "sms"],
+        &["one", "two", "other"],
+    ),
+    (&["dsb", "gd", "hsb", "sl"], &["one", "two", "few", "other"]),
+    (
+        &["be", "cs", "lt", "pl", "ru", "sk", "uk"],
+        &["one", "few", "many", "other"],
+    ),
+    (
+        &["br", "ga", "gv", "mt"],
+        &["one", "two", "few", "many", "other"],
+    ),
+    (
+        &["ar", "ars", "cy", "kw"],
+        &["zero", "one", "two", "few", "many", "other"],
+    ),
+];
+
+/// Returns the CLDR plural categories of `lang`, `None` if it is unknown.
+///
+/// Regional variants have the categories of their language, e.g., `zh_CN`
+/// those of `zh`.
+pub(crate) fn cldr_categories(lang: &str) -> Option<&'static [&'static str]> {
+    let base = lang
+        .split(['_', '-'])
+        .next()
+        .unwrap_or(lang)
+        .to_ascii_lowercase();
+    CLDR_CATEGORIES
+        .iter()
+        .find(|(langs, _)| langs.contains(&base.as_str()))
+        .map(|(_, categories)| *categories)
+}
+
+/// Unknown languages need the categories of English.
+const FALLBACK_CATEGORIES: &[&str] = &["one", "other"];
+
+/// Plural forms that stand for several numbers, so they should interpolate
+/// `count`, other than `other`, which is compared with English by
+/// `PlaceholderConsistency`. `zero`, `one` and `two` may spell the number out,
+/// e.g., "an update".
+const COUNTED_CATEGORIES: [&str; 2] = ["few", "many"];
+
+/// Name of the argument that picks the plural form, as in rust-i18n.
+const COUNT: &str = "count";
+
+/// Checks if every translation of a plural key has exactly the plural forms
+/// CLDR defines for its language, e.g., `one`, `few`, `many` and `other` for
+/// `ru`, and if every `t!()` invocation of a plural key passes `count`, which
+/// picks the form.
+///
+/// A plain translation of a plural key is its `other` form, which is enough
+/// for languages that do not inflect for number. Languages unknown to
+/// [`CLDR_CATEGORIES`] need the [`FALLBACK_CATEGORIES`] and may have other
+/// forms. The severity depends on the tier of the language.
+///
+/// `count` is special, as in rust-i18n: the English translation of a plural
+/// key should interpolate it, and the [`COUNTED_CATEGORIES`] forms should not
+/// drop it. This is skipped with [`PlaceholderSyntax::Positional`], whose
+/// placeholders cannot name it.
+pub(crate) struct PluralCategories {
+    /// Language tiers.
+    tiers: LanguageTiers,
+    /// How placeholders are written in translations.
+    syntax: PlaceholderSyntax,
+}
+
+impl PluralCategories {
+    /// Creates the rule with the given language `tiers` and the placeholder
+    /// `syntax` used in translations.
+    pub(crate) fn new(tiers: LanguageTiers, syntax: PlaceholderSyntax) -> Self {
+        Self { tiers, syntax }
+    }
+
+    /// Returns true if `translation` interpolates `count`.
+    fn interpolates_count(&self, translation: &str) -> bool {
+        placeholders_of_translation(translation, self.syntax).contains(&COUNT)
+    }
+}
+
+/// Documentation of [`PluralCategories`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "PluralCategories",
+    code: "I18N027",
+    description: "Checks if every translation of a plural key, i.e., one with plural forms in some language, has exactly the plural forms CLDR defines for its language, if its English translation interpolates `%{count}` and its `few` and `many` forms keep it, and if every `t!()` of a plural key passes `count`.",
+    rationale: "A missing plural form shows the wrong grammatical number, e.g., \"1 updates\", or the wrong case, e.g., the `few` form of Russian, and a form the language does not have is never shown. Without `count` the form cannot be picked, and a form that drops it hides the number. Problems of tier-1 languages are errors, tier-2 ones are warnings, others are informational.",
+    options: &[
+        ("placeholders.syntax", "How placeholders are written in translations, `count` is not checked with `{0}`."),
+        ("languages.tiers.tier1", "Languages whose missing plural forms are errors, every language is tier 1 if no tier is configured."),
+        ("languages.tiers.tier2", "Languages whose missing plural forms are warnings."),
+    ],
+    example_syntax: "yaml",
+    failing: r#""{count} updates":
+  en:
+    one: "%{count} update"
+    other: "%{count} updates"
+  de: "%{count} Updates"
+  ru:
+    one: "%{count} обновление"
+    other: "%{count} обновления"
+"#,
+    passing: r#""{count} updates":
+  en:
+    one: "%{count} update"
+    other: "%{count} updates"
+  de:
+    one: "%{count} Update"
+    other: "%{count} Updates"
+  ru:
+    one: "%{count} обновление"
+    few: "%{count} обновления"
+    many: "%{count} обновлений"
+    other: "%{count} обновления"
+"#,
+};
+
+impl Rule for PluralCategories {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            if !translations.is_plural() {
+                continue;
+            }
+
+            let checks_count = self.syntax != PlaceholderSyntax::Positional;
+            let en_has_count = translations
+                .en
+                .as_ref()
+                .is_some_and(|en| self.interpolates_count(en));
+            if checks_count && translations.en.is_some() && !en_has_count {
+                diagnostics.push(
+                    Diagnostic::error(
+                        key.clone(),
+                        Some(
+                            "English translation of a plural key should interpolate `count`".into(),
+                        ),
+                    )
+                    .with_langs(vec!["en".into()]),
+                );
+            }
+
+            let en = translations.en.iter().map(|_| Arc::from("en"));
+            let others = translations.others.keys().cloned();
+            for lang in en.chain(others) {
+                let cldr = cldr_categories(&lang);
+                let required = cldr.unwrap_or(FALLBACK_CATEGORIES);
+                let forms = match translations.plurals.get(&lang) {
+                    Some(forms) => forms.keys().map(|category| &**category).collect(),
+                    // A plain translation is the `other` form
+                    None => vec!["other"],
+                };
+
+                let missing = PLURAL_CATEGORIES
+                    .into_iter()
+                    .filter(|category| required.contains(category) && !forms.contains(category))
+                    .collect::<Vec<_>>();
+                let extra = PLURAL_CATEGORIES
+                    .into_iter()
+                    .filter(|category| {
+                        cldr.is_some() && forms.contains(category) && !required.contains(category)
+                    })
+                    .collect::<Vec<_>>();
+                let error_msgs = [
+                    (missing, "Missing plural forms"),
+                    (extra, "Plural forms not in CLDR"),
+                ]
+                .into_iter()
+                .filter(|(categories, _)| !categories.is_empty())
+                .map(|(categories, msg)| format!("{} [{}]", msg, categories.join(", ")));
+
+                let dropped = COUNTED_CATEGORIES
+                    .into_iter()
+                    .filter(|category| {
+                        let form = translations
+                            .plurals
+                            .get(&lang)
+                            .and_then(|forms| forms.get(*category));
+                        checks_count
+                            && en_has_count
+                            && form.is_some_and(|form| !self.interpolates_count(form))
+                    })
+                    .collect::<Vec<_>>();
+                let dropped = (!dropped.is_empty())
+                    .then(|| format!("Plural forms drop `count` [{}]", dropped.join(", ")));
+                let error_msgs = error_msgs.chain(dropped);
+
+                for error_msg in error_msgs {
+                    diagnostics.push(Diagnostic {
+                        severity: self.tiers.severity_of(&lang),
+                        ..Diagnostic::error(key.clone(), Some(error_msg))
+                            .with_langs(vec![Arc::clone(&lang)])
+                    });
+                }
+            }
+        }
+
+        for locale_key in ctx.checked_locale_keys() {
+            let is_plural = ctx
+                .localized_texts
+                .texts
+                .get(&locale_key.key)
+                .is_some_and(|translations| translations.is_plural());
+            if is_plural && !locale_key.args.iter().any(|arg| &**arg == "count") {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Arc::clone(&locale_key.key),
+                        Some("t!() of a plural key should pass `count`".into()),
+                    )
+                    .at(
+                        Arc::clone(&locale_key.file),
+                        locale_key.line,
+                        locale_key.column,
+                    ),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interner::Interner;
+    use crate::locale_file_parser::LocalizedTexts;
+    use crate::locale_key_collector::LocaleKey;
+    use crate::rules::Severity;
+    use std::path::Path;
+
+    #[test]
+    fn test_rule_works() {
+        let yaml = serde_yaml_ng::from_str(
+            r#"
+_version: 2
+"{count} updates":
+  en:
+    one: "%{count} update"
+    other: "%{count} updates"
+  de: "%{count} Updates"
+  fr:
+    other: "%{count} mises à jour"
+  ja: "%{count} 件の更新"
+  zh_CN:
+    one: "%{count} 个更新"
+    other: "%{count} 个更新"
+  ru:
+    one: "%{count} обновление"
+    other: "%{count} обновления"
+  tlh: "%{count} updates"
+Restarting:
+  en: Restarting
+  de: Neustart
+"#,
+        )
+        .unwrap();
+        let localized_texts = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &Default::default(),
+            &Default::default(),
+        )
+        .unwrap();
+        let locale_keys = [
+            ("{count} updates", vec!["count".into()]),
+            ("{count} updates", Vec::new()),
+            ("Restarting", Vec::new()),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (key, args))| LocaleKey {
+            key: key.into(),
+            file: Path::new("foo.rs").into(),
+            line: idx + 1,
+            column: 0,
+            args,
+            locale: None,
+            ignored: false,
+        })
+        .collect::<Vec<_>>();
+
+        let tiers = LanguageTiers {
+            tier1: vec!["de".into()],
+            tier2: vec!["fr".into()],
+        };
+        let diagnostics =
+            PluralCategories::new(tiers, PlaceholderSyntax::default()).check(&CheckContext {
+                localized_texts: &localized_texts,
+                locale_keys: &locale_keys,
+                set_locale_calls: &[],
+                concatenations: &[],
+                sink_literals: &[],
+            });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "{count} updates".into(),
+                Some("Missing plural forms [one]".into()),
+            )
+            .with_langs(vec!["de".into()]),
+            Diagnostic {
+                severity: Severity::Warning,
+                ..Diagnostic::error(
+                    "{count} updates".into(),
+                    Some("Missing plural forms [one, many]".into()),
+                )
+                .with_langs(vec!["fr".into()])
+            },
+            Diagnostic {
+                severity: Severity::Info,
+                ..Diagnostic::error(
+                    "{count} updates".into(),
+                    Some("Plural forms not in CLDR [one]".into()),
+                )
+                .with_langs(vec!["zh_CN".into()])
+            },
+            Diagnostic {
+                severity: Severity::Info,
+                ..Diagnostic::error(
+                    "{count} updates".into(),
+                    Some("Missing plural forms [few, many]".into()),
+                )
+                .with_langs(vec!["ru".into()])
+            },
+            Diagnostic {
+                severity: Severity::Info,
+                ..Diagnostic::error(
+                    "{count} updates".into(),
+                    Some("Missing plural forms [one]".into()),
+                )
+                .with_langs(vec!["tlh".into()])
+            },
+            Diagnostic::error(
+                "{count} updates".into(),
+                Some("t!() of a plural key should pass `count`".into()),
+            )
+            .at(Path::new("foo.rs").into(), 2, 0),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_count() {
+        let yaml = serde_yaml_ng::from_str(
+            r#"
+_version: 2
+"{count} updates":
+  en:
+    one: "%{count} update"
+    other: "%{count} updates"
+  ru:
+    one: "обновление"
+    few: "%{count} обновления"
+    many: "обновлений"
+    other: "%{count} обновления"
+Updates:
+  en:
+    one: An update
+    other: Updates
+"#,
+        )
+        .unwrap();
+        let localized_texts = LocalizedTexts::new(
+            yaml,
+            &mut Interner::new(),
+            &Default::default(),
+            &Default::default(),
+        )
+        .unwrap();
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+
+        let diagnostics =
+            PluralCategories::new(LanguageTiers::default(), PlaceholderSyntax::default())
+                .check(&ctx);
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "{count} updates".into(),
+                Some("Plural forms drop `count` [many]".into()),
+            )
+            .with_langs(vec!["ru".into()]),
+            Diagnostic::error(
+                "Updates".into(),
+                Some("English translation of a plural key should interpolate `count`".into()),
+            )
+            .with_langs(vec!["en".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+
+        let diagnostics =
+            PluralCategories::new(LanguageTiers::default(), PlaceholderSyntax::Positional)
+                .check(&ctx);
+        assert_eq!(diagnostics, []);
+    }
+
+    #[test]
+    fn test_cldr_categories() {
+        assert_eq!(cldr_categories("en"), Some(&["one", "other"][..]));
+        assert_eq!(cldr_categories("zh_CN"), Some(&["other"][..]));
+        assert_eq!(
+            cldr_categories("pt-BR"),
+            Some(&["one", "many", "other"][..])
+        );
+        assert_eq!(
+            cldr_categories("ar"),
+            Some(&["zero", "one", "two", "few", "many", "other"][..])
+        );
+        assert_eq!(cldr_categories("tlh"), None);
+
+        // Every language is listed once, with categories in canonical order
+        let mut langs = CLDR_CATEGORIES
+            .iter()
+            .flat_map(|(langs, _)| langs.iter())
+            .collect::<Vec<_>>();
+        let n_langs = langs.len();
+        langs.sort();
+        langs.dedup();
+        assert_eq!(langs.len(), n_langs);
+        for (_, categories) in CLDR_CATEGORIES {
+            let mut sorted = categories.to_vec();
+            sorted.sort_by_key(|category| PLURAL_CATEGORIES.iter().position(|c| c == category));
+            assert_eq!(sorted, categories);
+            assert_eq!(categories.last(), Some(&"other"));
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! A rule that checks if any translation looks like gibberish or test text.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use crate::error::Error;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Translations that are test strings, compared case-insensitively, with
+/// leading and trailing whitespace ignored.
+const TEST_STRINGS: [&str; 6] = ["test", "testing", "asdf", "qwerty", "foo", "foobar"];
+
+/// A character repeated this many times in a row is suspicious.
+const MAX_REPEATED_CHARS: usize = 5;
+
+/// Translations with at least this many characters are checked for entropy.
+const MIN_LEN_FOR_ENTROPY: usize = 8;
+
+/// Translations whose entropy, in bits per character, is lower than this are
+/// suspicious, e.g., `abababab`.
+const MIN_ENTROPY: f64 = 1.5;
+
+/// Returns the Shannon entropy of the characters in `text`, in bits per
+/// character.
+fn entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut len = 0;
+    for c in text.chars() {
+        *counts.entry(c).or_default() += 1;
+        len += 1;
+    }
+
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Returns the length of the longest run of the same non-whitespace
+/// character in `text`.
+fn longest_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut prev = None;
+    for c in text.chars() {
+        if Some(c) == prev && !c.is_whitespace() {
+            current += 1;
+        } else {
+            current = 1;
+        }
+        prev = Some(c);
+        longest = longest.max(current);
+    }
+
+    longest
+}
+
+/// Checks if any translation looks like pseudo text, i.e., lorem ipsum,
+/// repeated characters, test strings, text with very low entropy, or text
+/// that matches a configured pattern. These are usually leftovers from
+/// debugging sessions.
+///
+/// As it is a heuristic, problems are reported as warnings.
+pub(crate) struct PseudoText {
+    /// Configured patterns of pseudo text.
+    patterns: Vec<Regex>,
+}
+
+impl PseudoText {
+    /// Creates the rule with the configured `patterns` of pseudo text, fails
+    /// if any of them is not a valid regular expression.
+    pub(crate) fn new(patterns: &[String]) -> Result<Self, Error> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    Error::Usage(format!(
+                        "invalid pseudo text pattern {:?} in `rules.pseudo_text.patterns`: {}",
+                        pattern, e
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// Returns why `translation` looks like pseudo text, `None` if it does
+    /// not.
+    fn reason(&self, translation: &str) -> Option<&'static str> {
+        let trimmed = translation.trim();
+
+        if trimmed.to_lowercase().contains("lorem ipsum") {
+            Some("lorem ipsum")
+        } else if TEST_STRINGS
+            .iter()
+            .any(|test_string| test_string.eq_ignore_ascii_case(trimmed))
+        {
+            Some("test string")
+        } else if longest_run(trimmed) >= MAX_REPEATED_CHARS {
+            Some("repeated characters")
+        } else if trimmed.chars().count() >= MIN_LEN_FOR_ENTROPY && entropy(trimmed) < MIN_ENTROPY {
+            Some("low entropy")
+        } else if self
+            .patterns
+            .iter()
+            .any(|pattern| pattern.is_match(translation))
+        {
+            Some("matches a configured pattern")
+        } else {
+            None
+        }
+    }
+}
+
+/// Documentation of [`PseudoText`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "PseudoText",
+    code: "I18N014",
+    description: "Checks if any translation looks like pseudo text, i.e., lorem ipsum, repeated characters, test strings, text with very low entropy, or text that matches a configured pattern.",
+    rationale: "Pseudo text is usually a leftover from debugging sessions. As it is a heuristic, problems are reported as warnings.",
+    options: &[
+        ("rules.pseudo_text.patterns", "Regular expressions, translations that match any of them are pseudo text."),
+    ],
+    example_syntax: "yaml",
+    failing: "Restarting:
+  en: Restarting
+  de: Lorem ipsum dolor
+",
+    passing: "Restarting:
+  en: Restarting
+  de: Neustart
+",
+};
+
+impl Rule for PseudoText {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let en = translations.en.iter().map(|en| ("en".into(), en));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation));
+            for (lang, translation) in en.chain(others) {
+                if let Some(reason) = self.reason(translation) {
+                    diagnostics.push(Diagnostic {
+                        location: None,
+                        key: key.clone(),
+                        error_msg: Some(format!("Looks like pseudo text ({})", reason)),
+                        severity: Severity::Warning,
+                        langs: vec![lang],
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_reason() {
+        let rule = PseudoText::new(&["^DEBUG".to_string()]).unwrap();
+        assert_eq!(rule.reason("Lorem ipsum dolor"), Some("lorem ipsum"));
+        assert_eq!(rule.reason(" ASDF "), Some("test string"));
+        assert_eq!(rule.reason("Updaaaaating"), Some("repeated characters"));
+        assert_eq!(rule.reason("abababab"), Some("low entropy"));
+        assert_eq!(
+            rule.reason("DEBUG Restarting"),
+            Some("matches a configured pattern")
+        );
+        assert_eq!(rule.reason("Restarting %{app}"), None);
+        assert_eq!(rule.reason("Aktualisierung"), None);
+        assert_eq!(rule.reason("重启"), None);
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Restarting".into(),
+                Translations {
+                    en: Some("Restarting".into()),
+                    others: IndexMap::from([("de".into(), "test".into())]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = PseudoText::new(&[]).unwrap();
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic {
+            location: None,
+            key: "Restarting".into(),
+            error_msg: Some("Looks like pseudo text (test string)".into()),
+            severity: Severity::Warning,
+            langs: vec!["de".into()],
+        }];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,238 @@
+//! A rule that checks if quotes around placeholders are consistent across
+//! languages.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use crate::config::PlaceholderSyntax;
+use crate::error::Error;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use topgrade_i18n_locale_checker::placeholder::placeholders_of_translation;
+
+/// Quote pairs recognized around placeholders, in addition to the configured
+/// ones.
+const QUOTE_PAIRS: [(char, char); 12] = [
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+    ('“', '”'),
+    ('‘', '’'),
+    ('«', '»'),
+    ('»', '«'),
+    ('„', '“'),
+    ('‚', '‘'),
+    ('‹', '›'),
+    ('「', '」'),
+    ('『', '』'),
+];
+
+/// A pair of opening and closing quotes.
+type QuotePair = (char, char);
+
+/// Checks if the placeholders quoted in English are quoted in the other
+/// languages as well, and vice versa.
+///
+/// A language listed in the `[rules.quoting_style.languages]` table should use
+/// one of its declared quote pairs, e.g., `de = ["»«", "„“"]`, other languages
+/// should use the same quotes as English. Keys without an English translation
+/// are skipped, problems are reported as warnings.
+pub(crate) struct QuotingStyle {
+    /// How placeholders are written in translations.
+    syntax: PlaceholderSyntax,
+    /// `Language => Quote pairs allowed`
+    languages: HashMap<String, Vec<QuotePair>>,
+}
+
+impl QuotingStyle {
+    /// Creates the rule with the placeholder `syntax` used in translations and
+    /// the quote pairs allowed for each language, a quote pair is written as
+    /// the opening and closing quote characters, e.g., `»«`, fails if it is
+    /// not.
+    pub(crate) fn new(
+        syntax: PlaceholderSyntax,
+        languages: &HashMap<String, Vec<String>>,
+    ) -> Result<Self, Error> {
+        let languages = languages
+            .iter()
+            .map(|(lang, pairs)| {
+                let pairs = pairs
+                    .iter()
+                    .map(|pair| {
+                        let mut chars = pair.chars();
+                        match (chars.next(), chars.next(), chars.next()) {
+                            (Some(open), Some(close), None) => Ok((open, close)),
+                            _ => Err(Error::Usage(format!(
+                                "invalid quote style {:?} of language {} in `rules.quoting_style.languages`, expect 2 characters, e.g., \"»«\"",
+                                pair, lang
+                            ))),
+                        }
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok((lang.clone(), pairs))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self { syntax, languages })
+    }
+
+    /// Returns true if `pair` is a recognized quote pair.
+    fn is_quote_pair(&self, pair: QuotePair) -> bool {
+        QUOTE_PAIRS.contains(&pair) || self.languages.values().flatten().any(|p| *p == pair)
+    }
+
+    /// Returns the quotes around each distinct placeholder in `text`, `None`
+    /// if it is not quoted. Only the first occurrence of a placeholder is
+    /// considered.
+    fn quotes<'text>(&self, text: &'text str) -> IndexMap<&'text str, Option<QuotePair>> {
+        let mut quotes = IndexMap::new();
+
+        for name in placeholders_of_translation(text, self.syntax) {
+            if quotes.contains_key(name) {
+                continue;
+            }
+
+            let placeholder = self.syntax.format(name);
+            let quote = text.find(&placeholder).and_then(|idx| {
+                let open = text[..idx].chars().next_back()?;
+                let close = text[idx + placeholder.len()..].chars().next()?;
+                Some((open, close)).filter(|pair| self.is_quote_pair(*pair))
+            });
+            quotes.insert(name, quote);
+        }
+
+        quotes
+    }
+}
+
+/// Documentation of [`QuotingStyle`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "QuotingStyle",
+    code: "I18N017",
+    description: "Checks if the placeholders quoted in English are quoted in the other languages as well, and vice versa.",
+    rationale: "A language listed in the config should use one of its declared quote pairs, other languages should use the same quotes as English. Problems are reported as warnings.",
+    options: &[
+        ("rules.quoting_style.languages", r#"`Language => Quote pairs allowed`, e.g., `de = ["»«", "„“"]`."#),
+    ],
+    example_syntax: "yaml",
+    failing: r#""Removing \"{path}\"":
+  en: "Removing \"%{path}\""
+  de: "Entferne %{path}"
+"#,
+    passing: r#""Removing \"{path}\"":
+  en: "Removing \"%{path}\""
+  de: "Entferne „%{path}“"
+"#,
+};
+
+impl Rule for QuotingStyle {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let Some(en) = &translations.en else {
+                continue;
+            };
+            let en_quotes = self.quotes(en);
+
+            for (lang, translation) in translations.others.iter() {
+                let mismatches = self
+                    .quotes(translation)
+                    .into_iter()
+                    .filter(|(name, quote)| {
+                        let Some(en_quote) = en_quotes.get(name) else {
+                            // Unknown placeholders are not our business
+                            return false;
+                        };
+                        match (en_quote, quote) {
+                            (None, None) => false,
+                            (Some(en_quote), Some(quote)) => match self.languages.get(&**lang) {
+                                Some(allowed) => !allowed.contains(quote),
+                                None => en_quote != quote,
+                            },
+                            _ => true,
+                        }
+                    })
+                    .map(|(name, _)| self.syntax.format(name))
+                    .collect::<Vec<_>>();
+
+                if !mismatches.is_empty() {
+                    diagnostics.push(Diagnostic {
+                        location: None,
+                        key: key.clone(),
+                        error_msg: Some(format!(
+                            "Quotes around placeholders differ from English: [{}]",
+                            mismatches.join(", ")
+                        )),
+                        severity: Severity::Warning,
+                        langs: vec![Arc::clone(lang)],
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+
+    #[test]
+    fn test_quotes() {
+        let rule = QuotingStyle::new(PlaceholderSyntax::PercentBrace, &HashMap::new()).unwrap();
+        assert_eq!(
+            rule.quotes("\"%{app}\" in %{dir}, '%{app}'"),
+            IndexMap::from([("app", Some(('"', '"'))), ("dir", None)])
+        );
+        assert_eq!(rule.quotes("(%{app})"), IndexMap::from([("app", None)]));
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Restarting \"{app}\" in {dir}".into(),
+                Translations {
+                    en: Some("Restarting \"%{app}\" in %{dir}".into()),
+                    others: IndexMap::from([
+                        ("de".into(), "Starte »%{app}« in %{dir} neu".into()),
+                        ("fr".into(), "Redémarrage de «%{app}» dans %{dir}".into()),
+                        ("es".into(), "Reiniciando %{app} en \"%{dir}\"".into()),
+                        ("it".into(), "Riavvio di \"%{app}\" in %{dir}".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let languages = HashMap::from([("de".to_string(), vec!["»«".to_string()])]);
+        let rule = QuotingStyle::new(PlaceholderSyntax::PercentBrace, &languages).unwrap();
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic {
+                location: None,
+                key: "Restarting \"{app}\" in {dir}".into(),
+                error_msg: Some("Quotes around placeholders differ from English: [%{app}]".into()),
+                severity: Severity::Warning,
+                langs: vec!["fr".into()],
+            },
+            Diagnostic {
+                location: None,
+                key: "Restarting \"{app}\" in {dir}".into(),
+                error_msg: Some(
+                    "Quotes around placeholders differ from English: [%{app}, %{dir}]".into(),
+                ),
+                severity: Severity::Warning,
+                langs: vec!["es".into()],
+            },
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
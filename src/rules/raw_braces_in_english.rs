@@ -0,0 +1,163 @@
+//! A rule that checks if any English translation contains `{name}` without the
+//! leading `%`.
+
+use super::key_and_eng_matches::derived_en;
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::config::PlaceholderSyntax;
+
+/// Returns the `{name}`s in `text` that are not preceded by `%`, `name` should
+/// be an identifier.
+fn raw_braces_of(text: &str) -> Vec<&str> {
+    let mut raw_braces = Vec::new();
+
+    for (start, _) in text.match_indices('{') {
+        if text[..start].ends_with('%') {
+            continue;
+        }
+        let Some(len) = text[start..].find('}') else {
+            continue;
+        };
+        let name = &text[start + 1..start + len];
+        let is_identifier = !name.is_empty()
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit());
+        if is_identifier {
+            raw_braces.push(&text[start..=start + len]);
+        }
+    }
+
+    raw_braces
+}
+
+/// Checks if any English translation contains `{name}` without the leading
+/// `%`, which rust-i18n prints literally instead of interpolating.
+///
+/// It only makes sense when placeholders are written as `%{name}`, this rule
+/// does nothing with other placeholder syntaxes.
+pub(crate) struct RawBracesInEnglish {
+    /// How placeholders are written in translations.
+    syntax: PlaceholderSyntax,
+}
+
+impl RawBracesInEnglish {
+    /// Creates the rule with the placeholder `syntax` used in translations.
+    pub(crate) fn new(syntax: PlaceholderSyntax) -> Self {
+        Self { syntax }
+    }
+}
+
+/// Documentation of [`RawBracesInEnglish`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "RawBracesInEnglish",
+    code: "I18N012",
+    description: "Checks if any English translation contains `{name}` without the leading `%`.",
+    rationale: "rust-i18n prints `{name}` literally instead of interpolating it. The rule only applies when placeholders are written as `%{name}`.",
+    options: &[
+        ("placeholders.syntax", "The rule does nothing unless it is `%{name}`, the default."),
+    ],
+    example_syntax: "yaml",
+    failing: r#""Restarting {app}":
+  en: Restarting {app}
+"#,
+    passing: r#""Restarting {app}":
+  en: Restarting %{app}
+"#,
+};
+
+impl Rule for RawBracesInEnglish {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if self.syntax != PlaceholderSyntax::PercentBrace {
+            return diagnostics;
+        }
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let Some(en) = &translations.en else {
+                continue;
+            };
+            // Literal braces escaped in the key, e.g., `{{name}}`, are intended
+            let expected = derived_en(key, self.syntax);
+            let intended = raw_braces_of(&expected);
+            let raw_braces = raw_braces_of(en)
+                .into_iter()
+                .filter(|raw_brace| !intended.contains(raw_brace))
+                .collect::<Vec<_>>();
+            if !raw_braces.is_empty() {
+                diagnostics.push(
+                    Diagnostic::error(
+                        key.clone(),
+                        Some(format!(
+                            "Placeholders without `%` will be printed literally: [{}]",
+                            raw_braces.join(", ")
+                        )),
+                    )
+                    .with_langs(vec!["en".into()]),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_raw_braces_of() {
+        assert_eq!(
+            raw_braces_of("Restarting {app} in %{dir}, {0} { } {a b}"),
+            ["{app}"]
+        );
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting {app}".into(),
+                    Translations {
+                        en: Some("Restarting {app}".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating {app}".into(),
+                    Translations {
+                        en: Some("Updating %{app}".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Use {{name}} for {app}".into(),
+                    Translations {
+                        en: Some("Use {name} for %{app}".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+
+        let rule = RawBracesInEnglish::new(PlaceholderSyntax::PercentBrace);
+        let expected_diagnostics = vec![Diagnostic::error(
+            "Restarting {app}".into(),
+            Some("Placeholders without `%` will be printed literally: [{app}]".into()),
+        )
+        .with_langs(vec!["en".into()])];
+        assert_eq!(rule.check(&ctx), expected_diagnostics);
+
+        let rule = RawBracesInEnglish::new(PlaceholderSyntax::Brace);
+        assert!(rule.check(&ctx).is_empty());
+    }
+}
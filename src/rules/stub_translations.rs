@@ -0,0 +1,155 @@
+//! A rule that checks if any translation is a stub like `TODO`.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::error::Error;
+use regex::Regex;
+use std::sync::Arc;
+
+/// Translations that are stubs, compared case-insensitively, with leading and
+/// trailing whitespace ignored.
+const STUBS: [&str; 3] = ["TODO", "FIXME", "xxx"];
+
+/// Checks if any translation is a stub, i.e., one in [`STUBS`] or one that
+/// matches a configured pattern.
+///
+/// Stubs satisfy [`MissingTranslations`], but ship garbage to users.
+///
+/// [`MissingTranslations`]: super::missing_translations::MissingTranslations
+pub(crate) struct StubTranslations {
+    /// Configured patterns of stubs.
+    patterns: Vec<Regex>,
+}
+
+impl StubTranslations {
+    /// Creates the rule with the configured `patterns` of stubs, fails if any
+    /// of them is not a valid regular expression.
+    pub(crate) fn new(patterns: &[String]) -> Result<Self, Error> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    Error::Usage(format!(
+                        "invalid stub translation pattern {:?} in `rules.stub_translations.patterns`: {}",
+                        pattern, e
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// Returns true if `translation` is a stub.
+    fn is_stub(&self, translation: &str) -> bool {
+        let trimmed = translation.trim();
+
+        STUBS.iter().any(|stub| stub.eq_ignore_ascii_case(trimmed))
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| pattern.is_match(translation))
+    }
+}
+
+/// Documentation of [`StubTranslations`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "StubTranslations",
+    code: "I18N013",
+    description:
+        "Checks if any translation is a stub, e.g., `TODO`, or matches a configured pattern.",
+    rationale: "Stubs satisfy `MissingTranslations`, but ship garbage to users.",
+    options: &[(
+        "rules.stub_translations.patterns",
+        "Regular expressions, translations that match any of them are stubs.",
+    )],
+    example_syntax: "yaml",
+    failing: "Restarting:
+  en: Restarting
+  de: TODO
+",
+    passing: "Restarting:
+  en: Restarting
+  de: Neustart
+",
+};
+
+impl Rule for StubTranslations {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let en = translations.en.iter().map(|en| ("en".into(), en));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation));
+            for (lang, translation) in en.chain(others) {
+                if self.is_stub(translation) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            key.clone(),
+                            Some(format!("Stub translation {:?}", &**translation)),
+                        )
+                        .with_langs(vec![lang]),
+                    );
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Restarting".into(),
+                Translations {
+                    en: Some("Restarting".into()),
+                    others: IndexMap::from([
+                        ("de".into(), " todo ".into()),
+                        ("fr".into(), "[fr] Restarting".into()),
+                        ("es".into(), "Reiniciando, TODO: check".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = StubTranslations::new(&[r"^\[\w+\]".to_string()]).unwrap();
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "Restarting".into(),
+                Some(r#"Stub translation " todo ""#.into()),
+            )
+            .with_langs(vec!["de".into()]),
+            Diagnostic::error(
+                "Restarting".into(),
+                Some(r#"Stub translation "[fr] Restarting""#.into()),
+            )
+            .with_langs(vec!["fr".into()]),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let Err(Error::Usage(message)) = StubTranslations::new(&["(".to_string()]) else {
+            panic!("the pattern should be invalid");
+        };
+        assert!(message.starts_with("invalid stub translation pattern \"(\""));
+    }
+}
@@ -0,0 +1,166 @@
+//! A rule that checks if any translation contains duplicated internal
+//! whitespace or a space before punctuation.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Punctuation that should not be preceded by a space.
+const PUNCTUATION: [char; 6] = [',', '.', ':', ';', '!', '?'];
+
+/// Checks if any translation contains double spaces or a space before
+/// punctuation, which are common copy-paste artifacts. Leading and trailing
+/// whitespace is not checked.
+///
+/// Some languages, e.g., French, legitimately use a space before `:`, the
+/// space-before-punctuation check is skipped for them. Problems are reported
+/// as warnings.
+pub(crate) struct SuspiciousWhitespace {
+    /// Languages that allow a space before punctuation.
+    space_before_punctuation_langs: HashSet<String>,
+}
+
+impl SuspiciousWhitespace {
+    /// Creates the rule, `space_before_punctuation_langs` are the languages
+    /// that allow a space before punctuation.
+    pub(crate) fn new(space_before_punctuation_langs: &[String]) -> Self {
+        Self {
+            space_before_punctuation_langs: space_before_punctuation_langs
+                .iter()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns the problems found in the translation `text` of `lang`.
+    fn problems(&self, lang: &str, text: &str) -> Vec<&'static str> {
+        let text = text.trim();
+        let mut problems = Vec::new();
+
+        if text.contains("  ") {
+            problems.push("double spaces");
+        }
+
+        if !self.space_before_punctuation_langs.contains(lang) {
+            let space_before_punctuation = text
+                .char_indices()
+                .filter(|(_, c)| *c == ' ')
+                .any(|(idx, _)| text[idx + 1..].starts_with(PUNCTUATION));
+            if space_before_punctuation {
+                problems.push("space before punctuation");
+            }
+        }
+
+        problems
+    }
+}
+
+/// Documentation of [`SuspiciousWhitespace`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "SuspiciousWhitespace",
+    code: "I18N016",
+    description: "Checks if any translation contains double spaces or a space before punctuation.",
+    rationale: "They are common copy-paste artifacts. Some languages, e.g., French, legitimately use a space before `:`, the check is skipped for them. Problems are reported as warnings.",
+    options: &[
+        ("rules.suspicious_whitespace.space_before_punctuation", r#"Languages that legitimately use a space before punctuation, `["fr"]` by default."#),
+    ],
+    example_syntax: "yaml",
+    failing: "Done:
+  en: Done!
+  de: Fertig  !
+",
+    passing: "Done:
+  en: Done!
+  de: Fertig!
+",
+};
+
+impl Rule for SuspiciousWhitespace {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let en = translations.en.iter().map(|en| ("en".into(), en));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation));
+            for (lang, translation) in en.chain(others) {
+                let problems = self.problems(&lang, translation);
+                if problems.is_empty() {
+                    continue;
+                }
+
+                diagnostics.push(Diagnostic {
+                    location: None,
+                    key: key.clone(),
+                    error_msg: Some(format!("Suspicious whitespace: {}", problems.join(", "))),
+                    severity: Severity::Warning,
+                    langs: vec![lang],
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Error: {err}".into(),
+                    Translations {
+                        en: Some("Error  : %{err}".into()),
+                        others: IndexMap::from([
+                            ("fr".into(), "Erreur : %{err}".into()),
+                            ("de".into(), "Fehler : %{err}".into()),
+                        ]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Done.".into(),
+                    Translations {
+                        en: Some(" Done. ".into()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let rule = SuspiciousWhitespace::new(&["fr".to_string()]);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic {
+                location: None,
+                key: "Error: {err}".into(),
+                error_msg: Some(
+                    "Suspicious whitespace: double spaces, space before punctuation".into(),
+                ),
+                severity: Severity::Warning,
+                langs: vec!["en".into()],
+            },
+            Diagnostic {
+                location: None,
+                key: "Error: {err}".into(),
+                error_msg: Some("Suspicious whitespace: space before punctuation".into()),
+                severity: Severity::Warning,
+                langs: vec!["de".into()],
+            },
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
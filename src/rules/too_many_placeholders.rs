@@ -0,0 +1,103 @@
+//! A rule that checks if any key declares too many placeholders.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use indexmap::IndexSet;
+use topgrade_i18n_locale_checker::key_parser::placeholders_of_key;
+
+/// Checks if any key declares more than `max` distinct placeholders.
+///
+/// Such messages are hard to translate and easy to misinterpolate, they should
+/// usually be split. Problems are reported as warnings.
+pub(crate) struct TooManyPlaceholders {
+    /// The maximum number of distinct placeholders a key can have.
+    max: usize,
+}
+
+impl TooManyPlaceholders {
+    /// Creates the rule that allows at most `max` placeholders per key.
+    pub(crate) fn new(max: usize) -> Self {
+        Self { max }
+    }
+}
+
+/// Documentation of [`TooManyPlaceholders`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "TooManyPlaceholders",
+    code: "I18N015",
+    description: "Checks if any key declares more than the configured number of distinct placeholders.",
+    rationale: "Such messages are hard to translate and easy to misinterpolate, they should usually be split. Problems are reported as warnings.",
+    options: &[
+        ("rules.too_many_placeholders.max", "The maximum number of distinct placeholders a key can have, 4 by default."),
+    ],
+    example_syntax: "yaml",
+    failing: r#""{a} {b} {c} {d} {e}":
+  en: "%{a} %{b} %{c} %{d} %{e}"
+"#,
+    passing: r#""{a} {b}":
+  en: "%{a} %{b}"
+"#,
+};
+
+impl Rule for TooManyPlaceholders {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        ctx.localized_texts
+            .texts
+            .keys()
+            .filter_map(|key| {
+                let placeholders = placeholders_of_key(key)
+                    .into_iter()
+                    .collect::<IndexSet<_>>();
+                if placeholders.len() <= self.max {
+                    return None;
+                }
+
+                Some(Diagnostic {
+                    location: None,
+                    key: key.clone(),
+                    error_msg: Some(format!(
+                        "The key has {} placeholders, more than {}, consider splitting it",
+                        placeholders.len(),
+                        self.max
+                    )),
+                    severity: Severity::Warning,
+                    langs: Vec::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                ("{a} {b} {c}".into(), Translations::default()),
+                ("{a} {b} {a}".into(), Translations::default()),
+            ]),
+        };
+        let rule = TooManyPlaceholders::new(2);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic {
+            location: None,
+            key: "{a} {b} {c}".into(),
+            error_msg: Some(
+                "The key has 3 placeholders, more than 2, consider splitting it".into(),
+            ),
+            severity: Severity::Warning,
+            langs: Vec::new(),
+        }];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,137 @@
+//! A rule that checks if any translation ends with a newline that English
+//! does not have.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use std::sync::Arc;
+
+/// Checks if any translation ends with `\n` while the English translation
+/// does not, which is usually an artifact of YAML block scalars (`|`) and
+/// creates uneven spacing in Topgrade's summary output.
+///
+/// The English translation itself is compared with the key, so are the other
+/// translations when English is missing. Problems are reported as warnings.
+pub(crate) struct TrailingNewline;
+
+/// Documentation of [`TrailingNewline`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "TrailingNewline",
+    code: "I18N019",
+    description: "Checks if any translation ends with a newline while the English translation does not.",
+    rationale: "It is usually an artifact of YAML block scalars (`|`) and creates uneven spacing in the summary output. Problems are reported as warnings.",
+    options: &[],
+    example_syntax: "yaml",
+    failing: "Summary:
+  en: Summary
+  de: |
+    Zusammenfassung
+",
+    passing: "Summary:
+  en: Summary
+  de: Zusammenfassung
+",
+};
+
+impl Rule for TrailingNewline {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let key_has_newline = key.ends_with('\n');
+            let en_has_newline = translations
+                .en
+                .as_ref()
+                .map_or(key_has_newline, |en| en.ends_with('\n'));
+
+            let en = translations
+                .en
+                .iter()
+                .map(|en| ("en".into(), en, key_has_newline));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation, en_has_newline));
+            for (lang, translation, expect_newline) in en.chain(others) {
+                if expect_newline || !translation.ends_with('\n') {
+                    continue;
+                }
+
+                diagnostics.push(Diagnostic {
+                    location: None,
+                    key: key.clone(),
+                    error_msg: Some(String::from("Trailing newline that English does not have")),
+                    severity: Severity::Warning,
+                    langs: vec![lang],
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting".into(),
+                    Translations {
+                        en: Some("Restarting\n".into()),
+                        others: IndexMap::from([("de".into(), "Neustart\n".into())]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Updating".into(),
+                    Translations {
+                        en: None,
+                        others: IndexMap::from([
+                            ("de".into(), "Aktualisierung\n".into()),
+                            ("fr".into(), "Mise à jour".into()),
+                        ]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Summary\n".into(),
+                    Translations {
+                        en: Some("Summary\n".into()),
+                        others: IndexMap::from([("de".into(), "Zusammenfassung\n".into())]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let rule = TrailingNewline;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic {
+                location: None,
+                key: "Restarting".into(),
+                error_msg: Some("Trailing newline that English does not have".into()),
+                severity: Severity::Warning,
+                langs: vec!["en".into()],
+            },
+            Diagnostic {
+                location: None,
+                key: "Updating".into(),
+                error_msg: Some("Trailing newline that English does not have".into()),
+                severity: Severity::Warning,
+                langs: vec!["de".into()],
+            },
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
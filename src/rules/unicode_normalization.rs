@@ -0,0 +1,174 @@
+//! A rule that checks if keys and translations are in the configured Unicode
+//! normalization form.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::config::NormalizationForm;
+use std::sync::Arc;
+use unicode_normalization::{is_nfc, is_nfd, is_nfkc, is_nfkd};
+
+/// Checks if keys and translations are in the configured Unicode normalization
+/// form.
+///
+/// Visually identical strings can be composed differently, e.g., `é` can be
+/// either `U+00E9` or `e` followed by `U+0301`. If a key in the locale file
+/// and the one used in `t!()` are composed differently, the key won't be found,
+/// which is nearly impossible to debug by eye.
+///
+/// Keys used in `t!()` are checked as well.
+pub(crate) struct UnicodeNormalization {
+    /// The expected normalization form.
+    form: NormalizationForm,
+}
+
+impl UnicodeNormalization {
+    /// Creates the rule with the expected normalization `form`.
+    pub(crate) fn new(form: NormalizationForm) -> Self {
+        Self { form }
+    }
+
+    /// Returns true if `text` is in the expected normalization form.
+    fn is_normalized(&self, text: &str) -> bool {
+        match self.form {
+            NormalizationForm::Nfc => is_nfc(text),
+            NormalizationForm::Nfd => is_nfd(text),
+            NormalizationForm::Nfkc => is_nfkc(text),
+            NormalizationForm::Nfkd => is_nfkd(text),
+        }
+    }
+
+    /// Returns the error message for text that is not normalized.
+    fn error_msg(&self, location: &str) -> String {
+        format!("{} is not in {}", location, self.form.as_str())
+    }
+}
+
+/// Documentation of [`UnicodeNormalization`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "UnicodeNormalization",
+    code: "I18N006",
+    description: "Checks if keys, translations and the keys used in `t!()` are in the configured Unicode normalization form.",
+    rationale: "Visually identical strings can be composed differently, a key composed differently in the locale file and in `t!()` is not found, which is nearly impossible to debug by eye.",
+    options: &[
+        ("rules.unicode_normalization.form", "`NFC` (default), `NFD`, `NFKC` or `NFKD`."),
+    ],
+    example_syntax: "yaml",
+    failing: r#"# "e" followed by U+0301 COMBINING ACUTE ACCENT
+"Cafe\u0301":
+  en: "Cafe\u0301"
+"#,
+    passing: r#""Café":
+  en: Café
+"#,
+};
+
+impl Rule for UnicodeNormalization {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            if !self.is_normalized(key) {
+                diagnostics.push(Diagnostic::error(
+                    key.clone(),
+                    Some(self.error_msg("The key")),
+                ));
+            }
+
+            let en = translations.en.iter().map(|en| ("en".into(), en));
+            let others = translations
+                .others
+                .iter()
+                .map(|(lang, translation)| (Arc::clone(lang), translation));
+            for (lang, translation) in en.chain(others) {
+                if !self.is_normalized(translation) {
+                    diagnostics.push(
+                        Diagnostic::error(key.clone(), Some(self.error_msg("The translation")))
+                            .with_langs(vec![lang]),
+                    );
+                }
+            }
+        }
+
+        for locale_key in ctx.checked_locale_keys() {
+            if !self.is_normalized(&locale_key.key) {
+                diagnostics.push(
+                    Diagnostic::error(Arc::clone(&locale_key.key), Some(self.error_msg("The key")))
+                        .at(
+                            Arc::clone(&locale_key.file),
+                            locale_key.line,
+                            locale_key.column,
+                        ),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use crate::locale_key_collector::LocaleKey;
+    use indexmap::IndexMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    // `e` + COMBINING ACUTE ACCENT
+                    "Cafe\u{0301}".into(),
+                    Translations {
+                        en: Some("Caf\u{00E9}".into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Caf\u{00E9}".into(),
+                    Translations {
+                        en: Some("Caf\u{00E9}".into()),
+                        others: IndexMap::from([("fr".into(), "Cafe\u{0301}".into())]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let locale_keys = vec![LocaleKey {
+            key: "Cafe\u{0301}".into(),
+            file: Path::new("foo.rs").into(),
+            line: 1,
+            column: 1,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
+        }];
+        let rule = UnicodeNormalization::new(NormalizationForm::Nfc);
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error("Cafe\u{0301}".into(), Some("The key is not in NFC".into())),
+            Diagnostic::error(
+                "Caf\u{00E9}".into(),
+                Some("The translation is not in NFC".into()),
+            )
+            .with_langs(vec!["fr".into()]),
+            Diagnostic::error("Cafe\u{0301}".into(), Some("The key is not in NFC".into())).at(
+                Path::new("foo.rs").into(),
+                1,
+                1,
+            ),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+
+        let rule = UnicodeNormalization::new(NormalizationForm::Nfd);
+        assert!(rule.is_normalized("Cafe\u{0301}"));
+        assert!(!rule.is_normalized("Caf\u{00E9}"));
+    }
+}
@@ -0,0 +1,139 @@
+//! A rule that checks if the locale file has keys that Topgrade does not use.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity};
+use crate::config::VariantsConfig;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Checks if any key of the locale file is not used by any `t!()` invocation,
+/// the inverse of [`UseOfKeysDoNotExist`](super::use_of_keys_do_not_exist::UseOfKeysDoNotExist).
+///
+/// A variant, e.g., `Welcome@feminine`, is used if its key is, as the variant
+/// is picked at runtime. Problems are reported as warnings.
+pub(crate) struct UnusedLocaleKeys {
+    /// How key variants are defined.
+    variants: VariantsConfig,
+}
+
+impl UnusedLocaleKeys {
+    /// Creates the rule with the given key `variants` convention.
+    pub(crate) fn new(variants: VariantsConfig) -> Self {
+        Self { variants }
+    }
+
+    /// Returns the keys of `localized_texts` not used by any of `locale_keys`.
+    pub(crate) fn unused_keys<'t>(
+        &self,
+        localized_texts: &'t LocalizedTexts,
+        locale_keys: &[LocaleKey],
+    ) -> Vec<&'t Arc<str>> {
+        let used_keys = locale_keys
+            .iter()
+            .map(|locale_key| &*locale_key.key)
+            .collect::<HashSet<_>>();
+
+        localized_texts
+            .texts
+            .keys()
+            .filter(|key| !used_keys.contains(self.variants.split(key).0))
+            .collect()
+    }
+}
+
+/// Documentation of [`UnusedLocaleKeys`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "UnusedLocaleKeys",
+    code: "I18N028",
+    description: "Checks if every key of the locale file is used by a `t!()` invocation, directly or through its variants.",
+    rationale: "Keys of removed steps are dead weight that translators keep translating. Keys only used by `t!()` invocations generated by macros are found with `scan.cargo_expand`. Problems are reported as warnings.",
+    options: &[
+        ("variants.separator", "Separator between a key and the name of its variant, e.g., `@`, variants are not recognized if it is not specified."),
+        ("scan.cargo_expand", "Also collect the keys of the `t!()` invocations generated by macros, from the output of `cargo expand`."),
+    ],
+    example_syntax: "yaml",
+    failing: r#"# No `t!("Pulling")` in the Rust files
+Pulling:
+  en: Pulling
+"#,
+    passing: r#"# Used by `t!("Restarting")`
+Restarting:
+  en: Restarting
+"#,
+};
+
+impl Rule for UnusedLocaleKeys {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        self.unused_keys(ctx.localized_texts, ctx.locale_keys)
+            .into_iter()
+            .map(|key| Diagnostic {
+                location: None,
+                key: key.clone(),
+                error_msg: Some(String::from("Not used by any `t!()` invocation")),
+                severity: Severity::Warning,
+                langs: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::Translations;
+    use indexmap::IndexMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: [
+                "Restarting",
+                "Welcome@feminine",
+                "Pulling",
+                "{count} updates",
+            ]
+            .into_iter()
+            .map(|key| (key.into(), Translations::default()))
+            .collect::<IndexMap<_, _>>(),
+        };
+        let locale_keys = ["Restarting", "Welcome", "{count} updates", "Undefined"]
+            .into_iter()
+            .map(|key| LocaleKey {
+                key: key.into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 0,
+                args: Vec::new(),
+                locale: None,
+                ignored: false,
+            })
+            .collect::<Vec<_>>();
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+        let unused = |rule: UnusedLocaleKeys| {
+            rule.check(&ctx)
+                .into_iter()
+                .map(|diagnostic| diagnostic.key.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            unused(UnusedLocaleKeys::new(VariantsConfig::default())),
+            ["Welcome@feminine", "Pulling"]
+        );
+        assert_eq!(
+            unused(UnusedLocaleKeys::new(VariantsConfig {
+                separator: Some("@".into()),
+                names: Vec::new(),
+            })),
+            ["Pulling"]
+        );
+    }
+}
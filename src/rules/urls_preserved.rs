@@ -0,0 +1,161 @@
+//! A rule that checks if the URLs in English are preserved in the other
+//! translations.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use std::sync::Arc;
+
+/// Schemes of the URLs to extract.
+const URL_SCHEMES: [&str; 2] = ["https://", "http://"];
+
+/// Returns the URLs in `text`, sorted.
+///
+/// A URL starts with a scheme in [`URL_SCHEMES`] and ends at a whitespace,
+/// trailing punctuation (e.g., the `.` ending a sentence) is not part of it.
+fn urls_of(text: &str) -> Vec<&str> {
+    let mut urls = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = URL_SCHEMES
+        .iter()
+        .filter_map(|scheme| rest.find(scheme))
+        .min()
+    {
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`'))
+            .unwrap_or(rest.len());
+        let url = rest[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+        urls.push(url);
+        rest = &rest[end..];
+    }
+
+    urls.sort_unstable();
+    urls
+}
+
+/// Checks if every non-English translation contains exactly the URLs in the
+/// English translation, translated or truncated links are broken links.
+pub(crate) struct UrlsPreserved;
+
+/// Documentation of [`UrlsPreserved`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "UrlsPreserved",
+    code: "I18N010",
+    description: "Checks if every non-English translation contains exactly the URLs in the English translation.",
+    rationale: "Translated or truncated links are broken links.",
+    options: &[],
+    example_syntax: "yaml",
+    failing: "See the wiki:
+  en: See https://github.com/topgrade-rs/topgrade/wiki
+  de: Siehe https://github.com/topgrade-rs/topgrade/wiki/de
+",
+    passing: "See the wiki:
+  en: See https://github.com/topgrade-rs/topgrade/wiki
+  de: Siehe https://github.com/topgrade-rs/topgrade/wiki
+",
+};
+
+impl Rule for UrlsPreserved {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (key, translations) in ctx.localized_texts.texts.iter() {
+            let Some(en) = &translations.en else {
+                continue;
+            };
+            let expected = urls_of(en);
+
+            for (lang, translation) in translations.others.iter() {
+                let found = urls_of(translation);
+                if found == expected {
+                    continue;
+                }
+
+                let missing = expected
+                    .iter()
+                    .filter(|url| !found.contains(url))
+                    .copied()
+                    .collect::<Vec<_>>();
+                let unexpected = found
+                    .iter()
+                    .filter(|url| !expected.contains(url))
+                    .copied()
+                    .collect::<Vec<_>>();
+                let error_msg = if missing.is_empty() && unexpected.is_empty() {
+                    // Same URLs, but different numbers of occurrences
+                    format!(
+                        "URLs differ from English: expected [{}], found [{}]",
+                        expected.join(", "),
+                        found.join(", ")
+                    )
+                } else {
+                    format!(
+                        "URLs differ from English: missing [{}], unexpected [{}]",
+                        missing.join(", "),
+                        unexpected.join(", ")
+                    )
+                };
+
+                diagnostics.push(
+                    Diagnostic::error(key.clone(), Some(error_msg))
+                        .with_langs(vec![Arc::clone(lang)]),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_urls_of() {
+        assert_eq!(
+            urls_of("See https://github.com/topgrade-rs/topgrade. Or (http://example.com/a?b=c)"),
+            [
+                "http://example.com/a?b=c",
+                "https://github.com/topgrade-rs/topgrade"
+            ]
+        );
+        assert!(urls_of("No links here").is_empty());
+    }
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "See the docs".into(),
+                Translations {
+                    en: Some("See https://docs.rs/topgrade".into()),
+                    others: IndexMap::from([
+                        ("de".into(), "Siehe https://docs.rs/topgrade.".into()),
+                        ("fr".into(), "Voir https://docs.rs/fr/topgrade".into()),
+                    ]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let rule = UrlsPreserved;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic::error(
+            "See the docs".into(),
+            Some(
+                "URLs differ from English: missing [https://docs.rs/topgrade], unexpected [https://docs.rs/fr/topgrade]"
+                    .into(),
+            ),
+        )
+        .with_langs(vec!["fr".into()])];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
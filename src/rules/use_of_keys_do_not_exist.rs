@@ -1,35 +1,90 @@
 //! A rule that checks if Topgrade uses any locale keys that do not exist.
 
-use super::Rule;
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::config::VariantsConfig;
 use crate::locale_file_parser::LocalizedTexts;
 use crate::locale_key_collector::LocaleKey;
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 /// Checks if Topgrade uses any locale keys that do not exist.
-pub(crate) struct UseOfKeysDoNotExist;
+///
+/// A key exists if it is defined, or if any of its variants is, e.g.,
+/// `Welcome@feminine`, as the variant is picked at runtime.
+///
+/// Invocations with an `// i18n-checker: ignore` comment on or above them are
+/// skipped, e.g., the ones of experimental keys.
+pub(crate) struct UseOfKeysDoNotExist {
+    /// How key variants are defined.
+    variants: VariantsConfig,
+}
 
-impl Rule for UseOfKeysDoNotExist {
-    fn check(
+impl UseOfKeysDoNotExist {
+    /// Creates the rule with the given key `variants` convention.
+    pub(crate) fn new(variants: VariantsConfig) -> Self {
+        Self { variants }
+    }
+}
+
+/// Documentation of [`UseOfKeysDoNotExist`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "UseOfKeysDoNotExist",
+    code: "I18N003",
+    description: "Checks if every key used by `t!()` exists in the locale file, directly or as the key of variants, e.g., `Welcome@feminine`.",
+    rationale: "rust-i18n prints the key itself when it is not found, so a typo in a key silently ships untranslated text. Invocations that are missing on purpose are skipped with an `// i18n-checker: ignore` comment on or above them.",
+    options: &[
+        ("variants.separator", "Separator between a key and the name of its variant, e.g., `@`, variants are not recognized if it is not specified."),
+    ],
+    example_syntax: "rust",
+    failing: r#"// "Restartng" is not in the locale file
+println!("{}", t!("Restartng"));
+"#,
+    passing: r#"println!("{}", t!("Restarting"));
+// i18n-checker: ignore
+println!("{}", t!("Experimental"));
+"#,
+};
+
+impl UseOfKeysDoNotExist {
+    /// Returns the `t!()` invocations of `locale_keys` whose keys do not exist
+    /// in `localized_texts`, ignored invocations are skipped.
+    pub(crate) fn missing_keys<'k>(
         &self,
         localized_texts: &LocalizedTexts,
-        locale_keys: &[LocaleKey],
-        errors: &mut HashMap<String, Vec<(String, Option<String>)>>,
-    ) {
-        for locale_key in locale_keys {
-            if !localized_texts.texts.contains_key(&locale_key.key) {
-                Self::report_error(
-                    format!(
-                        "file '{}' / line '{}' / column '{}' / key '{}'",
-                        locale_key.file.display(),
-                        locale_key.line,
-                        locale_key.column,
-                        locale_key.key
-                    ),
-                    None,
-                    errors,
-                );
-            }
-        }
+        locale_keys: &'k [LocaleKey],
+    ) -> Vec<&'k LocaleKey> {
+        let keys_of_variants = localized_texts
+            .texts
+            .keys()
+            .filter_map(|key| match self.variants.split(key) {
+                (base, Some(_)) => Some(base),
+                (_, None) => None,
+            })
+            .collect::<HashSet<_>>();
+
+        locale_keys
+            .iter()
+            .filter(|locale_key| {
+                !locale_key.ignored
+                    && !localized_texts.texts.contains_key(&*locale_key.key)
+                    && !keys_of_variants.contains(&*locale_key.key)
+            })
+            .collect()
+    }
+}
+
+impl Rule for UseOfKeysDoNotExist {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        self.missing_keys(ctx.localized_texts, ctx.locale_keys)
+            .into_iter()
+            .map(|locale_key| {
+                Diagnostic::error(Arc::clone(&locale_key.key), None).at(
+                    Arc::clone(&locale_key.file),
+                    locale_key.line,
+                    locale_key.column,
+                )
+            })
+            .collect()
     }
 }
 
@@ -48,40 +103,133 @@ mod tests {
         };
         let locale_keys = vec![LocaleKey {
             key: "Restarting".into(),
-            file: Path::new("foo.rs"),
+            file: Path::new("foo.rs").into(),
             line: 1,
             column: 1,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
         }];
-        let mut errors = HashMap::new();
-        let rule = UseOfKeysDoNotExist;
-        rule.check(&localized_texts, &locale_keys, &mut errors);
-        let expected_errors = HashMap::from([(
-            <UseOfKeysDoNotExist as Rule>::name().into(),
-            vec![(
-                "file 'foo.rs' / line '1' / column '1' / key 'Restarting'".into(),
-                None,
-            )],
-        )]);
-        assert_eq!(errors, expected_errors);
+        let rule = UseOfKeysDoNotExist::new(Default::default());
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics =
+            vec![Diagnostic::error("Restarting".into(), None).at(Path::new("foo.rs").into(), 1, 1)];
+        assert_eq!(diagnostics, expected_diagnostics);
 
         let localized_texts = LocalizedTexts {
             texts: IndexMap::from([(
                 "Restarting".into(),
                 Translations {
                     en: Some("Restarting".into()),
+                    ..Default::default()
                 },
             )]),
         };
         let locale_keys = vec![LocaleKey {
             key: "Restarting".into(),
-            file: Path::new("foo.rs"),
+            file: Path::new("foo.rs").into(),
             line: 1,
             column: 1,
+            args: Vec::new(),
+            locale: None,
+            ignored: false,
         }];
-        let mut errors = HashMap::new();
-        let rule = UseOfKeysDoNotExist;
-        rule.check(&localized_texts, &locale_keys, &mut errors);
-        let expected_errors = HashMap::new();
-        assert_eq!(errors, expected_errors);
+        let rule = UseOfKeysDoNotExist::new(Default::default());
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics: Vec<Diagnostic> = Vec::new();
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_variants() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Welcome@feminine".into(),
+                Translations {
+                    en: Some("Welcome".into()),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let locale_keys = ["Welcome", "Welcome@feminine", "Welcome@masculine"]
+            .into_iter()
+            .map(|key| LocaleKey {
+                key: key.into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 1,
+                args: Vec::new(),
+                locale: None,
+                ignored: false,
+            })
+            .collect::<Vec<_>>();
+        let ctx = CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        };
+
+        let rule = UseOfKeysDoNotExist::new(VariantsConfig {
+            separator: Some("@".into()),
+            names: Vec::new(),
+        });
+        assert_eq!(
+            rule.check(&ctx),
+            [Diagnostic::error("Welcome@masculine".into(), None).at(
+                Path::new("foo.rs").into(),
+                1,
+                1
+            )]
+        );
+        assert_eq!(
+            UseOfKeysDoNotExist::new(Default::default())
+                .check(&ctx)
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_ignored() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::new(),
+        };
+        let locale_keys = [("Restarting", true), ("Pulling", false)]
+            .into_iter()
+            .map(|(key, ignored)| LocaleKey {
+                key: key.into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 1,
+                args: Vec::new(),
+                locale: None,
+                ignored,
+            })
+            .collect::<Vec<_>>();
+        let diagnostics = UseOfKeysDoNotExist::new(Default::default()).check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        assert_eq!(
+            diagnostics,
+            [Diagnostic::error("Pulling".into(), None).at(Path::new("foo.rs").into(), 1, 1)]
+        );
     }
 }
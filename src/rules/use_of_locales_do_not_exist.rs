@@ -0,0 +1,236 @@
+//! A rule that checks if Topgrade sets or passes any locales that do not
+//! exist.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc};
+use crate::config::LanguageAliases;
+use std::sync::Arc;
+
+/// Checks if the locales passed to `set_locale()`, and to `t!()` with
+/// `locale = ...`, exist in the locale file.
+///
+/// Locales are canonicalized with the configured language aliases, so
+/// `set_locale("zh-CN")` is fine if `zh-CN` is an alias of a language that
+/// exists. `t!()` looks the locale up as is, so it should be spelled exactly
+/// as in the locale file.
+pub(crate) struct UseOfLocalesDoNotExist {
+    /// Language aliases.
+    aliases: LanguageAliases,
+}
+
+impl UseOfLocalesDoNotExist {
+    /// Creates the rule with the given language `aliases`.
+    pub(crate) fn new(aliases: LanguageAliases) -> Self {
+        Self { aliases }
+    }
+}
+
+/// Documentation of [`UseOfLocalesDoNotExist`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "UseOfLocalesDoNotExist",
+    code: "I18N004",
+    description: "Checks if the locales passed to `set_locale()` exist in the locale file, after resolving the language aliases, and if the locales passed to `t!()` with `locale = ...`, as a string literal or a variable bound to one, are spelled exactly as in the locale file.",
+    rationale: "Switching to a locale that does not exist falls back to English for every text, and `t!()` does not resolve aliases, so a misspelled locale falls back to English as well.",
+    options: &[
+        ("languages.aliases", r#"`Alias => CanonicalName`, e.g., `"zh-CN" = "zh_CN"`, aliases of existing languages are accepted by `set_locale()`."#),
+    ],
+    example_syntax: "rust",
+    failing: r#"// the locale file has no "de_DE" translations
+rust_i18n::set_locale("de_DE");
+// the locale file has "zh_CN" translations
+t!("Restarting", locale = "zh-CN");
+"#,
+    passing: r#"rust_i18n::set_locale("de");
+t!("Restarting", locale = "zh_CN");
+"#,
+};
+
+impl Rule for UseOfLocalesDoNotExist {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let languages = ctx.localized_texts.languages();
+
+        for locale_key in ctx.checked_locale_keys() {
+            let Some(locale_arc) = &locale_key.locale else {
+                continue;
+            };
+            let locale = &**locale_arc;
+            if languages.contains(locale) {
+                continue;
+            }
+
+            // `t!()` does not resolve aliases
+            let canonical = Some(self.aliases.canonicalize(locale))
+                .filter(|canonical| languages.contains(*canonical))
+                .or_else(|| {
+                    languages
+                        .iter()
+                        .map(|lang| &**lang)
+                        .find(|lang| loosely_eq(lang, locale))
+                });
+            let error_msg = match canonical {
+                Some(canonical) => format!(
+                    "locale '{}' should be spelled as in the locale file, '{}'",
+                    locale, canonical
+                ),
+                None => format!("locale '{}' does not exist in the locale file", locale),
+            };
+            diagnostics.push(
+                Diagnostic::error(Arc::clone(&locale_key.key), Some(error_msg))
+                    .with_langs(vec![Arc::clone(locale_arc)])
+                    .at(
+                        Arc::clone(&locale_key.file),
+                        locale_key.line,
+                        locale_key.column,
+                    ),
+            );
+        }
+
+        for call in ctx.set_locale_calls {
+            if !languages.contains(self.aliases.canonicalize(&call.locale)) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "".into(),
+                        Some(format!(
+                            "locale '{}' does not exist in the locale file",
+                            call.locale
+                        )),
+                    )
+                    .with_langs(vec![Arc::clone(&call.locale)])
+                    .at(Arc::clone(&call.file), call.line, call.column),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Returns true if locales `a` and `b` only differ in case and in `-` versus
+/// `_`, e.g., `zh-cn` and `zh_CN`.
+fn loosely_eq(a: &str, b: &str) -> bool {
+    let normalize = |c: char| match c {
+        '-' => '_',
+        c => c.to_ascii_lowercase(),
+    };
+    a.len() == b.len() && a.chars().map(normalize).eq(b.chars().map(normalize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::{LocalizedTexts, Translations};
+    use crate::locale_key_collector::{LocaleKey, SetLocaleCall};
+    use indexmap::IndexMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Restarting".into(),
+                Translations {
+                    en: Some("Restarting".into()),
+                    others: IndexMap::from([("zh_CN".into(), "重启".into())]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let set_locale_calls = vec![
+            SetLocaleCall {
+                locale: "zh-CN".into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 1,
+            },
+            SetLocaleCall {
+                locale: "en".into(),
+                file: Path::new("foo.rs").into(),
+                line: 2,
+                column: 1,
+            },
+            SetLocaleCall {
+                locale: "de".into(),
+                file: Path::new("foo.rs").into(),
+                line: 3,
+                column: 1,
+            },
+        ];
+        let rule = UseOfLocalesDoNotExist::new(LanguageAliases::from([("zh-CN", "zh_CN")]));
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &set_locale_calls,
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic::error(
+            "".into(),
+            Some("locale 'de' does not exist in the locale file".into()),
+        )
+        .with_langs(vec!["de".into()])
+        .at(Path::new("foo.rs").into(), 3, 1)];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+
+    #[test]
+    fn test_locale_overrides() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([(
+                "Restarting".into(),
+                Translations {
+                    en: Some("Restarting".into()),
+                    others: IndexMap::from([("zh_CN".into(), "重启".into())]),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let locale_keys = [
+            Some("zh_CN"),
+            Some("zh-CN"),
+            Some("ZH-cn"),
+            Some("de"),
+            None,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, locale)| LocaleKey {
+            key: "Restarting".into(),
+            file: Path::new("foo.rs").into(),
+            line: idx + 1,
+            column: 0,
+            args: Vec::new(),
+            locale: locale.map(Into::into),
+            ignored: false,
+        })
+        .collect::<Vec<_>>();
+        let rule = UseOfLocalesDoNotExist::new(LanguageAliases::from([("zh-CN", "zh_CN")]));
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            set_locale_calls: &[],
+            concatenations: &[],
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![
+            Diagnostic::error(
+                "Restarting".into(),
+                Some("locale 'zh-CN' should be spelled as in the locale file, 'zh_CN'".into()),
+            )
+            .with_langs(vec!["zh-CN".into()])
+            .at(Path::new("foo.rs").into(), 2, 0),
+            Diagnostic::error(
+                "Restarting".into(),
+                Some("locale 'ZH-cn' should be spelled as in the locale file, 'zh_CN'".into()),
+            )
+            .with_langs(vec!["ZH-cn".into()])
+            .at(Path::new("foo.rs").into(), 3, 0),
+            Diagnostic::error(
+                "Restarting".into(),
+                Some("locale 'de' does not exist in the locale file".into()),
+            )
+            .with_langs(vec!["de".into()])
+            .at(Path::new("foo.rs").into(), 4, 0),
+        ];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,105 @@
+//! A rule that checks if Topgrade surrounds the results of `t!()` with words
+//! in format strings.
+
+use super::{CheckContext, Diagnostic, Rule, RuleDoc, Severity, SourceLocation};
+use crate::locale_key_collector::ConcatenationKind;
+use std::sync::Arc;
+
+/// Checks if the result of any `t!()` invocation is surrounded by literal
+/// words in the same format string, e.g.,
+/// `format!("Failed to {}: error", t!("update"))`.
+///
+/// The whole sentence should be a single key with placeholders, so that word
+/// order can change per language. Problems are reported as warnings.
+pub(crate) struct WordsAroundTranslations;
+
+/// Documentation of [`WordsAroundTranslations`].
+pub(crate) const DOC: RuleDoc = RuleDoc {
+    name: "WordsAroundTranslations",
+    code: "I18N023",
+    description: "Checks if the result of any `t!()` invocation is surrounded by literal words in the same format string.",
+    rationale: "The whole sentence should be a single key with placeholders, so that word order can change per language. Problems are reported as warnings.",
+    options: &[],
+    example_syntax: "rust",
+    failing: r#"println!("Failed to {}: error", t!("update"));
+"#,
+    passing: r#"println!("{}", t!("Failed to update: error"));
+"#,
+};
+
+impl Rule for WordsAroundTranslations {
+    fn check(&self, ctx: &CheckContext<'_>) -> Vec<Diagnostic> {
+        ctx.concatenations
+            .iter()
+            .filter(|concatenation| concatenation.kind == ConcatenationKind::FormatString)
+            .map(|concatenation| Diagnostic {
+                location: Some(SourceLocation {
+                    file: Arc::clone(&concatenation.file),
+                    line: concatenation.line,
+                    column: concatenation.column,
+                }),
+                key: Arc::clone(&concatenation.key),
+                error_msg: Some(String::from(
+                    "The result of t!() is surrounded by words in the format string, make the whole sentence a key with placeholders",
+                )),
+                severity: Severity::Warning,
+                langs: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::LocalizedTexts;
+    use crate::locale_key_collector::Concatenation;
+    use indexmap::IndexMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_rule_works() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::new(),
+        };
+        let concatenations = vec![
+            Concatenation {
+                kind: ConcatenationKind::FormatString,
+                key: "update".into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 1,
+            },
+            Concatenation {
+                kind: ConcatenationKind::AddOperator,
+                key: "Restarting".into(),
+                file: Path::new("foo.rs").into(),
+                line: 2,
+                column: 1,
+            },
+        ];
+        let rule = WordsAroundTranslations;
+        let diagnostics = rule.check(&CheckContext {
+            localized_texts: &localized_texts,
+            locale_keys: &[],
+            set_locale_calls: &[],
+            concatenations: &concatenations,
+            sink_literals: &[],
+        });
+        let expected_diagnostics = vec![Diagnostic {
+            location: Some(SourceLocation {
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 1,
+            }),
+            key: "update".into(),
+            error_msg: Some(
+                "The result of t!() is surrounded by words in the format string, make the whole sentence a key with placeholders"
+                    .into(),
+            ),
+            severity: Severity::Warning,
+            langs: Vec::new(),
+        }];
+        assert_eq!(diagnostics, expected_diagnostics);
+    }
+}
@@ -0,0 +1,143 @@
+//! This file contains the SARIF 2.1.0 output, which GitHub code scanning
+//! ingests to annotate pull requests with the diagnostics.
+//!
+//! Every rule of [`RULES`] is described in the driver, results reference them
+//! by code, e.g., `I18N003`, so that code scanning shows their documentation.
+
+use crate::editor::subject_of;
+use crate::report::Report;
+use crate::rules::{Severity, RULES};
+use serde_json::{json, Value as Json};
+
+/// Name of the tool in the log.
+const TOOL_NAME: &str = "topgrade-i18n-check";
+
+/// Where the tool is documented.
+const TOOL_URI: &str = "https://github.com/topgrade-rs/topgrade_i18n_locale_checker";
+
+/// Returns the SARIF level of `severity`.
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Renders the diagnostics of `report` as a SARIF log with a single run.
+///
+/// Paths are relative to `%SRCROOT%`, the directory the check ran in, which
+/// code scanning resolves to the repository root.
+pub(crate) fn render(report: &Report) -> String {
+    let rules = RULES
+        .iter()
+        .map(|doc| {
+            json!({
+                "id": doc.code,
+                "name": doc.name,
+                "shortDescription": { "text": doc.description },
+                "fullDescription": { "text": doc.rationale },
+            })
+        })
+        .collect::<Vec<_>>();
+    let results = report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut message = subject_of(&diagnostic.key).into_owned();
+            if let Some(msg) = &diagnostic.message {
+                message.push_str(": ");
+                message.push_str(msg);
+            }
+            let rule_index = RULES.iter().position(|doc| doc.code == diagnostic.code);
+
+            json!({
+                "ruleId": diagnostic.code,
+                "ruleIndex": rule_index,
+                "level": level(diagnostic.severity),
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": diagnostic.file.to_string_lossy().replace('\\', "/"),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": {
+                            "startLine": diagnostic.line,
+                            "startColumn": diagnostic.column,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let log: Json = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "informationUri": TOOL_URI,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&log).expect("SARIF log should be serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ReportDiagnostic;
+
+    #[test]
+    fn test_render() {
+        let mut report = Report::new();
+        report.diagnostics.push(ReportDiagnostic {
+            project: None,
+            rule: "UseOfKeysDoNotExist".into(),
+            code: "I18N003".into(),
+            severity: Severity::Warning,
+            in_source: true,
+            file: "src/steps/git.rs".into(),
+            line: 10,
+            column: 5,
+            key: "Pulling".into(),
+            message: Some("typo?".into()),
+            langs: Vec::new(),
+        });
+
+        let log = serde_json::from_str::<Json>(&render(&report)).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+            RULES.len()
+        );
+        assert_eq!(run["tool"]["driver"]["rules"][2]["id"], "I18N003");
+        assert_eq!(
+            run["results"],
+            json!([{
+                "ruleId": "I18N003",
+                "ruleIndex": 2,
+                "level": "warning",
+                "message": { "text": "key 'Pulling': typo?" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": "src/steps/git.rs",
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": { "startLine": 10, "startColumn": 5 },
+                    },
+                }],
+            }])
+        );
+    }
+}
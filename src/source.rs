@@ -0,0 +1,452 @@
+//! This file contains type [`Source`], where the locale file and Rust files are
+//! read from.
+//!
+//! The files of a git revision are read by running git rather than through a
+//! git library such as gix or git2: git is needed anyway to find the commit of
+//! the work tree, a library would pull a large dependency tree, or libgit2 for
+//! git2, into the build, and a single `git cat-file --batch` process reads the
+//! files about as fast. Git failures are reported as [`Error::Git`], with the
+//! command and what git printed to stderr.
+
+use crate::error::Error;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+/// Where the files to check are read from.
+#[derive(Debug)]
+pub(crate) enum Source {
+    /// The file system.
+    WorkTree,
+    /// A revision in a git repository.
+    GitRev(GitRev),
+    /// The file system, except for a Rust file whose contents are read from
+    /// stdin, e.g., the unsaved buffer of an editor, it is the only Rust file
+    /// checked.
+    Stdin(StdinBuffer),
+}
+
+impl Source {
+    /// Reads the contents of `file` into `buf`, `buf` will be cleared first.
+    ///
+    /// Fails if the contents are not valid UTF-8.
+    pub(crate) fn read_to_string(&self, file: &Path, buf: &mut String) -> Result<(), Error> {
+        let mut bytes = std::mem::take(buf).into_bytes();
+        self.read(file, &mut bytes)?;
+        *buf = String::from_utf8(bytes).map_err(|e| Error::Read {
+            path: file.to_path_buf(),
+            error: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })?;
+
+        Ok(())
+    }
+
+    /// Reads the raw contents of `file` into `buf`, `buf` will be cleared
+    /// first.
+    pub(crate) fn read(&self, file: &Path, buf: &mut Vec<u8>) -> Result<(), Error> {
+        buf.clear();
+        match self {
+            Source::WorkTree => {
+                std::fs::File::open(file)
+                    .and_then(|mut f| f.read_to_end(buf))
+                    .map_err(|error| Error::Read {
+                        path: file.to_path_buf(),
+                        error,
+                    })?;
+            }
+            Source::GitRev(git_rev) => git_rev.read(file, buf)?,
+            Source::Stdin(buffer) if file == buffer.path => buf.extend_from_slice(&buffer.contents),
+            Source::Stdin(_) => Source::WorkTree.read(file, buf)?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns the hash of the commit being checked, i.e., `HEAD` for the
+    /// work tree, `None` if it is not in a git repository.
+    pub(crate) fn commit(&self) -> Option<String> {
+        let (rev, work_dir) = match self {
+            Source::WorkTree | Source::Stdin(_) => ("HEAD", Path::new(".")),
+            Source::GitRev(git_rev) => (git_rev.rev(), git_rev.work_dir.as_path()),
+        };
+        let output = Command::new("git")
+            .current_dir(work_dir)
+            .args(["rev-parse", "--verify", "--quiet"])
+            .arg(format!("{}^{{commit}}", rev))
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+    }
+}
+
+/// A Rust file read from stdin.
+#[derive(Debug)]
+pub(crate) struct StdinBuffer {
+    /// The path the file is reported at, it does not need to exist.
+    path: PathBuf,
+    /// Contents of the file.
+    contents: Vec<u8>,
+}
+
+impl StdinBuffer {
+    /// Creates a [`StdinBuffer`] of `contents`, reported at `path`.
+    pub(crate) fn new(path: PathBuf, contents: Vec<u8>) -> Self {
+        Self { path, contents }
+    }
+
+    /// Reads stdin to the end, the file is reported at `path`.
+    pub(crate) fn read(path: PathBuf) -> Result<Self, Error> {
+        let mut contents = Vec::new();
+        std::io::stdin()
+            .lock()
+            .read_to_end(&mut contents)
+            .map_err(|error| Error::Read {
+                path: PathBuf::from("stdin"),
+                error,
+            })?;
+
+        Ok(Self::new(path, contents))
+    }
+
+    /// Accesses the path the file is reported at.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// A revision in a git repository.
+///
+/// Files are read from the git object database directly, so no worktree is
+/// needed for the revision.
+#[derive(Debug)]
+pub(crate) struct GitRev {
+    /// The revision, anything that `git rev-parse` accepts.
+    rev: String,
+    /// The directory where git commands are executed, paths are relative to it.
+    work_dir: PathBuf,
+    /// A long-running `git cat-file --batch` process, spawned on the first read.
+    cat_file: Mutex<Option<CatFile>>,
+}
+
+impl GitRev {
+    /// Creates a [`GitRev`], paths will be resolved relative to `work_dir`.
+    pub(crate) fn new(rev: String, work_dir: PathBuf) -> Self {
+        Self {
+            rev,
+            work_dir,
+            cat_file: Mutex::new(None),
+        }
+    }
+
+    /// Accesses the revision.
+    pub(crate) fn rev(&self) -> &str {
+        &self.rev
+    }
+
+    /// Lists all the files under `paths` in this revision.
+    pub(crate) fn list_files(&self, paths: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+        let mut command = Command::new("git");
+        command
+            .current_dir(&self.work_dir)
+            .args(["ls-tree", "-r", "-z", "--name-only", &self.rev, "--"])
+            .args(paths);
+        let stdout = run(&mut command)?;
+
+        Ok(stdout
+            .split(|byte| *byte == b'\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| PathBuf::from(String::from_utf8_lossy(path).into_owned()))
+            .collect())
+    }
+
+    /// Reads the contents of `file` in this revision into `buf`.
+    fn read(&self, file: &Path, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let mut guard = self.cat_file.lock().expect("lock should not be poisoned");
+        let cat_file = match guard.as_mut() {
+            Some(cat_file) => cat_file,
+            None => {
+                // Otherwise, every file of a revision that does not exist
+                // would be reported as missing
+                run(Command::new("git")
+                    .current_dir(&self.work_dir)
+                    .args(["rev-parse", "--verify"])
+                    .arg(format!("{}^{{tree}}", self.rev)))?;
+                guard.insert(CatFile::spawn(&self.work_dir)?)
+            }
+        };
+
+        // `./` makes git resolve the path relative to the working directory
+        let object = format!("{}:./{}", self.rev, file.display());
+        match cat_file.read(&object, buf) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Error::Read {
+                path: file.to_path_buf(),
+                error: std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("it does not exist in revision {}", self.rev),
+                ),
+            }),
+            Err(error) => {
+                // The output of the process is out of sync, a new one is
+                // spawned on the next read
+                let cat_file = guard.take().expect("it is spawned");
+                Err(cat_file.into_error(error))
+            }
+        }
+    }
+}
+
+/// Runs `command`, a git command, and returns its stdout.
+fn run(command: &mut Command) -> Result<Vec<u8>, Error> {
+    let output = command.output().map_err(|e| Error::Git {
+        command: git_args(command),
+        message: format!("cannot execute git due to error {}", e),
+    })?;
+    if !output.status.success() {
+        return Err(Error::Git {
+            command: git_args(command),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    Ok(output.stdout)
+}
+
+/// Returns the arguments of `command`, a git command, as they would be typed.
+fn git_args(command: &Command) -> String {
+    command
+        .get_args()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A `git cat-file --batch` process.
+#[derive(Debug)]
+struct CatFile {
+    /// The process.
+    child: Child,
+    /// Its stdin, where object names are written to.
+    stdin: ChildStdin,
+    /// Its stdout, where object contents are read from.
+    stdout: BufReader<ChildStdout>,
+}
+
+impl CatFile {
+    /// The arguments of the process.
+    const ARGS: [&'static str; 2] = ["cat-file", "--batch"];
+
+    /// Spawns the process in `work_dir`.
+    fn spawn(work_dir: &Path) -> Result<Self, Error> {
+        let mut child = Command::new("git")
+            .current_dir(work_dir)
+            .args(Self::ARGS)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Git {
+                command: Self::ARGS.join(" "),
+                message: format!("cannot execute git due to error {}", e),
+            })?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    /// Reads the contents of `object` into `buf`, returns false if it does not
+    /// exist.
+    fn read(&mut self, object: &str, buf: &mut Vec<u8>) -> std::io::Result<bool> {
+        writeln!(self.stdin, "{}", object)?;
+        self.stdin.flush()?;
+
+        // header: `<oid> <type> <size>` or `<object> missing`
+        let mut header = String::new();
+        if self.stdout.read_line(&mut header)? == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = header.trim_end();
+        if header.ends_with(" missing") {
+            return Ok(false);
+        }
+        let size = header
+            .rsplit_once(' ')
+            .and_then(|(_, size)| size.parse::<u64>().ok())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("unexpected output {:?}", header),
+                )
+            })?;
+
+        (&mut self.stdout).take(size).read_to_end(buf)?;
+        // contents are followed by a LF
+        let mut lf = [0_u8; 1];
+        self.stdout.read_exact(&mut lf)?;
+
+        Ok(true)
+    }
+
+    /// Stops the process, which failed with `error`, and returns the error
+    /// along with what it printed to stderr.
+    fn into_error(mut self, error: std::io::Error) -> Error {
+        let _ = self.child.kill();
+        let mut stderr = String::new();
+        if let Some(mut pipe) = self.child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        let message = match stderr.trim() {
+            "" => error.to_string(),
+            stderr => format!("{}: {}", error, stderr),
+        };
+
+        Error::Git {
+            command: Self::ARGS.join(" "),
+            message,
+        }
+    }
+}
+
+impl Drop for CatFile {
+    fn drop(&mut self) {
+        // The output is not needed anymore, so errors are ignored.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn git(work_dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(work_dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@test"])
+            .args(args)
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_git_rev_reads_committed_files() {
+        let repo = tempdir().unwrap();
+        let repo_path = repo.path();
+        git(repo_path, &["init", "-q"]);
+        std::fs::create_dir(repo_path.join("src")).unwrap();
+        std::fs::write(repo_path.join("src/main.rs"), "committed").unwrap();
+        std::fs::write(repo_path.join("README"), "readme").unwrap();
+        git(repo_path, &["add", "."]);
+        git(repo_path, &["commit", "-q", "-m", "init"]);
+        std::fs::write(repo_path.join("src/main.rs"), "modified").unwrap();
+
+        let source = Source::GitRev(GitRev::new("HEAD".into(), repo_path.to_path_buf()));
+        let Source::GitRev(git_rev) = &source else {
+            unreachable!()
+        };
+        assert_eq!(
+            git_rev.list_files(&[PathBuf::from("src")]).unwrap(),
+            [PathBuf::from("src/main.rs")]
+        );
+
+        let mut buf = String::new();
+        source
+            .read_to_string(Path::new("src/main.rs"), &mut buf)
+            .unwrap();
+        assert_eq!(buf, "committed");
+        // the process is reused
+        source
+            .read_to_string(Path::new("README"), &mut buf)
+            .unwrap();
+        assert_eq!(buf, "readme");
+    }
+
+    #[test]
+    fn test_git_rev_errors() {
+        let repo = tempdir().unwrap();
+        let repo_path = repo.path();
+        git(repo_path, &["init", "-q"]);
+        std::fs::write(repo_path.join("README"), "readme").unwrap();
+        git(repo_path, &["add", "."]);
+        git(repo_path, &["commit", "-q", "-m", "init"]);
+
+        let source = Source::GitRev(GitRev::new("HEAD".into(), repo_path.to_path_buf()));
+        let mut buf = String::new();
+        let error = source
+            .read_to_string(Path::new("missing.rs"), &mut buf)
+            .unwrap_err();
+        assert!(matches!(&error, Error::Read { path, .. } if path == Path::new("missing.rs")));
+        assert!(error
+            .to_string()
+            .ends_with("does not exist in revision HEAD"));
+        // the process is still usable
+        source
+            .read_to_string(Path::new("README"), &mut buf)
+            .unwrap();
+        assert_eq!(buf, "readme");
+
+        let source = Source::GitRev(GitRev::new("nope".into(), repo_path.to_path_buf()));
+        let error = source
+            .read_to_string(Path::new("README"), &mut buf)
+            .unwrap_err();
+        let Error::Git { command, message } = &error else {
+            panic!("unexpected error {:?}", error);
+        };
+        assert_eq!(command, "rev-parse --verify nope^{tree}");
+        assert!(message.starts_with("fatal: "));
+
+        let Source::GitRev(git_rev) = &source else {
+            unreachable!()
+        };
+        let error = git_rev.list_files(&[PathBuf::from("src")]).unwrap_err();
+        assert!(
+            matches!(&error, Error::Git { command, .. } if command == "ls-tree -r -z --name-only nope -- src")
+        );
+    }
+
+    #[test]
+    fn test_stdin_buffer_overrides_its_file() {
+        let dir = tempdir().unwrap();
+        let on_disk = dir.path().join("app.yml");
+        std::fs::write(&on_disk, "on disk").unwrap();
+        let source = Source::Stdin(StdinBuffer::new(
+            PathBuf::from("src/unsaved.rs"),
+            b"fn main() {}".to_vec(),
+        ));
+
+        let mut buf = String::new();
+        source
+            .read_to_string(Path::new("src/unsaved.rs"), &mut buf)
+            .unwrap();
+        assert_eq!(buf, "fn main() {}");
+        source.read_to_string(&on_disk, &mut buf).unwrap();
+        assert_eq!(buf, "on disk");
+    }
+
+    #[test]
+    fn test_commit() {
+        let repo = tempdir().unwrap();
+        let repo_path = repo.path();
+        git(repo_path, &["init", "-q"]);
+        let source = Source::GitRev(GitRev::new("HEAD".into(), repo_path.to_path_buf()));
+        assert_eq!(source.commit(), None);
+
+        git(repo_path, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        let commit = source.commit().unwrap();
+        assert_eq!(commit.len(), 40);
+        assert!(commit.bytes().all(|b| b.is_ascii_hexdigit()));
+    }
+}
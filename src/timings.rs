@@ -0,0 +1,109 @@
+//! This file contains type [`Timings`], which records how much time is spent
+//! in every phase of a run.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Number of files listed in the "slowest files" section of the report.
+const N_SLOWEST_FILES: usize = 10;
+
+/// Time spent in every phase of a run.
+#[derive(Debug, Default)]
+pub(crate) struct Timings {
+    /// `(PhaseName, Duration)`, in the order they are recorded.
+    phases: Vec<(String, Duration)>,
+    /// `(File, Duration)`, time spent on parsing every Rust file.
+    files: Vec<(PathBuf, Duration)>,
+}
+
+impl Timings {
+    /// Creates an empty [`Timings`].
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f` and records the time it takes as phase `phase`.
+    pub(crate) fn time<T>(&mut self, phase: impl Into<String>, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let ret = f();
+        self.record_phase(phase, start.elapsed());
+
+        ret
+    }
+
+    /// Records that phase `phase` takes `duration`.
+    pub(crate) fn record_phase(&mut self, phase: impl Into<String>, duration: Duration) {
+        let phase = phase.into();
+        tracing::info!(
+            phase,
+            duration_ms = duration.as_secs_f64() * 1000.0,
+            "phase finished"
+        );
+        self.phases.push((phase, duration));
+    }
+
+    /// Records that parsing `file` takes `duration`.
+    pub(crate) fn record_file(&mut self, file: &Path, duration: Duration) {
+        tracing::debug!(
+            file = %file.display(),
+            duration_ms = duration.as_secs_f64() * 1000.0,
+            "file parsed"
+        );
+        self.files.push((file.to_path_buf(), duration));
+    }
+
+    /// Returns at most `n` files that take the most time to parse, slowest first.
+    fn slowest_files(&self, n: usize) -> Vec<&(PathBuf, Duration)> {
+        let mut files = self.files.iter().collect::<Vec<_>>();
+        files.sort_by(|(_, a), (_, b)| b.cmp(a));
+        files.truncate(n);
+
+        files
+    }
+
+    /// Print the recorded timings in a human-readable way.
+    pub(crate) fn report_to_user(&self) {
+        println!("Timings:");
+        for (phase, duration) in self.phases.iter() {
+            println!("  {}: {:?}", phase, duration);
+        }
+
+        if !self.files.is_empty() {
+            println!("  Slowest files:");
+            for (file, duration) in self.slowest_files(N_SLOWEST_FILES) {
+                println!("    {}: {:?}", file.display(), duration);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slowest_files() {
+        let mut timings = Timings::new();
+        timings.record_file(Path::new("a.rs"), Duration::from_millis(1));
+        timings.record_file(Path::new("b.rs"), Duration::from_millis(3));
+        timings.record_file(Path::new("c.rs"), Duration::from_millis(2));
+
+        let slowest = timings
+            .slowest_files(2)
+            .into_iter()
+            .map(|(file, _)| file.as_path())
+            .collect::<Vec<_>>();
+        assert_eq!(slowest, [Path::new("b.rs"), Path::new("c.rs")]);
+    }
+
+    #[test]
+    fn test_time_records_phase() {
+        let mut timings = Timings::new();
+        let ret = timings.time("phase", || 1);
+        assert_eq!(ret, 1);
+        assert_eq!(timings.phases.len(), 1);
+        assert_eq!(timings.phases[0].0, "phase");
+    }
+}
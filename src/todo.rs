@@ -0,0 +1,248 @@
+//! This file contains the `todo` subcommand, which writes `<lang>.md` files
+//! listing the untranslated keys of every language with their English texts
+//! and where they are used.
+//!
+//! The files are meant to be checked in, so that translators can work from
+//! them instead of CI logs: they are regenerated on each run, and the output
+//! only depends on the locale file and the Rust files.
+
+use crate::config::LanguageMaintainers;
+use crate::error::Error;
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use indexmap::IndexMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// First line of every TODO file, files starting with it are considered
+/// generated, and removed once their language is gone.
+const HEADER: &str =
+    "<!-- Generated by `topgrade-i18n-check todo`, do not edit, it is regenerated on each run. -->";
+
+/// The untranslated keys of a project.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Todo<'a> {
+    /// The locale file.
+    pub(crate) locale_file: &'a Path,
+    /// The parsed locale file.
+    pub(crate) localized_texts: &'a LocalizedTexts,
+    /// The `t!()` invocations found.
+    pub(crate) locale_keys: &'a [LocaleKey],
+    /// The maintainers, listed in the files of their languages.
+    pub(crate) maintainers: &'a LanguageMaintainers,
+}
+
+impl Todo<'_> {
+    /// Renders the TODO file of `lang`, keys are listed in the order they are
+    /// defined.
+    pub(crate) fn render(&self, lang: &str) -> String {
+        // `Key => CallSites`, sorted so that the output is stable
+        let mut call_sites: IndexMap<&str, Vec<&LocaleKey>> = IndexMap::new();
+        for locale_key in self.locale_keys {
+            call_sites
+                .entry(&locale_key.key)
+                .or_default()
+                .push(locale_key);
+        }
+        for locale_keys in call_sites.values_mut() {
+            locale_keys
+                .sort_by_key(|locale_key| (&locale_key.file, locale_key.line, locale_key.column));
+        }
+
+        let untranslated = self
+            .localized_texts
+            .texts
+            .iter()
+            .filter(|(_, translations)| match lang {
+                "en" => translations.en.is_none(),
+                _ => !translations.others.contains_key(lang),
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = String::new();
+        writeln!(out, "{}", HEADER).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "# Untranslated keys of `{}`", lang).unwrap();
+        writeln!(out).unwrap();
+        let maintainers = self.maintainers.of(lang);
+        if !maintainers.is_empty() {
+            writeln!(out, "Maintainers: {}", maintainers.join(" ")).unwrap();
+            writeln!(out).unwrap();
+        }
+        if untranslated.is_empty() {
+            writeln!(out, "Every key is translated, thank you!").unwrap();
+            return out;
+        }
+        writeln!(
+            out,
+            "{} of {} keys are not translated, add their `{}` translations to `{}`.",
+            untranslated.len(),
+            self.localized_texts.texts.len(),
+            lang,
+            self.locale_file.display()
+        )
+        .unwrap();
+
+        for (key, translations) in untranslated {
+            writeln!(out).unwrap();
+            writeln!(out, "## {}", code_span(key)).unwrap();
+            writeln!(out).unwrap();
+            if let Some(en) = &translations.en {
+                writeln!(out, "English: {}", code_span(en)).unwrap();
+                writeln!(out).unwrap();
+            }
+            match call_sites.get(&**key) {
+                Some(locale_keys) => {
+                    let locations = locale_keys
+                        .iter()
+                        .map(|locale_key| {
+                            format!("`{}:{}`", locale_key.file.display(), locale_key.line)
+                        })
+                        .collect::<Vec<_>>();
+                    writeln!(out, "Used in: {}", locations.join(", ")).unwrap();
+                }
+                None => writeln!(out, "Not used by any `t!()` invocation.").unwrap(),
+            }
+        }
+
+        out
+    }
+
+    /// Writes the TODO file of every language but English to `dir`, and
+    /// removes the generated files of the languages that are gone, returns
+    /// the files written.
+    pub(crate) fn write_files(&self, dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        std::fs::create_dir_all(dir).map_err(|error| Error::Write {
+            path: dir.to_path_buf(),
+            error,
+        })?;
+
+        let mut written = Vec::new();
+        for lang in self.localized_texts.languages() {
+            if &*lang == "en" {
+                continue;
+            }
+            let file = dir.join(format!("{}.md", lang));
+            std::fs::write(&file, self.render(&lang)).map_err(|error| Error::Write {
+                path: file.clone(),
+                error,
+            })?;
+            written.push(file);
+        }
+
+        let entries = std::fs::read_dir(dir).map_err(|error| Error::Read {
+            path: dir.to_path_buf(),
+            error,
+        })?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_stale = path.extension().is_some_and(|extension| extension == "md")
+                && !written.contains(&path)
+                && std::fs::read_to_string(&path)
+                    .is_ok_and(|contents| contents.starts_with(HEADER));
+            if is_stale {
+                std::fs::remove_file(&path).map_err(|error| Error::Write { path, error })?;
+            }
+        }
+
+        Ok(written)
+    }
+}
+
+/// Renders `text` as a Markdown code span.
+fn code_span(text: &str) -> String {
+    let text = text.replace('\n', " ");
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::Translations;
+    use tempfile::tempdir;
+
+    fn translations(en: &str, langs: &[&str]) -> Translations {
+        Translations {
+            en: Some(en.into()),
+            others: langs
+                .iter()
+                .map(|lang| ((*lang).into(), "translated".into()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_todo() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                (
+                    "Restarting {app}".into(),
+                    translations("Restarting %{app}", &["fr"]),
+                ),
+                ("Unused".into(), translations("Unused", &[])),
+                ("Done".into(), translations("Done", &["de", "fr"])),
+            ]),
+        };
+        let locale_keys = [("b.rs", 2), ("a.rs", 9)].map(|(file, line)| LocaleKey {
+            key: "Restarting {app}".into(),
+            file: Path::new(file).into(),
+            line,
+            column: 0,
+            args: vec!["app".into()],
+            locale: None,
+            ignored: false,
+        });
+        let todo = Todo {
+            locale_file: Path::new("locales/app.yml"),
+            localized_texts: &localized_texts,
+            locale_keys: &locale_keys,
+            maintainers: &LanguageMaintainers::from([("de", &["@alice"][..])]),
+        };
+
+        assert_eq!(
+            todo.render("de"),
+            format!(
+                "{}
+
+# Untranslated keys of `de`
+
+Maintainers: @alice
+
+2 of 3 keys are not translated, add their `de` translations to `locales/app.yml`.
+
+## `Restarting {{app}}`
+
+English: `Restarting %{{app}}`
+
+Used in: `a.rs:9`, `b.rs:2`
+
+## `Unused`
+
+English: `Unused`
+
+Not used by any `t!()` invocation.
+",
+                HEADER
+            )
+        );
+
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("ja.md"), format!("{}\n", HEADER)).unwrap();
+        std::fs::write(dir.path().join("README.md"), "Hand-written\n").unwrap();
+        let written = todo.write_files(dir.path()).unwrap();
+        assert_eq!(
+            written,
+            [dir.path().join("fr.md"), dir.path().join("de.md")]
+        );
+        assert!(!dir.path().join("ja.md").exists());
+        assert!(dir.path().join("README.md").exists());
+        assert!(std::fs::read_to_string(dir.path().join("fr.md"))
+            .unwrap()
+            .contains("1 of 3 keys are not translated"));
+    }
+}
@@ -0,0 +1,151 @@
+//! This file contains type [`UsageMap`], where every locale key is used.
+
+use crate::locale_file_parser::LocalizedTexts;
+use crate::locale_key_collector::LocaleKey;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+
+/// An invocation of `t!()`.
+#[derive(Debug, PartialEq, Serialize)]
+struct Usage {
+    /// File where `t!()` is invoked.
+    file: Arc<Path>,
+    /// Line number, starts from 1.
+    line: usize,
+    /// Column number, starts from 0.
+    column: usize,
+    /// Names of the named arguments.
+    args: Vec<Arc<str>>,
+}
+
+/// `Key => Usages` for every locale key, serialized as a JSON object.
+///
+/// Keys that are defined in the locale file come first, in the order they are
+/// defined, a key that is never used has no usages. Keys that are used but not
+/// defined come after them.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(transparent)]
+pub(crate) struct UsageMap(IndexMap<Arc<str>, Vec<Usage>>);
+
+impl UsageMap {
+    /// Creates the usage map of the keys in `localized_texts` and `locale_keys`.
+    pub(crate) fn new(localized_texts: &LocalizedTexts, locale_keys: &[LocaleKey]) -> Self {
+        let mut usage_map = Self(
+            localized_texts
+                .texts
+                .keys()
+                .map(|key| (Arc::clone(key), Vec::new()))
+                .collect(),
+        );
+        usage_map.add_usages(locale_keys);
+
+        usage_map
+    }
+
+    /// Creates the usage map of the keys in `locale_keys`, in the order they
+    /// are first used, no locale file is involved.
+    pub(crate) fn from_locale_keys(locale_keys: &[LocaleKey]) -> Self {
+        let mut usage_map = Self(IndexMap::new());
+        usage_map.add_usages(locale_keys);
+
+        usage_map
+    }
+
+    /// Adds the usages in `locale_keys`.
+    fn add_usages(&mut self, locale_keys: &[LocaleKey]) {
+        for locale_key in locale_keys {
+            self.0
+                .entry(Arc::clone(&locale_key.key))
+                .or_default()
+                .push(Usage {
+                    file: Arc::clone(&locale_key.file),
+                    line: locale_key.line,
+                    column: locale_key.column,
+                    args: locale_key.args.clone(),
+                });
+        }
+    }
+
+    /// Prints the usage map in `format`.
+    pub(crate) fn report_to_user(&self, format: UsageMapFormat) {
+        match format {
+            UsageMapFormat::Text => {
+                for (key, usages) in self.0.iter() {
+                    println!("{}", key);
+                    for usage in usages {
+                        println!(
+                            "    {}:{}:{}",
+                            usage.file.display(),
+                            usage.line,
+                            usage.column
+                        );
+                    }
+                }
+            }
+            UsageMapFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(self).expect("usage map should be serializable")
+            ),
+        }
+    }
+}
+
+/// How [`UsageMap::report_to_user()`] prints the usage map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum UsageMapFormat {
+    /// Every key followed by its locations, one per line.
+    #[default]
+    Text,
+    /// `{key: [{file, line, column, args}]}`
+    Json,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale_file_parser::Translations;
+
+    #[test]
+    fn test_usage_map() {
+        let localized_texts = LocalizedTexts {
+            texts: IndexMap::from([
+                ("Restarting {app}".into(), Translations::default()),
+                ("Unused".into(), Translations::default()),
+            ]),
+        };
+        let locale_keys = vec![
+            LocaleKey {
+                key: "Restarting {app}".into(),
+                file: Path::new("foo.rs").into(),
+                line: 1,
+                column: 4,
+                args: vec!["app".into()],
+                locale: None,
+                ignored: false,
+            },
+            LocaleKey {
+                key: "Undefined".into(),
+                file: Path::new("bar.rs").into(),
+                line: 2,
+                column: 0,
+                args: Vec::new(),
+                locale: None,
+                ignored: false,
+            },
+        ];
+
+        let usage_map = UsageMap::new(&localized_texts, &locale_keys);
+        assert_eq!(
+            serde_json::to_string(&usage_map).unwrap(),
+            r#"{"Restarting {app}":[{"file":"foo.rs","line":1,"column":4,"args":["app"]}],"Unused":[],"Undefined":[{"file":"bar.rs","line":2,"column":0,"args":[]}]}"#
+        );
+
+        let usage_map = UsageMap::from_locale_keys(&locale_keys);
+        assert_eq!(
+            serde_json::to_string(&usage_map).unwrap(),
+            r#"{"Restarting {app}":[{"file":"foo.rs","line":1,"column":4,"args":["app"]}],"Undefined":[{"file":"bar.rs","line":2,"column":0,"args":[]}]}"#
+        );
+    }
+}